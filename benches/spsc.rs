@@ -0,0 +1,201 @@
+//! Benchmarks for the spsc flavors, run with `cargo bench --features spsc-all`.
+//!
+//! The `bench-crossbeam` and `bench-rtrb` features add other crates' queues as
+//! baselines, next to `std::sync::mpsc`, which is always measured.
+use concurrent_qs::spsc::{bounded, unbounded};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How many values a burst sends.
+const BURST: u64 = 10_000;
+/// The capacity of bounded queues.
+const CAPACITY: usize = 1024;
+
+/// A queue under test, with blocking `send` and `recv`.
+trait Flavor<T: Send + 'static> {
+    const NAME: &'static str;
+    type Tx: Send + 'static;
+    type Rx: Send + 'static;
+    fn channel() -> (Self::Tx, Self::Rx);
+    fn send(tx: &mut Self::Tx, item: T);
+    fn recv(rx: &mut Self::Rx) -> T;
+}
+
+struct Bounded;
+impl<T: Send + 'static> Flavor<T> for Bounded {
+    const NAME: &'static str = "bounded";
+    type Tx = bounded::Sender<T>;
+    type Rx = bounded::Receiver<T>;
+    fn channel() -> (Self::Tx, Self::Rx) {
+        bounded::channel(CAPACITY)
+    }
+    fn send(tx: &mut Self::Tx, item: T) {
+        tx.send(item).ok().unwrap()
+    }
+    fn recv(rx: &mut Self::Rx) -> T {
+        rx.recv().unwrap()
+    }
+}
+
+struct Unbounded;
+impl<T: Send + 'static> Flavor<T> for Unbounded {
+    const NAME: &'static str = "unbounded";
+    type Tx = unbounded::Sender<T>;
+    type Rx = unbounded::Receiver<T>;
+    fn channel() -> (Self::Tx, Self::Rx) {
+        unbounded::channel()
+    }
+    fn send(tx: &mut Self::Tx, item: T) {
+        tx.send(item).ok().unwrap()
+    }
+    fn recv(rx: &mut Self::Rx) -> T {
+        rx.recv().unwrap()
+    }
+}
+
+struct Std;
+impl<T: Send + 'static> Flavor<T> for Std {
+    const NAME: &'static str = "std";
+    type Tx = std::sync::mpsc::SyncSender<T>;
+    type Rx = std::sync::mpsc::Receiver<T>;
+    fn channel() -> (Self::Tx, Self::Rx) {
+        std::sync::mpsc::sync_channel(CAPACITY)
+    }
+    fn send(tx: &mut Self::Tx, item: T) {
+        tx.send(item).ok().unwrap()
+    }
+    fn recv(rx: &mut Self::Rx) -> T {
+        rx.recv().unwrap()
+    }
+}
+
+#[cfg(feature = "bench-crossbeam")]
+struct Crossbeam;
+#[cfg(feature = "bench-crossbeam")]
+impl<T: Send + 'static> Flavor<T> for Crossbeam {
+    const NAME: &'static str = "crossbeam";
+    type Tx = crossbeam_channel::Sender<T>;
+    type Rx = crossbeam_channel::Receiver<T>;
+    fn channel() -> (Self::Tx, Self::Rx) {
+        crossbeam_channel::bounded(CAPACITY)
+    }
+    fn send(tx: &mut Self::Tx, item: T) {
+        tx.send(item).ok().unwrap()
+    }
+    fn recv(rx: &mut Self::Rx) -> T {
+        rx.recv().unwrap()
+    }
+}
+
+/// rtrb never blocks, so this busy-waits.
+#[cfg(feature = "bench-rtrb")]
+struct Rtrb;
+#[cfg(feature = "bench-rtrb")]
+impl<T: Send + 'static> Flavor<T> for Rtrb {
+    const NAME: &'static str = "rtrb";
+    type Tx = rtrb::Producer<T>;
+    type Rx = rtrb::Consumer<T>;
+    fn channel() -> (Self::Tx, Self::Rx) {
+        rtrb::RingBuffer::new(CAPACITY)
+    }
+    fn send(tx: &mut Self::Tx, mut item: T) {
+        while let Err(rtrb::PushError::Full(ret)) = tx.push(item) {
+            item = ret;
+            std::hint::spin_loop();
+        }
+    }
+    fn recv(rx: &mut Self::Rx) -> T {
+        loop {
+            if let Ok(item) = rx.pop() {
+                return item;
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+/// Measures `iters` round trips through two queues and an echo thread.
+fn ping_pong<F: Flavor<u64>>(iters: u64) -> Duration {
+    let (mut ping_tx, mut ping_rx) = F::channel();
+    let (mut pong_tx, mut pong_rx) = F::channel();
+    let echo = thread::spawn(move || {
+        for _ in 0..iters {
+            let item = F::recv(&mut ping_rx);
+            F::send(&mut pong_tx, item);
+        }
+    });
+    let start = Instant::now();
+    for i in 0..iters {
+        F::send(&mut ping_tx, i);
+        assert_eq!(F::recv(&mut pong_rx), i);
+    }
+    let elapsed = start.elapsed();
+    echo.join().unwrap();
+    elapsed
+}
+
+/// Measures receiving `iters` bursts of values from another thread.
+fn burst<F: Flavor<[u8; N]>, const N: usize>(iters: u64) -> Duration {
+    let (mut tx, mut rx) = F::channel();
+    let producer = thread::spawn(move || {
+        for _ in 0..iters * BURST {
+            F::send(&mut tx, [1; N]);
+        }
+    });
+    let start = Instant::now();
+    for _ in 0..iters * BURST {
+        criterion::black_box(F::recv(&mut rx));
+    }
+    let elapsed = start.elapsed();
+    producer.join().unwrap();
+    elapsed
+}
+
+macro_rules! for_each_flavor {
+    ($f:ident!($($args:tt)*)) => {
+        $f!(Bounded, $($args)*);
+        $f!(Unbounded, $($args)*);
+        $f!(Std, $($args)*);
+        #[cfg(feature = "bench-crossbeam")]
+        $f!(Crossbeam, $($args)*);
+        #[cfg(feature = "bench-rtrb")]
+        $f!(Rtrb, $($args)*);
+    };
+}
+
+fn bench_ping_pong(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ping_pong");
+    macro_rules! bench {
+        ($flavor:ident,) => {
+            group.bench_function(<$flavor as Flavor<u64>>::NAME, |b| {
+                b.iter_custom(ping_pong::<$flavor>)
+            });
+        };
+    }
+    for_each_flavor!(bench!());
+    group.finish();
+}
+
+fn bench_burst(c: &mut Criterion) {
+    macro_rules! bench {
+        ($flavor:ident, $group:ident, $size:literal) => {
+            $group.bench_function(
+                BenchmarkId::new(<$flavor as Flavor<[u8; $size]>>::NAME, $size),
+                |b| b.iter_custom(burst::<$flavor, $size>),
+            );
+        };
+    }
+    macro_rules! sizes {
+        ($($size:literal),*) => {$({
+            let mut group = c.benchmark_group(concat!("burst_", $size));
+            group.throughput(Throughput::Elements(BURST));
+            for_each_flavor!(bench!(group, $size));
+            group.finish();
+        })*};
+    }
+    sizes!(8, 64, 512);
+}
+
+criterion_group!(benches, bench_ping_pong, bench_burst);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+#![no_main]
+
+use concurrent_qs::spsc::bounded;
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/model/harness.rs"]
+mod harness;
+use harness::{run, Op};
+
+// the first byte picks the flavor and capacity, the rest are the operations.
+fuzz_target!(|data: &[u8]| {
+    let Some((&config, ops)) = data.split_first() else {
+        return;
+    };
+    let ops: Vec<Op> = ops.iter().copied().map(Op::from_byte).collect();
+    let capacity = (config & 0x7f) as usize + 1;
+    if config & 0x80 == 0 {
+        let channel = bounded::channel(capacity);
+        run(channel, Some(capacity.next_power_of_two()), &ops, |err| {
+            (err.kind(), err.into_inner())
+        });
+    } else {
+        let channel = bounded::channel_exact(capacity);
+        run(channel, Some(capacity), &ops, |err| (err.kind(), err.into_inner()));
+    }
+});
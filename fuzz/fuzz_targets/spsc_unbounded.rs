@@ -0,0 +1,13 @@
+#![no_main]
+
+use concurrent_qs::spsc::unbounded;
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/model/harness.rs"]
+mod harness;
+use harness::{run, Op};
+
+fuzz_target!(|data: &[u8]| {
+    let ops: Vec<Op> = data.iter().copied().map(Op::from_byte).collect();
+    run(unbounded::channel(), None, &ops, |err| (err.kind(), err.into_inner()));
+});
@@ -3,5 +3,6 @@ cfg_loom! {
 }
 
 cfg_not_loom! {
+    #[allow(unused_imports)]
     pub(crate) use std::alloc::*;
 }
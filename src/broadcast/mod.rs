@@ -0,0 +1,354 @@
+use crate::error::{BroadcastRecvError, BroadcastTryRecvError, SendError};
+use crate::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release, SeqCst};
+use crate::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicUsize};
+use crate::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::util::marker::PhantomUnsync;
+use crate::util::park::Parker;
+
+use std::cell::Cell;
+
+/// Creates a broadcast channel that keeps the last `capacity` values.
+///
+/// # Panics
+///
+/// The function panics if `capacity` is 0 or it can't
+/// allocate the memory needed for the channel.
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity != 0, "a broadcast channel needs a non-zero capacity");
+    let shared = Arc::new(Shared {
+        slots: (0..capacity)
+            .map(|_| {
+                RwLock::new(Slot {
+                    pos: UNWRITTEN,
+                    value: None,
+                })
+            })
+            .collect(),
+        tail: AtomicU64::new(0),
+        waiters: Mutex::new(Vec::new()),
+        waiting: AtomicUsize::new(0),
+        sender_connected: AtomicBool::new(true),
+        receivers: AtomicUsize::new(1),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+            _unsync: PhantomUnsync {},
+        },
+        Receiver {
+            next: Cell::new(0),
+            park: Arc::new(Parker::new()),
+            shared,
+        },
+    )
+}
+
+/// The sending endpoint of a [`channel`].
+///
+/// Values are published with [`send`](Sender::send), which never waits for
+/// the receivers to catch up.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+    _unsync: PhantomUnsync,
+}
+
+/// A receiving endpoint of a [`channel`].
+///
+/// Every [`Receiver`] has its own cursor and sees every value sent after it
+/// was created, unless it falls behind by more than the channel's capacity.
+/// Values can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<T> {
+    /// The position of the next value to receive.
+    next: Cell<u64>,
+    /// Parked on when waiting for the [`Sender`].
+    park: Arc<Parker>,
+    shared: Arc<Shared<T>>,
+}
+
+/// The `pos` of a slot that was never written to.
+const UNWRITTEN: u64 = u64::MAX;
+
+struct Shared<T> {
+    /// The value sent at position `pos` is stored at `slots[pos % slots.len()]`.
+    slots: Box<[RwLock<Slot<T>>]>,
+    /// The position of the next value to send.
+    ///
+    /// Only written by the sender, while it holds the lock of the written slot.
+    tail: AtomicU64,
+    /// The parkers of blocked receivers.
+    waiters: Mutex<Vec<Arc<Parker>>>,
+    /// The length of `waiters`, so sends can skip the lock when it's empty.
+    waiting: AtomicUsize,
+    sender_connected: AtomicBool,
+    receivers: AtomicUsize,
+}
+
+struct Slot<T> {
+    pos: u64,
+    value: Option<T>,
+}
+
+impl<T> Shared<T> {
+    fn waiters(&self) -> MutexGuard<'_, Vec<Arc<Parker>>> {
+        //the guarded state is always consistent, so we ignore poison.
+        match self.waiters.lock() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn read(&self, pos: u64) -> RwLockReadGuard<'_, Slot<T>> {
+        //a panic while dropping an overwritten value can't break the slot.
+        match self.slots[(pos % self.slots.len() as u64) as usize].read() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn write(&self, pos: u64) -> RwLockWriteGuard<'_, Slot<T>> {
+        match self.slots[(pos % self.slots.len() as u64) as usize].write() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn wake_receivers(&self) {
+        // pairs with the fence in start_waiting.
+        fence(SeqCst);
+        if self.waiting.load(Relaxed) == 0 {
+            return;
+        }
+        for waiter in self.waiters().iter() {
+            waiter.unpark();
+        }
+    }
+}
+
+impl<T: Clone> Sender<T> {
+    /// Publishes a value to every connected [`Receiver`].
+    ///
+    /// Doesn't wait for the receivers to catch up. If the channel is full,
+    /// the oldest value is overwritten and the receivers that didn't see it
+    /// yet will get a `Lagged` error. Returns a [`SendError`] if all
+    /// [`Receiver`]s are disconnected.
+    ///
+    /// May still block briefly while a lagging [`Receiver`] clones the
+    /// value that's being overwritten.
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        if self.shared.receivers.load(Acquire) == 0 {
+            return Err(SendError(item));
+        }
+        let pos = self.shared.tail.load(Relaxed);
+        let old = {
+            let mut slot = self.shared.write(pos);
+            slot.pos = pos;
+            let old = slot.value.replace(item);
+            //stored under the lock, so a receiver that sees
+            //the slot overwritten also sees the new tail.
+            self.shared.tail.store(pos + 1, Release);
+            old
+        };
+        //the overwritten value is dropped outside the lock.
+        drop(old);
+        self.shared.wake_receivers();
+        Ok(())
+    }
+
+    /// Creates a new [`Receiver`] that will see the values sent from now on.
+    pub fn subscribe(&self) -> Receiver<T> {
+        self.shared.receivers.fetch_add(1, Relaxed);
+        Receiver {
+            next: Cell::new(self.shared.tail.load(Relaxed)),
+            park: Arc::new(Parker::new()),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Returns the number of connected [`Receiver`]s.
+    #[inline]
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receivers.load(Acquire)
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Tries to return the next value.
+    ///
+    /// If this [`Receiver`] fell behind and the next value was overwritten,
+    /// returns [`BroadcastTryRecvError::Lagged`] with the number of skipped
+    /// values and moves on to the oldest value still in the channel.
+    ///
+    /// # Note
+    ///
+    /// Returns [`BroadcastTryRecvError::Disconnected`] only after receiving all
+    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn try_recv(&self) -> Result<T, BroadcastTryRecvError> {
+        match self.try_read() {
+            Err(BroadcastTryRecvError::Empty) if !self.shared.sender_connected.load(Acquire) => {
+                // the sender might have sent a value before disconnecting.
+                match self.try_read() {
+                    Err(BroadcastTryRecvError::Empty) => Err(BroadcastTryRecvError::Disconnected),
+                    res => res,
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Reads the next value.
+    ///
+    /// If the [`channel`] has no new values, blocks and waits for the [`Sender`].
+    /// If this [`Receiver`] fell behind, returns [`BroadcastRecvError::Lagged`]
+    /// like [`try_recv`](Receiver::try_recv).
+    ///
+    /// # Note
+    ///
+    /// [`BroadcastRecvError::Disconnected`] is only returned after receiving all
+    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn recv(&self) -> Result<T, BroadcastRecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(BroadcastTryRecvError::Lagged(n)) => return Err(BroadcastRecvError::Lagged(n)),
+                Err(BroadcastTryRecvError::Disconnected) => {
+                    return Err(BroadcastRecvError::Disconnected)
+                }
+                Err(BroadcastTryRecvError::Empty) => {}
+            }
+            self.start_waiting();
+            //a send that finished before registering was seen by the recheck,
+            //and any later send will wake us up.
+            if !self.is_ready() {
+                //SAFETY: only this Receiver parks on its Parker and it's !Sync.
+                unsafe { self.park.park() };
+            }
+            self.stop_waiting();
+        }
+    }
+
+    fn try_read(&self) -> Result<T, BroadcastTryRecvError> {
+        let next = self.next.get();
+        let slot = self.shared.read(next);
+        if slot.pos == next {
+            if let Some(item) = slot.value.clone() {
+                self.next.set(next + 1);
+                return Ok(item);
+            }
+        }
+        if slot.pos == UNWRITTEN || slot.pos < next {
+            return Err(BroadcastTryRecvError::Empty);
+        }
+        drop(slot);
+        // the value at next was overwritten, skip to the oldest one available.
+        let oldest = self.shared.tail.load(Acquire) - self.shared.slots.len() as u64;
+        self.next.set(oldest);
+        Err(BroadcastTryRecvError::Lagged(oldest - next))
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
+    /// methods return `Disconnected` only after receiving all previously
+    /// sent data, even if the [`Sender`] isn't connected. This method
+    /// doesn't take pending data into account and can be used to avoid this behaviour.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.shared.sender_connected.load(Acquire)
+    }
+
+    fn is_ready(&self) -> bool {
+        self.shared.tail.load(Acquire) != self.next.get()
+            || !self.shared.sender_connected.load(Acquire)
+    }
+
+    fn start_waiting(&self) {
+        let mut waiters = self.shared.waiters();
+        waiters.push(self.park.clone());
+        self.shared.waiting.store(waiters.len(), Relaxed);
+        drop(waiters);
+        // the is_ready recheck after this sees the send,
+        // or the send sees this waiter.
+        fence(SeqCst);
+    }
+
+    fn stop_waiting(&self) {
+        let mut waiters = self.shared.waiters();
+        waiters.retain(|waiter| !Arc::ptr_eq(waiter, &self.park));
+        self.shared.waiting.store(waiters.len(), Relaxed);
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    /// Creates a [`Receiver`] with the same cursor as this one.
+    fn clone(&self) -> Self {
+        self.shared.receivers.fetch_add(1, Relaxed);
+        Receiver {
+            next: Cell::new(self.next.get()),
+            park: Arc::new(Parker::new()),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> crate::select::Selectable for Receiver<T> {}
+
+impl<T> crate::select::sealed::Sealed for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        Receiver::is_ready(self)
+    }
+
+    unsafe fn register(&self, waker: crate::select::sealed::Waker) -> bool {
+        self.start_waiting();
+        self.park.register(waker.0)
+    }
+
+    unsafe fn unregister(&self) {
+        self.park.unregister();
+        self.stop_waiting();
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.sender_connected.store(false, Release);
+        self.shared.wake_receivers();
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receivers.fetch_sub(1, AcqRel);
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "broadcast::Sender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            Arc::as_ptr(&self.shared)
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "broadcast::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            Arc::as_ptr(&self.shared)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,131 @@
+use super::*;
+
+cfg_not_loom! {
+
+#[test]
+fn st_every_receiver_sees_every_value() {
+    let (src, sink1) = channel::<u32>(4);
+    let sink2 = sink1.clone();
+    assert_eq!(src.receiver_count(), 2);
+    for i in 0..3 {
+        src.send(i).unwrap();
+    }
+    for i in 0..3 {
+        assert_eq!(sink1.try_recv(), Ok(i));
+    }
+    assert_eq!(sink1.try_recv(), Err(BroadcastTryRecvError::Empty));
+    let sink3 = src.subscribe();
+    src.send(3).unwrap();
+    for i in 0..4 {
+        assert_eq!(sink2.try_recv(), Ok(i));
+    }
+    assert_eq!(sink3.try_recv(), Ok(3));
+    drop(src);
+    assert_eq!(sink1.try_recv(), Ok(3));
+    assert_eq!(sink1.try_recv(), Err(BroadcastTryRecvError::Disconnected));
+    assert_eq!(sink3.recv(), Err(BroadcastRecvError::Disconnected));
+}
+
+#[test]
+fn st_lagged() {
+    let (src, sink) = channel::<u32>(2);
+    for i in 0..5 {
+        src.send(i).unwrap();
+    }
+    assert_eq!(sink.try_recv(), Err(BroadcastTryRecvError::Lagged(3)));
+    assert_eq!(sink.try_recv(), Ok(3));
+    src.send(5).unwrap();
+    src.send(6).unwrap();
+    src.send(7).unwrap();
+    assert_eq!(sink.recv(), Err(BroadcastRecvError::Lagged(2)));
+    assert_eq!(sink.recv(), Ok(6));
+    assert_eq!(sink.recv(), Ok(7));
+}
+
+#[test]
+fn st_receivers_disconnect() {
+    let (src, sink) = channel::<u32>(2);
+    let sink2 = sink.clone();
+    drop(sink);
+    src.send(0).unwrap();
+    drop(sink2);
+    assert_eq!(src.receiver_count(), 0);
+    assert_eq!(src.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn mt_select() {
+    use crate::select::Select;
+    let (src, sink1) = channel::<u32>(4);
+    let sink2 = sink1.clone();
+    std::thread::spawn(move || src.send(1).unwrap());
+    let select = Select::new().recv(&sink1);
+    assert_eq!(select.wait(), 0);
+    assert_eq!(sink1.try_recv(), Ok(1));
+    assert_eq!(sink2.recv(), Ok(1));
+}
+
+#[test]
+fn mt_fan_out() {
+    let (src, sink) = channel::<u32>(16);
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let sink = sink.clone();
+            std::thread::spawn(move || {
+                let (mut received, mut lagged) = (0, 0);
+                let mut last = None;
+                loop {
+                    match sink.recv() {
+                        Ok(i) => {
+                            assert!(last < Some(i));
+                            last = Some(i);
+                            received += 1;
+                        }
+                        Err(BroadcastRecvError::Lagged(n)) => lagged += n,
+                        Err(BroadcastRecvError::Disconnected) => break,
+                    }
+                }
+                assert_eq!(last, Some(999));
+                received + lagged
+            })
+        })
+        .collect();
+    drop(sink);
+    for i in 0..1000 {
+        src.send(i).unwrap();
+    }
+    drop(src);
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 1000);
+    }
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn fan_out() {
+    let mut model = loom::model::Builder::new();
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (src, sink1) = channel::<u8>(2);
+        let sink2 = sink1.clone();
+        let handle = loom::thread::spawn(move || {
+            src.send(1).unwrap();
+            src.send(2).unwrap();
+        });
+        let other = loom::thread::spawn(move || {
+            assert_eq!(sink2.recv(), Ok(1));
+            assert_eq!(sink2.recv(), Ok(2));
+            assert_eq!(sink2.recv(), Err(BroadcastRecvError::Disconnected));
+        });
+        assert_eq!(sink1.recv(), Ok(1));
+        assert_eq!(sink1.recv(), Ok(2));
+        assert_eq!(sink1.recv(), Err(BroadcastRecvError::Disconnected));
+        handle.join().unwrap();
+        other.join().unwrap();
+    });
+}
+
+}
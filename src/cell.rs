@@ -3,6 +3,7 @@ cfg_loom! {
 }
 
 cfg_not_loom! {
+    #[repr(transparent)]
     pub(crate) struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
     #[allow(dead_code)]
     impl<T> UnsafeCell<T> {
@@ -19,5 +20,21 @@ cfg_not_loom! {
         pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R{
             f(self.0.get())
         }
+
+        /// Returns a raw pointer to the data, like [`std::cell::UnsafeCell::get`].
+        ///
+        /// Not available with loom, which tracks accesses through the closures.
+        #[inline(always)]
+        pub(crate) const fn get(&self) -> *mut T {
+            self.0.get()
+        }
+
+        /// Returns a mutable reference to the data.
+        ///
+        /// Not available with loom, which tracks accesses through the closures.
+        #[inline(always)]
+        pub(crate) fn get_mut(&mut self) -> &mut T {
+            self.0.get_mut()
+        }
     }
 }
@@ -0,0 +1,152 @@
+use crate::error::{CodecRecvError, CodecSendError};
+use crate::spsc::bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{ErrorKind, Read, Write};
+use std::marker::PhantomData;
+
+/// The size of the length prefix in front of every frame.
+const PREFIX: usize = std::mem::size_of::<u32>();
+
+/// Creates a typed channel over a byte stream with a buffer of at least `min_capacity` bytes.
+///
+/// Values larger than the buffer are still sent, they just
+/// pass through it in several parts.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the stream.
+pub fn channel<T: Serialize + DeserializeOwned>(
+    min_capacity: usize,
+) -> (TypedSender<T>, TypedReceiver<T>) {
+    let (tx, rx) = bytes::channel(min_capacity);
+    (TypedSender::new(tx), TypedReceiver::new(rx))
+}
+
+/// The sending half of a typed [`channel`].
+///
+/// Every value is serialized into a frame, made of its
+/// length as a little endian `u32` and the payload.
+pub struct TypedSender<T: Serialize> {
+    stream: bytes::Sender,
+    frame: Vec<u8>,
+    _marker: PhantomData<fn(T)>,
+}
+
+/// The receiving half of a typed [`channel`].
+///
+/// Reads the frames written by a [`TypedSender`] and deserializes them.
+pub struct TypedReceiver<T: DeserializeOwned> {
+    stream: bytes::Receiver,
+    frame: Vec<u8>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize> TypedSender<T> {
+    /// Creates a [`TypedSender`] that writes frames into `stream`.
+    pub fn new(stream: bytes::Sender) -> Self {
+        Self {
+            stream,
+            frame: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Serializes `value` and writes it as a single frame,
+    /// blocking until the whole frame is in the stream.
+    ///
+    /// Fails with [`Encode`](CodecSendError::Encode) if `value` can't be
+    /// serialized or its frame would be longer than [`u32::MAX`], and with
+    /// [`Disconnected`](CodecSendError::Disconnected) if the [`TypedReceiver`]
+    /// is disconnected.
+    pub fn send(&mut self, value: &T) -> Result<(), CodecSendError> {
+        self.frame.clear();
+        self.frame.extend_from_slice(&[0; PREFIX]);
+        serde_json::to_writer(&mut self.frame, value).map_err(CodecSendError::Encode)?;
+        let len = u32::try_from(self.frame.len() - PREFIX).map_err(|_| {
+            CodecSendError::Encode(serde::ser::Error::custom("frame is longer than u32::MAX"))
+        })?;
+        self.frame[..PREFIX].copy_from_slice(&len.to_le_bytes());
+        self.stream
+            .write_all(&self.frame)
+            .map_err(|_| CodecSendError::Disconnected)
+    }
+
+    /// Checks if the [`channel`]'s [`TypedReceiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.stream.receiver_connected()
+    }
+
+    /// Returns the underlying [`bytes::Sender`].
+    pub fn into_inner(self) -> bytes::Sender {
+        self.stream
+    }
+}
+
+impl<T: DeserializeOwned> TypedReceiver<T> {
+    /// Creates a [`TypedReceiver`] that reads frames from `stream`.
+    pub fn new(stream: bytes::Receiver) -> Self {
+        Self {
+            stream,
+            frame: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reads the next frame and deserializes it, blocking until
+    /// the whole frame is available.
+    ///
+    /// Fails with [`Decode`](CodecRecvError::Decode) if the payload can't be
+    /// deserialized into `T`, and with [`Disconnected`](CodecRecvError::Disconnected)
+    /// once the [`TypedSender`] disconnected and all sent frames were received.
+    pub fn recv(&mut self) -> Result<T, CodecRecvError> {
+        let mut prefix = [0; PREFIX];
+        read_exact(&mut self.stream, &mut prefix)?;
+        self.frame.clear();
+        self.frame.resize(u32::from_le_bytes(prefix) as usize, 0);
+        read_exact(&mut self.stream, &mut self.frame)?;
+        serde_json::from_slice(&self.frame).map_err(CodecRecvError::Decode)
+    }
+
+    /// Checks if the [`channel`]'s [`TypedSender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// [`recv`](TypedReceiver::recv) only fails with `Disconnected` after
+    /// all sent frames were received. This method doesn't take pending
+    /// frames into account.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.stream.sender_connected()
+    }
+
+    /// Returns the underlying [`bytes::Receiver`].
+    pub fn into_inner(self) -> bytes::Receiver {
+        self.stream
+    }
+}
+
+fn read_exact(stream: &mut bytes::Receiver, buf: &mut [u8]) -> Result<(), CodecRecvError> {
+    // the stream only ends when the sender disconnects, and a
+    // sender only disconnects between frames unless it panics.
+    stream.read_exact(buf).map_err(|e| {
+        debug_assert_eq!(e.kind(), ErrorKind::UnexpectedEof);
+        CodecRecvError::Disconnected
+    })
+}
+
+impl<T: Serialize> std::fmt::Debug for TypedSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "codec::TypedSender {{ stream: {:?} }}", self.stream)
+    }
+}
+
+impl<T: DeserializeOwned> std::fmt::Debug for TypedReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "codec::TypedReceiver {{ stream: {:?} }}", self.stream)
+    }
+}
+
+#[cfg(test)]
+mod tests;
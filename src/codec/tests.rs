@@ -0,0 +1,66 @@
+use super::*;
+
+cfg_not_loom! {
+
+#[test]
+fn st_send_recv() {
+    let (mut src, mut sink) = channel::<Vec<String>>(4);
+    let value = vec!["Hello".to_string(), "World".to_string()];
+    let expected = value.clone();
+    // the frame doesn't fit into the buffer, so it's sent in parts.
+    let handle = std::thread::spawn(move || src.send(&value).unwrap());
+    assert_eq!(sink.recv().unwrap(), expected);
+    handle.join().unwrap();
+    assert!(matches!(sink.recv(), Err(CodecRecvError::Disconnected)));
+
+    let (mut src, sink) = channel::<u32>(16);
+    drop(sink);
+    assert!(matches!(src.send(&1), Err(CodecSendError::Disconnected)));
+}
+
+#[test]
+fn mt_frames() {
+    let (mut src, mut sink) = channel::<(u64, String)>(16);
+    let handle = std::thread::spawn(move || {
+        for i in 0..10_000 {
+            src.send(&(i, i.to_string())).unwrap();
+        }
+    });
+    for i in 0..10_000 {
+        assert_eq!(sink.recv().unwrap(), (i, i.to_string()));
+    }
+    assert!(matches!(sink.recv(), Err(CodecRecvError::Disconnected)));
+    handle.join().unwrap();
+}
+
+#[test]
+fn decode_error() {
+    let (src, sink) = bytes::channel(64);
+    let (mut src, mut sink) = (TypedSender::<&str>::new(src), TypedReceiver::<u32>::new(sink));
+    src.send(&"not a number").unwrap();
+    src.send(&"7").unwrap();
+    assert!(matches!(sink.recv(), Err(CodecRecvError::Decode(_))));
+    // a bad frame is consumed whole, so the next one is still readable.
+    assert!(matches!(sink.recv(), Err(CodecRecvError::Decode(_))));
+    let mut src = TypedSender::<u32>::new(src.into_inner());
+    src.send(&7).unwrap();
+    assert_eq!(sink.recv().unwrap(), 7);
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn frame() {
+    let mut model = loom::model::Builder::new();
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (mut src, mut sink) = channel::<u8>(4);
+        loom::thread::spawn(move || src.send(&42).unwrap());
+        assert_eq!(sink.recv().unwrap(), 42);
+        assert!(matches!(sink.recv(), Err(CodecRecvError::Disconnected)));
+    });
+}
+
+}
@@ -17,6 +17,11 @@ pub enum TrySendError<T> {
     ///
     /// Contains the data that failed to send.
     Disconnected(T),
+    /// The `bounded::Receiver` panicked on a `bounded::channel` built with
+    /// `bounded::Builder::poisoning` and any further sends will not succeed.
+    ///
+    /// Contains the data that failed to send.
+    Poisoned(T),
 }
 
 /// An enumeration listing the failure modes of the `try_recv` method of a `Receiver`.
@@ -31,6 +36,9 @@ pub enum TryRecvError {
     /// The `Sender` bound to the `channel` disconnected
     /// and all previously sent data was already received.
     Disconnected,
+    /// The `bounded::Sender` panicked on a `bounded::channel` built with
+    /// `bounded::Builder::poisoning` and all previously sent data was already received.
+    Poisoned,
 }
 
 /// Error for the `send` method of a `Sender`.
@@ -56,16 +64,584 @@ pub struct SendError<T>(pub T);
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct RecvError {}
 
+/// An enumeration listing the failure modes of the `send_timeout` method of a `bounded::Sender`.
+///
+/// The available `bounded::Sender`s are
+/// - [spsc::bounded::Sender](crate::spsc::bounded::Sender)
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum SendTimeoutError<T> {
+    /// The data couldn't be sent on the `bounded::channel`
+    /// because it stayed full until the timeout elapsed.
+    ///
+    /// Contains the data that failed to send.
+    Timeout(T),
+    /// The `bounded::Receiver` connected to the `bounded::channel`
+    /// disconnected and any further sends will not succeed.
+    ///
+    /// Contains the data that failed to send.
+    Disconnected(T),
+    /// The `bounded::Receiver` panicked on a `bounded::channel` built with
+    /// `bounded::Builder::poisoning` and any further sends will not succeed.
+    ///
+    /// Contains the data that failed to send.
+    Poisoned(T),
+}
+
+/// An enumeration listing the failure modes of the `recv_timeout` method of a `Receiver`.
+///
+/// The available `Receiver`s are:
+/// - [spsc::bounded::Receiver](crate::spsc::bounded::Receiver)
+/// - [spsc::unbounded::Receiver](crate::spsc::unbounded::Receiver)
+/// - [spsc::unbounded_chunked::Receiver](crate::spsc::unbounded_chunked::Receiver)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RecvTimeoutError {
+    /// No data was received from the `channel` because
+    /// it stayed empty until the timeout elapsed.
+    Timeout,
+    /// The `Sender` bound to the `channel` disconnected
+    /// and all previously sent data was already received.
+    Disconnected,
+    /// The `bounded::Sender` panicked on a `bounded::channel` built with
+    /// `bounded::Builder::poisoning` and all previously sent data was already received.
+    Poisoned,
+}
+
+/// An enumeration listing the failure modes of the `recv_cancellable` method of a `Receiver`.
+///
+/// The available `Receiver`s are:
+/// - [spsc::bounded::Receiver](crate::spsc::bounded::Receiver)
+/// - [spsc::unbounded::Receiver](crate::spsc::unbounded::Receiver)
+/// - [spsc::unbounded_chunked::Receiver](crate::spsc::unbounded_chunked::Receiver)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RecvCancelError {
+    /// The [`CancellationToken`](crate::sync::CancellationToken)
+    /// was cancelled. Pending data stays in the `channel`.
+    Cancelled,
+    /// The `Sender` bound to the `channel` disconnected
+    /// and all previously sent data was already received.
+    Disconnected,
+}
+
+/// An enumeration listing the failure modes of the `try_send` method of an `unbounded::Sender`.
+///
+/// The available `unbounded::Sender`s are
+/// - [spsc::unbounded::Sender](crate::spsc::unbounded::Sender)
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum TrySendAllocError<T> {
+    /// The data couldn't be sent because memory
+    /// for it couldn't be allocated.
+    ///
+    /// Contains the data that failed to send.
+    OutOfMemory(T),
+    /// The data couldn't be sent because the soft bound
+    /// set by `unbounded::Builder::max_len` was reached.
+    ///
+    /// Contains the data that failed to send.
+    Full(T),
+    /// The `unbounded::Receiver` connected to the `unbounded::channel`
+    /// disconnected and any further sends will not succeed.
+    ///
+    /// Contains the data that failed to send.
+    Disconnected(T),
+}
+
+/// Error for the `try_channel` functions.
+///
+/// This error is returned when the memory
+/// needed for a `channel` can't be allocated.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct AllocError {}
+
+/// An enumeration listing the failure modes of the `try_recv` method of a `broadcast::Receiver`.
+///
+/// The available `broadcast::Receiver`s are:
+/// - [broadcast::Receiver](crate::broadcast::Receiver)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BroadcastTryRecvError {
+    /// No data was received from the `channel` because
+    /// the `Receiver` already saw all sent data.
+    Empty,
+    /// The `Receiver` fell behind and the oldest values it didn't
+    /// receive were overwritten.
+    ///
+    /// Contains the number of skipped values.
+    Lagged(u64),
+    /// The `Sender` bound to the `channel` disconnected
+    /// and all previously sent data was already received.
+    Disconnected,
+}
+
+/// An enumeration listing the failure modes of the `recv` method of a `broadcast::Receiver`.
+///
+/// The available `broadcast::Receiver`s are:
+/// - [broadcast::Receiver](crate::broadcast::Receiver)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BroadcastRecvError {
+    /// The `Receiver` fell behind and the oldest values it didn't
+    /// receive were overwritten.
+    ///
+    /// Contains the number of skipped values.
+    Lagged(u64),
+    /// The `Sender` bound to the `channel` disconnected
+    /// and all previously sent data was already received.
+    Disconnected,
+}
+
+/// An enumeration listing the failure modes of the `send` method of a `codec::TypedSender`.
+///
+/// The available `TypedSender`s are:
+/// - [codec::TypedSender](crate::codec::TypedSender)
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum CodecSendError {
+    /// The value couldn't be serialized into a frame.
+    ///
+    /// Contains the serialization error.
+    Encode(serde_json::Error),
+    /// The `TypedReceiver` connected to the `channel`
+    /// disconnected and any further sends will not succeed.
+    Disconnected,
+}
+
+/// An enumeration listing the failure modes of the `recv` method of a `codec::TypedReceiver`.
+///
+/// The available `TypedReceiver`s are:
+/// - [codec::TypedReceiver](crate::codec::TypedReceiver)
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum CodecRecvError {
+    /// The received frame couldn't be deserialized.
+    ///
+    /// Contains the deserialization error.
+    Decode(serde_json::Error),
+    /// The `TypedSender` bound to the `channel` disconnected
+    /// and all previously sent frames were already received.
+    Disconnected,
+}
+
+/// The kind of an error, without the data that failed to send.
+///
+/// Every error type in this module has a `kind` method, which
+/// makes it easy to log or count errors of different types.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::error::{ErrorKind, TrySendError};
+///
+/// let err = TrySendError::Full(vec![1, 2, 3]);
+/// assert_eq!(err.kind(), ErrorKind::Full);
+/// assert_eq!(err.kind().to_string(), "full");
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The `channel` was full.
+    Full,
+    /// The `channel` was empty.
+    Empty,
+    /// The timeout elapsed.
+    Timeout,
+    /// Memory couldn't be allocated.
+    OutOfMemory,
+    /// A `broadcast::Receiver` fell behind.
+    Lagged,
+    /// A value couldn't be encoded or decoded.
+    Codec,
+    /// The other end point disconnected.
+    Disconnected,
+    /// The other end point panicked.
+    Poisoned,
+    /// A `CancellationToken` was cancelled.
+    Cancelled,
+}
+
+impl<T> TrySendError<T> {
+    /// Returns the data that failed to send.
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(item)
+            | TrySendError::Disconnected(item)
+            | TrySendError::Poisoned(item) => item,
+        }
+    }
+
+    /// Checks if the `channel` was full.
+    pub fn is_full(&self) -> bool {
+        matches!(self, TrySendError::Full(_))
+    }
+
+    /// Checks if the `Receiver` disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, TrySendError::Disconnected(_))
+    }
+
+    /// Checks if the `Receiver` panicked.
+    pub fn is_poisoned(&self) -> bool {
+        matches!(self, TrySendError::Poisoned(_))
+    }
+
+    /// Maps the data that failed to send with `f`, keeping the failure mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::error::TrySendError;
+    ///
+    /// let err = TrySendError::Full(Box::new(1));
+    /// assert_eq!(err.map(|item| *item), TrySendError::Full(1));
+    /// ```
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> TrySendError<U> {
+        match self {
+            TrySendError::Full(item) => TrySendError::Full(f(item)),
+            TrySendError::Disconnected(item) => TrySendError::Disconnected(f(item)),
+            TrySendError::Poisoned(item) => TrySendError::Poisoned(f(item)),
+        }
+    }
+
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TrySendError::Full(_) => ErrorKind::Full,
+            TrySendError::Disconnected(_) => ErrorKind::Disconnected,
+            TrySendError::Poisoned(_) => ErrorKind::Poisoned,
+        }
+    }
+}
+
+impl<T> SendError<T> {
+    /// Returns the data that failed to send.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    /// Maps the data that failed to send with `f`.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SendError<U> {
+        SendError(f(self.0))
+    }
+
+    /// Returns the [`ErrorKind`] of this error, which is always
+    /// [`Disconnected`](ErrorKind::Disconnected).
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::Disconnected
+    }
+}
+
+impl<T> SendTimeoutError<T> {
+    /// Returns the data that failed to send.
+    pub fn into_inner(self) -> T {
+        match self {
+            SendTimeoutError::Timeout(item)
+            | SendTimeoutError::Disconnected(item)
+            | SendTimeoutError::Poisoned(item) => item,
+        }
+    }
+
+    /// Checks if the timeout elapsed.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, SendTimeoutError::Timeout(_))
+    }
+
+    /// Checks if the `Receiver` disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, SendTimeoutError::Disconnected(_))
+    }
+
+    /// Checks if the `Receiver` panicked.
+    pub fn is_poisoned(&self) -> bool {
+        matches!(self, SendTimeoutError::Poisoned(_))
+    }
+
+    /// Maps the data that failed to send with `f`, keeping the failure mode.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SendTimeoutError<U> {
+        match self {
+            SendTimeoutError::Timeout(item) => SendTimeoutError::Timeout(f(item)),
+            SendTimeoutError::Disconnected(item) => SendTimeoutError::Disconnected(f(item)),
+            SendTimeoutError::Poisoned(item) => SendTimeoutError::Poisoned(f(item)),
+        }
+    }
+
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            SendTimeoutError::Timeout(_) => ErrorKind::Timeout,
+            SendTimeoutError::Disconnected(_) => ErrorKind::Disconnected,
+            SendTimeoutError::Poisoned(_) => ErrorKind::Poisoned,
+        }
+    }
+}
+
+impl<T> TrySendAllocError<T> {
+    /// Returns the data that failed to send.
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendAllocError::OutOfMemory(item)
+            | TrySendAllocError::Full(item)
+            | TrySendAllocError::Disconnected(item) => item,
+        }
+    }
+
+    /// Checks if memory for the data couldn't be allocated.
+    pub fn is_out_of_memory(&self) -> bool {
+        matches!(self, TrySendAllocError::OutOfMemory(_))
+    }
+
+    /// Checks if the soft bound of the `channel` was reached.
+    pub fn is_full(&self) -> bool {
+        matches!(self, TrySendAllocError::Full(_))
+    }
+
+    /// Checks if the `Receiver` disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, TrySendAllocError::Disconnected(_))
+    }
+
+    /// Maps the data that failed to send with `f`, keeping the failure mode.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> TrySendAllocError<U> {
+        match self {
+            TrySendAllocError::OutOfMemory(item) => TrySendAllocError::OutOfMemory(f(item)),
+            TrySendAllocError::Full(item) => TrySendAllocError::Full(f(item)),
+            TrySendAllocError::Disconnected(item) => TrySendAllocError::Disconnected(f(item)),
+        }
+    }
+
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TrySendAllocError::OutOfMemory(_) => ErrorKind::OutOfMemory,
+            TrySendAllocError::Full(_) => ErrorKind::Full,
+            TrySendAllocError::Disconnected(_) => ErrorKind::Disconnected,
+        }
+    }
+}
+
+impl TryRecvError {
+    /// Checks if the `channel` was empty.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, TryRecvError::Empty)
+    }
+
+    /// Checks if the `Sender` disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, TryRecvError::Disconnected)
+    }
+
+    /// Checks if the `Sender` panicked.
+    pub fn is_poisoned(&self) -> bool {
+        matches!(self, TryRecvError::Poisoned)
+    }
+
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TryRecvError::Empty => ErrorKind::Empty,
+            TryRecvError::Disconnected => ErrorKind::Disconnected,
+            TryRecvError::Poisoned => ErrorKind::Poisoned,
+        }
+    }
+}
+
+impl RecvError {
+    /// Returns the [`ErrorKind`] of this error, which is always
+    /// [`Disconnected`](ErrorKind::Disconnected).
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::Disconnected
+    }
+}
+
+impl RecvTimeoutError {
+    /// Checks if the timeout elapsed.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, RecvTimeoutError::Timeout)
+    }
+
+    /// Checks if the `Sender` disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, RecvTimeoutError::Disconnected)
+    }
+
+    /// Checks if the `Sender` panicked.
+    pub fn is_poisoned(&self) -> bool {
+        matches!(self, RecvTimeoutError::Poisoned)
+    }
+
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            RecvTimeoutError::Timeout => ErrorKind::Timeout,
+            RecvTimeoutError::Disconnected => ErrorKind::Disconnected,
+            RecvTimeoutError::Poisoned => ErrorKind::Poisoned,
+        }
+    }
+}
+
+impl RecvCancelError {
+    /// Checks if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, RecvCancelError::Cancelled)
+    }
+
+    /// Checks if the `Sender` disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, RecvCancelError::Disconnected)
+    }
+
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            RecvCancelError::Cancelled => ErrorKind::Cancelled,
+            RecvCancelError::Disconnected => ErrorKind::Disconnected,
+        }
+    }
+}
+
+impl AllocError {
+    /// Returns the [`ErrorKind`] of this error, which is always
+    /// [`OutOfMemory`](ErrorKind::OutOfMemory).
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::OutOfMemory
+    }
+}
+
+impl BroadcastTryRecvError {
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            BroadcastTryRecvError::Empty => ErrorKind::Empty,
+            BroadcastTryRecvError::Lagged(_) => ErrorKind::Lagged,
+            BroadcastTryRecvError::Disconnected => ErrorKind::Disconnected,
+        }
+    }
+}
+
+impl BroadcastRecvError {
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            BroadcastRecvError::Lagged(_) => ErrorKind::Lagged,
+            BroadcastRecvError::Disconnected => ErrorKind::Disconnected,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl CodecSendError {
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            CodecSendError::Encode(_) => ErrorKind::Codec,
+            CodecSendError::Disconnected => ErrorKind::Disconnected,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl CodecRecvError {
+    /// Returns the [`ErrorKind`] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            CodecRecvError::Decode(_) => ErrorKind::Codec,
+            CodecRecvError::Disconnected => ErrorKind::Disconnected,
+        }
+    }
+}
+
+/// Lets `?` turn the error of a blocking send into the error of a `try_send`.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::error::{SendError, TrySendError};
+///
+/// fn forward(item: u32) -> Result<(), TrySendError<u32>> {
+///     Err(SendError(item))?
+/// }
+/// assert_eq!(forward(1), Err(TrySendError::Disconnected(1)));
+/// ```
+impl<T> From<SendError<T>> for TrySendError<T> {
+    fn from(err: SendError<T>) -> Self {
+        TrySendError::Disconnected(err.0)
+    }
+}
+
+impl<T> From<SendError<T>> for SendTimeoutError<T> {
+    fn from(err: SendError<T>) -> Self {
+        SendTimeoutError::Disconnected(err.0)
+    }
+}
+
+impl<T> From<SendError<T>> for TrySendAllocError<T> {
+    fn from(err: SendError<T>) -> Self {
+        TrySendAllocError::Disconnected(err.0)
+    }
+}
+
+impl From<RecvError> for TryRecvError {
+    fn from(_: RecvError) -> Self {
+        TryRecvError::Disconnected
+    }
+}
+
+impl From<RecvError> for RecvTimeoutError {
+    fn from(_: RecvError) -> Self {
+        RecvTimeoutError::Disconnected
+    }
+}
+
+impl From<RecvError> for RecvCancelError {
+    fn from(_: RecvError) -> Self {
+        RecvCancelError::Disconnected
+    }
+}
+
+impl From<RecvError> for BroadcastTryRecvError {
+    fn from(_: RecvError) -> Self {
+        BroadcastTryRecvError::Disconnected
+    }
+}
+
+impl From<RecvError> for BroadcastRecvError {
+    fn from(_: RecvError) -> Self {
+        BroadcastRecvError::Disconnected
+    }
+}
+
 impl<T> Error for TrySendError<T> {}
 impl Error for TryRecvError {}
 impl<T> Error for SendError<T> {}
 impl Error for RecvError {}
+impl<T> Error for SendTimeoutError<T> {}
+impl Error for RecvTimeoutError {}
+impl Error for RecvCancelError {}
+impl<T> Error for TrySendAllocError<T> {}
+impl Error for AllocError {}
+impl Error for BroadcastTryRecvError {}
+impl Error for BroadcastRecvError {}
+#[cfg(feature = "serde")]
+impl Error for CodecSendError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodecSendError::Encode(e) => Some(e),
+            CodecSendError::Disconnected => None,
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl Error for CodecRecvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodecRecvError::Decode(e) => Some(e),
+            CodecRecvError::Disconnected => None,
+        }
+    }
+}
 
 impl<T> fmt::Display for TrySendError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
             TrySendError::Full(_) => f.write_str("writing to a full queue"),
             TrySendError::Disconnected(_) => f.write_str("writing to a disconnected queue"),
+            TrySendError::Poisoned(_) => f.write_str("writing to a poisoned queue"),
         }
     }
 }
@@ -75,6 +651,7 @@ impl fmt::Display for TryRecvError {
         match *self {
             TryRecvError::Empty => f.write_str("reading from an empty queue"),
             TryRecvError::Disconnected => f.write_str("reading from a disconnected queue"),
+            TryRecvError::Poisoned => f.write_str("reading from a poisoned queue"),
         }
     }
 }
@@ -91,11 +668,114 @@ impl fmt::Display for RecvError {
     }
 }
 
+impl<T> fmt::Display for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SendTimeoutError::Timeout(_) => f.write_str("timed out writing to a full queue"),
+            SendTimeoutError::Disconnected(_) => f.write_str("writing to a disconnected queue"),
+            SendTimeoutError::Poisoned(_) => f.write_str("writing to a poisoned queue"),
+        }
+    }
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RecvTimeoutError::Timeout => f.write_str("timed out reading from an empty queue"),
+            RecvTimeoutError::Disconnected => f.write_str("reading from a disconnected queue"),
+            RecvTimeoutError::Poisoned => f.write_str("reading from a poisoned queue"),
+        }
+    }
+}
+
+impl fmt::Display for RecvCancelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RecvCancelError::Cancelled => f.write_str("reading from a queue was cancelled"),
+            RecvCancelError::Disconnected => f.write_str("reading from a disconnected queue"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendAllocError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TrySendAllocError::OutOfMemory(_) => f.write_str("out of memory"),
+            TrySendAllocError::Full(_) => f.write_str("writing to a full queue"),
+            TrySendAllocError::Disconnected(_) => f.write_str("writing to a disconnected queue"),
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            ErrorKind::Full => "full",
+            ErrorKind::Empty => "empty",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::OutOfMemory => "out of memory",
+            ErrorKind::Lagged => "lagged",
+            ErrorKind::Codec => "codec",
+            ErrorKind::Disconnected => "disconnected",
+            ErrorKind::Poisoned => "poisoned",
+            ErrorKind::Cancelled => "cancelled",
+        })
+    }
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+impl fmt::Display for BroadcastTryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BroadcastTryRecvError::Empty => f.write_str("reading from an empty queue"),
+            BroadcastTryRecvError::Lagged(n) => write!(f, "receiver lagged behind by {n} values"),
+            BroadcastTryRecvError::Disconnected => {
+                f.write_str("reading from a disconnected queue")
+            }
+        }
+    }
+}
+
+impl fmt::Display for BroadcastRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BroadcastRecvError::Lagged(n) => write!(f, "receiver lagged behind by {n} values"),
+            BroadcastRecvError::Disconnected => f.write_str("reading from a disconnected queue"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for CodecSendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecSendError::Encode(e) => write!(f, "failed to encode a frame: {e}"),
+            CodecSendError::Disconnected => f.write_str("writing to a disconnected queue"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for CodecRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecRecvError::Decode(e) => write!(f, "failed to decode a frame: {e}"),
+            CodecRecvError::Disconnected => f.write_str("reading from a disconnected queue"),
+        }
+    }
+}
+
 impl<T> fmt::Debug for TrySendError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             TrySendError::Full(_) => "Full(..)".fmt(f),
             TrySendError::Disconnected(_) => "Disconnected(..)".fmt(f),
+            TrySendError::Poisoned(_) => "Poisoned(..)".fmt(f),
         }
     }
 }
@@ -105,3 +785,23 @@ impl<T> fmt::Debug for SendError<T> {
         f.write_str("SendError(..)")
     }
 }
+
+impl<T> fmt::Debug for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SendTimeoutError::Timeout(_) => "Timeout(..)".fmt(f),
+            SendTimeoutError::Disconnected(_) => "Disconnected(..)".fmt(f),
+            SendTimeoutError::Poisoned(_) => "Poisoned(..)".fmt(f),
+        }
+    }
+}
+
+impl<T> fmt::Debug for TrySendAllocError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TrySendAllocError::OutOfMemory(_) => "OutOfMemory(..)".fmt(f),
+            TrySendAllocError::Full(_) => "Full(..)".fmt(f),
+            TrySendAllocError::Disconnected(_) => "Disconnected(..)".fmt(f),
+        }
+    }
+}
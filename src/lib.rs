@@ -5,7 +5,22 @@
 macro_rules! has_any_feature {
     ($($item:item)*) => {
         $(
-            #[cfg(any(doc, feature = "spsc-bounded", feature = "spsc-unbounded"))]
+            #[cfg(any(
+                doc,
+                feature = "spsc-bounded",
+                feature = "spsc-unbounded",
+                feature = "spsc-unbounded-chunked",
+                feature = "spsc-signal",
+                feature = "spsc-swap",
+                feature = "spsc-coalescing",
+                feature = "spsc-arc",
+                feature = "oneshot",
+                feature = "watch",
+                feature = "broadcast",
+                feature = "unsync",
+                feature = "testing",
+                feature = "utils"
+            ))]
             $item
         )*
     }
@@ -33,23 +48,239 @@ macro_rules! cfg_not_loom {
     };
 }
 
+// metrics integration
+#[doc(hidden)]
+macro_rules! metrics {
+    ($($body:tt)*) => {
+        #[cfg(feature = "metrics")]
+        {
+            $($body)*
+        }
+    };
+}
+
 #[doc(hidden)]
 mod alloc;
 #[doc(hidden)]
 mod cell;
-#[doc(hidden)]
-mod sync;
+/// A module containing synchronization primitives the queues are built on.
+pub mod sync;
 #[doc(hidden)]
 mod thread;
 //loom integration finished.
 
 /// A module containing the error types used by the library.
+///
+/// Every flavor uses these types, the flavor modules only re-export them.
+/// Errors that can fail in several ways, like the
+/// [`TrySendAllocError`](error::TrySendAllocError) of an unbounded `try_send`,
+/// are enums with a variant per failure, and [`ErrorKind`](error::ErrorKind)
+/// tells them apart uniformly.
 pub mod error;
 
 /// A module containing flavors of Single Producer Single Consumer queues.
-#[cfg(any(doc, feature = "spsc-bounded", feature = "spsc-unbounded"))]
+#[cfg(any(
+    doc,
+    feature = "spsc-bounded",
+    feature = "spsc-unbounded",
+    feature = "spsc-unbounded-chunked",
+    feature = "spsc-swap",
+    feature = "spsc-coalescing",
+    feature = "spsc-arc"
+))]
 pub mod spsc;
 
+/// A module containing flavors of Multi Producer Single Consumer queues.
+#[cfg(any(doc, feature = "mpsc-sharded"))]
+pub mod mpsc;
+
+/// A channel for sending a single value between threads.
+/// Enabled by the `oneshot` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::oneshot;
+/// use std::thread;
+///
+/// let (src, sink) = oneshot::channel::<u32>();
+/// thread::spawn(move || src.send(42).unwrap());
+/// assert_eq!(sink.recv(), Ok(42));
+/// ```
+#[cfg(any(doc, feature = "oneshot"))]
+pub mod oneshot;
+
+/// A channel that only keeps the latest sent value.
+/// Enabled by the `watch` feature.
+///
+/// Sending never blocks and replaces the value that wasn't received yet,
+/// so the receiver always gets the most recent one. This fits propagating
+/// state, like configuration, where intermediate values don't matter.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::watch;
+///
+/// let (src, sink) = watch::channel::<u32>();
+/// src.send(1).unwrap();
+/// src.send(2).unwrap();
+/// assert_eq!(sink.recv(), Ok(2));
+///
+/// std::thread::spawn(move || src.send(3).unwrap());
+/// assert_eq!(sink.recv(), Ok(3));
+/// ```
+#[cfg(any(doc, feature = "watch"))]
+pub mod watch;
+
+/// A bounded Single Producer Multi Consumer channel,
+/// where every receiver sees every sent value.
+/// Enabled by the `broadcast` feature.
+///
+/// The sender never blocks: when the channel is full, it overwrites the
+/// oldest value, and receivers that didn't see it yet get a `Lagged` error.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::broadcast;
+/// use std::thread;
+///
+/// let (src, sink) = broadcast::channel::<u32>(8);
+/// let other = sink.clone();
+/// src.send(42).unwrap();
+/// thread::spawn(move || assert_eq!(other.recv(), Ok(42)));
+/// assert_eq!(sink.recv(), Ok(42));
+/// ```
+#[cfg(any(doc, feature = "broadcast"))]
+pub mod broadcast;
+
+/// A typed channel that sends owned values as serialized frames.
+/// Enabled by the `serde` feature.
+///
+/// Values are serialized to JSON and written to a [`spsc::bytes`] stream,
+/// each prefixed with its length, so any `Serialize` type can be exchanged
+/// without manual framing.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::codec;
+/// use std::thread;
+///
+/// let (mut src, mut sink) = codec::channel::<(String, u32)>(64);
+/// thread::spawn(move || src.send(&("answer".to_string(), 42)).unwrap());
+/// assert_eq!(sink.recv().unwrap(), ("answer".to_string(), 42));
+/// ```
+#[cfg(feature = "serde")]
+pub mod codec;
+
+/// A module containing queues for end points on the same thread.
+#[cfg(any(doc, feature = "unsync"))]
+pub mod unsync;
+
+/// A mock channel for unit testing code that handles full, empty or disconnected channels.
+/// Enabled by the `testing` feature.
+///
+/// The end points implement the same [`traits`] as the real ones, but every
+/// call takes the next step of a script, which makes it fail with a
+/// chosen error or work like it would on an unbounded channel.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::error::TryRecvError;
+/// use concurrent_qs::mock::{self, RecvStep};
+/// use concurrent_qs::traits::TryRecv;
+///
+/// fn poll(sink: &impl TryRecv<u32>) -> Option<u32> {
+///     match sink.try_recv() {
+///         Ok(value) => Some(value),
+///         Err(TryRecvError::Empty) => None,
+///         Err(_) => panic!("the sender is gone"),
+///     }
+/// }
+///
+/// let (src, sink) = mock::channel::<u32>();
+/// src.send(1).unwrap();
+/// sink.script([RecvStep::Empty]);
+/// assert_eq!(poll(&sink), None);
+/// assert_eq!(poll(&sink), Some(1));
+/// ```
+#[cfg(any(doc, feature = "testing"))]
+pub mod mock;
+
+/// A module containing traits for writing code that's generic over the queue flavor.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::{bounded, unbounded};
+/// use concurrent_qs::traits::{BlockingRecv, BlockingSend};
+///
+/// fn pipe<T>(src: &impl BlockingSend<T>, sink: &impl BlockingRecv<T>, item: T) -> T {
+///     src.send(item).ok().unwrap();
+///     sink.recv().unwrap()
+/// }
+///
+/// let (src, sink) = bounded::channel::<u32>(4);
+/// assert_eq!(pipe(&src, &sink, 1), 1);
+/// let (src, sink) = unbounded::channel::<u32>();
+/// assert_eq!(pipe(&src, &sink, 2), 2);
+/// ```
+pub mod traits;
+
+mod pipe;
+pub use pipe::{pipe, spawn_pipe};
+
+/// A module containing the counters collected by the `metrics` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+///
+/// let (src, sink) = bounded::channel_exact::<u32>(1);
+/// src.send(1).unwrap();
+/// assert!(src.try_send(2).is_err());
+/// assert_eq!(sink.recv(), Ok(1));
+///
+/// let stats = sink.stats();
+/// assert_eq!((stats.sent, stats.received, stats.full), (1, 1, 1));
+/// ```
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+/// A module for waiting on multiple receivers at once.
+pub mod select;
+
+/// A module for waiting on receivers inside of `epoll`-style event loops.
+/// Enabled by the `fd-notify` feature on unix platforms.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+///
+/// let (src, sink) = bounded::channel::<u32>(4);
+/// let fd = sink.notify_fd().unwrap();
+/// // register `fd` with the event loop, which wakes up when it's readable.
+/// src.send(1).unwrap();
+///
+/// fd.clear();
+/// while let Ok(value) = sink.try_recv() {
+///     assert_eq!(value, 1);
+/// }
+/// ```
+#[cfg(all(unix, any(doc, feature = "fd-notify")))]
+pub mod notify_fd;
+
+/// A module containing building blocks for lock-free data structures,
+/// which the queues are built on.
+/// Enabled by the `utils` feature.
+#[cfg(any(doc, feature = "utils"))]
+pub mod utils;
+
 mod util;
 
 }
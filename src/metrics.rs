@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+use std::time::{Duration, Instant};
+
+/// A snapshot of a channel's counters, returned by the
+/// `stats` methods of its end points.
+///
+/// Counters are updated without synchronizing with each other,
+/// so a snapshot taken while the channel is in use can be slightly
+/// inconsistent, e.g. show more received than sent values.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Stats {
+    /// How many values were sent.
+    pub sent: u64,
+    /// How many values were received.
+    pub received: u64,
+    /// How many times the sender found the channel full.
+    pub full: u64,
+    /// How many times the receiver found the channel empty.
+    pub empty: u64,
+    /// How many times the sender went to sleep waiting for the receiver.
+    pub sender_parks: u64,
+    /// How many times the receiver went to sleep waiting for the sender.
+    pub receiver_parks: u64,
+    /// The most values that were pending at once.
+    ///
+    /// Only sampled when one end point checks the index of the other,
+    /// so short peaks between checks can be missed.
+    pub max_depth: u64,
+    /// How long the sender slept waiting for the receiver.
+    pub sender_park_time: Duration,
+    /// How long the receiver slept waiting for the sender.
+    pub receiver_park_time: Duration,
+}
+
+/// A counter that's only modified by one thread, but can be read by any.
+///
+/// Always uses std atomics, so it doesn't add states to loom models.
+pub(crate) struct Counter(AtomicU64);
+
+impl Counter {
+    pub(crate) const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    /// Adds `n` to the counter. Can't be called concurrently.
+    #[inline(always)]
+    pub(crate) fn add(&self, n: u64) {
+        self.0.store(self.0.load(Relaxed).wrapping_add(n), Relaxed);
+    }
+
+    /// Subtracts `n` from the counter. Can't be called concurrently.
+    #[allow(dead_code)]
+    #[inline(always)]
+    pub(crate) fn sub(&self, n: u64) {
+        self.0.store(self.0.load(Relaxed).wrapping_sub(n), Relaxed);
+    }
+
+    /// Raises the counter to `n` if it's lower. Can't be called concurrently.
+    #[allow(dead_code)]
+    #[inline(always)]
+    pub(crate) fn max(&self, n: u64) {
+        if n > self.0.load(Relaxed) {
+            self.0.store(n, Relaxed);
+        }
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn get(&self) -> u64 {
+        self.0.load(Relaxed)
+    }
+
+    /// Returns a guard that adds the nanoseconds until it's dropped to the counter.
+    #[allow(dead_code)]
+    pub(crate) fn time(&self) -> Timer<'_> {
+        Timer(self, Instant::now())
+    }
+
+    /// Reads a counter of nanoseconds, see [`time`](Counter::time).
+    #[allow(dead_code)]
+    pub(crate) fn get_duration(&self) -> Duration {
+        Duration::from_nanos(self.get())
+    }
+}
+
+/// Times a scope, see [`Counter::time`].
+pub(crate) struct Timer<'a>(&'a Counter, Instant);
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        let nanos = self.1.elapsed().as_nanos();
+        self.0.add(nanos.try_into().unwrap_or(u64::MAX));
+    }
+}
@@ -0,0 +1,348 @@
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// Creates a mock channel with unbounded capacity and empty scripts.
+///
+/// Until steps are scripted with [`Sender::script`] and [`Receiver::script`],
+/// the end points work like the ones of an unbounded **spsc** channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Inner {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            send_script: VecDeque::new(),
+            recv_script: VecDeque::new(),
+            sender_connected: true,
+            receiver_connected: true,
+        }),
+        cond: Condvar::new(),
+    });
+    (Sender(inner.clone()), Receiver(inner))
+}
+
+/// The outcome of a scripted call to a [`Sender`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SendStep {
+    /// The call works like it would on a real channel.
+    Pass,
+    /// [`try_send`](Sender::try_send) fails with [`TrySendError::Full`].
+    ///
+    /// [`send`](Sender::send) skips this step, like it would wait for room.
+    Full,
+    /// The call fails as if the [`Receiver`] disconnected.
+    Disconnected,
+    /// The call fails as if the [`Receiver`] panicked on a poisoning channel.
+    ///
+    /// [`send`](Sender::send) fails with a [`SendError`].
+    Poisoned,
+}
+
+/// The outcome of a scripted call to a [`Receiver`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RecvStep {
+    /// The call works like it would on a real channel.
+    Pass,
+    /// [`try_recv`](Receiver::try_recv) fails with [`TryRecvError::Empty`],
+    /// even if there are pending values.
+    ///
+    /// [`recv`](Receiver::recv) skips this step, like it would wait for a value.
+    Empty,
+    /// The call fails as if the [`Sender`] disconnected
+    /// and all sent values were received.
+    Disconnected,
+    /// The call fails as if the [`Sender`] panicked on a poisoning channel.
+    ///
+    /// [`recv`](Receiver::recv) fails with a [`RecvError`].
+    Poisoned,
+}
+
+/// The sending endpoint of a mock [`channel`].
+///
+/// Every call takes the next step of its script, so
+/// its outcome can be set up in advance with [`script`](Sender::script).
+pub struct Sender<T>(Arc<Inner<T>>);
+
+/// The receiving endpoint of a mock [`channel`].
+///
+/// Every call takes the next step of its script, so
+/// its outcome can be set up in advance with [`script`](Receiver::script).
+pub struct Receiver<T>(Arc<Inner<T>>);
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+    /// Notified when a value is sent or an end point disconnects.
+    cond: Condvar,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    send_script: VecDeque<SendStep>,
+    recv_script: VecDeque<RecvStep>,
+    sender_connected: bool,
+    receiver_connected: bool,
+}
+
+impl<T> Inner<T> {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        // the state is consistent after every call, so a panic doesn't matter.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<T> Sender<T> {
+    /// Appends `steps` to the script of this [`Sender`].
+    ///
+    /// Once the script runs out, every call takes [`SendStep::Pass`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::error::TrySendError;
+    /// use concurrent_qs::mock::{self, SendStep};
+    ///
+    /// let (src, sink) = mock::channel::<u32>();
+    /// src.script([SendStep::Full, SendStep::Pass, SendStep::Disconnected]);
+    /// assert_eq!(src.try_send(1), Err(TrySendError::Full(1)));
+    /// assert_eq!(src.try_send(1), Ok(()));
+    /// assert_eq!(src.try_send(2), Err(TrySendError::Disconnected(2)));
+    /// assert_eq!(src.try_send(2), Ok(()));
+    /// assert_eq!(sink.try_iter().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn script(&self, steps: impl IntoIterator<Item = SendStep>) {
+        self.0.lock().send_script.extend(steps);
+    }
+
+    /// Returns the number of steps left in the script of this [`Sender`].
+    pub fn script_len(&self) -> usize {
+        self.0.lock().send_script.len()
+    }
+
+    /// Sends a value through this [`channel`] unless the
+    /// next step of the script makes it fail.
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.0.lock();
+        match state.send_script.pop_front().unwrap_or(SendStep::Pass) {
+            SendStep::Pass if !state.receiver_connected => Err(TrySendError::Disconnected(item)),
+            SendStep::Pass => {
+                state.queue.push_back(item);
+                self.0.cond.notify_all();
+                Ok(())
+            }
+            SendStep::Full => Err(TrySendError::Full(item)),
+            SendStep::Disconnected => Err(TrySendError::Disconnected(item)),
+            SendStep::Poisoned => Err(TrySendError::Poisoned(item)),
+        }
+    }
+
+    /// Sends a value through this [`channel`] unless the
+    /// next step of the script makes it fail.
+    ///
+    /// Skips the [`SendStep::Full`] steps at the front of the script.
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        let mut state = self.0.lock();
+        loop {
+            match state.send_script.pop_front().unwrap_or(SendStep::Pass) {
+                SendStep::Pass if !state.receiver_connected => break Err(SendError(item)),
+                SendStep::Pass => {
+                    state.queue.push_back(item);
+                    self.0.cond.notify_all();
+                    break Ok(());
+                }
+                SendStep::Full => continue,
+                SendStep::Disconnected | SendStep::Poisoned => break Err(SendError(item)),
+            }
+        }
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    ///
+    /// This ignores the script.
+    pub fn receiver_connected(&self) -> bool {
+        self.0.lock().receiver_connected
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Appends `steps` to the script of this [`Receiver`].
+    ///
+    /// Once the script runs out, every call takes [`RecvStep::Pass`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::error::TryRecvError;
+    /// use concurrent_qs::mock::{self, RecvStep};
+    ///
+    /// let (src, sink) = mock::channel::<u32>();
+    /// src.send(1).unwrap();
+    /// sink.script([RecvStep::Empty, RecvStep::Poisoned]);
+    /// assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    /// assert_eq!(sink.try_recv(), Err(TryRecvError::Poisoned));
+    /// assert_eq!(sink.try_recv(), Ok(1));
+    /// ```
+    pub fn script(&self, steps: impl IntoIterator<Item = RecvStep>) {
+        self.0.lock().recv_script.extend(steps);
+    }
+
+    /// Returns the number of steps left in the script of this [`Receiver`].
+    pub fn script_len(&self) -> usize {
+        self.0.lock().recv_script.len()
+    }
+
+    /// Tries to return a pending value unless the
+    /// next step of the script makes it fail.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.0.lock();
+        match state.recv_script.pop_front().unwrap_or(RecvStep::Pass) {
+            RecvStep::Pass => match state.queue.pop_front() {
+                Some(item) => Ok(item),
+                None if state.sender_connected => Err(TryRecvError::Empty),
+                None => Err(TryRecvError::Disconnected),
+            },
+            RecvStep::Empty => Err(TryRecvError::Empty),
+            RecvStep::Disconnected => Err(TryRecvError::Disconnected),
+            RecvStep::Poisoned => Err(TryRecvError::Poisoned),
+        }
+    }
+
+    /// Receives a value, waiting for one if the [`channel`] is
+    /// empty, unless the next step of the script makes it fail.
+    ///
+    /// Skips the [`RecvStep::Empty`] steps at the front of the script.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let mut state = self.0.lock();
+        loop {
+            match state.recv_script.pop_front().unwrap_or(RecvStep::Pass) {
+                RecvStep::Pass => break,
+                RecvStep::Empty => continue,
+                RecvStep::Disconnected | RecvStep::Poisoned => return Err(RecvError {}),
+            }
+        }
+        loop {
+            match state.queue.pop_front() {
+                Some(item) => return Ok(item),
+                None if !state.sender_connected => return Err(RecvError {}),
+                None => {
+                    state = self
+                        .0
+                        .cond
+                        .wait(state)
+                        .unwrap_or_else(|err| err.into_inner())
+                }
+            }
+        }
+    }
+
+    /// Returns the number of pending values.
+    ///
+    /// This ignores the script.
+    pub fn len(&self) -> usize {
+        self.0.lock().queue.len()
+    }
+
+    /// Checks if there are no pending values.
+    ///
+    /// This ignores the script.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// This ignores the script.
+    pub fn sender_connected(&self) -> bool {
+        self.0.lock().sender_connected
+    }
+
+    /// Returns an iterator over the currently pending values.
+    ///
+    /// The iterator ends when [`try_recv`](Receiver::try_recv) fails.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+}
+
+/// An iterator over the pending values of a mock [`channel`].
+///
+/// Created by [`Receiver::try_iter`].
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.0.lock().sender_connected = false;
+        self.0.cond.notify_all();
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.0.lock().receiver_connected = false;
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mock::Sender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.0
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mock::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.0
+        )
+    }
+}
+
+impl<T> crate::traits::TrySend<T> for Sender<T> {
+    type Error = TrySendError<T>;
+
+    #[inline]
+    fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        Sender::try_send(self, item)
+    }
+}
+
+impl<T> crate::traits::BlockingSend<T> for Sender<T> {
+    #[inline]
+    fn send(&self, item: T) -> Result<(), SendError<T>> {
+        Sender::send(self, item)
+    }
+}
+
+impl<T> crate::traits::TryRecv<T> for Receiver<T> {
+    #[inline]
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        Receiver::try_recv(self)
+    }
+}
+
+impl<T> crate::traits::BlockingRecv<T> for Receiver<T> {
+    #[inline]
+    fn recv(&self) -> Result<T, RecvError> {
+        Receiver::recv(self)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,68 @@
+use super::*;
+use crate::traits::{BlockingRecv, TrySend};
+
+cfg_not_loom! {
+
+/// Retries `try_send` until it works, like code handling a full channel would.
+fn send_retrying<S: TrySend<u32, Error = TrySendError<u32>>>(src: &S, mut item: u32) -> usize {
+    let mut retries = 0;
+    loop {
+        item = match src.try_send(item) {
+            Ok(()) => return retries,
+            Err(TrySendError::Full(item)) => item,
+            Err(_) => panic!("unexpected error"),
+        };
+        retries += 1;
+    }
+}
+
+#[test]
+fn scripted_send() {
+    let (src, sink) = channel::<u32>();
+    src.script([SendStep::Full, SendStep::Full, SendStep::Pass, SendStep::Full]);
+    assert_eq!(send_retrying(&src, 1), 2);
+    assert_eq!(src.script_len(), 1);
+    assert_eq!(src.send(2), Ok(()));
+    src.script([SendStep::Poisoned, SendStep::Disconnected]);
+    assert_eq!(src.try_send(3), Err(TrySendError::Poisoned(3)));
+    assert_eq!(src.send(3), Err(SendError(3)));
+    assert_eq!(sink.try_iter().collect::<Vec<_>>(), [1, 2]);
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.try_send(4), Err(TrySendError::Disconnected(4)));
+}
+
+#[test]
+fn scripted_recv() {
+    let (src, sink) = channel::<u32>();
+    src.send(1).unwrap();
+    sink.script([RecvStep::Empty, RecvStep::Pass, RecvStep::Disconnected]);
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(sink.len(), 1);
+    assert_eq!(sink.try_recv(), Ok(1));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    sink.script([RecvStep::Empty, RecvStep::Empty, RecvStep::Poisoned]);
+    assert_eq!(BlockingRecv::recv(&sink), Err(RecvError {}));
+    assert_eq!(sink.script_len(), 0);
+    drop(src);
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn mt_recv() {
+    let (src, sink) = channel::<u32>();
+    sink.script([RecvStep::Empty]);
+    let handle = std::thread::spawn(move || {
+        for i in 0..100 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..100 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    handle.join().unwrap();
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+}
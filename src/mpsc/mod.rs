@@ -0,0 +1,38 @@
+/// A bounded Multi Producer Single Consumer queue built from SPSC rings.
+/// Enabled by the `mpsc-sharded` feature.
+///
+/// Every [`Sender`](sharded::Sender) owns a [`spsc::bounded`](crate::spsc::bounded)
+/// ring, so producers never contend with each other. Cloning a
+/// [`Sender`](sharded::Sender) creates a new ring, which the
+/// [`Receiver`](sharded::Receiver) picks up and polls in round-robin order.
+/// Values from the same [`Sender`](sharded::Sender) are received in order,
+/// but values from different ones can be interleaved arbitrarily.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::mpsc::sharded;
+/// use std::thread;
+///
+/// fn main() {
+///     let (src, sink) = sharded::channel::<u32>(16);
+///
+///     for id in 0..4 {
+///         let src = src.clone();
+///         thread::spawn(move || {
+///             for i in 0..100 {
+///                 src.send(id * 100 + i).unwrap();
+///             }
+///         });
+///     }
+///     drop(src);
+///
+///     let mut sum = 0;
+///     while let Ok(i) = sink.recv() {
+///         sum += i;
+///     }
+///     assert_eq!(sum, (0..400).sum::<u32>());
+/// }
+/// ```
+#[cfg(any(doc, feature = "mpsc-sharded"))]
+pub mod sharded;
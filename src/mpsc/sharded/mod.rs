@@ -0,0 +1,320 @@
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::select::sealed::{Sealed, Waker};
+use crate::spsc::bounded;
+use crate::sync::atomic::AtomicBool;
+use crate::sync::atomic::Ordering::{Acquire, Release};
+use crate::sync::{Arc, Mutex, MutexGuard};
+use crate::util::park::Parker;
+
+use std::cell::{Cell, RefCell};
+
+/// Creates a sharded MPSC channel, where every [`Sender`] has
+/// storage for at least `capacity_per_sender` elements.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+pub fn channel<T>(capacity_per_sender: usize) -> (Sender<T>, Receiver<T>) {
+    let (ring_tx, ring_rx) = bounded::channel(capacity_per_sender);
+    let shared = Arc::new(Shared {
+        capacity: capacity_per_sender,
+        new_rings: Mutex::new(NewRings {
+            rings: Vec::new(),
+            closed: false,
+        }),
+        has_new_rings: AtomicBool::new(false),
+        waker: Waker(Arc::new(Parker::new())),
+    });
+    (
+        Sender {
+            ring: ring_tx,
+            shared: shared.clone(),
+        },
+        Receiver {
+            rings: RefCell::new(vec![ring_rx]),
+            next: Cell::new(0),
+            shared,
+        },
+    )
+}
+
+/// The sending endpoint of a [`channel`].
+///
+/// Every [`Sender`] sends through its own ring, so a
+/// [`clone`](Clone::clone) doesn't share capacity with the original.
+pub struct Sender<T> {
+    ring: bounded::Sender<T>,
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiving endpoint of a [`channel`].
+///
+/// Data can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<T> {
+    rings: RefCell<Vec<bounded::Receiver<T>>>,
+    /// The ring to poll first, for round-robin fairness.
+    next: Cell<usize>,
+    shared: Arc<Shared<T>>,
+}
+
+struct Shared<T> {
+    capacity: usize,
+    new_rings: Mutex<NewRings<T>>,
+    /// Set when `new_rings` isn't empty, so the receiver doesn't have to lock.
+    has_new_rings: AtomicBool,
+    /// Registered on every ring while the receiver blocks.
+    waker: Waker,
+}
+
+struct NewRings<T> {
+    /// Rings of cloned senders, not yet picked up by the receiver.
+    rings: Vec<bounded::Receiver<T>>,
+    /// Set when the receiver disconnects.
+    closed: bool,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, NewRings<T>> {
+        //the guarded state is always consistent, so we ignore poison.
+        match self.new_rings.lock() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Tries to send a value through this [`Sender`]'s ring.
+    ///
+    /// Returns [`TrySendError::Full`] if this [`Sender`]'s ring is full,
+    /// regardless of the other [`Sender`]s.
+    #[inline]
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        self.ring.try_send(item)
+    }
+
+    /// Sends a value through this [`Sender`]'s ring.
+    ///
+    /// If the ring is full, blocks and waits for the [`Receiver`].
+    /// Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.ring.send(item)
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.ring.receiver_connected()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    /// Creates a [`Sender`] with a new ring of the same capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it can't allocate the memory needed for the ring.
+    fn clone(&self) -> Self {
+        let (ring, ring_rx) = bounded::channel(self.shared.capacity);
+        let mut new_rings = self.shared.lock();
+        if new_rings.closed {
+            // the new Sender sees the disconnect right away.
+            drop(ring_rx);
+        } else {
+            new_rings.rings.push(ring_rx);
+            self.shared.has_new_rings.store(true, Release);
+        }
+        drop(new_rings);
+        self.shared.waker.0.unpark();
+        Self {
+            ring,
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to return a pending value from any [`Sender`].
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after all [`Sender`]s
+    /// disconnected and all sent data was received.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.collect_new_rings();
+        let mut rings = self.rings.borrow_mut();
+        let mut index = self.next.get();
+        let mut polled = 0;
+        while polled < rings.len() {
+            if index >= rings.len() {
+                index = 0;
+            }
+            match rings[index].try_recv() {
+                Ok(item) => {
+                    self.next.set(index + 1);
+                    return Ok(item);
+                }
+                Err(TryRecvError::Empty) => {
+                    index += 1;
+                    polled += 1;
+                }
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    // the next ring is moved to `index`, so it's polled next.
+                    rings.swap_remove(index);
+                }
+            }
+        }
+        self.next.set(index);
+
+        if !rings.is_empty() {
+            return Err(TryRecvError::Empty);
+        }
+        drop(rings);
+        /*
+        A Sender is cloned before the original can disconnect,
+        so seeing the disconnect means seeing `has_new_rings` as well.
+        */
+        if self.shared.has_new_rings.load(Acquire) {
+            self.try_recv()
+        } else {
+            Err(TryRecvError::Disconnected)
+        }
+    }
+
+    /// Reads a value from any [`Sender`].
+    ///
+    /// If the [`channel`] is empty, blocks and waits for the [`Sender`]s.
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after all [`Sender`]s
+    /// disconnected and all sent data was received.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let waker = &self.shared.waker;
+        loop {
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+
+            let rings = self.rings.borrow();
+            let mut notified = false;
+            for ring in rings.iter() {
+                //SAFETY: unregistered below.
+                notified |= unsafe { ring.register(waker.clone()) };
+            }
+            // data sent before registering doesn't unpark `waker`.
+            if !notified && !self.is_ready(&rings) {
+                //SAFETY: only the Receiver parks `waker` and it's !Sync.
+                unsafe { waker.0.park() };
+            }
+            for ring in rings.iter() {
+                //SAFETY: registered above.
+                unsafe { ring.unregister() };
+            }
+        }
+    }
+
+    /// Checks if the [`channel`] has any connected [`Sender`]s.
+    ///
+    /// # Note
+    ///
+    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
+    /// methods return [`TryRecvError::Disconnected`] or [`RecvError`] only
+    /// after consuming all previously sent data. This method doesn't take
+    /// pending data into account.
+    pub fn sender_connected(&self) -> bool {
+        self.collect_new_rings();
+        self.rings.borrow().iter().any(|ring| ring.sender_connected())
+    }
+
+    /// Returns the number of rings, which is the number of
+    /// [`Sender`]s, not counting ones that disconnected and
+    /// whose data was already received.
+    pub fn senders(&self) -> usize {
+        self.collect_new_rings();
+        self.rings.borrow().len()
+    }
+
+    fn is_ready(&self, rings: &[bounded::Receiver<T>]) -> bool {
+        self.shared.has_new_rings.load(Acquire) || rings.iter().any(|ring| ring.is_ready())
+    }
+
+    /// Moves the rings of new [`Sender`]s to `rings`.
+    fn collect_new_rings(&self) {
+        if self.shared.has_new_rings.load(Acquire) {
+            let mut new_rings = self.shared.lock();
+            self.shared.has_new_rings.store(false, Release);
+            self.rings.borrow_mut().append(&mut new_rings.rings);
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut new_rings = self.shared.lock();
+        new_rings.closed = true;
+        new_rings.rings.clear();
+        // the rings in `self.rings` are dropped after this.
+    }
+}
+
+impl<T> crate::traits::TrySend<T> for Sender<T> {
+    type Error = TrySendError<T>;
+
+    #[inline]
+    fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        Sender::try_send(self, item)
+    }
+}
+
+impl<T> crate::traits::BlockingSend<T> for Sender<T> {
+    #[inline]
+    fn send(&self, item: T) -> Result<(), SendError<T>> {
+        Sender::send(self, item)
+    }
+}
+
+impl<T> crate::traits::TryRecv<T> for Receiver<T> {
+    #[inline]
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        Receiver::try_recv(self)
+    }
+}
+
+impl<T> crate::traits::BlockingRecv<T> for Receiver<T> {
+    #[inline]
+    fn recv(&self) -> Result<T, RecvError> {
+        Receiver::recv(self)
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mpsc::sharded::Sender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            Arc::as_ptr(&self.shared)
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mpsc::sharded::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            Arc::as_ptr(&self.shared)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests;
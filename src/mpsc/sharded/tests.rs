@@ -0,0 +1,107 @@
+use super::*;
+
+cfg_not_loom! {
+
+#[test]
+fn st_round_robin() {
+    let (src1, sink) = channel::<u32>(4);
+    let src2 = src1.clone();
+    assert_eq!(sink.senders(), 2);
+    for i in 0..3 {
+        src1.send(i).unwrap();
+        src2.send(10 + i).unwrap();
+    }
+    let mut received = std::iter::from_fn(|| sink.try_recv().ok()).collect::<Vec<_>>();
+    received.sort();
+    assert_eq!(received, [0, 1, 2, 10, 11, 12]);
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+
+    drop(src1);
+    assert!(sink.sender_connected());
+    src2.send(5).unwrap();
+    drop(src2);
+    assert_eq!(sink.try_recv(), Ok(5));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(sink.senders(), 0);
+}
+
+#[test]
+fn st_capacity_per_sender() {
+    let (src1, _sink) = channel::<u32>(1);
+    let src2 = src1.clone();
+    assert_eq!(src1.try_send(1), Ok(()));
+    assert_eq!(src1.try_send(2), Err(TrySendError::Full(2)));
+    assert_eq!(src2.try_send(3), Ok(()));
+}
+
+#[test]
+fn receiver_disconnect() {
+    let (src1, sink) = channel::<u32>(1);
+    drop(sink);
+    assert!(!src1.receiver_connected());
+    let src2 = src1.clone();
+    assert!(!src2.receiver_connected());
+    assert_eq!(src2.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn mt_many_senders() {
+    let (src, sink) = channel::<u32>(8);
+    for id in 0..4 {
+        let src = src.clone();
+        std::thread::spawn(move || {
+            for i in 0..1000 {
+                src.send(id * 1000 + i).unwrap();
+            }
+        });
+    }
+    drop(src);
+    let mut last = [None; 4];
+    let mut count = 0;
+    while let Ok(v) = sink.recv() {
+        let id = (v / 1000) as usize;
+        if let Some(prev) = last[id] {
+            assert!(prev < v, "out of order");
+        }
+        last[id] = Some(v);
+        count += 1;
+    }
+    assert_eq!(count, 4000);
+}
+
+#[test]
+fn mt_clone_while_blocked() {
+    let (src, sink) = channel::<u32>(1);
+    let handle = std::thread::spawn(move || sink.recv());
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    let src2 = src.clone();
+    drop(src);
+    src2.send(7).unwrap();
+    assert_eq!(handle.join().unwrap(), Ok(7));
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn clone_and_recv() {
+    let mut model = loom::model::Builder::new();
+    model.max_threads = 3;
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (src, sink) = channel::<u8>(1);
+        loom::thread::spawn(move || {
+            let src2 = src.clone();
+            src.send(1).unwrap();
+            drop(src);
+            src2.send(2).unwrap();
+        });
+        let mut received = [sink.recv().unwrap(), sink.recv().unwrap()];
+        received.sort();
+        assert_eq!(received, [1, 2]);
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    });
+}
+
+}
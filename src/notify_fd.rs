@@ -0,0 +1,73 @@
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::os::unix::net::UnixStream;
+
+/// A file descriptor that becomes readable when a `Receiver` is notified.
+///
+/// Created by the `notify_fd` method of a `Receiver`, which makes every send
+/// and disconnect of the `Sender` write to the other end of a socket pair.
+/// The descriptor can be registered with `epoll`, `kqueue` or `mio` to wait
+/// for the channel inside an event loop.
+///
+/// The descriptor stays readable until [`clear`](NotifyFd::clear) is called,
+/// so an event loop should call it before receiving the pending values.
+///
+/// The available `Receiver`s are:
+/// - [spsc::bounded::Receiver](crate::spsc::bounded::Receiver)
+/// - [spsc::unbounded::Receiver](crate::spsc::unbounded::Receiver)
+/// - [spsc::unbounded_chunked::Receiver](crate::spsc::unbounded_chunked::Receiver)
+pub struct NotifyFd {
+    reader: UnixStream,
+}
+
+impl NotifyFd {
+    /// Creates a [`NotifyFd`] and the callback that makes it readable.
+    #[allow(dead_code)]
+    pub(crate) fn new() -> io::Result<(Self, impl Fn() + Send + Sync + 'static)> {
+        let (reader, writer) = UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        writer.set_nonblocking(true)?;
+        let notify = move || {
+            /* WouldBlock means the socket buffer is full, so the
+             * descriptor is readable anyway, and other errors mean
+             * that the NotifyFd was dropped.
+             */
+            let _ = (&writer).write(&[1]);
+        };
+        Ok((Self { reader }, notify))
+    }
+
+    /// Consumes all pending notifications, making the descriptor unreadable.
+    ///
+    /// Values sent after this call will make the descriptor readable again,
+    /// so calling this before receiving doesn't miss any notifications.
+    pub fn clear(&self) {
+        let mut buf = [0; 64];
+        loop {
+            match (&self.reader).read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl AsRawFd for NotifyFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.reader.as_raw_fd()
+    }
+}
+
+impl AsFd for NotifyFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.reader.as_fd()
+    }
+}
+
+impl std::fmt::Debug for NotifyFd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NotifyFd {{ fd: {} }}", self.as_raw_fd())
+    }
+}
@@ -0,0 +1,141 @@
+use crate::alloc::Layout;
+use crate::cell::UnsafeCell;
+use crate::error::{RecvError, SendError, TryRecvError};
+use crate::sync::atomic::AtomicUsize;
+use crate::sync::Arc;
+use crate::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+use crate::util::park::Parker;
+use std::mem::MaybeUninit;
+
+const EMPTY: usize = 0;
+const FULL: usize = 1;
+const TAKEN: usize = 2;
+
+pub(super) struct Inner<T> {
+    state: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+    park: Parker,
+    /*
+    starts off as 0, incremented when entering Sender/Receiver drop.
+    match 'previous value' {
+        0 => {
+            Now the channel is disconnected. We try to wake the other end point.
+            If the other end point was asleep, it will detect the disconnect and unblock.
+            Then, we increment 'drop_count' again and repeat this decision tree with the
+            new 'previous value'.
+        }
+        1 => just fall off drop.
+        2 => deallocate the inner state.
+    }
+    */
+    pub(super) drop_count: AtomicUsize,
+}
+
+impl<T> Inner<T> {
+    pub(super) const LAYOUT: Layout = Layout::new::<Inner<T>>();
+
+    pub(super) fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(EMPTY),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            park: Parker::new(),
+            drop_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Can only be called once, by the sender.
+    pub(super) fn send(&self, item: T) -> Result<(), SendError<T>> {
+        if self.drop_count.load(Relaxed) != 0 {
+            return Err(SendError(item));
+        }
+        /*SAFETY:
+         *the receiver doesn't read value before state is FULL,
+         *and this is the only write.
+         */
+        self.value.with_mut(|ptr| unsafe { (ptr as *mut T).write(item) });
+        self.state.store(FULL, Release);
+        self.wake_receiver();
+        Ok(())
+    }
+
+    pub(super) fn try_recv(&self) -> Result<T, TryRecvError> {
+        match self.state.load(Acquire) {
+            FULL => {}
+            TAKEN => return Err(TryRecvError::Disconnected),
+            _ => {
+                if self.drop_count.load(Acquire) == 0 {
+                    return Err(TryRecvError::Empty);
+                }
+                // the sender might have sent the value before disconnecting.
+                if self.state.load(Acquire) != FULL {
+                    return Err(TryRecvError::Disconnected);
+                }
+            }
+        }
+        /*SAFETY:
+         *state is FULL, so value was initialised
+         *and only the receiver can take it out.
+         */
+        let item = self.value.with_mut(|ptr| unsafe { (ptr as *mut T).read() });
+        self.state.store(TAKEN, Relaxed);
+        Ok(item)
+    }
+
+    pub(super) fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                Err(TryRecvError::Empty) => unsafe { self.park.park() },
+            }
+        }
+    }
+
+    pub(super) fn peer_connected(&self) -> bool {
+        self.drop_count.load(Acquire) == 0
+    }
+
+    /// Checks if `try_recv` would return something other than [`TryRecvError::Empty`].
+    pub(super) fn is_ready(&self) -> bool {
+        self.state.load(Acquire) != EMPTY || self.drop_count.load(Acquire) != 0
+    }
+
+    /// Makes [`wake_receiver`](Inner::wake_receiver) also wake `waker`.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::register`.
+    pub(super) unsafe fn register_receiver(&self, waker: Arc<Parker>) -> bool {
+        self.park.register(waker)
+    }
+
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::unregister`.
+    pub(super) unsafe fn unregister_receiver(&self) {
+        self.park.unregister()
+    }
+
+    #[inline]
+    pub(super) fn wake_receiver(&self) {
+        self.park.unpark();
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        #[cfg(not(feature = "loom"))]
+        let state = *self.state.get_mut();
+        #[cfg(feature = "loom")]
+        let state = self.state.with_mut(|state| *state);
+
+        if state == FULL {
+            //SAFETY: FULL means the value was sent, but not received.
+            self.value
+                .with_mut(|ptr| unsafe { (ptr as *mut T).drop_in_place() });
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::*;
+cfg_not_loom! {
+
+#[test]
+fn st_send_recv() {
+    let (src, sink) = channel::<i32>();
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    src.send(1).unwrap();
+    assert_eq!(sink.try_recv(), Ok(1));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, sink) = channel::<i32>();
+    drop(src);
+    assert_eq!(sink.recv(), Err(RecvError {}));
+
+    let (src, sink) = channel::<i32>();
+    drop(sink);
+    assert_eq!(src.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn drop_unreceived() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let (src, sink) = channel();
+    src.send(rc.clone()).unwrap();
+    drop(sink);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn mt_send_recv() {
+    let (src, sink) = channel::<Box<str>>();
+    std::thread::spawn(move || src.send("Hello".into()).unwrap());
+    assert_eq!(sink.recv().as_deref(), Ok("Hello"));
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn send_recv() {
+    loom::model(|| {
+        let (src, sink) = channel::<i32>();
+        loom::thread::spawn(move || src.send(1).unwrap());
+        assert_eq!(sink.recv(), Ok(1));
+    });
+}
+
+#[test]
+fn sender_dc() {
+    loom::model(|| {
+        let (src, sink) = channel::<i32>();
+        loom::thread::spawn(move || drop(src));
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    });
+}
+
+#[test]
+fn receiver_dc() {
+    loom::model(|| {
+        use loom::sync::Arc;
+        let arc = Arc::new(());
+        {
+            let (src, sink) = channel();
+            let arc = arc.clone();
+            let handle = loom::thread::spawn(move || {
+                let _ = src.send(arc);
+            });
+            drop(sink);
+            handle.join().unwrap();
+        }
+        assert_eq!(Arc::strong_count(&arc), 1);
+    });
+}
+
+}
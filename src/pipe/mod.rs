@@ -0,0 +1,70 @@
+use crate::error::SendError;
+use crate::traits::{BlockingRecv, BlockingSend};
+use std::thread::JoinHandle;
+
+/// Forwards every value received from `sink` to `src`, until
+/// either side of the pipe disconnects.
+///
+/// This glues together two stages of a pipeline, which can use different
+/// kinds of channels. Returns how many values were forwarded once the
+/// sending side of `sink` disconnected and every value was received,
+/// or the value that couldn't be sent once the receiving side of `src`
+/// disconnected. Either way, both end points are dropped on return,
+/// so the disconnect reaches the rest of the pipeline.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::{bounded, unbounded};
+///
+/// let (src, sink) = bounded::channel::<u32>(4);
+/// let (out_src, out_sink) = unbounded::channel::<u32>();
+/// src.send(1).unwrap();
+/// src.send(2).unwrap();
+/// drop(src);
+/// assert_eq!(concurrent_qs::pipe(sink, out_src), Ok(2));
+/// assert_eq!(out_sink.iter().collect::<Vec<_>>(), [1, 2]);
+/// ```
+pub fn pipe<T, R, S>(sink: R, src: S) -> Result<usize, SendError<T>>
+where
+    R: BlockingRecv<T>,
+    S: BlockingSend<T>,
+{
+    let mut forwarded = 0;
+    while let Ok(item) = sink.recv() {
+        src.send(item)?;
+        forwarded += 1;
+    }
+    Ok(forwarded)
+}
+
+/// Runs [`pipe`] on a new thread.
+///
+/// The result of [`pipe`] is returned by joining the thread.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+///
+/// let (src, sink) = bounded::channel::<u32>(4);
+/// let (out_src, out_sink) = bounded::channel::<u32>(4);
+/// let handle = concurrent_qs::spawn_pipe(sink, out_src);
+/// for i in 0..10 {
+///     src.send(i).unwrap();
+///     assert_eq!(out_sink.recv(), Ok(i));
+/// }
+/// drop(src);
+/// assert_eq!(handle.join().unwrap(), Ok(10));
+/// ```
+pub fn spawn_pipe<T, R, S>(sink: R, src: S) -> JoinHandle<Result<usize, SendError<T>>>
+where
+    T: Send + 'static,
+    R: BlockingRecv<T> + Send + 'static,
+    S: BlockingSend<T> + Send + 'static,
+{
+    std::thread::spawn(move || pipe(sink, src))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,44 @@
+cfg_not_loom! {
+
+#[cfg(any(
+    feature = "spsc-bounded",
+    all(feature = "spsc-unbounded", feature = "spsc-unbounded-chunked")
+))]
+use super::*;
+
+#[test]
+#[cfg(feature = "spsc-bounded")]
+fn mt_pipe_receiver_disconnect() {
+    use crate::spsc::bounded;
+    let (src, sink) = bounded::channel::<u32>(4);
+    let (out_src, out_sink) = bounded::channel::<u32>(4);
+    let handle = spawn_pipe(sink, out_src);
+    for i in 0..3 {
+        src.send(i).unwrap();
+    }
+    assert_eq!(out_sink.recv(), Ok(0));
+    drop(out_sink);
+    // the disconnect travels upstream once the pipe fails to send.
+    while src.send(3).is_ok() {}
+    assert!(handle.join().unwrap().is_err());
+}
+
+#[test]
+#[cfg(all(feature = "spsc-unbounded", feature = "spsc-unbounded-chunked"))]
+fn mt_pipe_chain() {
+    use crate::spsc::{unbounded, unbounded_chunked};
+    let (src, sink) = unbounded::channel::<u32>();
+    let (mid_src, mid_sink) = unbounded_chunked::channel::<u32>();
+    let (out_src, out_sink) = unbounded::channel::<u32>();
+    let first = spawn_pipe(sink, mid_src);
+    let second = spawn_pipe(mid_sink, out_src);
+    for i in 0..1000 {
+        src.send(i).unwrap();
+    }
+    drop(src);
+    assert_eq!(out_sink.iter().collect::<Vec<_>>(), (0..1000).collect::<Vec<_>>());
+    assert_eq!(first.join().unwrap(), Ok(1000));
+    assert_eq!(second.join().unwrap(), Ok(1000));
+}
+
+}
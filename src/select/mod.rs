@@ -0,0 +1,144 @@
+use crate::sync::Arc;
+use crate::util::park::Parker;
+
+/// Waits on multiple [`Selectable`] receivers at once.
+///
+/// A receiver is ready when its `try_recv` method wouldn't return
+/// [`TryRecvError::Empty`](crate::error::TryRecvError::Empty), i.e.
+/// it either has pending data or its sender disconnected.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::select::Select;
+/// use concurrent_qs::spsc::{bounded, unbounded};
+/// use std::thread;
+///
+/// let (tx1, rx1) = bounded::channel::<i32>(4);
+/// let (tx2, rx2) = unbounded::channel::<&'static str>();
+///
+/// thread::spawn(move || tx2.send("Hello").unwrap());
+///
+/// let ready = Select::new().recv(&rx1).recv(&rx2).wait();
+/// assert_eq!(ready, 1);
+/// assert_eq!(rx2.recv(), Ok("Hello"));
+/// # drop(tx1);
+/// ```
+pub struct Select<'a> {
+    receivers: Vec<&'a dyn Selectable>,
+}
+
+/// A receiver that can be used with [`Select`].
+///
+/// This trait is sealed and implemented by:
+/// - [spsc::bounded::Receiver](crate::spsc::bounded::Receiver)
+/// - [spsc::unbounded::Receiver](crate::spsc::unbounded::Receiver)
+/// - [spsc::unbounded_chunked::Receiver](crate::spsc::unbounded_chunked::Receiver)
+/// - [spsc::signal::Receiver](crate::spsc::signal::Receiver)
+/// - [oneshot::Receiver](crate::oneshot::Receiver)
+/// - [watch::Receiver](crate::watch::Receiver)
+/// - [broadcast::Receiver](crate::broadcast::Receiver)
+pub trait Selectable: sealed::Sealed {}
+
+pub(crate) mod sealed {
+    use crate::sync::Arc;
+    use crate::util::park::Parker;
+
+    /// The handle woken by the senders of the selected receivers.
+    #[derive(Clone)]
+    pub struct Waker(pub(crate) Arc<Parker>);
+
+    pub trait Sealed {
+        /// Checks if `try_recv` would return something other than `Empty`.
+        fn is_ready(&self) -> bool;
+        /// Makes the sender also wake `waker`. Returns `true` if
+        /// there's a pending notification.
+        ///
+        /// # Safety
+        ///
+        /// Must be followed by `unregister` before the receiver is used again.
+        unsafe fn register(&self, waker: Waker) -> bool;
+        /// Undoes `register`.
+        ///
+        /// # Safety
+        ///
+        /// Must follow a call to `register`.
+        unsafe fn unregister(&self);
+    }
+}
+
+impl<'a> Select<'a> {
+    /// Creates an empty [`Select`].
+    pub fn new() -> Self {
+        Self {
+            receivers: Vec::new(),
+        }
+    }
+
+    /// Adds a receiver to the set. Its index is the number of
+    /// receivers added before it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `receiver` was already added, since [`wait`](Select::wait)
+    /// can only register a receiver once.
+    pub fn recv<R: Selectable>(mut self, receiver: &'a R) -> Self {
+        let addr = crate::util::addr(receiver);
+        assert!(
+            self.receivers.iter().all(|r| crate::util::addr(*r) != addr),
+            "receiver added to a Select twice"
+        );
+        self.receivers.push(receiver);
+        self
+    }
+
+    /// Returns the index of the first ready receiver, if any.
+    pub fn try_ready(&self) -> Option<usize> {
+        self.receivers.iter().position(|r| r.is_ready())
+    }
+
+    /// Blocks until one of the receivers is ready and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no receivers were added, since it would block forever.
+    pub fn wait(&self) -> usize {
+        assert!(!self.receivers.is_empty(), "waiting on an empty Select");
+        let waker = sealed::Waker(Arc::new(Parker::new()));
+        loop {
+            if let Some(ready) = self.try_ready() {
+                return ready;
+            }
+
+            let mut notified = false;
+            for r in &self.receivers {
+                //SAFETY: unregistered below.
+                notified |= unsafe { r.register(waker.clone()) };
+            }
+            // data sent before registering doesn't unpark `waker`.
+            if !notified && self.try_ready().is_none() {
+                //SAFETY: `waker` is local to this call.
+                unsafe { waker.0.park() };
+            }
+            for r in &self.receivers {
+                //SAFETY: registered above.
+                unsafe { r.unregister() };
+            }
+        }
+    }
+}
+
+impl Default for Select<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Select<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Select {{ receivers: {} }}", self.receivers.len())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,82 @@
+cfg_not_loom! {
+
+#[cfg(feature = "spsc-bounded")]
+use super::*;
+#[cfg(feature = "spsc-bounded")]
+use crate::spsc::bounded;
+#[cfg(all(feature = "spsc-bounded", feature = "spsc-unbounded"))]
+use crate::spsc::unbounded;
+
+#[test]
+#[cfg(all(feature = "spsc-bounded", feature = "spsc-unbounded"))]
+fn st_ready() {
+    let (tx1, rx1) = bounded::channel::<i32>(2);
+    let (tx2, rx2) = unbounded::channel::<i32>();
+    let select = Select::new().recv(&rx1).recv(&rx2);
+    assert_eq!(select.try_ready(), None);
+    tx2.send(1).unwrap();
+    assert_eq!(select.try_ready(), Some(1));
+    assert_eq!(select.wait(), 1);
+    drop(tx1);
+    assert_eq!(select.try_ready(), Some(0));
+    drop(tx2);
+}
+
+#[test]
+#[cfg(all(feature = "spsc-bounded", feature = "spsc-unbounded"))]
+fn mt_wait() {
+    let (tx1, rx1) = bounded::channel::<i32>(2);
+    let (tx2, rx2) = unbounded::channel::<i32>();
+    std::thread::spawn(move || {
+        for i in 0..100 {
+            if i % 2 == 0 {
+                tx1.send(i).unwrap();
+            } else {
+                tx2.send(i).unwrap();
+            }
+        }
+    });
+    let mut received = Vec::new();
+    let select = Select::new().recv(&rx1).recv(&rx2);
+    while received.len() != 100 {
+        select.wait();
+        received.extend(rx1.try_iter());
+        received.extend(rx2.try_iter());
+    }
+    received.sort();
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg(feature = "spsc-bounded")]
+#[should_panic(expected = "receiver added to a Select twice")]
+fn st_recv_twice() {
+    let (_tx, rx) = bounded::channel::<i32>(2);
+    let _select = Select::new().recv(&rx).recv(&rx);
+}
+
+}
+
+cfg_loom! {
+
+#[cfg(feature = "spsc-bounded")]
+use super::*;
+#[cfg(feature = "spsc-bounded")]
+use crate::spsc::bounded;
+
+#[test]
+#[cfg(feature = "spsc-bounded")]
+fn wait_send() {
+    loom::model(|| {
+        let (tx1, rx1) = bounded::channel::<i32>(1);
+        let (tx2, rx2) = bounded::channel::<i32>(1);
+        loom::thread::spawn(move || {
+            tx2.send(1).unwrap();
+            drop(tx1);
+        });
+        let select = Select::new().recv(&rx1).recv(&rx2);
+        assert!(select.wait() < 2);
+    });
+}
+
+}
@@ -0,0 +1,186 @@
+use crate::error::{SendTimeoutError, TryRecvError, TrySendError};
+use crate::sync::atomic::Ordering::{Acquire, Release};
+use crate::sync::atomic::{AtomicPtr, AtomicUsize};
+use crate::util::cache::CachePadded;
+use crate::util::park::Parker;
+use std::cell::Cell; //There's only a Sender exclusive cell and a Receiver exclusive cell.
+use std::ptr;
+use std::sync::Arc;
+use std::time::Instant;
+
+pub(super) struct Inner<T> {
+    /// A slot is null while it's empty, and holds a pointer from
+    /// [`Arc::into_raw`] while it's full.
+    ///
+    /// Only the sender fills slots and only the receiver empties them,
+    /// so the slots are the only state the end points share.
+    slots: Box<[AtomicPtr<T>]>,
+    /// The index of the next slot to fill.
+    tail: CachePadded<Cell<usize>>,
+    /// The index of the next slot to empty.
+    head: CachePadded<Cell<usize>>,
+    sender_park: Parker,
+    receiver_park: Parker,
+    /*
+    starts off as 0, incremented when entering Sender/Receiver drop.
+    match 'previous value' {
+        0 => {
+            Now the channel is disconnected. We try to wake the other end point.
+            If the other end point was asleep, it will detect the disconnect and unblock.
+            Then, we increment 'drop_count' again and repeat this decision tree with the
+            new 'previous value'.
+        }
+        1 => just fall off drop.
+        2 => deallocate the inner state.
+    }
+    */
+    pub(super) drop_count: AtomicUsize,
+}
+
+impl<T> Inner<T> {
+    /// Creates a channel with room for exactly `capacity` values,
+    /// which must be a power of two.
+    pub(super) fn new(capacity: usize) -> Self {
+        debug_assert!(capacity.is_power_of_two());
+        Self {
+            slots: (0..capacity)
+                .map(|_| AtomicPtr::new(ptr::null_mut()))
+                .collect(),
+            tail: CachePadded::new(Cell::new(0)),
+            head: CachePadded::new(Cell::new(0)),
+            sender_park: Parker::new(),
+            receiver_park: Parker::new(),
+            drop_count: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline(always)]
+    fn slot(&self, index: usize) -> &AtomicPtr<T> {
+        &self.slots[index & (self.slots.len() - 1)]
+    }
+
+    /// Can only be called by the sender.
+    pub(super) fn try_send(&self, item: Arc<T>) -> Result<(), TrySendError<Arc<T>>> {
+        if self.drop_count.load(Acquire) != 0 {
+            return Err(TrySendError::Disconnected(item));
+        }
+        let tail = self.tail.get();
+        let slot = self.slot(tail);
+        if !slot.load(Acquire).is_null() {
+            return Err(TrySendError::Full(item));
+        }
+        slot.store(Arc::into_raw(item) as *mut T, Release);
+        self.tail.set(tail.wrapping_add(1));
+        self.wake_receiver();
+        Ok(())
+    }
+
+    /// Can only be called by the sender.
+    pub(super) fn send_deadline(
+        &self,
+        mut item: Arc<T>,
+        deadline: Option<Instant>,
+    ) -> Result<(), SendTimeoutError<Arc<T>>> {
+        loop {
+            match self.try_send(item) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(returned)) => item = returned,
+                Err(TrySendError::Disconnected(item) | TrySendError::Poisoned(item)) => {
+                    return Err(SendTimeoutError::Disconnected(item))
+                }
+            }
+            match deadline {
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Err(SendTimeoutError::Timeout(item));
+                    }
+                    //SAFETY: park can't be called by different threads, since Sender is !Sync.
+                    unsafe { self.sender_park.park_deadline(deadline) };
+                }
+                //SAFETY: park can't be called by different threads, since Sender is !Sync.
+                None => unsafe { self.sender_park.park() },
+            }
+        }
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn try_recv(&self) -> Result<Arc<T>, TryRecvError> {
+        if let Some(item) = self.pop() {
+            return Ok(item);
+        }
+        if self.drop_count.load(Acquire) == 0 {
+            return Err(TryRecvError::Empty);
+        }
+        // the sender might have sent before disconnecting.
+        self.pop().ok_or(TryRecvError::Disconnected)
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn recv_deadline(&self, deadline: Option<Instant>) -> Result<Arc<T>, TryRecvError> {
+        loop {
+            match self.try_recv() {
+                Err(TryRecvError::Empty) => {}
+                result => return result,
+            }
+            match deadline {
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Err(TryRecvError::Empty);
+                    }
+                    //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                    unsafe { self.receiver_park.park_deadline(deadline) };
+                }
+                //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                None => unsafe { self.receiver_park.park() },
+            }
+        }
+    }
+
+    /// Empties the slot at `head`, if it's full.
+    ///
+    /// Can only be called by the receiver.
+    fn pop(&self) -> Option<Arc<T>> {
+        let head = self.head.get();
+        let slot = self.slot(head);
+        let item = slot.load(Acquire);
+        if item.is_null() {
+            return None;
+        }
+        slot.store(ptr::null_mut(), Release);
+        self.head.set(head.wrapping_add(1));
+        self.wake_sender();
+        //SAFETY: the sender stored a pointer from Arc::into_raw, which is only taken out once.
+        Some(unsafe { Arc::from_raw(item) })
+    }
+
+    /// Returns how many values the channel can hold.
+    pub(super) fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub(super) fn peer_connected(&self) -> bool {
+        self.drop_count.load(Acquire) == 0
+    }
+
+    #[inline]
+    pub(super) fn wake_sender(&self) {
+        self.sender_park.unpark();
+    }
+
+    #[inline]
+    pub(super) fn wake_receiver(&self) {
+        self.receiver_park.unpark();
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        for slot in self.slots.iter() {
+            let item = slot.swap(ptr::null_mut(), Acquire);
+            if !item.is_null() {
+                //SAFETY: both end points are gone, so nobody else can take the value.
+                drop(unsafe { Arc::from_raw(item) });
+            }
+        }
+    }
+}
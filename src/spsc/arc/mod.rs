@@ -0,0 +1,242 @@
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::ptr::NonNull;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub use crate::error::{
+    RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+};
+
+mod inner;
+use inner::Inner;
+
+/// Creates an [`Arc`] channel with storage for at least `min_capacity` values.
+///
+/// # Panics
+///
+/// The function panics if the rounded capacity overflows
+/// or it can't allocate the memory needed for the channel.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::arc;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let (src, sink) = arc::channel(8);
+/// let config = Arc::new(String::from("large, immutable payload"));
+/// let shared = config.clone();
+/// thread::spawn(move || {
+///     src.send(shared).unwrap();
+/// });
+///
+/// let received = sink.recv().unwrap();
+/// assert!(Arc::ptr_eq(&received, &config));
+/// ```
+pub fn channel<T>(min_capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = min_capacity
+        .checked_next_power_of_two()
+        .expect("capacity overflow");
+    /*SAFETY: freed by the last end point's Drop.*/
+    let inner = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Inner::new(capacity)))) };
+    (
+        Sender {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+        Receiver {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+    )
+}
+
+/// The sending endpoint of an [`Arc`] [`channel`].
+///
+/// Data can be sent using the [`try_send`](Sender::try_send)
+/// and [`send`](Sender::send) methods.
+///
+/// # Disconnection
+///
+/// When a [`Sender`] is dropped, the [`Receiver`] can still
+/// receive the pending values before seeing the disconnect.
+pub struct Sender<T> {
+    inner: NonNull<Inner<T>>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of an [`Arc`] [`channel`].
+///
+/// Data can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<T> {
+    inner: NonNull<Inner<T>>,
+    _unsync: PhantomUnsync,
+}
+
+impl<T> Sender<T> {
+    /// Tries to send an [`Arc`] through this [`channel`].
+    ///
+    /// Returns [`TrySendError::Full`] if every slot is taken,
+    /// and [`TrySendError::Disconnected`] if the [`Receiver`] is gone.
+    #[inline]
+    pub fn try_send(&self, item: Arc<T>) -> Result<(), TrySendError<Arc<T>>> {
+        self.inner_ref().try_send(item)
+    }
+
+    /// Sends an [`Arc`] through this [`channel`], waiting for a free slot.
+    ///
+    /// Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    pub fn send(&self, item: Arc<T>) -> Result<(), SendError<Arc<T>>> {
+        self.inner_ref()
+            .send_deadline(item, None)
+            .map_err(|err| SendError(err.into_inner()))
+    }
+
+    /// Like [`send`](Sender::send), but waits at most `timeout`.
+    pub fn send_timeout(
+        &self,
+        item: Arc<T>,
+        timeout: Duration,
+    ) -> Result<(), SendTimeoutError<Arc<T>>> {
+        self.inner_ref()
+            .send_deadline(item, Instant::now().checked_add(timeout))
+    }
+
+    /// Returns how many values the [`channel`] can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner_ref().capacity()
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<T> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to return a pending [`Arc`].
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after receiving all
+    /// pending values. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    #[inline]
+    pub fn try_recv(&self) -> Result<Arc<T>, TryRecvError> {
+        self.inner_ref().try_recv()
+    }
+
+    /// Waits for an [`Arc`] and receives it.
+    ///
+    /// Returns a [`RecvError`] once the [`Sender`] is disconnected
+    /// and all pending values were received.
+    pub fn recv(&self) -> Result<Arc<T>, RecvError> {
+        self.inner_ref()
+            .recv_deadline(None)
+            .map_err(|_| RecvError {})
+    }
+
+    /// Like [`recv`](Receiver::recv), but waits at most `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Arc<T>, RecvTimeoutError> {
+        match self
+            .inner_ref()
+            .recv_deadline(Instant::now().checked_add(timeout))
+        {
+            Ok(item) => Ok(item),
+            Err(TryRecvError::Empty) => Err(RecvTimeoutError::Timeout),
+            Err(TryRecvError::Disconnected) => Err(RecvTimeoutError::Disconnected),
+            Err(TryRecvError::Poisoned) => Err(RecvTimeoutError::Poisoned),
+        }
+    }
+
+    /// Returns how many values the [`channel`] can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner_ref().capacity()
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// This doesn't take the pending values into account.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<T> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_receiver(),
+                1 => break,
+                //SAFETY: the Receiver is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_sender(),
+                1 => break,
+                //SAFETY: the Sender is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::arc::Sender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::arc::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+// the end points move Arc<T>s between threads, which needs T: Send + Sync.
+unsafe impl<T: Send + Sync> Send for Sender<T> {}
+unsafe impl<T: Send + Sync> Send for Receiver<T> {}
+
+#[cfg(test)]
+mod tests;
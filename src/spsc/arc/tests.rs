@@ -0,0 +1,66 @@
+cfg_not_loom! {
+use super::*;
+
+#[test]
+fn st_send_recv() {
+    let (src, sink) = channel(3);
+    assert_eq!(src.capacity(), 4);
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    for i in 0..4 {
+        src.try_send(Arc::new(i)).unwrap();
+    }
+    assert_eq!(src.try_send(Arc::new(4)), Err(TrySendError::Full(Arc::new(4))));
+    for i in 0..4 {
+        assert_eq!(sink.recv(), Ok(Arc::new(i)));
+    }
+    assert_eq!(
+        sink.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, sink) = channel(4);
+    src.send(Arc::new("one")).unwrap();
+    drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(sink.try_recv(), Ok(Arc::new("one")));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+
+    let (src, sink) = channel(4);
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.send(Arc::new("two")), Err(SendError(Arc::new("two"))));
+}
+
+#[test]
+fn st_drop_pending() {
+    let value = Arc::new(0);
+    let (src, sink) = channel(4);
+    src.send(value.clone()).unwrap();
+    src.send(value.clone()).unwrap();
+    assert_eq!(Arc::strong_count(&value), 3);
+    drop(src);
+    drop(sink);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn mt_send_recv() {
+    let (src, sink) = channel(4);
+    std::thread::spawn(move || {
+        for i in 0..10_000 {
+            src.send(Arc::new(i)).unwrap();
+        }
+    });
+    let mut expected = 0;
+    while let Ok(item) = sink.recv() {
+        assert_eq!(*item, expected);
+        expected += 1;
+    }
+    assert_eq!(expected, 10_000);
+}
+
+}
@@ -0,0 +1,255 @@
+use super::inner::{Inner, Slot, Storage, CLOSED, POISONED};
+use super::round_capacity;
+use crate::alloc::{alloc, dealloc, Layout};
+use crate::error::{
+    RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+};
+use crate::sync::atomic::{AtomicUsize, Ordering::AcqRel};
+use crate::util::marker::PhantomUnsync;
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+/// Creates a pair of connected [`Endpoint`]s.
+///
+/// Values sent by the first [`Endpoint`] go through a bounded ring with room
+/// for at least `cap_a_to_b` of them, and the ones sent by the second through
+/// a ring with room for at least `cap_b_to_a`. Both rings and their shared
+/// state are placed in a single allocation.
+///
+/// # Panics
+///
+/// The function panics if a capacity is greater than
+/// [`MAX_CAPACITY`](super::MAX_CAPACITY) or it can't allocate the memory
+/// needed for the channels.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bidir;
+/// use std::thread;
+///
+/// let (client, server) = bidir::pair::<u32, String>(4, 4);
+/// thread::spawn(move || {
+///     while let Ok(request) = server.recv() {
+///         server.send(format!("#{request}")).unwrap();
+///     }
+/// });
+/// for i in 0..3 {
+///     client.send(i).unwrap();
+///     assert_eq!(client.recv(), Ok(format!("#{i}")));
+/// }
+/// ```
+pub fn pair<A, B>(cap_a_to_b: usize, cap_b_to_a: usize) -> (Endpoint<A, B>, Endpoint<B, A>) {
+    let (cap_a, cap_b) = (round_capacity(cap_a_to_b), round_capacity(cap_b_to_a));
+    let layout = Layout::new::<Header>();
+    let (layout, a_offset) = layout
+        .extend(Layout::new::<Inner<A, RawSlots<A>>>())
+        .expect("capacity overflow");
+    let (layout, b_offset) = layout
+        .extend(Layout::new::<Inner<B, RawSlots<B>>>())
+        .expect("capacity overflow");
+    let (layout, a_slots_offset) = Layout::array::<Slot<A>>(cap_a)
+        .and_then(|slots| layout.extend(slots))
+        .expect("capacity overflow");
+    let (layout, b_slots_offset) = Layout::array::<Slot<B>>(cap_b)
+        .and_then(|slots| layout.extend(slots))
+        .expect("capacity overflow");
+    let layout = layout.pad_to_align();
+
+    //SAFETY: the layout isn't zero-sized, since it starts with a Header.
+    let header = NonNull::new(unsafe { alloc(layout) } as *mut Header)
+        .expect("failed to allocate memory for the shared state");
+    let base = header.as_ptr() as *mut u8;
+    /*SAFETY:
+     *all offsets are within the allocation and aligned for their type.
+     *The slots are uninitialised memory, which is a valid value for them.
+     */
+    let (a_to_b, b_to_a) = unsafe {
+        header.as_ptr().write(Header {
+            endpoints: AtomicUsize::new(2),
+            layout,
+        });
+        let a_slots = RawSlots::new(base.add(a_slots_offset) as *mut Slot<A>, cap_a);
+        let b_slots = RawSlots::new(base.add(b_slots_offset) as *mut Slot<B>, cap_b);
+        let a_to_b = base.add(a_offset) as *mut Inner<A, RawSlots<A>>;
+        let b_to_a = base.add(b_offset) as *mut Inner<B, RawSlots<B>>;
+        a_to_b.write(Inner::in_storage(a_slots));
+        b_to_a.write(Inner::in_storage(b_slots));
+        (NonNull::new_unchecked(a_to_b), NonNull::new_unchecked(b_to_a))
+    };
+    (
+        Endpoint {
+            header,
+            tx: a_to_b,
+            rx: b_to_a,
+            _unsync: PhantomUnsync {},
+        },
+        Endpoint {
+            header,
+            tx: b_to_a,
+            rx: a_to_b,
+            _unsync: PhantomUnsync {},
+        },
+    )
+}
+
+/// One end of a bidirectional channel created by [`pair`].
+///
+/// It sends values of type `S` to the other [`Endpoint`] and receives values
+/// of type `R` from it. Each direction works like a bounded
+/// [`channel`](super::channel), where this [`Endpoint`] is the only sender
+/// of one and the only receiver of the other.
+///
+/// Dropping an [`Endpoint`] disconnects both directions.
+pub struct Endpoint<S, R> {
+    header: NonNull<Header>,
+    tx: NonNull<Inner<S, RawSlots<S>>>,
+    rx: NonNull<Inner<R, RawSlots<R>>>,
+    _unsync: PhantomUnsync,
+}
+
+/// The start of the allocation made by [`pair`].
+struct Header {
+    /// How many [`Endpoint`]s are left, the last one frees the allocation.
+    endpoints: AtomicUsize,
+    layout: Layout,
+}
+
+/// A ring buffer that lives in the allocation made by [`pair`].
+struct RawSlots<T> {
+    ptr: NonNull<Slot<T>>,
+    len: usize,
+}
+
+impl<T> RawSlots<T> {
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` slots that outlive the [`RawSlots`],
+    /// and `len` must be in `1..=MAX_CAPACITY`.
+    unsafe fn new(ptr: *mut Slot<T>, len: usize) -> Self {
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+            len,
+        }
+    }
+}
+
+//SAFETY: the length is fixed and checked by `RawSlots::new`'s caller.
+unsafe impl<T> Storage<T> for RawSlots<T> {
+    #[inline(always)]
+    fn slots(&self) -> &[Slot<T>] {
+        //SAFETY: see `RawSlots::new`.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<S, R> Endpoint<S, R> {
+    /// Tries to send a value to the other [`Endpoint`].
+    ///
+    /// See [`Sender::try_send`](super::Sender::try_send).
+    #[inline]
+    pub fn try_send(&self, item: S) -> Result<(), TrySendError<S>> {
+        self.tx().try_send(item)
+    }
+
+    /// Sends a value to the other [`Endpoint`], waiting for room if needed.
+    ///
+    /// See [`Sender::send`](super::Sender::send).
+    #[inline]
+    pub fn send(&self, item: S) -> Result<(), SendError<S>> {
+        self.tx().send(item)
+    }
+
+    /// Like [`send`](Endpoint::send), but waits at most `timeout`.
+    ///
+    /// See [`Sender::send_timeout`](super::Sender::send_timeout).
+    pub fn send_timeout(&self, item: S, timeout: Duration) -> Result<(), SendTimeoutError<S>> {
+        self.tx()
+            .send_deadline(item, Instant::now().checked_add(timeout))
+    }
+
+    /// Tries to return a value sent by the other [`Endpoint`].
+    ///
+    /// See [`Receiver::try_recv`](super::Receiver::try_recv).
+    #[inline]
+    pub fn try_recv(&self) -> Result<R, TryRecvError> {
+        self.rx().try_recv()
+    }
+
+    /// Receives a value sent by the other [`Endpoint`], waiting for one if needed.
+    ///
+    /// See [`Receiver::recv`](super::Receiver::recv).
+    #[inline]
+    pub fn recv(&self) -> Result<R, RecvError> {
+        self.rx().recv()
+    }
+
+    /// Like [`recv`](Endpoint::recv), but waits at most `timeout`.
+    ///
+    /// See [`Receiver::recv_timeout`](super::Receiver::recv_timeout).
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<R, RecvTimeoutError> {
+        self.rx()
+            .recv_deadline(Instant::now().checked_add(timeout))
+    }
+
+    /// Checks if the other [`Endpoint`] is still connected.
+    #[inline]
+    pub fn peer_connected(&self) -> bool {
+        self.tx().peer_connected()
+    }
+
+    #[inline(always)]
+    fn tx(&self) -> &Inner<S, RawSlots<S>> {
+        //SAFETY: the allocation is only freed by the last Endpoint's Drop.
+        unsafe { self.tx.as_ref() }
+    }
+
+    #[inline(always)]
+    fn rx(&self) -> &Inner<R, RawSlots<R>> {
+        //SAFETY: the allocation is only freed by the last Endpoint's Drop.
+        unsafe { self.rx.as_ref() }
+    }
+}
+
+impl<S, R> Drop for Endpoint<S, R> {
+    fn drop(&mut self) {
+        /*
+        Unlike the other channels, 'drop_count' only disconnects the
+        other end point, since the allocation is freed by 'endpoints'.
+        */
+        if self.tx().shared.drop_count.fetch_add(1, AcqRel) & !(CLOSED | POISONED) == 0 {
+            self.tx().wake_receiver();
+        }
+        if self.rx().shared.drop_count.fetch_add(1, AcqRel) & !(CLOSED | POISONED) == 0 {
+            self.rx().wake_sender();
+        }
+        //SAFETY: the header is only freed below.
+        let header = unsafe { self.header.as_ref() };
+        if header.endpoints.fetch_sub(1, AcqRel) == 1 {
+            let layout = header.layout;
+            /*SAFETY:
+             *the other Endpoint is gone, so nothing else can use the channels.
+             *Dropping them drops the values left in the rings.
+             */
+            unsafe {
+                self.tx.as_ptr().drop_in_place();
+                self.rx.as_ptr().drop_in_place();
+                dealloc(self.header.as_ptr() as *mut u8, layout);
+            }
+        }
+    }
+}
+
+impl<S, R> std::fmt::Debug for Endpoint<S, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bidir::Endpoint<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<S>(),
+            std::any::type_name::<R>(),
+            self.header
+        )
+    }
+}
+
+unsafe impl<S: Send, R: Send> Send for Endpoint<S, R> {}
@@ -0,0 +1,446 @@
+use super::inner::{IdleCallback, Inner, Watchdog, Watermarks};
+use super::{from_inner, round_capacity, try_from_inner, try_round_capacity, Receiver, Sender};
+use crate::error::AllocError;
+use crate::util::park::{Idle, WaitStrategy};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Configures and creates a bounded [`channel`](super::channel).
+///
+/// By default, blocking operations park the thread as soon as they can't
+/// make progress. [`spin`](Builder::spin), [`park`](Builder::park) and
+/// [`idle`](Builder::idle) trade CPU time for latency by busy-waiting instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded::Builder;
+/// use std::thread;
+///
+/// let (src, sink) = Builder::new(16).spin(1000).build();
+/// thread::spawn(move || {
+///     for i in 0..100 {
+///         src.send(i).unwrap();
+///     }
+/// });
+/// for i in 0..100 {
+///     assert_eq!(sink.recv(), Ok(i));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Builder {
+    min_capacity: usize,
+    wait: WaitStrategy,
+    check_interval: u32,
+    wake_threshold: usize,
+    poisoning: bool,
+    watchdog: Option<Watchdog>,
+    watermarks: Option<Watermarks>,
+    idle: IdleStrategy,
+    name: Option<&'static str>,
+}
+
+impl Builder {
+    /// Creates a [`Builder`] for a channel with storage for at least `min_capacity` elements.
+    pub fn new(min_capacity: usize) -> Self {
+        Self {
+            min_capacity,
+            wait: WaitStrategy::DEFAULT,
+            check_interval: 0,
+            wake_threshold: 0,
+            poisoning: false,
+            watchdog: None,
+            watermarks: None,
+            idle: IdleStrategy::Park,
+            name: None,
+        }
+    }
+
+    /// Names the channel, to tell it apart from others when debugging.
+    ///
+    /// The name shows up in the [`Debug`](std::fmt::Debug) output of the end
+    /// points, and in the spans of the `tracing` feature. It can be read back
+    /// with [`Sender::name`] and [`Receiver::name`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded::Builder;
+    ///
+    /// let (src, sink) = Builder::new(4).name("audio").build::<f32>();
+    /// assert_eq!(src.name(), Some("audio"));
+    /// assert!(format!("{:?}", sink).contains("\"audio\""));
+    /// ```
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets the minimum capacity of the channel.
+    pub fn capacity(mut self, min_capacity: usize) -> Self {
+        self.min_capacity = min_capacity;
+        self
+    }
+
+    /// Sets how many times blocking operations busy-wait before parking.
+    ///
+    /// Every try waits a bit longer, like [`Backoff::snooze`], and later
+    /// ones yield the thread instead of spinning. Defaults to 0.
+    ///
+    /// [`Backoff::snooze`]: crate::sync::Backoff::snooze
+    pub fn spin(mut self, count: u32) -> Self {
+        self.wait.spins = count;
+        self
+    }
+
+    /// Sets whether blocking operations park at all.
+    ///
+    /// If `false`, [`send`](Sender::send) and [`recv`](Receiver::recv) busy-wait
+    /// until they can make progress, which is only sensible when both threads
+    /// have a core to themselves. Defaults to `true`.
+    pub fn park(mut self, park: bool) -> Self {
+        self.idle = if park {
+            IdleStrategy::Park
+        } else {
+            IdleStrategy::Spin
+        };
+        self
+    }
+
+    /// Sets what blocking operations do once they're done spinning,
+    /// see [`IdleStrategy`]. Defaults to [`IdleStrategy::Park`].
+    ///
+    /// Either end point can change its own strategy later with
+    /// [`Sender::set_idle`] and [`Receiver::set_idle`].
+    pub fn idle(mut self, idle: IdleStrategy) -> Self {
+        self.idle = idle;
+        self
+    }
+
+    /// Sets how many values the [`Sender`] sends between checks for a disconnect.
+    ///
+    /// Checking means loading a counter the [`Receiver`] writes to when it
+    /// disconnects, which costs some throughput on every send. Since disconnecting
+    /// is permanent, the [`Sender`] can check less often and still notice it
+    /// eventually. In exchange, up to `interval` values can be sent after the
+    /// [`Receiver`] disconnected; they're dropped along with the channel.
+    /// A [`Sender`] that finds the channel full always checks.
+    ///
+    /// Defaults to 0, which checks on every send.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded::Builder;
+    ///
+    /// let (src, sink) = Builder::new(4).disconnect_check_interval(2).build();
+    /// src.send(1).unwrap();
+    /// drop(sink);
+    /// // the disconnect isn't noticed right away,
+    /// assert!(src.send(2).is_ok());
+    /// assert!(src.send(3).is_ok());
+    /// // but it is after at most 2 more sends.
+    /// assert!(src.send(4).is_err());
+    /// ```
+    pub fn disconnect_check_interval(mut self, interval: u32) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Makes the [`Sender`] only wake a blocked [`Receiver`] once at least
+    /// `threshold` values are pending.
+    ///
+    /// Waking the [`Receiver`] on every send costs an atomic swap, which adds up
+    /// for producers that send many small values. With a threshold, a blocked
+    /// [`Receiver`] is woken when enough values are pending, when the channel is
+    /// full, on [`Sender::flush`] or when the [`Sender`] disconnects, so it may
+    /// not see the last few values until one of those happens. This also
+    /// delays [`set_notify`](Receiver::set_notify) callbacks and [`Select`](crate::select::Select).
+    ///
+    /// Thresholds above the capacity act like the capacity. Defaults to 0, which,
+    /// like 1, wakes the [`Receiver`] on every send.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded::Builder;
+    /// use std::thread;
+    ///
+    /// let (src, sink) = Builder::new(64).wake_threshold(16).build();
+    /// let handle = thread::spawn(move || sink.iter().sum::<u32>());
+    /// for i in 0..100 {
+    ///     src.send(i).unwrap();
+    /// }
+    /// // the last values are seen once the Sender disconnects.
+    /// drop(src);
+    /// assert_eq!(handle.join().unwrap(), 4950);
+    /// ```
+    pub fn wake_threshold(mut self, threshold: usize) -> Self {
+        self.wake_threshold = threshold;
+        self
+    }
+
+    /// Makes a panic on one side of the channel visible to the other.
+    ///
+    /// If an end point, or a [`SendSlot`](super::SendSlot),
+    /// [`RecvRef`](super::RecvRef) or chunk borrowed from it, is dropped while
+    /// its thread is panicking, the channel is poisoned and disconnected.
+    /// Sends then fail with [`TrySendError::Poisoned`] and, once every pending
+    /// value was received, receives fail with [`TryRecvError::Poisoned`].
+    /// Blocking operations still return a plain [`SendError`](crate::error::SendError)
+    /// or [`RecvError`](crate::error::RecvError), see [`Sender::is_poisoned`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::error::TryRecvError;
+    /// use concurrent_qs::spsc::bounded::Builder;
+    /// use std::thread;
+    ///
+    /// let (src, sink) = Builder::new(4).poisoning().build::<u32>();
+    /// let handle = thread::spawn(move || {
+    ///     src.send(1).unwrap();
+    ///     panic!("the producer failed");
+    /// });
+    /// assert!(handle.join().is_err());
+    /// assert_eq!(sink.try_recv(), Ok(1));
+    /// assert_eq!(sink.try_recv(), Err(TryRecvError::Poisoned));
+    /// assert!(sink.is_poisoned());
+    /// ```
+    ///
+    /// [`TrySendError::Poisoned`]: crate::error::TrySendError::Poisoned
+    /// [`TryRecvError::Poisoned`]: crate::error::TryRecvError::Poisoned
+    pub fn poisoning(mut self) -> Self {
+        self.poisoning = true;
+        self
+    }
+
+    /// Calls `callback` when a blocking operation has been waiting
+    /// for longer than `threshold`, to help find stuck pipelines.
+    ///
+    /// The callback is called from the waiting thread, right before it goes
+    /// back to waiting, with how long it has waited so far. It's called at
+    /// most once per blocking [`send`](Sender::send), [`recv`](Receiver::recv)
+    /// or any of their variants, on either side of the channel. It must not
+    /// use the channel's end points, since the waiting one is still borrowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded::Builder;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// let starved = Arc::new(AtomicUsize::new(0));
+    /// let counter = starved.clone();
+    /// let (src, sink) = Builder::new(4)
+    ///     .on_starved(Duration::from_millis(10), move |waited| {
+    ///         assert!(waited >= Duration::from_millis(10));
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     })
+    ///     .build();
+    /// let handle = thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(50));
+    ///     src.send(1).unwrap();
+    /// });
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// assert_eq!(starved.load(Ordering::Relaxed), 1);
+    /// handle.join().unwrap();
+    /// ```
+    pub fn on_starved(
+        mut self,
+        threshold: Duration,
+        callback: impl Fn(Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.watchdog = Some(Watchdog {
+            threshold,
+            callback: Arc::new(callback),
+        });
+        self
+    }
+
+    /// Calls `on_high` once at least `high` values are pending, and `on_low`
+    /// once at most `low` values are pending again, to signal backpressure
+    /// without polling [`len_hint`](Receiver::len_hint).
+    ///
+    /// `on_high` is called from the sending thread, right after a send, and
+    /// `on_low` from the receiving thread, right after a receive. They take
+    /// turns: `on_high` isn't called again until `on_low` was, and the other
+    /// way around, so a queue depth hovering around one of the watermarks
+    /// doesn't call them over and over. They must not use the channel's end
+    /// points, since the calling one is still borrowed.
+    ///
+    /// A `high` watermark above the capacity acts like the capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low` isn't less than `high`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded::Builder;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let paused = Arc::new(AtomicBool::new(false));
+    /// let (pause, resume) = (paused.clone(), paused.clone());
+    /// let (src, sink) = Builder::new(8)
+    ///     .watermarks(
+    ///         6,
+    ///         2,
+    ///         move || pause.store(true, Ordering::Relaxed),
+    ///         move || resume.store(false, Ordering::Relaxed),
+    ///     )
+    ///     .build();
+    /// for i in 0..6 {
+    ///     src.send(i).unwrap();
+    /// }
+    /// // e.g. stop polling the upstream source for now.
+    /// assert!(paused.load(Ordering::Relaxed));
+    /// for i in 0..4 {
+    ///     assert_eq!(sink.recv(), Ok(i));
+    /// }
+    /// assert!(!paused.load(Ordering::Relaxed));
+    /// ```
+    pub fn watermarks(
+        mut self,
+        high: usize,
+        low: usize,
+        on_high: impl Fn() + Send + Sync + 'static,
+        on_low: impl Fn() + Send + Sync + 'static,
+    ) -> Self {
+        assert!(low < high, "the low watermark must be less than the high one");
+        self.watermarks = Some(Watermarks {
+            high,
+            low,
+            on_high: Arc::new(on_high),
+            on_low: Arc::new(on_low),
+        });
+        self
+    }
+
+    /// Creates the channel.
+    ///
+    /// # Panics
+    ///
+    /// The function panics if it can't allocate the memory needed for the channel.
+    pub fn build<T>(self) -> (Sender<T>, Receiver<T>) {
+        from_inner(
+            Inner::<T>::new(round_capacity(self.min_capacity))
+                .wait_strategy(self.wait)
+                .idle_strategy(self.idle)
+                .disconnect_check_interval(self.check_interval)
+                .wake_threshold(self.wake_threshold)
+                .poisoning(self.poisoning)
+                .watchdog(self.watchdog)
+                .watermarks(self.watermarks)
+                .named(self.name),
+        )
+    }
+
+    /// Like [`build`](Builder::build), but returns an [`AllocError`] instead of
+    /// panicking if the memory needed for the channel can't be allocated.
+    pub fn try_build<T>(self) -> Result<(Sender<T>, Receiver<T>), AllocError> {
+        let capacity = try_round_capacity(self.min_capacity)?;
+        try_from_inner(
+            Inner::<T>::try_new(capacity)?
+                .wait_strategy(self.wait)
+                .idle_strategy(self.idle)
+                .disconnect_check_interval(self.check_interval)
+                .wake_threshold(self.wake_threshold)
+                .poisoning(self.poisoning)
+                .watchdog(self.watchdog)
+                .watermarks(self.watermarks)
+                .named(self.name),
+        )
+    }
+}
+
+/// What the blocking operations of a bounded [`channel`](super::channel)
+/// do while they wait, once they're done spinning.
+///
+/// The strategies trade latency for CPU time: [`Spin`](IdleStrategy::Spin)
+/// reacts the fastest but keeps a core busy, while [`Park`](IdleStrategy::Park)
+/// frees the core until the other end point wakes the thread up.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded::{Builder, IdleStrategy};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let idle = Arc::new(AtomicUsize::new(0));
+/// let counter = idle.clone();
+/// let (src, sink) = Builder::new(4).build::<u32>();
+/// // e.g. enter a low-power mode every time nothing arrives for a while.
+/// sink.set_idle(IdleStrategy::park_timeout(Duration::from_millis(5), move || {
+///     counter.fetch_add(1, Ordering::Relaxed);
+/// }));
+/// let handle = thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(50));
+///     src.send(1).unwrap();
+/// });
+/// assert_eq!(sink.recv(), Ok(1));
+/// assert!(idle.load(Ordering::Relaxed) > 0);
+/// handle.join().unwrap();
+/// ```
+#[derive(Clone)]
+pub enum IdleStrategy {
+    /// Busy-waits, backing off like [`Backoff::snooze`](crate::sync::Backoff::snooze),
+    /// without ever parking.
+    Spin,
+    /// Yields the thread between checks, which leaves the core to other
+    /// threads but still keeps polling.
+    Yield,
+    /// Parks the thread until the other end point wakes it up.
+    Park,
+    /// Like [`Park`](IdleStrategy::Park), but wakes up every `timeout`
+    /// that passes without the other end point waking the thread, and
+    /// calls `on_idle` before parking again.
+    ParkTimeout {
+        /// How long to park at most before calling `on_idle`.
+        timeout: Duration,
+        /// Called from the waiting thread. It must not use the
+        /// channel's end points, since the waiting one is still borrowed.
+        on_idle: Arc<dyn Fn() + Send + Sync>,
+    },
+}
+
+impl IdleStrategy {
+    /// Creates an [`IdleStrategy::ParkTimeout`].
+    pub fn park_timeout(timeout: Duration, on_idle: impl Fn() + Send + Sync + 'static) -> Self {
+        Self::ParkTimeout {
+            timeout,
+            on_idle: Arc::new(on_idle),
+        }
+    }
+
+    pub(super) fn into_parts(self) -> (Idle, Option<IdleCallback>) {
+        match self {
+            Self::Spin => (Idle::Spin, None),
+            Self::Yield => (Idle::Yield, None),
+            Self::Park => (Idle::Park, None),
+            Self::ParkTimeout { timeout, on_idle } => (Idle::ParkTimeout(timeout), Some(on_idle)),
+        }
+    }
+}
+
+impl std::fmt::Debug for IdleStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spin => f.write_str("Spin"),
+            Self::Yield => f.write_str("Yield"),
+            Self::Park => f.write_str("Park"),
+            Self::ParkTimeout { timeout, .. } => f
+                .debug_struct("ParkTimeout")
+                .field("timeout", timeout)
+                .finish_non_exhaustive(),
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use super::{Receiver, ReleaseGuard, Sender};
+use std::mem::MaybeUninit;
+
+/// Pending values borrowed from the buffer by [`Receiver::read_chunk`].
+///
+/// The values stay in the [`channel`](super::channel)'s buffer, in at most
+/// two contiguous runs, until [`commit`](ReadChunk::commit) drops them and
+/// gives their slots back to the [`Sender`](super::Sender). Dropping the
+/// chunk without committing leaves every value in the [`channel`](super::channel),
+/// but poisons it if the thread is panicking and it was built with
+/// [`Builder::poisoning`](super::Builder::poisoning).
+///
+/// A value put back by [`Receiver::push_front`] is read as a chunk of its own.
+pub struct ReadChunk<'a, T> {
+    receiver: &'a Receiver<T>,
+    head: usize,
+    first: *mut [MaybeUninit<T>],
+    second: *mut [MaybeUninit<T>],
+    /// Set if `first` is the value put back by `push_front`.
+    front: bool,
+}
+
+impl<'a, T> ReadChunk<'a, T> {
+    pub(super) fn new(receiver: &'a Receiver<T>, head: usize, max: usize) -> Self {
+        let inner = receiver.inner_ref();
+        let len = inner.pending_from(head, max);
+        let (first, second) = inner.slot_runs(head, len);
+        Self {
+            receiver,
+            head,
+            first,
+            second,
+            front: false,
+        }
+    }
+
+    #[cold]
+    pub(super) fn front(receiver: &'a Receiver<T>, max: usize) -> Self {
+        let first = receiver.front_ptr() as *mut MaybeUninit<T>;
+        Self {
+            receiver,
+            head: 0,
+            first: std::ptr::slice_from_raw_parts_mut(first, max.min(1)),
+            second: std::ptr::slice_from_raw_parts_mut(std::ptr::NonNull::dangling().as_ptr(), 0),
+            front: true,
+        }
+    }
+
+    /// Returns the values in the order they were sent, split where the buffer wraps.
+    ///
+    /// The second slice is only non-empty if the first one is.
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        /*SAFETY:
+         *the values were written by the sender and
+         *their slots can't be reused before they're released.
+         */
+        unsafe { (&*(self.first as *const [T]), &*(self.second as *const [T])) }
+    }
+
+    /// Like [`as_slices`](ReadChunk::as_slices), but allows modifying the values in place.
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        //SAFETY: same as as_slices, and only the Receiver can access the slots.
+        unsafe {
+            (
+                &mut *(self.first as *mut [T]),
+                &mut *(self.second as *mut [T]),
+            )
+        }
+    }
+
+    /// Returns the number of values in the chunk.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.first.len() + self.second.len()
+    }
+
+    /// Checks if the chunk has no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops the first `n` values and frees their slots, leaving
+    /// the rest in the [`channel`](super::channel).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than [`len`](ReadChunk::len).
+    pub fn commit(self, n: usize) {
+        assert!(n <= self.len(), "committed more values than the chunk has");
+        let first = n.min(self.first.len());
+        let this = std::mem::ManuallyDrop::new(self);
+        if this.front {
+            if n != 0 {
+                this.receiver.has_front.set(false);
+                //SAFETY: the value was put back by push_front and nothing else refers to it.
+                unsafe { (this.first as *mut T).drop_in_place() };
+            }
+            return;
+        }
+        let _release = ReleaseGuard {
+            inner: this.receiver.inner_ref(),
+            head: this.head,
+            n,
+        };
+        /*SAFETY:
+         *the values are initialised and dropped before
+         *their slots are released to the sender.
+         */
+        unsafe {
+            std::ptr::slice_from_raw_parts_mut(this.first as *mut T, first).drop_in_place();
+            std::ptr::slice_from_raw_parts_mut(this.second as *mut T, n - first).drop_in_place();
+        }
+    }
+
+    /// Drops every value in the chunk and frees their slots.
+    #[inline]
+    pub fn commit_all(self) {
+        let len = self.len();
+        self.commit(len)
+    }
+}
+
+impl<'a, T> Drop for ReadChunk<'a, T> {
+    fn drop(&mut self) {
+        let inner = self.receiver.inner_ref();
+        if inner.poison_if_panicking() {
+            inner.wake_sender();
+        }
+        if self.front {
+            return;
+        }
+        //SAFETY: nothing is released.
+        unsafe { inner.release_n(self.head, 0) };
+    }
+}
+
+impl<'a, T: std::fmt::Debug> std::fmt::Debug for ReadChunk<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (first, second) = self.as_slices();
+        f.debug_list().entries(first).entries(second).finish()
+    }
+}
+
+/// Free slots borrowed from the buffer by [`Sender::write_chunk_uninit`].
+///
+/// The slots are in at most two contiguous runs and the values written
+/// to them are only visible to the [`Receiver`] after
+/// [`commit`](WriteChunk::commit). Dropping the chunk without committing
+/// doesn't send anything, and values written to its slots are leaked. Like
+/// [`SendSlot`](super::SendSlot), it poisons the [`channel`](super::channel)
+/// if it's dropped while panicking.
+pub struct WriteChunk<'a, T> {
+    sender: &'a Sender<T>,
+    tail: usize,
+    first: *mut [MaybeUninit<T>],
+    second: *mut [MaybeUninit<T>],
+}
+
+impl<'a, T> WriteChunk<'a, T> {
+    pub(super) fn new(sender: &'a Sender<T>, tail: usize, max: usize) -> Self {
+        let inner = sender.inner_ref();
+        let len = inner.free_from(tail, max);
+        let (first, second) = inner.slot_runs(tail, len);
+        Self {
+            sender,
+            tail,
+            first,
+            second,
+        }
+    }
+
+    /// Gives access to the free slots in the order they're sent, split where the buffer wraps.
+    ///
+    /// The second slice is only non-empty if the first one is.
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+        /*SAFETY:
+         *the slots are free and can't be read by
+         *the Receiver until they're committed.
+         */
+        unsafe { (&mut *self.first, &mut *self.second) }
+    }
+
+    /// Returns the number of slots in the chunk.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.first.len() + self.second.len()
+    }
+
+    /// Checks if the chunk has no slots.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sends the values in the first `n` slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than [`len`](WriteChunk::len).
+    ///
+    /// # Safety
+    ///
+    /// The first `n` slots must be initialised through
+    /// [`as_mut_slices`](WriteChunk::as_mut_slices).
+    pub unsafe fn commit(self, n: usize) {
+        assert!(n <= self.len(), "committed more values than the chunk has");
+        let this = std::mem::ManuallyDrop::new(self);
+        this.sender.inner_ref().commit_n(this.tail, n)
+    }
+}
+
+impl<'a, T> Drop for WriteChunk<'a, T> {
+    fn drop(&mut self) {
+        let inner = self.sender.inner_ref();
+        if inner.poison_if_panicking() {
+            inner.wake_receiver();
+        }
+    }
+}
+
+/// A group of `n` slots reserved with [`Sender::reserve_many`].
+///
+/// Values are written into the slots in order with [`push`](SlotGroup::push)
+/// and only become visible to the [`Receiver`] when
+/// [`commit`](SlotGroup::commit) publishes all of them at once, so a record
+/// split across multiple values is received contiguously. Dropping the group
+/// without committing drops the written values and doesn't send anything. Like
+/// [`SendSlot`](super::SendSlot), it poisons the [`channel`](super::channel)
+/// if it's dropped while panicking.
+pub struct SlotGroup<'a, T> {
+    sender: &'a Sender<T>,
+    tail: usize,
+    /// The number of reserved slots.
+    n: usize,
+    /// The number of values written to the front of the group.
+    len: usize,
+}
+
+impl<'a, T> SlotGroup<'a, T> {
+    pub(super) fn new(sender: &'a Sender<T>, tail: usize, n: usize) -> Self {
+        Self {
+            sender,
+            tail,
+            n,
+            len: 0,
+        }
+    }
+
+    /// Writes `item` into the next reserved slot.
+    ///
+    /// Returns `item` back if every slot of the group is written.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.len == self.n {
+            return Err(item);
+        }
+        let inner = self.sender.inner_ref();
+        let tail = inner.advance_index(self.tail, self.len);
+        /*SAFETY:
+         *the slot is reserved and can't be read by the
+         *Receiver until it's committed.
+         */
+        unsafe { (inner.slot_ptr(tail) as *mut T).write(item) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the number of values written to the group.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if no values were written to the group.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of reserved slots.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.n
+    }
+
+    /// Sends the written values at once.
+    ///
+    /// Slots that weren't written to are given back to the [`Sender`].
+    #[inline]
+    pub fn commit(self) {
+        let this = std::mem::ManuallyDrop::new(self);
+        //SAFETY: the first `len` slots were written by push.
+        unsafe { this.sender.inner_ref().commit_n(this.tail, this.len) }
+    }
+}
+
+impl<'a, T> Drop for SlotGroup<'a, T> {
+    fn drop(&mut self) {
+        let inner = self.sender.inner_ref();
+        if inner.poison_if_panicking() {
+            inner.wake_receiver();
+        }
+        let (first, second) = inner.slot_runs(self.tail, self.len);
+        //SAFETY: the slots were written by push and were never sent.
+        unsafe {
+            (first as *mut [T]).drop_in_place();
+            (second as *mut [T]).drop_in_place();
+        }
+    }
+}
+
+impl<'a, T> std::fmt::Debug for SlotGroup<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::SlotGroup<{}> {{ channel: {:p}, len: {}, capacity: {} }}",
+            std::any::type_name::<T>(),
+            self.sender.inner,
+            self.len,
+            self.n
+        )
+    }
+}
+
+impl<'a, T> std::fmt::Debug for WriteChunk<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::WriteChunk<{}> {{ channel: {:p}, len: {} }}",
+            std::any::type_name::<T>(),
+            self.sender.inner,
+            self.len()
+        )
+    }
+}
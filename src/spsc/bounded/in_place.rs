@@ -0,0 +1,216 @@
+use super::inner::{Inner, Slot};
+use super::MAX_CAPACITY;
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::mem::MaybeUninit;
+
+/// A bounded SPSC channel built in memory provided by the caller.
+///
+/// Unlike [`channel`](super::channel), [`init_in`](Channel::init_in) doesn't
+/// use the global allocator at all: both the shared state and the buffer live
+/// wherever the caller put them, e.g. in an arena or a `static`. Unlike a
+/// [`StaticChannel`](super::StaticChannel), the capacity is chosen at runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded::Channel;
+/// use std::mem::MaybeUninit;
+/// use std::thread;
+///
+/// let mut place = MaybeUninit::uninit();
+/// let mut buffer = [const { MaybeUninit::uninit() }; 3];
+/// let (src, sink) = Channel::init_in(&mut place, &mut buffer);
+/// thread::scope(|s| {
+///     s.spawn(move || {
+///         for i in 0..10 {
+///             src.send(i).unwrap();
+///         }
+///     });
+///     for i in 0..10 {
+///         assert_eq!(sink.recv(), Ok(i));
+///     }
+/// });
+/// ```
+pub struct Channel<'a, T> {
+    inner: Inner<T, &'a [Slot<T>]>,
+}
+
+/// The sending endpoint of a [`Channel`].
+///
+/// Data can be sent using the [`try_send`](ChannelSender::try_send)
+/// and [`send`](ChannelSender::send) methods.
+pub struct ChannelSender<'a, T> {
+    inner: &'a Inner<T, &'a [Slot<T>]>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a [`Channel`].
+///
+/// Data can be received using the [`try_recv`](ChannelReceiver::try_recv)
+/// and [`recv`](ChannelReceiver::recv) methods.
+pub struct ChannelReceiver<'a, T> {
+    inner: &'a Inner<T, &'a [Slot<T>]>,
+    _unsync: PhantomUnsync,
+}
+
+impl<'a, T> Channel<'a, T> {
+    /// Builds a channel in `place`, which uses `buffer` for its values,
+    /// and returns its endpoints.
+    ///
+    /// The capacity is exactly `buffer.len()`, though power of two capacities
+    /// use cheaper index arithmetic. Values still in the channel are dropped
+    /// once both endpoints are; `place` itself is never dropped.
+    ///
+    /// # Panics
+    ///
+    /// The function panics if `buffer` is empty or longer than [`MAX_CAPACITY`].
+    pub fn init_in(
+        place: &'a mut MaybeUninit<Self>,
+        buffer: &'a mut [MaybeUninit<T>],
+    ) -> (ChannelSender<'a, T>, ChannelReceiver<'a, T>) {
+        assert!(
+            (1..=MAX_CAPACITY).contains(&buffer.len()),
+            "capacity out of range"
+        );
+        /*SAFETY:
+         *UnsafeCell is transparent, so Slot<T> has the same layout as MaybeUninit<T>,
+         *and the buffer is borrowed mutably for 'a, so only the channel accesses it.
+         */
+        let slots = unsafe { &*(buffer as *mut [MaybeUninit<T>] as *const [Slot<T>]) };
+        let channel: &'a Self = place.write(Self {
+            inner: Inner::borrowed(slots),
+        });
+        (
+            ChannelSender {
+                inner: &channel.inner,
+                _unsync: PhantomUnsync {},
+            },
+            ChannelReceiver {
+                inner: &channel.inner,
+                _unsync: PhantomUnsync {},
+            },
+        )
+    }
+}
+
+impl<'a, T> ChannelSender<'a, T> {
+    /// Tries to send a value through this [`Channel`].
+    ///
+    /// See [`Sender::try_send`](super::Sender::try_send).
+    #[inline]
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(item)
+    }
+
+    /// Sends a value through this [`Channel`].
+    ///
+    /// See [`Sender::send`](super::Sender::send).
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.inner.send(item)
+    }
+
+    /// Checks if the [`ChannelReceiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner.peer_connected()
+    }
+}
+
+impl<'a, T> ChannelReceiver<'a, T> {
+    /// Tries to return a pending value.
+    ///
+    /// See [`Receiver::try_recv`](super::Receiver::try_recv).
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner.try_recv()
+    }
+
+    /// Reads a value from the [`Channel`].
+    ///
+    /// See [`Receiver::recv`](super::Receiver::recv).
+    #[inline]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner.recv()
+    }
+
+    /// Checks if the [`ChannelSender`] is still connected.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner.peer_connected()
+    }
+}
+
+/// Drops the values left in the channel, since nobody drops the [`Channel`].
+fn release<T>(inner: &Inner<T, &[Slot<T>]>) {
+    /*SAFETY:
+     *both endpoints are gone, so nothing else can use the channel.
+     */
+    unsafe { inner.clear() };
+}
+
+impl<'a, T> Drop for ChannelSender<'a, T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner.shared.drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner.wake_receiver(),
+                1 => break,
+                2 => break release(self.inner),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<'a, T> Drop for ChannelReceiver<'a, T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner.shared.drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner.wake_sender(),
+                1 => break,
+                2 => break release(self.inner),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<'a, T> std::fmt::Debug for Channel<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::Channel<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            &self.inner
+        )
+    }
+}
+
+impl<'a, T> std::fmt::Debug for ChannelSender<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::ChannelSender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+impl<'a, T> std::fmt::Debug for ChannelReceiver<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::ChannelReceiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+unsafe impl<'a, T: Send> Send for ChannelSender<'a, T> {}
+unsafe impl<'a, T: Send> Send for ChannelReceiver<'a, T> {}
@@ -1,30 +1,103 @@
 use crate::alloc::Layout;
 use crate::cell::UnsafeCell;
-use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
-use crate::sync::atomic::AtomicUsize;
-use crate::sync::atomic::Ordering::{Acquire, Relaxed, Release};
-use crate::util::cache::CacheAligned;
-use crate::util::park::Parker;
+use crate::error::{
+    AllocError, RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError,
+    TrySendError,
+};
+use crate::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
+use crate::sync::Arc;
+use crate::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+use crate::util::cache::CachePadded;
+use crate::util::park::{Notify, Parker, WaitStrategy};
+use super::IdleStrategy;
+use super::MAX_CAPACITY;
 use std::cell::Cell; //There's only a Sender exclusive cell and a Receiver exclusive cell.
-use std::default::Default;
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
+#[cfg(not(feature = "loom"))]
+use std::ptr::addr_of_mut;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{Counter, Stats};
+
+pub(super) type Slot<T> = UnsafeCell<MaybeUninit<T>>;
+
+/// The bit of `drop_count` set by `close`, see its declaration.
+pub(super) const CLOSED: usize = 1 << (usize::BITS - 1);
+
+/// The bit of `drop_count` set by `poison_if_panicking`, see its declaration.
+pub(super) const POISONED: usize = 1 << (usize::BITS - 2);
+
+/// How many times `send_until` busy-waits before it falls back to parking.
+const SEND_UNTIL_SPINS: u32 = 64;
+
+/// Adds `n` to a sequence number, which only the end point moving
+/// the matching index writes, so it doesn't need a read-modify-write.
+#[inline(always)]
+fn add_seq(seq: &AtomicU64, n: u64) {
+    seq.store(seq.load(Relaxed).wrapping_add(n), Relaxed);
+}
+
+/// The storage of a bounded channel's buffer.
+///
+/// # Safety
+///
+/// `slots().len()` must never change and must be in `1..=MAX_CAPACITY`.
+pub(crate) unsafe trait Storage<T> {
+    fn slots(&self) -> &[Slot<T>];
+}
+
+unsafe impl<T> Storage<T> for Box<[Slot<T>]> {
+    #[inline(always)]
+    fn slots(&self) -> &[Slot<T>] {
+        self
+    }
+}
+
+unsafe impl<T> Storage<T> for &[Slot<T>] {
+    #[inline(always)]
+    fn slots(&self) -> &[Slot<T>] {
+        self
+    }
+}
+
+unsafe impl<T, const N: usize> Storage<T> for [Slot<T>; N] {
+    #[inline(always)]
+    fn slots(&self) -> &[Slot<T>] {
+        self
+    }
+}
 
 #[repr(C)]
-pub(crate) struct Inner<T> {
-    sender: CacheAligned<SenderData>,
-    receiver: CacheAligned<ReceiverData>,
-    pub(super) shared: SharedData<T>,
+pub(crate) struct Inner<T, S: Storage<T> = Box<[Slot<T>]>> {
+    sender: CachePadded<SenderData>,
+    receiver: CachePadded<ReceiverData>,
+    pub(super) shared: SharedData<T, S>,
 }
 
 impl<T> Inner<T> {
     pub(super) const LAYOUT: Layout = Layout::new::<Inner<T>>();
 
+    /// Creates a channel with room for exactly `capacity` elements.
+    ///
+    /// Power of two capacities use cheaper index arithmetic.
     pub(super) fn new(capacity: usize) -> Self {
+        Self::try_new(capacity).expect("failed to allocate memory for the buffer")
+    }
+
+    /// Like [`new`](Inner::new), but returns an error if the buffer can't be allocated.
+    pub(super) fn try_new(capacity: usize) -> Result<Self, AllocError> {
         // should already be ensured in channel()
-        debug_assert!(capacity.is_power_of_two(), "capacity wasn't a power of two");
+        debug_assert!(
+            (1..=MAX_CAPACITY).contains(&capacity),
+            "capacity out of range"
+        );
+        let mut vec = Vec::new();
+        vec.try_reserve_exact(capacity)
+            .map_err(|_| AllocError {})?;
         #[cfg(not(feature = "loom"))]
         let buffer = {
-            let mut vec = Vec::with_capacity(capacity);
             /*SAFETY:
              *elements are MaybeUninit, so uninitialised
              *data is a valid value for them.
@@ -35,116 +108,1551 @@ impl<T> Inner<T> {
         /*
         !!!IMPORTANT!!!
 
-        In loom, UnsafeCell::new(MaybeUninit::uninit()) isn't uninitialised memory.
-        It initialises extra fields used for keeping track of accesses to the cell.
+        In loom, UnsafeCell::new(MaybeUninit::uninit()) isn't uninitialised memory.
+        It initialises extra fields used for keeping track of accesses to the cell.
+
+        !!!DO NOT DELETE THE CODE BELOW!!!
+        */
+        #[cfg(feature = "loom")]
+        let buffer = {
+            vec.extend((0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())));
+            vec.into_boxed_slice()
+        };
+        let mut this = Self::with_storage(buffer);
+        this.shared.exact = !capacity.is_power_of_two();
+        Ok(this)
+    }
+}
+
+impl<T, const N: usize> Inner<T, [Slot<T>; N]> {
+    #[cfg(not(feature = "loom"))]
+    pub(super) const fn new_inline() -> Self {
+        const { assert!(N.is_power_of_two(), "capacity must be a power of two") };
+        Self::with_storage([const { UnsafeCell::new(MaybeUninit::uninit()) }; N])
+    }
+
+    /*
+    !!!IMPORTANT!!!
+
+    In loom, UnsafeCell::new(MaybeUninit::uninit()) isn't uninitialised memory.
+    See Inner::new.
+    */
+    #[cfg(feature = "loom")]
+    pub(super) fn new_inline() -> Self {
+        const { assert!(N.is_power_of_two(), "capacity must be a power of two") };
+        Self::with_storage(std::array::from_fn(|_| UnsafeCell::new(MaybeUninit::uninit())))
+    }
+
+    /// Like [`new_inline`](Inner::new_inline), but writes the channel straight
+    /// into its allocation, so a large `N` can't overflow the stack.
+    #[cfg(not(feature = "loom"))]
+    pub(super) fn boxed_inline() -> Box<Self> {
+        const { assert!(N.is_power_of_two(), "capacity must be a power of two") };
+        let mut this = Box::<Self>::new_uninit();
+        let ptr = this.as_mut_ptr();
+        /*SAFETY:
+         *every field but the buffer is written below. The buffer's slots are
+         *MaybeUninit, so they're left uninitialised, like in new_inline.
+         */
+        unsafe {
+            addr_of_mut!((*ptr).sender).write(CachePadded::new(SenderData::new()));
+            addr_of_mut!((*ptr).receiver).write(CachePadded::new(ReceiverData::new()));
+            SharedData::init_without_buffer(addr_of_mut!((*ptr).shared));
+            this.assume_init()
+        }
+    }
+}
+
+// only used by the in-place and bidirectional channels, which loom builds don't have.
+#[cfg(not(feature = "loom"))]
+impl<T, S: Storage<T>> Inner<T, S> {
+    /// Creates a channel that uses `buffer` as its buffer.
+    ///
+    /// The capacity is exactly the length of the buffer.
+    pub(super) fn in_storage(buffer: S) -> Self {
+        let exact = !buffer.slots().len().is_power_of_two();
+        let mut this = Self::with_storage(buffer);
+        this.shared.exact = exact;
+        this
+    }
+}
+
+// only used by the in-place channel, which loom builds don't have.
+#[cfg(not(feature = "loom"))]
+impl<'a, T> Inner<T, &'a [Slot<T>]> {
+    /// Creates a channel that uses `slots` as its buffer.
+    ///
+    /// `slots.len()` must be in `1..=MAX_CAPACITY`.
+    pub(super) fn borrowed(slots: &'a [Slot<T>]) -> Self {
+        debug_assert!(
+            (1..=MAX_CAPACITY).contains(&slots.len()),
+            "capacity out of range"
+        );
+        Self::in_storage(slots)
+    }
+}
+
+impl<T, S: Storage<T>> Inner<T, S> {
+    #[cfg(not(feature = "loom"))]
+    const fn with_storage(buffer: S) -> Self {
+        Self {
+            sender: CachePadded::new(SenderData::new()),
+            receiver: CachePadded::new(ReceiverData::new()),
+            shared: SharedData::new(buffer),
+        }
+    }
+
+    #[cfg(feature = "loom")]
+    fn with_storage(buffer: S) -> Self {
+        Self {
+            sender: CachePadded::new(SenderData::new()),
+            receiver: CachePadded::new(ReceiverData::new()),
+            shared: SharedData {
+                buffer,
+                _marker: PhantomData,
+                exact: false,
+                overwrite: false,
+                rendezvous: false,
+                poisoning: false,
+                watchdog: None,
+                watermarks: None,
+                name: None,
+                above_high: AtomicBool::new(false),
+                consuming: AtomicBool::new(false),
+                drop_count: AtomicUsize::new(0),
+            },
+        }
+    }
+
+    /// Makes [`send`](Inner::send) wait for the value to be received and
+    /// [`try_send`](Inner::try_send) fail unless the receiver is waiting.
+    ///
+    /// The capacity must be 1.
+    pub(super) fn rendezvous(mut self) -> Self {
+        debug_assert_eq!(self.buffer().len(), 1);
+        self.shared.rendezvous = true;
+        self
+    }
+
+    /// Sets how both end points wait for each other in blocking operations.
+    pub(super) fn wait_strategy(self, wait: WaitStrategy) -> Self {
+        self.sender.wait.set(wait);
+        self.receiver.wait.set(wait);
+        self
+    }
+
+    /// Makes the sender load `drop_count` only once every `interval + 1` sends,
+    /// see [`sender_disconnected`](Inner::sender_disconnected).
+    pub(super) fn disconnect_check_interval(self, interval: u32) -> Self {
+        self.sender.check_interval.set(interval);
+        self
+    }
+
+    /// Makes sends only wake the receiver once at least `threshold` values are
+    /// pending, see [`wake_after_send`](Inner::wake_after_send).
+    pub(super) fn wake_threshold(self, threshold: usize) -> Self {
+        // a full buffer always wakes the receiver, so higher thresholds are pointless.
+        let threshold = threshold.min(self.buffer().len());
+        self.sender
+            .wake_threshold
+            .set(if threshold > 1 { threshold } else { 0 });
+        self
+    }
+
+    /// Sets what both sides do once they're done spinning.
+    pub(super) fn idle_strategy(self, idle: IdleStrategy) -> Self {
+        self.set_sender_idle(idle.clone());
+        self.set_receiver_idle(idle);
+        self
+    }
+
+    /// Sets what the sender does once it's done spinning.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn set_sender_idle(&self, idle: IdleStrategy) {
+        let (kind, on_idle) = idle.into_parts();
+        let mut wait = self.sender.wait.get();
+        wait.idle = kind;
+        self.sender.wait.set(wait);
+        self.sender.on_idle.set(on_idle);
+    }
+
+    /// Sets what the receiver does once it's done spinning.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn set_receiver_idle(&self, idle: IdleStrategy) {
+        let (kind, on_idle) = idle.into_parts();
+        let mut wait = self.receiver.wait.get();
+        wait.idle = kind;
+        self.receiver.wait.set(wait);
+        self.receiver.on_idle.set(on_idle);
+    }
+
+    /// Sets how many times the sender busy-waits before parking.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn set_sender_spin(&self, spins: u32) {
+        let mut wait = self.sender.wait.get();
+        wait.spins = spins;
+        self.sender.wait.set(wait);
+    }
+
+    /// Sets how many times the receiver busy-waits before parking.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn set_receiver_spin(&self, spins: u32) {
+        let mut wait = self.receiver.wait.get();
+        wait.spins = spins;
+        self.receiver.wait.set(wait);
+    }
+
+    /// Moves every index to `index`, as if that many values were sent and received.
+    ///
+    /// `index` must be in `[0, 2 * cap)` if the capacity isn't a power of two.
+    #[cfg(all(test, not(feature = "loom")))]
+    pub(super) fn with_index(self, index: usize) -> Self {
+        self.sender.tail.store(index, Relaxed);
+        self.sender.head_cache.set(index);
+        self.receiver.head.store(index, Relaxed);
+        self.receiver.tail_cache.set(index);
+        self
+    }
+
+    /// Enables [`force_send`](Inner::force_send) to overwrite the oldest value.
+    pub(super) fn overwriting(mut self) -> Self {
+        self.shared.overwrite = true;
+        self
+    }
+
+    /// Makes [`poison_if_panicking`](Inner::poison_if_panicking) poison the channel.
+    pub(super) fn poisoning(mut self, poisoning: bool) -> Self {
+        self.shared.poisoning = poisoning;
+        self
+    }
+
+    /// Makes blocking operations call `watchdog` once they waited too long.
+    pub(super) fn watchdog(mut self, watchdog: Option<Watchdog>) -> Self {
+        self.shared.watchdog = watchdog;
+        self
+    }
+
+    /// Makes the sender and the receiver call the callbacks of `watermarks`,
+    /// see [`check_high`](Inner::check_high) and [`check_low`](Inner::check_low).
+    pub(super) fn watermarks(mut self, watermarks: Option<Watermarks>) -> Self {
+        self.shared.watermarks = watermarks.map(|mut watermarks| {
+            // a full buffer has to reach the high watermark.
+            watermarks.high = watermarks.high.min(self.buffer().len());
+            watermarks
+        });
+        self
+    }
+
+    /// Names the channel in `Debug` output and tracing spans.
+    pub(super) fn named(mut self, name: Option<&'static str>) -> Self {
+        self.shared.name = name;
+        self
+    }
+
+    /// Returns the name set with [`named`](Inner::named).
+    #[inline]
+    pub(super) fn name(&self) -> Option<&'static str> {
+        self.shared.name
+    }
+
+    #[inline(always)]
+    fn buffer(&self) -> &[Slot<T>] {
+        self.shared.buffer.slots()
+    }
+
+    pub(super) fn send(&self, item: T) -> Result<(), SendError<T>> {
+        let mut resend = match self.try_push(item) {
+            Ok(_) => return self.wait_handoff(),
+            Err(TrySendError::Full(ret)) => ret,
+            Err(err) => return Err(SendError(err.into_inner())),
+        };
+        let mut spun = 0;
+        loop {
+            //SAFETY: park can't be called by different threads, since Sender is !Sync.
+            unsafe {
+                self.park_sender(&mut spun, None);
+            }
+
+            match self.try_push(resend) {
+                Ok(_) => break self.wait_handoff(),
+                Err(TrySendError::Full(ret)) => resend = ret,
+                Err(err) => break Err(SendError(err.into_inner())),
+            }
+        }
+    }
+
+    /// Like [`send`](Inner::send), but gives up at `deadline`.
+    ///
+    /// On rendezvous channels, this only sends while the receiver is waiting,
+    /// so a value is never left behind in the buffer on timeout.
+    pub(super) fn send_deadline(
+        &self,
+        item: T,
+        deadline: Option<Instant>,
+    ) -> Result<(), SendTimeoutError<T>> {
+        let mut resend = item;
+        let mut spun = 0;
+        loop {
+            match self.try_send(resend) {
+                Ok(_) => return Ok(()),
+                Err(TrySendError::Disconnected(ret)) => return Err(SendTimeoutError::Disconnected(ret)),
+                Err(TrySendError::Poisoned(ret)) => return Err(SendTimeoutError::Poisoned(ret)),
+                Err(TrySendError::Full(ret)) => resend = ret,
+            }
+            //SAFETY: park can't be called by different threads, since Sender is !Sync.
+            if !unsafe { self.park_sender(&mut spun, deadline) } {
+                return Err(SendTimeoutError::Timeout(resend));
+            }
+        }
+    }
+
+    /// Like [`send_deadline`](Inner::send_deadline), but busy-waits for a
+    /// while before parking, whatever the sender's [`WaitStrategy`].
+    pub(super) fn send_until(&self, item: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
+        let mut resend = item;
+        for _ in 0..SEND_UNTIL_SPINS {
+            match self.try_send(resend) {
+                Ok(_) => return Ok(()),
+                Err(TrySendError::Disconnected(ret)) => return Err(SendTimeoutError::Disconnected(ret)),
+                Err(TrySendError::Poisoned(ret)) => return Err(SendTimeoutError::Poisoned(ret)),
+                Err(TrySendError::Full(ret)) => resend = ret,
+            }
+            if Instant::now() >= deadline {
+                return Err(SendTimeoutError::Timeout(resend));
+            }
+            crate::util::spin_loop();
+        }
+        self.send_deadline(resend, Some(deadline))
+    }
+
+    /// Blocks until at least `n` values can be sent without blocking,
+    /// or gives up at `deadline`.
+    ///
+    /// Returns `Ok(false)` on timeout.
+    pub(super) fn wait_capacity(&self, n: usize, deadline: Option<Instant>) -> Result<bool, SendError<()>> {
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.unsync_load() };
+        let cap = self.buffer().len();
+        assert!(n <= cap, "n exceeds the capacity of the channel");
+        let mut spun = 0;
+        loop {
+            if self.shared.drop_count.load(Acquire) != 0 {
+                return Err(SendError(()));
+            }
+            self.sender.head_cache.set(self.receiver.head.load(Acquire));
+            if cap - self.distance(self.sender.head_cache.get(), tail) >= n {
+                return Ok(true);
+            }
+            //SAFETY: park can't be called by different threads, since Sender is !Sync.
+            if !unsafe { self.park_sender(&mut spun, deadline) } {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Blocks until the receiver took every sent value, or gives up at `deadline`.
+    ///
+    /// Returns `Ok(false)` on timeout and an error if the receiver
+    /// was dropped before taking every value.
+    pub(super) fn wait_empty(&self, deadline: Option<Instant>) -> Result<bool, SendError<()>> {
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.unsync_load() };
+        let mut spun = 0;
+        loop {
+            if self.receiver.head.load(Acquire) == tail {
+                self.sender.head_cache.set(tail);
+                return Ok(true);
+            }
+            // a closed channel can still be drained, so only a drop counts.
+            if self.shared.drop_count.load(Acquire) & !(CLOSED | POISONED) != 0 {
+                if self.receiver.head.load(Acquire) == tail {
+                    return Ok(true);
+                }
+                return Err(SendError(()));
+            }
+            //SAFETY: park can't be called by different threads, since Sender is !Sync.
+            if !unsafe { self.park_sender(&mut spun, deadline) } {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Like [`wait_empty`](Inner::wait_empty), but only waits on rendezvous
+    /// channels and never blocks, returning `Ok(false)` instead.
+    #[cfg(feature = "futures")]
+    pub(super) fn try_wait_handoff(&self) -> Result<bool, SendError<()>> {
+        if !self.shared.rendezvous {
+            return Ok(true);
+        }
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.unsync_load() };
+        if self.receiver.head.load(Acquire) == tail {
+            return Ok(true);
+        }
+        if self.shared.drop_count.load(Acquire) & !(CLOSED | POISONED) != 0 {
+            return match self.receiver.head.load(Acquire) == tail {
+                true => Ok(true),
+                false => Err(SendError(())),
+            };
+        }
+        Ok(false)
+    }
+
+    /// On rendezvous channels, waits until the last sent value is received.
+    ///
+    /// If the receiver is dropped first, the value is taken back out. If it's only
+    /// closed, the value is left in place, since the receiver can still take it.
+    #[inline]
+    fn wait_handoff(&self) -> Result<(), SendError<T>> {
+        if !self.shared.rendezvous {
+            return Ok(());
+        }
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.unsync_load() };
+        let mut spun = 0;
+        loop {
+            if self.receiver.head.load(Acquire) == tail {
+                return Ok(());
+            }
+            let drop_count = self.shared.drop_count.load(Acquire);
+            if drop_count & !(CLOSED | POISONED) != 0 {
+                // the receiver may have taken the value right before being dropped.
+                if self.receiver.head.load(Acquire) == tail {
+                    return Ok(());
+                }
+                let last = self.prev_index(tail);
+                /*SAFETY:
+                 *the receiver was dropped without taking the value,
+                 *so the slot is initialised and nobody else can access it.
+                 */
+                let item = unsafe { (self.slot_ptr(last) as *mut T).read() };
+                self.sender.tail.store(last, Relaxed);
+                add_seq(&self.sender.seq, u64::MAX);
+                metrics! { self.sender.sent.sub(1); }
+                return Err(SendError(item));
+            }
+            if drop_count != 0 {
+                // closed, but the value is published and can still be drained.
+                return Ok(());
+            }
+            //SAFETY: park can't be called by different threads, since Sender is !Sync.
+            unsafe {
+                self.park_sender(&mut spun, None);
+            }
+        }
+    }
+
+    pub(super) fn recv(&self) -> Result<T, RecvError> {
+        let head = self.peek()?;
+        /*SAFETY:
+         *everything before tail has been written to by the sender.
+         */
+        let item = unsafe { (self.slot_ptr(head) as *mut T).read() };
+        //SAFETY: the item was just taken out.
+        unsafe { self.release(head) };
+        Ok(item)
+    }
+
+    /// Like [`recv`](Inner::recv), but gives up at `deadline`.
+    pub(super) fn recv_deadline(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        let head = self.peek_deadline(deadline)?;
+        /*SAFETY:
+         *everything before tail has been written to by the sender.
+         */
+        let item = unsafe { (self.slot_ptr(head) as *mut T).read() };
+        //SAFETY: the item was just taken out.
+        unsafe { self.release(head) };
+        Ok(item)
+    }
+
+    /// Appends up to `max_items` values to `buf`, waiting for them until `deadline`.
+    ///
+    /// Returns the number of received values, or an error if the
+    /// sender disconnected before any value could be received.
+    pub(super) fn recv_batch_deadline(
+        &self,
+        buf: &mut Vec<T>,
+        max_items: usize,
+        deadline: Option<Instant>,
+    ) -> Result<usize, RecvError> {
+        let start = buf.len();
+        buf.reserve(max_items.min(self.buffer().len()));
+        while buf.len() - start < max_items {
+            match self.recv_deadline(deadline) {
+                Ok(item) => buf.push(item),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(_) if buf.len() == start => return Err(RecvError {}),
+                Err(_) => break,
+            }
+            // take the values that are already there without parking or checking the clock.
+            while buf.len() - start < max_items {
+                match self.try_recv() {
+                    Ok(item) => buf.push(item),
+                    Err(_) => break,
+                }
+            }
+        }
+        Ok(buf.len() - start)
+    }
+
+    /// Blocks until a value is available and returns the receiver's head,
+    /// which can be passed to [`slot_ptr`](Inner::slot_ptr) and [`release`](Inner::release).
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn peek(&self) -> Result<usize, RecvError> {
+        match self.peek_deadline(None) {
+            Ok(head) => Ok(head),
+            Err(RecvTimeoutError::Disconnected | RecvTimeoutError::Poisoned) => Err(RecvError {}),
+            Err(RecvTimeoutError::Timeout) => unreachable!("peek without a deadline timed out"),
+        }
+    }
+
+    /// Like [`peek`](Inner::peek), but gives up at `deadline`.
+    /// A `deadline` of `None` never passes.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn peek_deadline(&self, deadline: Option<Instant>) -> Result<usize, RecvTimeoutError> {
+        if self.shared.rendezvous {
+            self.receiver.waiting.store(true, Release);
+            // a sender in send_deadline waits for this.
+            self.wake_sender();
+            let res = self.peek_blocking(deadline);
+            self.receiver.waiting.store(false, Relaxed);
+            res
+        } else {
+            self.peek_blocking(deadline)
+        }
+    }
+
+    #[inline(always)]
+    fn peek_blocking(&self, deadline: Option<Instant>) -> Result<usize, RecvTimeoutError> {
+        let mut spun = 0;
+        loop {
+            match self.try_peek() {
+                Ok(head) => return Ok(head),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Poisoned) => return Err(RecvTimeoutError::Poisoned),
+                Err(TryRecvError::Empty) => {}
+            }
+            //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+            if !unsafe { self.park_receiver(&mut spun, deadline) } {
+                return Err(RecvTimeoutError::Timeout);
+            }
+        }
+    }
+
+    pub(super) fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        if self.shared.rendezvous && !self.receiver.waiting.load(Acquire) {
+            return match self.shared.drop_count.load(Relaxed) {
+                0 => {
+                    metrics! { self.sender.full.add(1); }
+                    Err(TrySendError::Full(item))
+                }
+                _ => Err(self.send_disconnected(item)),
+            };
+        }
+        self.try_push(item)
+    }
+
+    /// [`try_send`](Inner::try_send), but without the rendezvous check.
+    pub(super) fn try_push(&self, item: T) -> Result<(), TrySendError<T>> {
+        let tail = match self.reserve() {
+            Ok(tail) => tail,
+            Err(err) => return Err(err.map(|()| item)),
+        };
+
+        /*SAFETY:
+         *the slot was just reserved and this doesn't overwrite valid <T>s
+         *because it's either uninit from Self::new() or already taken out by reader.
+         */
+        unsafe {
+            (self.slot_ptr(tail) as *mut T).write(item);
+            self.commit(tail);
+        }
+        Ok(())
+    }
+
+    /// Finds the next free slot and returns the sender's tail, which
+    /// can be passed to [`slot_ptr`](Inner::slot_ptr) and [`commit`](Inner::commit).
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn reserve(&self) -> Result<usize, TrySendError<()>> {
+        if self.sender_disconnected() {
+            return Err(self.send_disconnected(()));
+        }
+
+        /*SAFETY:
+         *tail is only modified by commit and this is
+         *an SPSC, so no other thread is modifying it.
+         */
+        #[cfg(not(feature = "loom"))]
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        let tail = unsafe { self.sender.tail.unsync_load() };
+
+        let cap = self.buffer().len();
+
+        if self.distance(self.sender.head_cache.get(), tail) == cap {
+            self.sender.head_cache.set(self.receiver.head.load(Acquire));
+
+            if self.distance(self.sender.head_cache.get(), tail) == cap {
+                // the cached check may be stale, and a disconnect takes priority.
+                if self.check_sender_disconnected() {
+                    return Err(self.send_disconnected(()));
+                }
+                metrics! { self.sender.full.add(1); }
+                self.wake_receiver();
+                return Err(TrySendError::Full(()));
+            }
+        }
+        Ok(tail)
+    }
+
+    /// Checks if the channel is disconnected, but only loads `drop_count`
+    /// once every `check_interval + 1` calls.
+    ///
+    /// Disconnecting is permanent, so the sender only has to notice it eventually,
+    /// and skipping the load keeps the shared `drop_count` out of the fast path.
+    /// Can only be called by the sender.
+    #[inline(always)]
+    fn sender_disconnected(&self) -> bool {
+        if self.sender.disconnected.get() {
+            return true;
+        }
+        match self.sender.until_check.get() {
+            0 => {
+                self.sender.until_check.set(self.sender.check_interval.get());
+                self.check_sender_disconnected()
+            }
+            left => {
+                self.sender.until_check.set(left - 1);
+                false
+            }
+        }
+    }
+
+    /// Returns the error for `item` once the channel is disconnected.
+    #[cold]
+    fn send_disconnected<U>(&self, item: U) -> TrySendError<U> {
+        match self.is_poisoned() {
+            true => TrySendError::Poisoned(item),
+            false => TrySendError::Disconnected(item),
+        }
+    }
+
+    /// Loads `drop_count` and caches the result if the channel is disconnected.
+    ///
+    /// Can only be called by the sender.
+    fn check_sender_disconnected(&self) -> bool {
+        let disconnected = self.shared.drop_count.load(Relaxed) != 0;
+        if disconnected {
+            self.sender.disconnected.set(true);
+        }
+        disconnected
+    }
+
+    /// Returns a pointer to the slot at `index`.
+    #[inline]
+    pub(super) fn slot_ptr(&self, index: usize) -> *mut MaybeUninit<T> {
+        //SAFETY: slot_index returns values in [0, cap), so the get_unchecked call is valid.
+        let slot = unsafe { self.buffer().get_unchecked(self.slot_index(index)) };
+        #[cfg(not(feature = "loom"))]
+        return slot.get();
+        #[cfg(feature = "loom")]
+        return slot.with_mut(|ptr| ptr);
+    }
+
+    /// Returns the position of `index` in the buffer, which is in `[0, cap)`.
+    #[inline(always)]
+    fn slot_index(&self, index: usize) -> usize {
+        let cap = self.buffer().len();
+        if self.shared.exact {
+            // index is in [0, 2 * cap)
+            if index >= cap {
+                index - cap
+            } else {
+                index
+            }
+        } else {
+            // cap is a power of two
+            index & (cap - 1)
+        }
+    }
+
+    /// Returns the index after `index`.
+    #[inline(always)]
+    fn next_index(&self, index: usize) -> usize {
+        if self.shared.exact {
+            let next = index + 1;
+            if next == 2 * self.buffer().len() {
+                0
+            } else {
+                next
+            }
+        } else {
+            index.wrapping_add(1)
+        }
+    }
+
+    /// Returns the index `n` positions after `index`, where `n <= cap`.
+    #[inline(always)]
+    pub(super) fn advance_index(&self, index: usize, n: usize) -> usize {
+        if self.shared.exact {
+            let wrap = 2 * self.buffer().len();
+            if n >= wrap - index {
+                n - (wrap - index)
+            } else {
+                index + n
+            }
+        } else {
+            index.wrapping_add(n)
+        }
+    }
+
+    /// Returns the index before `index`.
+    #[inline(always)]
+    fn prev_index(&self, index: usize) -> usize {
+        if self.shared.exact && index == 0 {
+            2 * self.buffer().len() - 1
+        } else {
+            index.wrapping_sub(1)
+        }
+    }
+
+    /// Returns the number of indices in `[from, to)`.
+    ///
+    /// Power of two capacities wrap at `usize::MAX`, which takes only ~4B values
+    /// on 32-bit targets. The wrapping difference is still exact, since `to` is
+    /// never more than `cap <= MAX_CAPACITY` indices ahead of `from`: the caches
+    /// only lag behind the index they copy, and never by more than the buffer holds.
+    #[inline(always)]
+    fn distance(&self, from: usize, to: usize) -> usize {
+        if self.shared.exact && to < from {
+            // `to + 2 * cap` could overflow for capacities close to MAX_CAPACITY.
+            2 * self.buffer().len() - (from - to)
+        } else {
+            to.wrapping_sub(from)
+        }
+    }
+
+    /// Publishes the slot reserved by [`reserve`](Inner::reserve).
+    ///
+    /// # Safety
+    ///
+    /// `tail` must be the result of the last `reserve` call
+    /// and its slot must be initialised.
+    #[inline]
+    pub(super) unsafe fn commit(&self, tail: usize) {
+        let tail = self.next_index(tail);
+        self.sender.tail.store(tail, Release);
+        add_seq(&self.sender.seq, 1);
+        metrics! { self.sender.sent.add(1); }
+        self.wake_after_send(tail);
+    }
+
+    /// Publishes the `n` slots starting at the one reserved by [`reserve`](Inner::reserve).
+    ///
+    /// # Safety
+    ///
+    /// `tail` must be the result of the last `reserve` call, `n` can't be
+    /// greater than [`free_from`](Inner::free_from) returned and the first
+    /// `n` slots must be initialised.
+    pub(super) unsafe fn commit_n(&self, tail: usize, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let tail = self.advance_index(tail, n);
+        self.sender.tail.store(tail, Release);
+        add_seq(&self.sender.seq, n as u64);
+        metrics! { self.sender.sent.add(n as u64); }
+        self.wake_after_send(tail);
+    }
+
+    /// Like [`reserve`](Inner::reserve), but only succeeds if `n` slots are free.
+    ///
+    /// Can only be called by the sender.
+    #[allow(dead_code)]
+    pub(super) fn reserve_n(&self, n: usize) -> Result<usize, TrySendError<()>> {
+        assert!(n <= self.buffer().len(), "n exceeds the capacity of the channel");
+        let tail = self.reserve()?;
+        if self.free_from(tail, n) < n {
+            metrics! { self.sender.full.add(1); }
+            self.wake_receiver();
+            return Err(TrySendError::Full(()));
+        }
+        Ok(tail)
+    }
+
+    /// Returns how many values were sent, wrapping around at `u64::MAX`.
+    #[inline]
+    pub(super) fn tail_seq(&self) -> u64 {
+        self.sender.seq.load(Relaxed)
+    }
+
+    /// Returns how many values were received, wrapping around at `u64::MAX`.
+    ///
+    /// Values overwritten by `force_send` count as received.
+    #[inline]
+    pub(super) fn head_seq(&self) -> u64 {
+        self.receiver.seq.load(Relaxed)
+    }
+
+    /// Returns how many slots starting at `tail` are free, up to `max`.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn free_from(&self, tail: usize, max: usize) -> usize {
+        let cap = self.buffer().len();
+        if cap - self.distance(self.sender.head_cache.get(), tail) < max {
+            self.sender.head_cache.set(self.receiver.head.load(Acquire));
+        }
+        max.min(cap - self.distance(self.sender.head_cache.get(), tail))
+    }
+
+    /// Wakes the receiver after values up to `tail` were published, unless
+    /// fewer than `wake_threshold` values are pending.
+    ///
+    /// A receiver that goes to sleep with fewer values pending is only woken by
+    /// a later send, a full buffer, an explicit `wake_receiver` or a disconnect.
+    /// Can only be called by the sender.
+    #[inline(always)]
+    fn wake_after_send(&self, tail: usize) {
+        if let Some(watermarks) = &self.shared.watermarks {
+            self.check_high(watermarks, tail);
+        }
+        let threshold = self.sender.wake_threshold.get();
+        if threshold != 0 {
+            // head_cache is never ahead of head, so this can only overestimate.
+            if self.distance(self.sender.head_cache.get(), tail) < threshold {
+                return;
+            }
+            self.sender.head_cache.set(self.receiver.head.load(Acquire));
+            if self.distance(self.sender.head_cache.get(), tail) < threshold {
+                return;
+            }
+        }
+        // a receiver that's already notified doesn't need the swap in unpark.
+        if self.sender.recv_park.is_parked() {
+            self.wake_receiver();
+        }
+    }
+
+    /// Calls `on_high` if at least `high` values are pending after values up to
+    /// `tail` were published, unless it was called since `on_low` last was.
+    ///
+    /// Can only be called by the sender.
+    #[inline(never)]
+    fn check_high(&self, watermarks: &Watermarks, tail: usize) {
+        if self.shared.above_high.load(Relaxed) {
+            return;
+        }
+        // head_cache is never ahead of head, so this can only overestimate.
+        if self.distance(self.sender.head_cache.get(), tail) < watermarks.high {
+            return;
+        }
+        self.sender.head_cache.set(self.receiver.head.load(Acquire));
+        if self.distance(self.sender.head_cache.get(), tail) >= watermarks.high
+            && !self.shared.above_high.swap(true, AcqRel)
+        {
+            (watermarks.on_high)();
+        }
+    }
+
+    /// Calls `on_low` if at most `low` values are pending after values up to
+    /// `head` were received, and `on_high` was called since `on_low` last was.
+    ///
+    /// Can only be called by the receiver.
+    #[inline(always)]
+    fn check_low(&self, head: usize) {
+        if let Some(watermarks) = &self.shared.watermarks {
+            if self.shared.above_high.load(Relaxed) {
+                self.check_low_slow(watermarks, head);
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn check_low_slow(&self, watermarks: &Watermarks, head: usize) {
+        // tail_cache is never ahead of tail, so this can only underestimate.
+        if self.distance(head, self.receiver.tail_cache.get()) > watermarks.low {
+            return;
+        }
+        self.receiver.tail_cache.set(self.sender.tail.load(Acquire));
+        if self.distance(head, self.receiver.tail_cache.get()) <= watermarks.low
+            && self.shared.above_high.swap(false, AcqRel)
+        {
+            (watermarks.on_low)();
+        }
+    }
+
+    /// Copies as many values from `items` as fit into the buffer
+    /// and publishes them at once. Returns the number of copied values.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn try_send_slice(&self, items: &[T]) -> Result<usize, SendError<()>>
+    where
+        T: Copy,
+    {
+        if self.shared.rendezvous || items.is_empty() {
+            // a rendezvous only hands off one value at a time.
+            return match items.first().map(|&item| self.try_send(item)) {
+                Some(Ok(())) => Ok(1),
+                Some(Err(TrySendError::Disconnected(_) | TrySendError::Poisoned(_))) => {
+                    Err(SendError(()))
+                }
+                Some(Err(TrySendError::Full(_))) | None => match self.peer_connected() {
+                    true => Ok(0),
+                    false => Err(SendError(())),
+                },
+            };
+        }
+        let tail = match self.reserve() {
+            Ok(tail) => tail,
+            Err(TrySendError::Full(())) => return Ok(0),
+            Err(_) => return Err(SendError(())),
+        };
+
+        let len = self.free_from(tail, items.len());
+
+        /*SAFETY:
+         *the slots in [tail, tail + len) are free, since len is at most the free
+         *capacity. The buffer has the same layout as [T], like in drain_to_vec,
+         *and the free slots are in at most two contiguous runs.
+         */
+        #[cfg(not(feature = "loom"))]
+        unsafe {
+            let first = len.min(self.buffer().len() - self.slot_index(tail));
+            let src = items.as_ptr();
+            std::ptr::copy_nonoverlapping(src, self.slot_ptr(tail) as *mut T, first);
+            let start = self.buffer().as_ptr() as *mut T;
+            std::ptr::copy_nonoverlapping(src.add(first), start, len - first);
+        }
+        // loom's UnsafeCell has a different layout, so copy value by value.
+        #[cfg(feature = "loom")]
+        {
+            let mut index = tail;
+            for &item in &items[..len] {
+                //SAFETY: same as above.
+                unsafe { (self.slot_ptr(index) as *mut T).write(item) };
+                index = self.next_index(index);
+            }
+        }
+
+        //SAFETY: the values were just copied.
+        unsafe { self.commit_n(tail, len) };
+        Ok(len)
+    }
+
+    /// Like [`try_send_slice`](Inner::try_send_slice),
+    /// but blocks until at least one value is sent.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn send_slice(&self, items: &[T]) -> Result<usize, SendError<()>>
+    where
+        T: Copy,
+    {
+        if self.shared.rendezvous {
+            return match items.first() {
+                Some(&item) => self.send(item).map(|()| 1).map_err(|_| SendError(())),
+                None => self.try_send_slice(items),
+            };
+        }
+        loop {
+            match self.try_send_slice(items)? {
+                0 if !items.is_empty() => {
+                    self.wait_capacity(1, None)?;
+                }
+                n => return Ok(n),
+            }
+        }
+    }
+
+    /// Sends every value from `items`, blocking while the buffer is full.
+    ///
+    /// Only one value is taken from `items` before there's a free slot for it,
+    /// so on disconnect the unsent values are still in `items`, except for the
+    /// one returned in the error. Values are written into the free slots and
+    /// published together, which wakes the receiver once per run instead of
+    /// once per value.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn send_iter(&self, items: &mut impl Iterator<Item = T>) -> Result<(), SendError<Option<T>>> {
+        if self.shared.rendezvous {
+            // a rendezvous only hands off one value at a time.
+            for item in items {
+                self.send(item).map_err(|SendError(item)| SendError(Some(item)))?;
+            }
+            return Ok(());
+        }
+        let cap = self.buffer().len();
+        while let Some(item) = items.next() {
+            let tail = loop {
+                match self.reserve() {
+                    Ok(tail) => break tail,
+                    Err(TrySendError::Full(())) => {
+                        if self.wait_capacity(1, None).is_err() {
+                            return Err(SendError(Some(item)));
+                        }
+                    }
+                    Err(_) => return Err(SendError(Some(item))),
+                }
+            };
+            if self.distance(self.sender.head_cache.get(), tail) != 0 {
+                self.sender.head_cache.set(self.receiver.head.load(Acquire));
+            }
+            let free = cap - self.distance(self.sender.head_cache.get(), tail);
+
+            /*SAFETY:
+             *the slots in [tail, tail + free) are free, so this doesn't
+             *overwrite valid <T>s, and the receiver doesn't read them
+             *before tail is published.
+             */
+            unsafe { (self.slot_ptr(tail) as *mut T).write(item) };
+            let mut end = self.next_index(tail);
+            let mut sent = 1;
+            while sent < free {
+                let Some(item) = items.next() else { break };
+                //SAFETY: same as above.
+                unsafe { (self.slot_ptr(end) as *mut T).write(item) };
+                end = self.next_index(end);
+                sent += 1;
+            }
+            self.sender.tail.store(end, Release);
+            add_seq(&self.sender.seq, sent as u64);
+            metrics! { self.sender.sent.add(sent as u64); }
+            self.wake_after_send(end);
+        }
+        Ok(())
+    }
+
+    pub(super) fn try_recv(&self) -> Result<T, TryRecvError> {
+        let head = self.try_peek()?;
+        /*SAFETY:
+         *everything before tail has been written to by the sender.
+         */
+        let item = unsafe { (self.slot_ptr(head) as *mut T).read() };
+        //SAFETY: the item was just taken out.
+        unsafe { self.release(head) };
+        Ok(item)
+    }
+
+    /// Returns the receiver's head if a value is available, which can be
+    /// passed to [`slot_ptr`](Inner::slot_ptr) and [`release`](Inner::release).
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn try_peek(&self) -> Result<usize, TryRecvError> {
+        use TryRecvError::*;
+        let head = if self.shared.overwrite {
+            // force_send also modifies head, see `consuming`.
+            self.lock_consumer();
+            let head = self.receiver.head.load(Relaxed);
+            // force_send may have moved head past the cached tail.
+            if self.distance(head, self.receiver.tail_cache.get()) > self.buffer().len() {
+                self.receiver.tail_cache.set(head);
+            }
+            head
+        } else {
+            /*SAFETY:
+             *head is only modified by release and this is
+             *an SPSC, so no other thread is modifying it.
+             */
+            #[cfg(not(feature = "loom"))]
+            let head = unsafe { self.receiver.head.as_ptr().read() };
+            #[cfg(feature = "loom")]
+            let head = unsafe { self.receiver.head.unsync_load() };
+            head
+        };
+
+        if head == self.receiver.tail_cache.get() {
+            // tail_cache is up to date after a disconnect, so skip the loads.
+            if self.receiver.disconnected.get() {
+                if self.shared.overwrite {
+                    self.unlock_consumer();
+                }
+                return Err(self.recv_disconnected());
+            }
+            self.receiver.tail_cache.set(self.sender.tail.load(Acquire));
+            metrics! { self.record_depth(head); }
+            if head == self.receiver.tail_cache.get() {
+                if self.shared.overwrite {
+                    self.unlock_consumer();
+                }
+                // Let the receiver consume all the messages after sender disconnects.
+                if self.shared.drop_count.load(Acquire) != 0 {
+                    self.receiver.tail_cache.set(self.sender.tail.load(Relaxed));
+                    if head == self.receiver.tail_cache.get() {
+                        self.receiver.disconnected.set(true);
+                        return Err(self.recv_disconnected());
+                    }
+                }
+                metrics! { self.receiver.empty.add(1); }
+                self.wake_sender();
+                return Err(Empty);
+            }
+        }
+        Ok(head)
+    }
+
+    /// Returns the error once the channel is disconnected and empty.
+    #[cold]
+    fn recv_disconnected(&self) -> TryRecvError {
+        match self.is_poisoned() {
+            true => TryRecvError::Poisoned,
+            false => TryRecvError::Disconnected,
+        }
+    }
 
-        !!!DO NOT DELETE THE CODE BELOW!!!
-        */
+    /// Copies as many pending values as fit into `buf` and frees
+    /// their slots at once. Returns the number of copied values.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn try_recv_slice(&self, buf: &mut [T]) -> Result<usize, RecvError>
+    where
+        T: Copy,
+    {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.try_peek() {
+            //SAFETY: head was just returned by try_peek.
+            Ok(head) => Ok(unsafe { self.read_slice(head, buf) }),
+            Err(TryRecvError::Empty) => Ok(0),
+            Err(_) => Err(RecvError {}),
+        }
+    }
+
+    /// Like [`try_recv_slice`](Inner::try_recv_slice),
+    /// but blocks until at least one value is received.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn recv_slice(&self, buf: &mut [T]) -> Result<usize, RecvError>
+    where
+        T: Copy,
+    {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let head = self.peek()?;
+        //SAFETY: head was just returned by peek.
+        Ok(unsafe { self.read_slice(head, buf) })
+    }
+
+    /// Copies the values starting at `head` into `buf` and frees their slots.
+    ///
+    /// # Safety
+    ///
+    /// `head` must be the result of the last `peek` or `try_peek`
+    /// call and `buf` can't be empty.
+    unsafe fn read_slice(&self, head: usize, buf: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let len = self.pending_from(head, buf.len());
+
+        /*SAFETY:
+         *all values in [head, head + len) have been sent, but not received.
+         *The buffer has the same layout as [T], like in drain_to_vec,
+         *and the values are in at most two contiguous runs.
+         */
+        #[cfg(not(feature = "loom"))]
+        unsafe {
+            let dst = buf.as_mut_ptr();
+            let first = len.min(self.buffer().len() - self.slot_index(head));
+            std::ptr::copy_nonoverlapping(self.slot_ptr(head) as *const T, dst, first);
+            let start = self.buffer().as_ptr() as *const T;
+            std::ptr::copy_nonoverlapping(start, dst.add(first), len - first);
+        }
+        // loom's UnsafeCell has a different layout, so copy value by value.
         #[cfg(feature = "loom")]
-        let buffer = (0..capacity)
-            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
-            .collect::<Box<[UnsafeCell<MaybeUninit<T>>]>>();
-        Self {
-            sender: CacheAligned::default(),
-            receiver: CacheAligned::default(),
-            shared: SharedData {
-                buffer: buffer,
-                drop_count: AtomicUsize::default(),
-            },
+        {
+            let mut index = head;
+            for slot in &mut buf[..len] {
+                //SAFETY: same as above.
+                *slot = unsafe { (self.slot_ptr(index) as *mut T).read() };
+                index = self.next_index(index);
+            }
         }
+
+        //SAFETY: the values were copied out.
+        unsafe { self.release_n(head, len) };
+        len
     }
 
-    pub(super) fn send(&self, item: T) -> Result<(), SendError<T>> {
-        let mut resend = match self.try_send(item) {
-            Ok(_) => return Ok(()),
-            Err(TrySendError::Disconnected(ret)) => return Err(SendError(ret)),
-            Err(TrySendError::Full(ret)) => ret,
+    /// Moves all values sent so far into a `Vec`.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn drain_to_vec(&self) -> Vec<T> {
+        let head = if self.shared.overwrite {
+            // force_send also modifies head, see `consuming`.
+            self.lock_consumer();
+            self.receiver.head.load(Relaxed)
+        } else {
+            //SAFETY: head is only modified by the receiver.
+            #[cfg(not(feature = "loom"))]
+            let head = unsafe { self.receiver.head.as_ptr().read() };
+            //SAFETY: head is only modified by the receiver.
+            #[cfg(feature = "loom")]
+            let head = unsafe { self.receiver.head.unsync_load() };
+            head
         };
-        loop {
-            //SAFETY: park can't be called by different threads, since Sender is !Sync.
-            unsafe {
-                self.receiver.send_park.park();
-            }
+        let tail = self.sender.tail.load(Acquire);
+        self.receiver.tail_cache.set(tail);
 
-            match self.try_send(resend) {
-                Ok(_) => break Ok(()),
-                Err(TrySendError::Disconnected(ret)) => break Err(SendError(ret)),
-                Err(TrySendError::Full(ret)) => resend = ret,
+        let len = self.distance(head, tail);
+        let mut vec = Vec::with_capacity(len);
+        /*SAFETY:
+         *all values in [head, tail) have been sent, but not received, and
+         *the sender doesn't touch them until head is moved past them.
+         *UnsafeCell and MaybeUninit are transparent, so the buffer has the
+         *same layout as [T] and the values are in at most two contiguous runs.
+         */
+        #[cfg(not(feature = "loom"))]
+        unsafe {
+            let dst = vec.as_mut_ptr();
+            let first = len.min(self.buffer().len() - self.slot_index(head));
+            std::ptr::copy_nonoverlapping(self.slot_ptr(head) as *const T, dst, first);
+            let start = self.buffer().as_ptr() as *const T;
+            std::ptr::copy_nonoverlapping(start, dst.add(first), len - first);
+            vec.set_len(len);
+        }
+        // loom's UnsafeCell has a different layout, so copy value by value.
+        #[cfg(feature = "loom")]
+        {
+            let mut index = head;
+            while index != tail {
+                //SAFETY: same as above.
+                vec.push(unsafe { (self.slot_ptr(index) as *mut T).read() });
+                index = self.next_index(index);
             }
         }
+
+        self.receiver.head.store(tail, Release);
+        add_seq(&self.receiver.seq, len as u64);
+        metrics! { self.receiver.received.add(len as u64); }
+        if self.shared.overwrite {
+            self.unlock_consumer();
+        }
+        self.wake_sender();
+        self.check_low(tail);
+        vec
     }
 
-    pub(super) fn recv(&self) -> Result<T, RecvError> {
-        match self.try_recv() {
-            Ok(ret) => return Ok(ret),
-            Err(TryRecvError::Disconnected) => return Err(RecvError {}),
-            Err(TryRecvError::Empty) => {}
-        };
+    /// Frees the slot returned by [`peek`](Inner::peek) or [`try_peek`](Inner::try_peek).
+    ///
+    /// # Safety
+    ///
+    /// `head` must be the result of the last `peek` or `try_peek`
+    /// call and the value in its slot must be taken out or dropped.
+    #[inline]
+    pub(super) unsafe fn release(&self, head: usize) {
+        let head = self.next_index(head);
+        self.receiver.head.store(head, Release);
+        add_seq(&self.receiver.seq, 1);
+        metrics! { self.receiver.received.add(1); }
+        if self.shared.overwrite {
+            self.unlock_consumer();
+        }
+        self.wake_sender();
+        self.check_low(head);
+    }
+
+    /// Frees the `n` slots starting at the one returned by
+    /// [`peek`](Inner::peek) or [`try_peek`](Inner::try_peek).
+    ///
+    /// # Safety
+    ///
+    /// `head` must be the result of the last `peek` or `try_peek` call
+    /// and the values in the first `n` slots must be taken out or dropped.
+    pub(super) unsafe fn release_n(&self, head: usize, n: usize) {
+        let head = self.advance_index(head, n);
+        if n != 0 {
+            self.receiver.head.store(head, Release);
+            add_seq(&self.receiver.seq, n as u64);
+            metrics! { self.receiver.received.add(n as u64); }
+        }
+        if self.shared.overwrite {
+            self.unlock_consumer();
+        }
+        if n != 0 {
+            self.wake_sender();
+            self.check_low(head);
+        }
+    }
+
+    /// Returns how many values starting at `head` are pending, up to `max`.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn pending_from(&self, head: usize, max: usize) -> usize {
+        if self.distance(head, self.receiver.tail_cache.get()) < max {
+            self.receiver.tail_cache.set(self.sender.tail.load(Acquire));
+            metrics! { self.record_depth(head); }
+        }
+        max.min(self.distance(head, self.receiver.tail_cache.get()))
+    }
+
+    /// Returns the slots in `[index, index + len)` as at most two contiguous runs.
+    ///
+    /// `len` can't be greater than the capacity.
+    #[cfg(not(feature = "loom"))]
+    pub(super) fn slot_runs(
+        &self,
+        index: usize,
+        len: usize,
+    ) -> (*mut [MaybeUninit<T>], *mut [MaybeUninit<T>]) {
+        let first = len.min(self.buffer().len() - self.slot_index(index));
+        // Slot<T> is transparent, so the buffer has the same layout as [MaybeUninit<T>].
+        let start = self.buffer().as_ptr() as *mut MaybeUninit<T>;
+        (
+            std::ptr::slice_from_raw_parts_mut(self.slot_ptr(index), first),
+            std::ptr::slice_from_raw_parts_mut(start, len - first),
+        )
+    }
+
+    /// Sends `item`, replacing the oldest value if the channel is full.
+    ///
+    /// Behaves like [`send`](Inner::send) if `overwriting` wasn't called.
+    pub(super) fn force_send(&self, item: T) -> Result<Option<T>, SendError<T>> {
+        if !self.shared.overwrite {
+            return self.send(item).map(|()| None);
+        }
         loop {
-            //SAFETY: park can't be called by different threads, since Receiver is !Sync.
-            unsafe {
-                self.sender.recv_park.park();
+            match self.reserve() {
+                Ok(tail) => {
+                    //SAFETY: same as try_send.
+                    unsafe {
+                        (self.slot_ptr(tail) as *mut T).write(item);
+                        self.commit(tail);
+                    }
+                    return Ok(None);
+                }
+                Err(TrySendError::Full(())) => {}
+                Err(_) => return Err(SendError(item)),
             }
 
-            match self.try_recv() {
-                Ok(ret) => return Ok(ret),
-                Err(TryRecvError::Disconnected) => return Err(RecvError {}),
-                Err(TryRecvError::Empty) => {}
+            if !self.try_lock_consumer() {
+                // the receiver is taking a value out, so a slot is about to free up.
+                crate::util::spin_loop();
+                continue;
+            }
+            let head = self.receiver.head.load(Relaxed);
+            #[cfg(not(feature = "loom"))]
+            //SAFETY: tail is only modified by the sender.
+            let tail = unsafe { self.sender.tail.as_ptr().read() };
+            #[cfg(feature = "loom")]
+            //SAFETY: tail is only modified by the sender.
+            let tail = unsafe { self.sender.tail.unsync_load() };
+            if self.distance(head, tail) != self.buffer().len() {
+                self.unlock_consumer();
+                continue;
+            }
+            /*SAFETY:
+             *the channel is full, so the slot at head is initialised
+             *and the receiver can't access it while we hold the lock.
+             */
+            let oldest = unsafe { (self.slot_ptr(head) as *mut T).read() };
+            let head = self.next_index(head);
+            self.receiver.head.store(head, Relaxed);
+            // the overwritten value counts as received.
+            add_seq(&self.receiver.seq, 1);
+            self.unlock_consumer();
+            self.sender.head_cache.set(head);
+
+            /*SAFETY:
+             *the receiver only reads values in [head + 1, tail),
+             *and the slot at tail is the one emptied above.
+             */
+            unsafe {
+                (self.slot_ptr(tail) as *mut T).write(item);
+                self.commit(tail);
             }
+            return Ok(Some(oldest));
         }
     }
 
-    pub(super) fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
-        if self.shared.drop_count.load(Relaxed) != 0 {
-            return Err(TrySendError::Disconnected(item));
+    #[inline]
+    fn try_lock_consumer(&self) -> bool {
+        self.shared
+            .consuming
+            .compare_exchange(false, true, Acquire, Relaxed)
+            .is_ok()
+    }
+
+    #[inline]
+    fn lock_consumer(&self) {
+        while !self.try_lock_consumer() {
+            crate::util::spin_loop();
         }
+    }
 
-        /*SAFETY:
-         *tail is only modified by try_send and this is
-         *an SPSC, so no other thread is modifying it.
-         */
+    #[inline]
+    fn unlock_consumer(&self) {
+        self.shared.consuming.store(false, Release);
+    }
+
+    pub(super) fn peer_connected(&self) -> bool {
+        self.shared.drop_count.load(Acquire) == 0
+    }
+
+    /// Disconnects the channel without dropping an end point.
+    ///
+    /// Waking the other end point is left to the caller.
+    pub(super) fn close(&self) {
+        self.shared.drop_count.fetch_or(CLOSED, AcqRel);
+    }
+
+    /// Poisons and disconnects the channel if it was created with
+    /// [`poisoning`](Inner::poisoning) and the current thread is panicking.
+    ///
+    /// Returns `true` if the channel was poisoned, waking the other end point
+    /// is left to the caller.
+    pub(super) fn poison_if_panicking(&self) -> bool {
+        let poison = self.shared.poisoning && std::thread::panicking();
+        if poison {
+            self.shared.drop_count.fetch_or(CLOSED | POISONED, AcqRel);
+        }
+        poison
+    }
+
+    pub(super) fn is_poisoned(&self) -> bool {
+        self.shared.drop_count.load(Acquire) & POISONED != 0
+    }
+
+    /// Like [`close`](Inner::close), but the sender sees the disconnect right away.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn close_sender(&self) {
+        self.close();
+        self.sender.disconnected.set(true);
+    }
+
+    /// Checks if the buffer is full without updating the sender's cache.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn is_full(&self) -> bool {
         #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
         let tail = unsafe { self.sender.tail.as_ptr().read() };
         #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
         let tail = unsafe { self.sender.tail.unsync_load() };
+        let cap = self.buffer().len();
+        // the cached head is never ahead of the real one.
+        self.distance(self.sender.head_cache.get(), tail) == cap
+            && self.distance(self.receiver.head.load(Acquire), tail) == cap
+    }
 
-        let cap = self.shared.buffer.len();
+    /// Returns how many values the buffer can hold.
+    #[inline]
+    pub(super) fn capacity(&self) -> usize {
+        self.buffer().len()
+    }
 
-        if tail == self.sender.head_cache.get().wrapping_add(cap) {
-            self.sender.head_cache.set(self.receiver.head.load(Acquire));
+    /// Connects the channel again after end points that borrowed it were
+    /// dropped, keeping the values they left behind.
+    ///
+    /// Taking `&mut self` ensures that no end point is left.
+    pub(super) fn reconnect(&mut self) {
+        self.shared.drop_count.store(0, Relaxed);
+        self.sender.disconnected.set(false);
+        self.sender.until_check.set(0);
+        self.receiver.disconnected.set(false);
+    }
 
-            if tail == self.sender.head_cache.get().wrapping_add(cap) {
-                self.wake_receiver();
-                return Err(TrySendError::Full(item));
-            }
-        }
+    /// Returns a lower bound of how many values can be sent without blocking.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn slots_free(&self) -> usize {
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.unsync_load() };
+        self.buffer().len() - self.distance(self.receiver.head.load(Acquire), tail)
+    }
 
-        unsafe {
-            /*SAFETY:
-             *cap( = self.shared.buffer.len()) is a power of two,
-             *so <tail & (cap - 1)> is in [0, cap) and
-             *the get_unchecked call is valid.
-             */
-            let slot = self.shared.buffer.get_unchecked(tail & (cap - 1));
-            /*SAFETY:
-             *receiver only reads values past self.reader.head
-             *and the if block above checks for this.
-             */
-            slot.with_mut(|ptr| {
-                /*SAFETY:
-                 *this doesn't overwrite valid <T>s because it's either
-                 *uninit from Self::new() or already taken out by reader.
-                 */
-                (ptr as *mut T).write(item)
-            });
+    /// Like [`slots_free`](Inner::slots_free), but only uses the sender's cache
+    /// of the receiver's head, as of the last time the sender looked at it.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn last_known_free(&self) -> usize {
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.unsync_load() };
+        // the cached head is never ahead of the real one.
+        self.buffer().len() - self.distance(self.sender.head_cache.get(), tail)
+    }
+
+    /// Returns a lower bound of how many values are pending.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn len_hint(&self) -> usize {
+        let head = if self.shared.overwrite {
+            // force_send may modify head.
+            self.receiver.head.load(Acquire)
+        } else {
+            #[cfg(not(feature = "loom"))]
+            //SAFETY: head is only modified by the receiver.
+            let head = unsafe { self.receiver.head.as_ptr().read() };
+            #[cfg(feature = "loom")]
+            //SAFETY: head is only modified by the receiver.
+            let head = unsafe { self.receiver.head.unsync_load() };
+            head
+        };
+        // force_send may move both indices between the loads.
+        self.distance(head, self.sender.tail.load(Acquire))
+            .min(self.buffer().len())
+    }
+
+    /// Checks if a value is pending without updating the receiver's cache.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn has_pending(&self) -> bool {
+        if self.shared.overwrite {
+            // force_send may modify head, so the caches can't be used.
+            return self.receiver.head.load(Acquire) != self.sender.tail.load(Acquire);
         }
-        self.sender.tail.store(tail.wrapping_add(1), Release);
-        self.wake_receiver();
-        Ok(())
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: head is only modified by the receiver.
+        let head = unsafe { self.receiver.head.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: head is only modified by the receiver.
+        let head = unsafe { self.receiver.head.unsync_load() };
+        head != self.receiver.tail_cache.get() || head != self.sender.tail.load(Acquire)
     }
 
-    pub(super) fn try_recv(&self) -> Result<T, TryRecvError> {
-        use TryRecvError::*;
+    /// Checks if `try_recv` would return something other than [`TryRecvError::Empty`].
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn is_ready(&self) -> bool {
+        if self.shared.overwrite {
+            // force_send may modify head, so the caches can't be used.
+            let head = self.receiver.head.load(Acquire);
+            return head != self.sender.tail.load(Acquire)
+                || self.shared.drop_count.load(Acquire) != 0;
+        }
         /*SAFETY:
          *head is only modified by try_recv and this is
          *an SPSC, so no other thread is modifying it.
@@ -154,42 +1662,65 @@ impl<T> Inner<T> {
         #[cfg(feature = "loom")]
         let head = unsafe { self.receiver.head.unsync_load() };
 
-        if head == self.receiver.tail_cache.get() {
-            self.receiver.tail_cache.set(self.sender.tail.load(Acquire));
-            if head == self.receiver.tail_cache.get() {
-                // Let the receiver consume all the messages after sender disconnects.
-                if self.shared.drop_count.load(Acquire) != 0 {
-                    self.receiver.tail_cache.set(self.sender.tail.load(Relaxed));
-                    if head == self.receiver.tail_cache.get() {
-                        return Err(Disconnected);
-                    }
-                }
-                self.wake_sender();
-                return Err(Empty);
-            }
+        if head != self.receiver.tail_cache.get() {
+            return true;
         }
+        self.receiver.tail_cache.set(self.sender.tail.load(Acquire));
+        head != self.receiver.tail_cache.get() || self.shared.drop_count.load(Acquire) != 0
+    }
 
-        let buffer = &self.shared.buffer;
-        let item = unsafe {
-            /*SAFETY:
-             *buffer.len() is a power of two,
-             *so <tail & buffer.len()> is in [0, buffer.len()) and
-             *the get_unchecked call is valid.
-             */
-            let slot = buffer.get_unchecked(head & (buffer.len() - 1));
-            /*SAFETY:
-             *everything before tail has been written to by the sender.
-             */
-            slot.with_mut(|ptr| (ptr as *mut T).read())
-        };
+    /// Makes [`wake_receiver`](Inner::wake_receiver) also wake `waker`.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::register`.
+    pub(super) unsafe fn register_receiver(&self, waker: Arc<Parker>) -> bool {
+        self.sender.recv_park.register(waker)
+    }
 
-        self.receiver.head.store(head.wrapping_add(1), Release);
-        self.wake_sender();
-        Ok(item)
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::unregister`.
+    pub(super) unsafe fn unregister_receiver(&self) {
+        self.sender.recv_park.unregister()
     }
 
-    pub(super) fn peer_connected(&self) -> bool {
-        self.shared.drop_count.load(Acquire) == 0
+    /// Drops all pending items, leaving the channel empty.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have exclusive access to the channel,
+    /// i.e. no other thread may be using either endpoint.
+    pub(super) unsafe fn clear(&self) {
+        //head points to the first not read element
+        //tail points after the last written element
+        #[cfg(not(feature = "loom"))]
+        let (mut head, tail) = (
+            self.receiver.head.as_ptr().read(),
+            self.sender.tail.as_ptr().read(),
+        );
+        #[cfg(feature = "loom")]
+        let (mut head, tail) = (
+            self.receiver.head.unsync_load(),
+            self.sender.tail.unsync_load(),
+        );
+
+        while head != tail {
+            /*SAFETY:
+             *all elements in [head, tail) have been sent, but not received.
+             */
+            (self.slot_ptr(head) as *mut T).drop_in_place();
+            head = self.next_index(head);
+        }
+
+        self.receiver.head.store(tail, Relaxed);
+        // the dropped values count as received.
+        self.receiver.seq.store(self.sender.seq.load(Relaxed), Relaxed);
+        self.receiver.tail_cache.set(tail);
+        self.receiver.disconnected.set(false);
+        self.sender.head_cache.set(tail);
+        self.sender.disconnected.set(false);
+        self.sender.until_check.set(0);
     }
 
     #[inline]
@@ -197,66 +1728,270 @@ impl<T> Inner<T> {
         self.sender.recv_park.unpark();
     }
 
+    /// Makes [`wake_receiver`](Inner::wake_receiver) also call `notify`.
+    pub(super) fn set_receiver_notify(&self, notify: Option<Notify>) {
+        self.sender.recv_park.set_notify(notify);
+    }
+
+    /// Makes [`wake_receiver`](Inner::wake_receiver) also wake `waker`,
+    /// replacing the callback set by [`set_receiver_notify`](Inner::set_receiver_notify).
+    #[cfg(feature = "futures")]
+    pub(super) fn set_receiver_waker(&self, waker: &std::task::Waker) {
+        self.sender.recv_park.set_waker(waker);
+    }
+
+    /// Makes [`wake_sender`](Inner::wake_sender) also wake `waker`.
+    #[cfg(feature = "futures")]
+    pub(super) fn set_sender_waker(&self, waker: &std::task::Waker) {
+        self.receiver.send_park.set_waker(waker);
+    }
+
+    /// Waits for the receiver, see [`WaitStrategy::wait_deadline`].
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the sender.
+    #[inline(always)]
+    unsafe fn park_sender(&self, spun: &mut u32, deadline: Option<Instant>) -> bool {
+        if let Some(watchdog) = &self.shared.watchdog {
+            let wait = |spun: &mut u32, deadline| self.park_sender_inner(spun, deadline);
+            return watchdog.wait(&self.sender.waiting_since, spun, deadline, wait);
+        }
+        self.park_sender_inner(spun, deadline)
+    }
+
+    #[inline(always)]
+    unsafe fn park_sender_inner(&self, spun: &mut u32, deadline: Option<Instant>) -> bool {
+        let wait = self.sender.wait.get();
+        let on_idle = || call_on_idle(&self.sender.on_idle);
+        #[cfg(feature = "tracing")]
+        if wait.will_park(*spun) {
+            let park = || wait.wait_idle(&self.receiver.send_park, spun, deadline, on_idle);
+            return crate::util::trace::parked("send", self.id(), self.name(), self.depth(), park);
+        }
+        wait.wait_idle(&self.receiver.send_park, spun, deadline, on_idle)
+    }
+
+    /// Waits for the sender, see [`WaitStrategy::wait_deadline`].
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver.
+    #[inline(always)]
+    unsafe fn park_receiver(&self, spun: &mut u32, deadline: Option<Instant>) -> bool {
+        if let Some(watchdog) = &self.shared.watchdog {
+            let wait = |spun: &mut u32, deadline| self.park_receiver_inner(spun, deadline);
+            return watchdog.wait(&self.receiver.waiting_since, spun, deadline, wait);
+        }
+        self.park_receiver_inner(spun, deadline)
+    }
+
+    #[inline(always)]
+    unsafe fn park_receiver_inner(&self, spun: &mut u32, deadline: Option<Instant>) -> bool {
+        let wait = self.receiver.wait.get();
+        let on_idle = || call_on_idle(&self.receiver.on_idle);
+        #[cfg(feature = "tracing")]
+        if wait.will_park(*spun) {
+            let park = || wait.wait_idle(&self.sender.recv_park, spun, deadline, on_idle);
+            return crate::util::trace::parked("recv", self.id(), self.name(), self.depth(), park);
+        }
+        wait.wait_idle(&self.sender.recv_park, spun, deadline, on_idle)
+    }
+
+    /// The same id as `channel_id`.
+    #[cfg(feature = "tracing")]
+    fn id(&self) -> usize {
+        crate::util::addr(self)
+    }
+
+    /// How many values are queued, can be called by either side.
+    #[cfg(feature = "tracing")]
+    fn depth(&self) -> usize {
+        self.distance(self.receiver.head.load(Relaxed), self.sender.tail.load(Relaxed))
+    }
+
+    /// Returns a snapshot of the channel's counters.
+    #[cfg(feature = "metrics")]
+    pub(super) fn stats(&self) -> Stats {
+        Stats {
+            sent: self.sender.sent.get(),
+            received: self.receiver.received.get(),
+            full: self.sender.full.get(),
+            empty: self.receiver.empty.get(),
+            sender_parks: self.receiver.send_park.parks(),
+            receiver_parks: self.sender.recv_park.parks(),
+            max_depth: self.receiver.max_depth.get(),
+            sender_park_time: self.receiver.send_park.park_time(),
+            receiver_park_time: self.sender.recv_park.park_time(),
+        }
+    }
+
+    /// Samples how many values are pending after `tail_cache` was refreshed.
+    ///
+    /// Can only be called by the receiver.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    fn record_depth(&self, head: usize) {
+        let depth = self.distance(head, self.receiver.tail_cache.get());
+        self.receiver.max_depth.max(depth as u64);
+    }
+
     #[inline]
     pub(super) fn wake_sender(&self) {
         self.receiver.send_park.unpark();
     }
 }
 
-impl<T> Drop for Inner<T> {
+impl<T, S: Storage<T>> Drop for Inner<T, S> {
     fn drop(&mut self) {
-        //head points to the first not read element
-        //tail points after the last written element
         /*SAFETY:
          *this object is being destroyed so we
-         *have exclusive access to these atomics.
+         *have exclusive access to it.
          */
-        #[cfg(not(feature = "loom"))]
-        let (mut head, tail) = unsafe {
-            (
-                self.receiver.head.as_ptr().read(),
-                self.sender.tail.as_ptr().read(),
-            )
-        };
-        #[cfg(feature = "loom")]
-        let (mut head, tail) = unsafe {
-            (
-                self.receiver.head.unsync_load(),
-                self.sender.tail.unsync_load(),
-            )
-        };
+        unsafe { self.clear() };
+    }
+}
 
-        let mask = self.shared.buffer.len() - 1;
+/// Calls `callback` from a blocking operation that waited
+/// for longer than `threshold`, once per operation.
+#[derive(Clone)]
+pub(super) struct Watchdog {
+    pub(super) threshold: Duration,
+    pub(super) callback: std::sync::Arc<dyn Fn(Duration) + Send + Sync>,
+}
 
-        while head != tail {
-            /*SAFETY:
-             *self.shared.buffer.len() is a power of 2, so <head & mask>
-             *is in [0, self.shared.buffer.len()) and get_unchecked_mut is valid.
-             */
-            let slot = unsafe { self.shared.buffer.get_unchecked_mut(head & mask) };
-            /*SAFETY:
-             *all elements in [head, tail) have been sent, but not received.
-             */
-            unsafe { slot.with_mut(|ptr| std::ptr::drop_in_place(ptr)) };
-            head = head.wrapping_add(1);
+impl Watchdog {
+    /// Calls `wait`, first firing the callback if the operation
+    /// has been waiting since `since` for longer than the threshold.
+    ///
+    /// `spun` is 0 on the first wait of every blocking operation, which
+    /// resets `since`. Afterwards, `since` is `None` once the callback fired.
+    #[inline]
+    fn wait(
+        &self,
+        since: &Cell<Option<Instant>>,
+        spun: &mut u32,
+        deadline: Option<Instant>,
+        wait: impl FnOnce(&mut u32, Option<Instant>) -> bool,
+    ) -> bool {
+        let now = Instant::now();
+        if *spun == 0 {
+            since.set(Some(now));
+        }
+        let mut until = deadline;
+        if let Some(start) = since.get() {
+            let waited = now - start;
+            if waited >= self.threshold {
+                since.set(None);
+                (self.callback)(waited);
+            } else if let Some(fire_at) = start.checked_add(self.threshold) {
+                // wake up in time to fire the callback.
+                until = Some(deadline.map_or(fire_at, |deadline| deadline.min(fire_at)));
+            }
         }
+        let woke = wait(spun, until);
+        // parking doesn't count as spinning, but this isn't the first wait anymore.
+        *spun = (*spun).max(1);
+        // a wake-up for the callback isn't a timeout.
+        woke || !matches!(deadline, Some(deadline) if Instant::now() >= deadline)
+    }
+}
+
+impl std::fmt::Debug for Watchdog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Calls `on_high` once at least `high` values are pending, and
+/// `on_low` once at most `low` are pending again, see `Builder::watermarks`.
+#[derive(Clone)]
+pub(super) struct Watermarks {
+    pub(super) high: usize,
+    pub(super) low: usize,
+    pub(super) on_high: std::sync::Arc<dyn Fn() + Send + Sync>,
+    pub(super) on_low: std::sync::Arc<dyn Fn() + Send + Sync>,
+}
+
+impl std::fmt::Debug for Watermarks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watermarks")
+            .field("high", &self.high)
+            .field("low", &self.low)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The callback of [`IdleStrategy::ParkTimeout`].
+pub(super) type IdleCallback = std::sync::Arc<dyn Fn() + Send + Sync>;
+
+/// Calls the callback in `on_idle`, which stays set.
+#[inline(never)]
+fn call_on_idle(on_idle: &Cell<Option<IdleCallback>>) {
+    let callback = on_idle.take();
+    on_idle.set(callback.clone());
+    if let Some(callback) = callback {
+        callback();
     }
 }
 
 struct SenderData {
     tail: AtomicUsize,
+    /// How many values were sent, advanced along with `tail`.
+    seq: AtomicU64,
     head_cache: Cell<usize>,
     recv_park: Parker,
+    /// How the sender blocks.
+    wait: Cell<WaitStrategy>,
+    /// Called when an `Idle::ParkTimeout` park times out.
+    on_idle: Cell<Option<IdleCallback>>,
+    /// Set once the sender saw a disconnect.
+    disconnected: Cell<bool>,
+    /// Calls to `sender_disconnected` left until it loads `drop_count`.
+    until_check: Cell<u32>,
+    /// What `until_check` is reset to, 0 checks every time.
+    check_interval: Cell<u32>,
+    /// How many values have to be pending before a send wakes the receiver,
+    /// 0 wakes it on every send.
+    wake_threshold: Cell<usize>,
+    /// When the current blocking send started waiting, see `Watchdog::wait`.
+    waiting_since: Cell<Option<Instant>>,
+    #[cfg(feature = "metrics")]
+    sent: Counter,
+    #[cfg(feature = "metrics")]
+    full: Counter,
 }
 
 struct ReceiverData {
     head: AtomicUsize,
+    /// How many values were received, advanced along with `head`.
+    seq: AtomicU64,
     tail_cache: Cell<usize>,
     send_park: Parker,
+    /// How the receiver blocks.
+    wait: Cell<WaitStrategy>,
+    /// Called when an `Idle::ParkTimeout` park times out.
+    on_idle: Cell<Option<IdleCallback>>,
+    /// Only used on rendezvous channels, set while blocked in `peek`.
+    waiting: AtomicBool,
+    /// Set once the receiver saw a disconnect and took every value.
+    disconnected: Cell<bool>,
+    /// When the current blocking receive started waiting, see `Watchdog::wait`.
+    waiting_since: Cell<Option<Instant>>,
+    #[cfg(feature = "metrics")]
+    received: Counter,
+    #[cfg(feature = "metrics")]
+    empty: Counter,
+    /// The most values pending when `tail_cache` was refreshed.
+    #[cfg(feature = "metrics")]
+    max_depth: Counter,
 }
 
-pub(super) struct SharedData<T> {
-    buffer: Box<[UnsafeCell<MaybeUninit<T>>]>,
+pub(super) struct SharedData<T, S: Storage<T>> {
+    buffer: S,
+    _marker: PhantomData<T>,
     /*
     starts off as 0, incremented when entering Sender/Receiver drop.
     match 'previous value' {
@@ -271,28 +2006,184 @@ pub(super) struct SharedData<T> {
         1 => just fall off drop.
         2 => deallocate the inner state.
     }
+    The CLOSED bit is set by 'close', which disconnects the channel without
+    dropping an end point. The POISONED bit is set along with it by
+    'poison_if_panicking'. Both are masked out of the 'previous value' above.
     */
     pub(super) drop_count: AtomicUsize,
+    /*
+    Set if the capacity isn't a power of two. Indices normally run through all of
+    usize and are masked into the buffer, which only works for powers of two.
+    Otherwise, they run through [0, 2 * capacity) instead (see next_index).
+    */
+    exact: bool,
+    /// Set for channels created with `channel_overwriting`.
+    overwrite: bool,
+    /// Set for channels created with `channel_rendezvous`.
+    rendezvous: bool,
+    /// Set for channels created with `Builder::poisoning`.
+    poisoning: bool,
+    /// Set for channels created with `Builder::on_starved`.
+    watchdog: Option<Watchdog>,
+    /// Set for channels created with `Builder::watermarks`.
+    watermarks: Option<Watermarks>,
+    /// Set for channels created with `Builder::name`.
+    name: Option<&'static str>,
+    /// Set by the sender when it calls `on_high`, and
+    /// cleared by the receiver when it calls `on_low`.
+    above_high: AtomicBool,
+    /*
+    Only used if 'overwrite' is set. Guards taking values out of the buffer,
+    since both the receiver and force_send advance 'head' in that case.
+    The receiver holds it from try_peek to release.
+    */
+    consuming: AtomicBool,
+}
+
+#[cfg(not(feature = "loom"))]
+impl<T, S: Storage<T>> SharedData<T, S> {
+    const fn new(buffer: S) -> Self {
+        Self {
+            buffer,
+            _marker: PhantomData,
+            exact: false,
+            overwrite: false,
+            rendezvous: false,
+            poisoning: false,
+            watchdog: None,
+            watermarks: None,
+            name: None,
+            above_high: AtomicBool::new(false),
+            consuming: AtomicBool::new(false),
+            drop_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Writes every field of `ptr` but `buffer`, like [`new`](SharedData::new).
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes and aligned.
+    unsafe fn init_without_buffer(ptr: *mut Self) {
+        // exhaustive, so a new field can't be left uninitialised.
+        let SharedData {
+            buffer: [],
+            _marker,
+            drop_count,
+            exact,
+            overwrite,
+            rendezvous,
+            poisoning,
+            watchdog,
+            watermarks,
+            name,
+            above_high,
+            consuming,
+        } = SharedData::<T, [Slot<T>; 0]>::new([]);
+        //SAFETY: the caller guarantees ptr is valid for writes.
+        unsafe {
+            addr_of_mut!((*ptr).drop_count).write(drop_count);
+            addr_of_mut!((*ptr).exact).write(exact);
+            addr_of_mut!((*ptr).overwrite).write(overwrite);
+            addr_of_mut!((*ptr).rendezvous).write(rendezvous);
+            addr_of_mut!((*ptr).poisoning).write(poisoning);
+            addr_of_mut!((*ptr).watchdog).write(watchdog);
+            addr_of_mut!((*ptr).watermarks).write(watermarks);
+            addr_of_mut!((*ptr).name).write(name);
+            addr_of_mut!((*ptr).above_high).write(above_high);
+            addr_of_mut!((*ptr).consuming).write(consuming);
+        }
+    }
 }
 
-impl Default for SenderData {
+impl SenderData {
+    #[cfg(not(feature = "loom"))]
+    #[inline(always)]
+    const fn new() -> Self {
+        Self {
+            tail: AtomicUsize::new(0),
+            seq: AtomicU64::new(0),
+            head_cache: Cell::new(0),
+            recv_park: Parker::new(),
+            wait: Cell::new(WaitStrategy::DEFAULT),
+            on_idle: Cell::new(None),
+            disconnected: Cell::new(false),
+            until_check: Cell::new(0),
+            check_interval: Cell::new(0),
+            wake_threshold: Cell::new(0),
+            waiting_since: Cell::new(None),
+            #[cfg(feature = "metrics")]
+            sent: Counter::new(),
+            #[cfg(feature = "metrics")]
+            full: Counter::new(),
+        }
+    }
+
+    #[cfg(feature = "loom")]
     #[inline(always)]
-    fn default() -> Self {
+    fn new() -> Self {
         Self {
-            tail: AtomicUsize::default(),
-            head_cache: Cell::default(),
+            tail: AtomicUsize::new(0),
+            seq: AtomicU64::new(0),
+            head_cache: Cell::new(0),
             recv_park: Parker::new(),
+            wait: Cell::new(WaitStrategy::DEFAULT),
+            on_idle: Cell::new(None),
+            disconnected: Cell::new(false),
+            until_check: Cell::new(0),
+            check_interval: Cell::new(0),
+            wake_threshold: Cell::new(0),
+            waiting_since: Cell::new(None),
+            #[cfg(feature = "metrics")]
+            sent: Counter::new(),
+            #[cfg(feature = "metrics")]
+            full: Counter::new(),
         }
     }
 }
 
-impl Default for ReceiverData {
+impl ReceiverData {
+    #[cfg(not(feature = "loom"))]
+    #[inline(always)]
+    const fn new() -> Self {
+        Self {
+            head: AtomicUsize::new(0),
+            seq: AtomicU64::new(0),
+            tail_cache: Cell::new(0),
+            send_park: Parker::new(),
+            wait: Cell::new(WaitStrategy::DEFAULT),
+            on_idle: Cell::new(None),
+            waiting: AtomicBool::new(false),
+            disconnected: Cell::new(false),
+            waiting_since: Cell::new(None),
+            #[cfg(feature = "metrics")]
+            received: Counter::new(),
+            #[cfg(feature = "metrics")]
+            empty: Counter::new(),
+            #[cfg(feature = "metrics")]
+            max_depth: Counter::new(),
+        }
+    }
+
+    #[cfg(feature = "loom")]
     #[inline(always)]
-    fn default() -> Self {
+    fn new() -> Self {
         Self {
-            head: AtomicUsize::default(),
-            tail_cache: Cell::default(),
+            head: AtomicUsize::new(0),
+            seq: AtomicU64::new(0),
+            tail_cache: Cell::new(0),
             send_park: Parker::new(),
+            wait: Cell::new(WaitStrategy::DEFAULT),
+            on_idle: Cell::new(None),
+            waiting: AtomicBool::new(false),
+            disconnected: Cell::new(false),
+            waiting_since: Cell::new(None),
+            #[cfg(feature = "metrics")]
+            received: Counter::new(),
+            #[cfg(feature = "metrics")]
+            empty: Counter::new(),
+            #[cfg(feature = "metrics")]
+            max_depth: Counter::new(),
         }
     }
 }
@@ -1,49 +1,327 @@
 use crate::alloc::{alloc, dealloc};
-use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
-use crate::sync::atomic::Ordering::AcqRel;
+use crate::error::{
+    AllocError, RecvCancelError, RecvError, RecvTimeoutError, SendError, SendTimeoutError,
+    TryRecvError, TrySendError,
+};
+use crate::sync::atomic::Ordering::{AcqRel, Acquire, Release};
+use crate::sync::CancellationToken;
 use crate::util::marker::PhantomUnsync;
+use std::cell::{Cell, UnsafeCell};
+use std::iter::Chain;
+use std::mem::MaybeUninit;
+use std::option;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+#[cfg(feature = "futures")]
+use std::pin::Pin;
 use std::ptr::NonNull;
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
+mod builder;
 mod inner;
-use inner::Inner;
+pub use builder::{Builder, IdleStrategy};
+use inner::{Inner, CLOSED, POISONED};
+
+mod static_channel;
+pub use static_channel::{StaticChannel, StaticReceiver, StaticSender};
+
+mod small;
+pub use small::{channel_small, SmallReceiver, SmallSender};
+
+mod ring;
+pub use ring::{RingBuffer, RingReceiver, RingSender};
+
+#[cfg(not(feature = "loom"))]
+mod chunk;
+#[cfg(not(feature = "loom"))]
+pub use chunk::{ReadChunk, SlotGroup, WriteChunk};
+
+#[cfg(not(feature = "loom"))]
+mod in_place;
+#[cfg(not(feature = "loom"))]
+pub use in_place::{Channel, ChannelReceiver, ChannelSender};
+
+#[cfg(all(target_os = "linux", feature = "shm", not(feature = "loom")))]
+mod shm;
+#[cfg(all(target_os = "linux", feature = "shm", not(feature = "loom")))]
+pub use shm::{shm_channel, ShmChannel, ShmReceiver, ShmSender};
+
+// exported as spsc::bidir, since it's a separate flavor.
+#[cfg(all(any(doc, feature = "spsc-bidir"), not(feature = "loom")))]
+pub(super) mod bidir;
+
+/// The largest capacity a [`channel`] can have.
+///
+/// Indices into the buffer go up to twice the capacity, so larger capacities
+/// would overflow them. Since [`channel`] rounds capacities up to a power of
+/// two, it's limited to the largest power of two below this.
+///
+/// The limit also keeps the wrapping index arithmetic exact on 32-bit targets,
+/// where the indices of long-lived channels wrap around after ~4B values.
+pub const MAX_CAPACITY: usize = usize::MAX / 2;
 
 /// Creates a SPSC channel with storage for at least `min_capacity` elements.
 ///
 /// # Panics
 ///
-/// The function panics if it can't allocate the memory needed for the channel.
+/// The function panics if the rounded capacity is greater than [`MAX_CAPACITY`]
+/// or it can't allocate the memory needed for the channel.
 pub fn channel<T>(min_capacity: usize) -> (Sender<T>, Receiver<T>) {
-    let capacity = min_capacity
+    from_inner(Inner::<T>::new(round_capacity(min_capacity)))
+}
+
+/// Like [`channel`], but also returns the [`ChannelInfo`] of the channel,
+/// such as the capacity `min_capacity` was rounded up to.
+///
+/// # Panics
+///
+/// The function panics if the rounded capacity is greater than [`MAX_CAPACITY`]
+/// or it can't allocate the memory needed for the channel.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+///
+/// let (src, sink, info) = bounded::channel_with_info::<u32>(100);
+/// assert_eq!(info.requested, 100);
+/// assert_eq!(info.capacity, 128);
+/// assert_eq!(src.capacity(), sink.capacity());
+/// assert_eq!(src.capacity(), info.capacity);
+/// ```
+pub fn channel_with_info<T>(min_capacity: usize) -> (Sender<T>, Receiver<T>, ChannelInfo) {
+    let info = ChannelInfo {
+        requested: min_capacity,
+        capacity: round_capacity(min_capacity),
+    };
+    let (src, sink) = from_inner(Inner::<T>::new(info.capacity));
+    (src, sink, info)
+}
+
+/// Like [`channel`], but returns an [`AllocError`] instead of panicking
+/// if the memory needed for the channel can't be allocated.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded::{self, try_channel};
+///
+/// let (src, sink) = try_channel::<u32>(4).expect("out of memory");
+/// src.send(1).unwrap();
+/// assert_eq!(sink.recv(), Ok(1));
+///
+/// assert!(try_channel::<u64>(usize::MAX / 2).is_err());
+/// assert!(try_channel::<()>(bounded::MAX_CAPACITY).is_err());
+/// ```
+pub fn try_channel<T>(min_capacity: usize) -> Result<(Sender<T>, Receiver<T>), AllocError> {
+    try_from_inner(Inner::<T>::try_new(try_round_capacity(min_capacity)?)?)
+}
+
+/// Creates a SPSC channel with storage for exactly `CAP` elements.
+///
+/// Works like [`channel`], but `CAP` must be a power of two, which is checked
+/// at compile time, so the capacity never needs to be rounded up and can't
+/// overflow. [`channel_small`] also makes the capacity part of the end points'
+/// types, at the cost of placing the buffer in the channel's allocation.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+///
+/// let (src, sink) = bounded::channel_const::<u32, 4>();
+/// assert_eq!(src.slots_free(), 4);
+/// src.send(1).unwrap();
+/// assert_eq!(sink.recv(), Ok(1));
+/// ```
+pub fn channel_const<T, const CAP: usize>() -> (Sender<T>, Receiver<T>) {
+    const {
+        assert!(CAP.is_power_of_two(), "capacity must be a power of two");
+        assert!(CAP <= MAX_CAPACITY, "capacity overflow");
+    };
+    from_inner(Inner::<T>::new(CAP))
+}
+
+/// Creates a SPSC channel where [`Sender::force_send`] overwrites the oldest value.
+///
+/// Otherwise, the channel works just like one created with [`channel`].
+/// Receiving is a bit slower, since the [`Receiver`] has to synchronise with
+/// [`force_send`](Sender::force_send) when taking values out.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+pub fn channel_overwriting<T>(min_capacity: usize) -> (Sender<T>, Receiver<T>) {
+    from_inner(Inner::<T>::new(round_capacity(min_capacity)).overwriting())
+}
+
+/// Creates a SPSC channel without buffering, where every send is a rendezvous.
+///
+/// [`send`](Sender::send) blocks until the value is received, and
+/// [`try_send`](Sender::try_send) only succeeds while the [`Receiver`] is
+/// blocked in [`recv`](Receiver::recv). If the [`Receiver`] disconnects
+/// before taking the value, [`send`](Sender::send) returns it in the [`SendError`].
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+/// use concurrent_qs::error::TrySendError;
+/// use std::thread;
+///
+/// let (src, sink) = bounded::channel_rendezvous::<i32>();
+/// assert_eq!(src.try_send(1), Err(TrySendError::Full(1)));
+///
+/// let handle = thread::spawn(move || sink.recv().unwrap());
+/// src.send(2).unwrap();
+/// // `send` returned, so the value was already received.
+/// assert_eq!(handle.join().unwrap(), 2);
+/// ```
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+pub fn channel_rendezvous<T>() -> (Sender<T>, Receiver<T>) {
+    from_inner(Inner::<T>::new(1).rendezvous())
+}
+
+/// Creates a SPSC channel with storage for exactly `capacity` elements.
+///
+/// Unlike [`channel`], the capacity isn't rounded up to a power of two,
+/// which saves memory for large capacities. Channels with capacities that
+/// aren't powers of two use slightly more expensive index arithmetic.
+/// A `capacity` of 0 is treated as 1.
+///
+/// # Panics
+///
+/// The function panics if `capacity` is greater than [`MAX_CAPACITY`] or it
+/// can't allocate the memory needed for the channel.
+pub fn channel_exact<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity <= MAX_CAPACITY, "capacity overflow");
+    from_inner(Inner::<T>::new(capacity.max(1)))
+}
+
+/// Like [`channel_exact`], but returns an [`AllocError`] instead of panicking
+/// if `capacity` is greater than [`MAX_CAPACITY`] or the memory needed
+/// for the channel can't be allocated.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded::{self, try_channel_exact};
+///
+/// let (src, sink) = try_channel_exact::<u32>(3).expect("out of memory");
+/// src.send(1).unwrap();
+/// assert_eq!(sink.recv(), Ok(1));
+///
+/// assert!(try_channel_exact::<u8>(bounded::MAX_CAPACITY + 1).is_err());
+/// ```
+pub fn try_channel_exact<T>(capacity: usize) -> Result<(Sender<T>, Receiver<T>), AllocError> {
+    if capacity > MAX_CAPACITY {
+        return Err(AllocError {});
+    }
+    try_from_inner(Inner::<T>::try_new(capacity.max(1))?)
+}
+
+fn round_capacity(min_capacity: usize) -> usize {
+    /*from std::Vec: https://doc.rust-lang.org/src/alloc/raw_vec.rs.html*/
+    try_round_capacity(min_capacity).expect("capacity overflow")
+}
+
+fn try_round_capacity(min_capacity: usize) -> Result<usize, AllocError> {
+    min_capacity
         .checked_next_power_of_two()
-        .expect("capacity overflow"); /*from std::Vec: https://doc.rust-lang.org/src/alloc/raw_vec.rs.html*/
+        .filter(|&capacity| capacity <= MAX_CAPACITY)
+        .ok_or(AllocError {})
+}
+
+fn from_inner<T>(inner: Inner<T>) -> (Sender<T>, Receiver<T>) {
+    try_from_inner(inner).expect("failed to allocate memory for the shared state")
+}
 
-    let inner = Inner::<T>::new(capacity);
+fn try_from_inner<T>(inner: Inner<T>) -> Result<(Sender<T>, Receiver<T>), AllocError> {
     //order is important: Inner is RAII, but NonNull isn't.
     let inner = {
         /*SAFETY: deallocated in either Sender's or Receiver's Drop*/
         let inner_uninit = NonNull::new(unsafe { alloc(Inner::<T>::LAYOUT) as *mut Inner<T> })
-            .expect("failed to allocate memory for the shared state");
+            .ok_or(AllocError {})?;
         /*SAFETY: this is a safe way to write to _uninitialised memory_.*/
         unsafe { inner_uninit.as_ptr().write(inner) };
         inner_uninit
     };
-    unsafe { inner.as_ptr().write(Inner::new(capacity)) };
-    (
+    Ok((
         Sender {
-            inner: inner,
+            inner,
             _unsync: PhantomUnsync {},
         },
         Receiver {
-            inner: inner,
+            inner,
+            front: UnsafeCell::new(None),
+            has_front: Cell::new(false),
             _unsync: PhantomUnsync {},
         },
-    )
+    ))
+}
+
+/// How a [`channel`] was set up, returned by [`channel_with_info`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ChannelInfo {
+    /// The capacity that was asked for.
+    pub requested: usize,
+    /// How many values the [`channel`] can hold, which is the requested
+    /// capacity rounded up to a power of two.
+    pub capacity: usize,
+}
+
+/// The connection state of a [`channel`], as seen by its [`Receiver`].
+///
+/// Returned by [`Receiver::state`]. The `pending` counts are how many values
+/// can still be received before [`try_recv`](Receiver::try_recv) reports
+/// the disconnect, so a `pending` of 0 means all sent data was consumed.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ReceiverState {
+    /// The [`Sender`] is connected and the [`channel`] isn't closed.
+    Connected,
+    /// The [`Sender`] was dropped.
+    SenderDropped {
+        /// How many values can still be received.
+        pending: usize,
+    },
+    /// Either end point [`close`](Receiver::close)d the [`channel`].
+    Closed {
+        /// How many values can still be received.
+        pending: usize,
+    },
+    /// Either end point panicked on a [`channel`] built with [`Builder::poisoning`].
+    Poisoned {
+        /// How many values can still be received.
+        pending: usize,
+    },
 }
 
+/// The values that [`Sender::send_all`] couldn't send: the value it was
+/// sending, if any, followed by the rest of the iterator.
+pub type Unsent<T, I> = Chain<option::IntoIter<T>, I>;
+
 /// The sending endpoint of a [`channel`].
 ///
 /// Data can be sent using the [`try_send`](Sender::try_send)
 /// and [`send`](Sender::send) methods.
+///
+/// # Disconnection
+///
+/// Once a send fails because the [`Receiver`] disconnected, every later
+/// send fails the same way and gives the value back, so no value is
+/// silently lost. Only [`reconnect`](Sender::reconnect), which takes
+/// the [`Sender`] mutably, makes sending possible again.
+///
+/// A channel built with [`Builder::disconnect_check_interval`] may take
+/// a few sends to notice that the [`Receiver`] disconnected.
 pub struct Sender<T> {
     inner: NonNull<Inner<T>>,
     _unsync: PhantomUnsync,
@@ -55,6 +333,11 @@ pub struct Sender<T> {
 /// and [`recv`](Receiver::recv) methods.
 pub struct Receiver<T> {
     inner: NonNull<Inner<T>>,
+    /// Room for the value put back by `push_front`, initialised if `has_front` is set.
+    ///
+    /// It's boxed so `Receiver<T>` can be part of `T`, and allocated on the first use.
+    front: UnsafeCell<Option<Box<MaybeUninit<T>>>>,
+    has_front: Cell<bool>,
     _unsync: PhantomUnsync,
 }
 
@@ -85,132 +368,1899 @@ impl<T> Sender<T> {
         self.inner_ref().send(item)
     }
 
-    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    /// Sends a value through this [`channel`], passing it to `f`
+    /// instead if the [`Receiver`] is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// drop(sink);
+    /// let mut lost = Vec::new();
+    /// src.send_or_else(1, |item| lost.push(item));
+    /// src.send_or_else(2, |item| lost.push(item));
+    /// assert_eq!(lost, [1, 2]);
+    /// ```
     #[inline]
-    pub fn receiver_connected(&self) -> bool {
-        self.inner_ref().peer_connected()
+    pub fn send_or_else(&self, item: T, f: impl FnOnce(T)) {
+        if let Err(SendError(item)) = self.send(item) {
+            f(item)
+        }
     }
 
-    fn inner_ref(&self) -> &Inner<T> {
-        /*SAFETY:
-         *This type and Sender are responsible for inner's lifetime.
-         */
-        unsafe { self.inner.as_ref() }
+    /// Sends a value through this [`channel`], waiting at most `timeout` for space.
+    ///
+    /// Returns [`SendTimeoutError::Timeout`] if the [`channel`] is still full
+    /// after `timeout` and [`SendTimeoutError::Disconnected`] if the [`Receiver`]
+    /// is disconnected.
+    ///
+    /// On a [`channel_rendezvous`], the value is only sent if the [`Receiver`]
+    /// starts waiting for it within `timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use concurrent_qs::error::SendTimeoutError;
+    /// use std::time::Duration;
+    ///
+    /// let (src, _sink) = bounded::channel::<u32>(1);
+    /// src.send(1).unwrap();
+    /// assert_eq!(
+    ///     src.send_timeout(2, Duration::from_millis(10)),
+    ///     Err(SendTimeoutError::Timeout(2))
+    /// );
+    /// ```
+    pub fn send_timeout(&self, item: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.inner_ref()
+            .send_deadline(item, Instant::now().checked_add(timeout))
     }
-}
 
-impl<T> Receiver<T> {
-    /// Tries to return a pending value.
+    /// Sends a value through this [`channel`], giving up at `deadline`.
     ///
-    /// # Notes
+    /// Unlike [`send_timeout`](Sender::send_timeout), this first busy-waits
+    /// for a short while, even if the [`Sender`] doesn't [spin](Sender::set_spin),
+    /// and only then parks until `deadline`. A slot that frees up soon
+    /// is taken without paying for a wake up, which suits producers
+    /// with tight deadlines.
     ///
-    /// - Returns [`TryRecvError::Disconnected`] only after consuming all
-    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
-    /// - Will never block as long as [`send`](Sender::send) hasn't been called.
-    /// - After every call to [`send`](Sender::send), up to one [`try_recv`](Receiver::try_recv)
-    /// call may block for a short period.
-    #[inline]
-    pub fn try_recv(&self) -> Result<T, TryRecvError> {
-        self.inner_ref().try_recv()
+    /// Fails like [`send_timeout`](Sender::send_timeout).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use concurrent_qs::error::SendTimeoutError;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(1);
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert_eq!(src.try_send_until(1, deadline), Ok(()));
+    /// assert_eq!(src.try_send_until(2, deadline), Err(SendTimeoutError::Timeout(2)));
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// ```
+    pub fn try_send_until(&self, item: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
+        self.inner_ref().send_until(item, deadline)
     }
 
-    /// Reads a value from the [`channel`].
+    /// Sends every value from `items` through this [`channel`].
     ///
-    /// If the [`channel`] is empty, blocks and waits for the [`Sender`].
+    /// If the [`channel`] is full, blocks and waits for the [`Receiver`]. Values
+    /// are published in runs that fill the free slots, waking the [`Receiver`]
+    /// once per run instead of once per value.
     ///
-    /// # Notes
-    /// - [`RecvError`] is only returned after consuming all sent data. To
-    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
-    /// - Calling this method may result in a [`try_send`](Sender::try_send)
-    /// call blocking for a short period.
+    /// If the [`Receiver`] disconnects, returns the values that weren't sent.
+    /// At most one value is taken from `items` while the [`channel`] is full,
+    /// and that value is the first one returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::thread;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// let handle = thread::spawn(move || {
+    ///     src.send_all(0..10).unwrap();
+    ///     src
+    /// });
+    /// assert_eq!(sink.iter().take(10).collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+    ///
+    /// let src = handle.join().unwrap();
+    /// drop(sink);
+    /// let rest = src.send_all(10..13).unwrap_err().0;
+    /// assert_eq!(rest.collect::<Vec<_>>(), [10, 11, 12]);
+    /// ```
+    pub fn send_all<I: IntoIterator<Item = T>>(
+        &self,
+        items: I,
+    ) -> Result<(), SendError<Unsent<T, I::IntoIter>>> {
+        let mut items = items.into_iter();
+        self.inner_ref()
+            .send_iter(&mut items)
+            .map_err(|SendError(item)| SendError(item.into_iter().chain(items)))
+    }
+
+    /// Wakes the [`Receiver`] if it's blocked, so it sees every sent value.
+    ///
+    /// Only needed on channels built with [`Builder::wake_threshold`],
+    /// where sends don't always wake the [`Receiver`].
     #[inline]
-    pub fn recv(&self) -> Result<T, RecvError> {
-        self.inner_ref().recv()
+    pub fn flush(&self) {
+        self.inner_ref().wake_receiver();
     }
 
-    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    /// Blocks until at least `n` values can be sent without blocking.
+    ///
+    /// This lets a producer apply backpressure, e.g. by preparing a batch only
+    /// once there's room for it. Returns a [`SendError`] if the [`Receiver`]
+    /// is disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the capacity of the [`channel`].
+    pub fn wait_for_capacity(&self, n: usize) -> Result<(), SendError<()>> {
+        self.inner_ref().wait_capacity(n, None).map(|_| ())
+    }
+
+    /// Like [`wait_for_capacity`](Sender::wait_for_capacity), but waits at most `timeout`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the capacity of the [`channel`].
+    pub fn wait_for_capacity_timeout(
+        &self,
+        n: usize,
+        timeout: Duration,
+    ) -> Result<(), SendTimeoutError<()>> {
+        let deadline = Instant::now().checked_add(timeout);
+        match self.inner_ref().wait_capacity(n, deadline) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(SendTimeoutError::Timeout(())),
+            Err(SendError(())) => Err(SendTimeoutError::Disconnected(())),
+        }
+    }
+
+    /// Blocks until the [`Receiver`] took every value sent so far.
+    ///
+    /// This lets a producer know its data reached the consumer, e.g. before
+    /// shutting down. Returns a [`SendError`] if the [`Receiver`] disconnected
+    /// before taking every value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::thread;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// let consumer = thread::spawn(move || sink.iter().sum::<u32>());
+    /// for i in 0..4 {
+    ///     src.send(i).unwrap();
+    /// }
+    /// src.wait_empty().unwrap();
+    /// drop(src);
+    /// assert_eq!(consumer.join().unwrap(), 6);
+    /// ```
+    pub fn wait_empty(&self) -> Result<(), SendError<()>> {
+        self.inner_ref().wait_empty(None).map(|_| ())
+    }
+
+    /// Like [`wait_empty`](Sender::wait_empty), but waits at most `timeout`.
+    pub fn wait_empty_timeout(&self, timeout: Duration) -> Result<(), SendTimeoutError<()>> {
+        let deadline = Instant::now().checked_add(timeout);
+        match self.inner_ref().wait_empty(deadline) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(SendTimeoutError::Timeout(())),
+            Err(SendError(())) => Err(SendTimeoutError::Disconnected(())),
+        }
+    }
+
+    /// Sends a value, replacing the oldest pending value if the [`channel`] is full.
+    ///
+    /// Returns the replaced value, if any. Values that aren't replaced
+    /// are still received in the order they were sent.
+    ///
+    /// Only channels created with [`channel_overwriting`] replace values. On
+    /// other channels, this blocks like [`send`](Sender::send) and returns [`None`].
     ///
     /// # Note
     ///
-    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
-    /// methods return [`TryRecvError::Disconnected`] or [`RecvError`] only
-    /// after consuming all previously sent data, even if the [`Sender`] isn't
-    /// connected. This method doesn't take pending data into account and can
-    /// be used to avoid this behaviour.
+    /// While the [`Receiver`] holds a [`RecvRef`], this method spins if
+    /// it has to replace a value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel_overwriting::<i32>(2);
+    /// assert_eq!(src.force_send(1), Ok(None));
+    /// assert_eq!(src.force_send(2), Ok(None));
+    /// assert_eq!(src.force_send(3), Ok(Some(1)));
+    /// assert_eq!(sink.recv(), Ok(2));
+    /// assert_eq!(sink.recv(), Ok(3));
+    /// ```
     #[inline]
-    pub fn sender_connected(&self) -> bool {
-        self.inner_ref().peer_connected()
+    pub fn force_send(&self, item: T) -> Result<Option<T>, SendError<T>> {
+        self.inner_ref().force_send(item)
     }
 
-    fn inner_ref(&self) -> &Inner<T> {
-        /*SAFETY:
-         *This type and Receiver are responsible for inner's lifetime.
-         */
-        unsafe { self.inner.as_ref() }
+    /// Reserves a slot in the [`channel`] for in-place construction.
+    ///
+    /// The value is only visible to the [`Receiver`] after
+    /// [`SendSlot::commit`] or [`SendSlot::write`] is called.
+    /// Dropping the [`SendSlot`] gives up the reservation. It borrows the
+    /// [`Sender`] mutably, so nothing else can be sent while it's alive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (mut src, sink) = bounded::channel::<[u8; 4096]>(4);
+    /// let mut slot = src.reserve().unwrap();
+    /// // SAFETY: every byte pattern is a valid [u8; 4096].
+    /// unsafe {
+    ///     slot.as_uninit().as_mut_ptr().write_bytes(7, 1);
+    ///     slot.commit();
+    /// }
+    /// assert_eq!(sink.recv().unwrap()[4095], 7);
+    /// ```
+    #[inline]
+    pub fn reserve(&mut self) -> Result<SendSlot<'_, T>, TrySendError<()>> {
+        let tail = self.inner_ref().reserve()?;
+        Ok(SendSlot { sender: self, tail })
     }
-}
 
-impl<T> Drop for Sender<T> {
-    fn drop(&mut self) {
-        //this protocol is described at the declaration of 'drop_count'
-        loop {
-            match self.inner_ref().shared.drop_count.fetch_add(1, AcqRel) {
-                0 => self.inner_ref().wake_receiver(),
-                1 => break,
-                2 => {
-                    break unsafe {
-                        self.inner.as_ptr().drop_in_place();
-                        dealloc(self.inner.as_ptr() as *mut u8, Inner::<T>::LAYOUT)
-                    }
-                }
-                _ => unreachable!(),
-            }
-        }
+    /// Tries to borrow up to `max` free slots for in-place construction.
+    ///
+    /// Values can be produced straight into the [`channel`]'s buffer, e.g.
+    /// read from a socket, and are sent by [`WriteChunk::commit`]. Fails
+    /// like [`reserve`](Sender::reserve) if no slot is free, and borrows
+    /// the [`Sender`] mutably like it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let (mut src, sink) = bounded::channel::<u8>(8);
+    /// let mut chunk = src.write_chunk_uninit(3).unwrap();
+    /// let (first, _) = chunk.as_mut_slices();
+    /// first[0] = MaybeUninit::new(b'h');
+    /// first[1] = MaybeUninit::new(b'i');
+    /// // SAFETY: the first 2 slots were just initialised.
+    /// unsafe { chunk.commit(2) };
+    /// assert_eq!(sink.drain_to_vec(), b"hi");
+    /// ```
+    #[cfg(not(feature = "loom"))]
+    pub fn write_chunk_uninit(&mut self, max: usize) -> Result<WriteChunk<'_, T>, TrySendError<()>> {
+        let tail = self.inner_ref().reserve()?;
+        Ok(WriteChunk::new(self, tail, max))
     }
-}
 
-impl<T> Drop for Receiver<T> {
-    fn drop(&mut self) {
-        //this protocol is described at the declaration of 'drop_count'
-        loop {
-            match self.inner_ref().shared.drop_count.fetch_add(1, AcqRel) {
-                0 => self.inner_ref().wake_receiver(),
-                1 => break,
-                2 => {
-                    break unsafe {
-                        self.inner.as_ptr().drop_in_place();
-                        dealloc(self.inner.as_ptr() as *mut u8, Inner::<T>::LAYOUT)
-                    }
-                }
-                _ => unreachable!(),
-            }
-        }
+    /// Reserves `n` slots in the [`channel`] for building a batch in place.
+    ///
+    /// The values written to the [`SlotGroup`] are sent together by
+    /// [`SlotGroup::commit`], with a single update of the [`channel`]'s tail,
+    /// so the [`Receiver`] never sees part of a batch. Fails
+    /// like [`reserve`](Sender::reserve) if fewer than `n` slots are free;
+    /// [`wait_for_capacity`](Sender::wait_for_capacity) waits for them.
+    /// Like [`reserve`](Sender::reserve), it borrows the [`Sender`] mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the capacity of the [`channel`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (mut src, sink) = bounded::channel::<u8>(8);
+    /// let record = b"\x03abc";
+    /// src.wait_for_capacity(record.len()).unwrap();
+    /// let mut group = src.reserve_many(record.len()).unwrap();
+    /// for &byte in record {
+    ///     group.push(byte).unwrap();
+    /// }
+    /// assert!(sink.try_recv().is_err());
+    /// group.commit();
+    /// assert_eq!(sink.drain_to_vec(), record);
+    /// ```
+    #[cfg(not(feature = "loom"))]
+    pub fn reserve_many(&mut self, n: usize) -> Result<SlotGroup<'_, T>, TrySendError<()>> {
+        let tail = self.inner_ref().reserve_n(n)?;
+        Ok(SlotGroup::new(self, tail, n))
     }
-}
 
-impl<T> std::fmt::Debug for Sender<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "spsc::bounded::Sender<{}> {{ channel: {:p} }}",
-            std::any::type_name::<T>(),
-            self.inner
-        )
+    /// Sets how many times blocking operations of this [`Sender`]
+    /// busy-wait for the [`Receiver`] before parking the thread.
+    ///
+    /// A short spin avoids the cost of parking when the [`Receiver`] is expected
+    /// to make room quickly. See also [`Builder::spin`].
+    pub fn set_spin(&self, count: u32) {
+        self.inner_ref().set_sender_spin(count)
     }
-}
-impl<T> std::fmt::Debug for Receiver<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "spsc::bounded::Receiver<{}> {{ channel: {:p} }}",
-            std::any::type_name::<T>(),
-            self.inner
-        )
+
+    /// Sets what blocking operations of this [`Sender`] do once they're
+    /// done spinning, replacing the strategy set by [`Builder::idle`].
+    pub fn set_idle(&self, idle: IdleStrategy) {
+        self.inner_ref().set_sender_idle(idle)
     }
-}
 
-unsafe impl<T: Send> Send for Sender<T> {}
-unsafe impl<T: Send> Send for Receiver<T> {}
+    /// Checks if `receiver` belongs to the same [`channel`] as this [`Sender`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src1, sink1) = bounded::channel::<u32>(4);
+    /// let (_src2, sink2) = bounded::channel::<u32>(4);
+    /// assert!(src1.same_channel(&sink1));
+    /// assert!(!src1.same_channel(&sink2));
+    /// ```
+    #[inline]
+    pub fn same_channel(&self, receiver: &Receiver<T>) -> bool {
+        self.inner == receiver.inner
+    }
+
+    /// Returns an id of the [`channel`], shared by its [`Sender`] and [`Receiver`].
+    ///
+    /// The id is unique among live channels, but may be reused
+    /// once both end points of the [`channel`] are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::collections::HashMap;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// let mut names = HashMap::new();
+    /// names.insert(sink.channel_id(), "events");
+    /// assert_eq!(names[&src.channel_id()], "events");
+    /// ```
+    #[inline]
+    pub fn channel_id(&self) -> usize {
+        crate::util::addr(self.inner.as_ptr())
+    }
+
+    /// Returns the name the [`channel`] was built with by [`Builder::name`].
+    #[inline]
+    pub fn name(&self) -> Option<&'static str> {
+        self.inner_ref().name()
+    }
+
+    /// Checks if the [`channel`] is full, so [`try_send`](Sender::try_send)
+    /// would fail with [`TrySendError::Full`].
+    ///
+    /// Unlike [`try_send`](Sender::try_send), this doesn't modify the
+    /// [`channel`] or wake the [`Receiver`], so it's cheap to poll.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel_exact::<u32>(1);
+    /// assert!(!src.is_full());
+    /// src.send(1).unwrap();
+    /// assert!(src.is_full());
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// assert!(!src.is_full());
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.inner_ref().is_full()
+    }
+
+    /// Returns how many values can be sent without blocking.
+    ///
+    /// This is cheap enough to call before every batch. The estimate can
+    /// only be stale in one direction: the [`Receiver`] may free more slots
+    /// after the call, but only this [`Sender`] can use them up, so the
+    /// result is a lower bound until the next send. Without sends in between,
+    /// later calls never return less.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel_exact::<u32>(4);
+    /// src.send(1).unwrap();
+    /// assert_eq!(src.slots_free(), 3);
+    /// assert_eq!(sink.len_hint(), 1);
+    /// ```
+    #[inline]
+    pub fn slots_free(&self) -> usize {
+        self.inner_ref().slots_free()
+    }
+
+    /// Returns how many slots were free the last time this [`Sender`] looked
+    /// at the [`Receiver`]'s progress.
+    ///
+    /// Unlike [`slots_free`](Sender::slots_free), this doesn't touch any state
+    /// the [`Receiver`] writes to, so it costs nothing to call after every send.
+    /// It's a lower bound like [`slots_free`](Sender::slots_free), but a looser
+    /// one, since the [`Sender`] only looks again once its cached view runs out.
+    /// After a [`try_send`](Sender::try_send) fails with [`TrySendError::Full`],
+    /// the view is fresh, so `capacity() - last_known_free()` is the occupancy
+    /// an adaptive producer can scale its back off with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// for i in 0..4 {
+    ///     src.try_send(i).unwrap();
+    /// }
+    /// assert!(src.try_send(4).is_err());
+    /// assert_eq!(src.last_known_free(), 0);
+    /// assert_eq!(sink.recv(), Ok(0));
+    /// // only updated once the sender checks the receiver again.
+    /// assert_eq!(src.last_known_free(), 0);
+    /// assert_eq!(src.slots_free(), 1);
+    /// ```
+    #[inline]
+    pub fn last_known_free(&self) -> usize {
+        self.inner_ref().last_known_free()
+    }
+
+    /// Returns how many values the [`channel`] can hold.
+    ///
+    /// Capacities are rounded up to a power of two, unless the channel was
+    /// created with [`channel_exact`], so this can be more than was asked for.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner_ref().capacity()
+    }
+
+    /// Returns the sequence number of the next value sent, which
+    /// is how many values were sent through the [`channel`].
+    ///
+    /// Together with [`Receiver::head_seq`], this numbers every value without
+    /// wrapping it in an envelope, e.g. for recording that the consumer
+    /// processed everything up to some point. The numbers wrap around at
+    /// `u64::MAX`, so they should be compared with wrapping arithmetic.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<&str>(4);
+    /// let seq = src.tail_seq();
+    /// src.send("a").unwrap();
+    /// src.send("b").unwrap();
+    /// assert_eq!(src.tail_seq().wrapping_sub(seq), 2);
+    /// // every value was received once head_seq catches up.
+    /// assert_eq!(sink.recv(), Ok("a"));
+    /// assert_eq!(sink.head_seq(), seq + 1);
+    /// ```
+    #[inline]
+    pub fn tail_seq(&self) -> u64 {
+        self.inner_ref().tail_seq()
+    }
+
+    /// Returns a snapshot of the [`channel`]'s counters.
+    /// Enabled by the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::metrics::Stats {
+        self.inner_ref().stats()
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    /// Checks if either end point panicked on a [`channel`]
+    /// built with [`Builder::poisoning`].
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.inner_ref().is_poisoned()
+    }
+
+    /// Disconnects the [`channel`] without dropping the [`Sender`].
+    ///
+    /// The [`Receiver`] is woken up if it's blocked and can still receive
+    /// the values sent before closing. Any further sends fail like
+    /// they would if the [`Receiver`] disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// src.send(1).unwrap();
+    /// src.close();
+    /// assert!(src.is_closed());
+    /// assert!(src.send(2).is_err());
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// assert!(sink.recv().is_err());
+    /// ```
+    pub fn close(&self) {
+        self.inner_ref().close_sender();
+        self.inner_ref().wake_receiver();
+    }
+
+    /// Checks if the [`channel`] was closed by either end point or disconnected.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        !self.inner_ref().peer_connected()
+    }
+
+    /// Creates a new [`Receiver`] for this [`channel`] if the old one disconnected.
+    ///
+    /// All the data that wasn't received by the old [`Receiver`] is dropped,
+    /// and the [`channel`] can be used as if it was just created.
+    /// Returns [`None`] if the [`Receiver`] is still connected.
+    pub fn reconnect(&mut self) -> Option<Receiver<T>> {
+        let inner = self.inner_ref();
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match inner.shared.drop_count.load(Acquire) & !(CLOSED | POISONED) {
+                0 => return None,
+                //the Receiver is still waking us up.
+                1 => crate::thread::yield_now(),
+                2 => break,
+                _ => unreachable!(),
+            }
+        }
+        /*SAFETY:
+         *the Receiver is gone and Sender is borrowed
+         *mutably, so nothing else can use the channel.
+         */
+        unsafe { inner.clear() };
+        inner.shared.drop_count.store(0, Release);
+        Some(Receiver {
+            inner: self.inner,
+            front: UnsafeCell::new(None),
+            has_front: Cell::new(false),
+            _unsync: PhantomUnsync {},
+        })
+    }
+
+    /// Takes back the values the [`Receiver`] didn't receive before disconnecting.
+    ///
+    /// Returns the [`Sender`] unchanged if the [`Receiver`] is still connected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// assert_eq!(sink.recv(), Ok(1));
+    ///
+    /// let src = src.into_pending().unwrap_err();
+    /// drop(sink);
+    /// assert_eq!(src.into_pending().unwrap(), vec![2]);
+    /// ```
+    pub fn into_pending(self) -> Result<Vec<T>, Sender<T>> {
+        let inner = self.inner_ref();
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match inner.shared.drop_count.load(Acquire) & !(CLOSED | POISONED) {
+                0 => return Err(self),
+                //the Receiver is still waking us up.
+                1 => crate::thread::yield_now(),
+                2 => break,
+                _ => unreachable!(),
+            }
+        }
+        //the Receiver is gone, so the Sender can take its place.
+        Ok(inner.drain_to_vec())
+    }
+
+    fn inner_ref(&self) -> &Inner<T> {
+        /*SAFETY:
+         *This type and Sender are responsible for inner's lifetime.
+         */
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T: Copy> Sender<T> {
+    /// Tries to send as many values from `items` as fit into the [`channel`].
+    ///
+    /// The values are copied into the buffer in at most two contiguous
+    /// runs and published together, which is much faster than sending
+    /// them one by one. Returns the number of sent values, which is 0 if
+    /// the [`channel`] is full, or a [`SendError`] if the [`Receiver`]
+    /// is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<f32>(4);
+    /// let frame = [0.0, 0.25, 0.5, 0.75, 1.0];
+    /// assert_eq!(src.try_send_slice(&frame), Ok(4));
+    /// assert_eq!(src.try_send_slice(&frame[4..]), Ok(0));
+    /// assert_eq!(sink.try_iter().collect::<Vec<_>>(), frame[..4]);
+    /// ```
+    pub fn try_send_slice(&self, items: &[T]) -> Result<usize, SendError<()>> {
+        self.inner_ref().try_send_slice(items)
+    }
+
+    /// Sends as many values from `items` as fit into the [`channel`], like
+    /// [`try_send_slice`](Sender::try_send_slice).
+    ///
+    /// If the [`channel`] is full, blocks until at least one value can be sent.
+    /// Returns the number of sent values, which is only 0 if `items` is empty,
+    /// or a [`SendError`] if the [`Receiver`] is disconnected.
+    pub fn send_slice(&self, items: &[T]) -> Result<usize, SendError<()>> {
+        self.inner_ref().send_slice(items)
+    }
+}
+
+impl<T: Copy> Receiver<T> {
+    /// Tries to receive as many values as fit into `buf`.
+    ///
+    /// The values are copied out of the buffer in at most two contiguous
+    /// runs and their slots are freed together, which is much faster than
+    /// receiving them one by one. Returns the number of received values,
+    /// which is 0 if the [`channel`] is empty.
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after consuming all sent data. To
+    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<f32>(8);
+    /// assert_eq!(src.try_send_slice(&[0.0, 0.5, 1.0]), Ok(3));
+    ///
+    /// let mut frame = [0.0; 2];
+    /// assert_eq!(sink.try_recv_slice(&mut frame), Ok(2));
+    /// assert_eq!(frame, [0.0, 0.5]);
+    /// assert_eq!(sink.try_recv_slice(&mut frame), Ok(1));
+    /// assert_eq!(sink.try_recv_slice(&mut frame), Ok(0));
+    /// ```
+    pub fn try_recv_slice(&self, buf: &mut [T]) -> Result<usize, RecvError> {
+        match self.recv_front_into(buf) {
+            0 => self.inner_ref().try_recv_slice(buf),
+            n => Ok(n),
+        }
+    }
+
+    /// Receives as many values as fit into `buf`, like
+    /// [`try_recv_slice`](Receiver::try_recv_slice).
+    ///
+    /// If the [`channel`] is empty, blocks until at least one value can be
+    /// received. Returns the number of received values, which is only 0 if
+    /// `buf` is empty.
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after consuming all sent data. To
+    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn recv_slice(&self, buf: &mut [T]) -> Result<usize, RecvError> {
+        match self.recv_front_into(buf) {
+            0 => self.inner_ref().recv_slice(buf),
+            n => Ok(n),
+        }
+    }
+
+    /// Copies the value put back by [`push_front`](Receiver::push_front) and as
+    /// many pending values as fit after it into `buf`, without blocking.
+    ///
+    /// Returns 0 if there's no such value or `buf` is empty.
+    fn recv_front_into(&self, buf: &mut [T]) -> usize {
+        let Some((first, rest)) = buf.split_first_mut() else {
+            return 0;
+        };
+        match self.take_front() {
+            // a disconnect is reported by the next call.
+            Some(item) => {
+                *first = item;
+                1 + self.inner_ref().try_recv_slice(rest).unwrap_or(0)
+            }
+            None => 0,
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to return a pending value.
+    ///
+    /// # Notes
+    ///
+    /// - Returns [`TryRecvError::Disconnected`] only after consuming all
+    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    /// - Will never block as long as [`send`](Sender::send) hasn't been called.
+    /// - After every call to [`send`](Sender::send), up to one [`try_recv`](Receiver::try_recv)
+    /// call may block for a short period.
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        match self.take_front() {
+            Some(item) => Ok(item),
+            None => self.inner_ref().try_recv(),
+        }
+    }
+
+    /// Reads a value from the [`channel`].
+    ///
+    /// If the [`channel`] is empty, blocks and waits for the [`Sender`].
+    ///
+    /// # Notes
+    /// - [`RecvError`] is only returned after consuming all sent data. To
+    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
+    /// - Calling this method may result in a [`try_send`](Sender::try_send)
+    /// call blocking for a short period.
+    #[inline]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        match self.take_front() {
+            Some(item) => Ok(item),
+            None => self.inner_ref().recv(),
+        }
+    }
+
+    /// Reads a value from the [`channel`], waiting at most `timeout` for the [`Sender`].
+    ///
+    /// Returns [`RecvTimeoutError::Timeout`] if the [`channel`] is still empty
+    /// after `timeout` and [`RecvTimeoutError::Disconnected`] like
+    /// [`recv`](Receiver::recv) returns [`RecvError`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use concurrent_qs::error::RecvTimeoutError;
+    /// use std::time::Duration;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(1);
+    /// assert_eq!(
+    ///     sink.recv_timeout(Duration::from_millis(10)),
+    ///     Err(RecvTimeoutError::Timeout)
+    /// );
+    /// src.send(1).unwrap();
+    /// assert_eq!(sink.recv_timeout(Duration::from_millis(10)), Ok(1));
+    /// ```
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        match self.take_front() {
+            Some(item) => Ok(item),
+            None => self
+                .inner_ref()
+                .recv_deadline(Instant::now().checked_add(timeout)),
+        }
+    }
+
+    /// Reads a value from the [`channel`], blocking until one is sent,
+    /// the [`Sender`] disconnects or `token` is cancelled.
+    ///
+    /// Once `token` is cancelled, this returns [`RecvCancelError::Cancelled`]
+    /// right away, even if values are pending. They stay in the [`channel`] and
+    /// can still be received with the other methods. The [`Receiver`] is only
+    /// woken up by the [`Sender`] or the token, so it doesn't return spuriously.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::error::RecvCancelError;
+    /// use concurrent_qs::spsc::bounded;
+    /// use concurrent_qs::sync::CancellationToken;
+    /// use std::thread;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(1);
+    /// let token = CancellationToken::new();
+    /// let cancel = token.clone();
+    /// thread::spawn(move || cancel.cancel());
+    /// assert_eq!(sink.recv_cancellable(&token), Err(RecvCancelError::Cancelled));
+    /// src.send(1).unwrap();
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// ```
+    pub fn recv_cancellable(&self, token: &CancellationToken) -> Result<T, RecvCancelError> {
+        loop {
+            if token.is_cancelled() {
+                return Err(RecvCancelError::Cancelled);
+            }
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Empty) => token.wait(self),
+                Err(_) => return Err(RecvCancelError::Disconnected),
+            }
+        }
+    }
+
+    /// Receives values into `buf` until `max_items` were received or `timeout` elapses.
+    ///
+    /// Values that are already in the [`channel`] are taken without waiting, and
+    /// in between, this parks like [`recv_timeout`](Receiver::recv_timeout).
+    /// Returns the number of values appended to `buf`, which is 0 if the timeout
+    /// elapsed before any value arrived.
+    ///
+    /// Returns a [`RecvError`] only if the [`Sender`] disconnected and all sent
+    /// data was consumed before any value was received. Otherwise, the
+    /// disconnect is reported by the next call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::time::Duration;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(8);
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    ///
+    /// let mut batch = Vec::new();
+    /// assert_eq!(sink.recv_batch_timeout(&mut batch, 4, Duration::from_millis(10)), Ok(2));
+    /// assert_eq!(batch, [1, 2]);
+    /// ```
+    pub fn recv_batch_timeout(
+        &self,
+        buf: &mut Vec<T>,
+        max_items: usize,
+        timeout: Duration,
+    ) -> Result<usize, RecvError> {
+        let deadline = Instant::now().checked_add(timeout);
+        match self.take_front_if(max_items != 0) {
+            Some(item) => {
+                buf.push(item);
+                // a disconnect is reported by the next call.
+                let rest = self.inner_ref().recv_batch_deadline(buf, max_items - 1, deadline);
+                Ok(1 + rest.unwrap_or(0))
+            }
+            None => self.inner_ref().recv_batch_deadline(buf, max_items, deadline),
+        }
+    }
+
+    /// Reads a value from the [`channel`] without moving it out of the buffer.
+    ///
+    /// Blocks like [`recv`](Receiver::recv). The value is dropped and its slot
+    /// is given back to the [`Sender`] when the returned [`RecvRef`] is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, mut sink) = bounded::channel::<[u64; 512]>(2);
+    /// src.send([1; 512]).unwrap();
+    /// let value = sink.recv_ref().unwrap();
+    /// assert_eq!(value.iter().sum::<u64>(), 512);
+    /// ```
+    ///
+    /// The [`RecvRef`] borrows the [`Receiver`] mutably, so the
+    /// same value can't be received again while it's alive:
+    ///
+    /// ```compile_fail
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, mut sink) = bounded::channel::<u32>(2);
+    /// src.send(1).unwrap();
+    /// let value = sink.recv_ref().unwrap();
+    /// let again = sink.try_recv();
+    /// drop(value);
+    /// ```
+    #[inline]
+    pub fn recv_ref(&mut self) -> Result<RecvRef<'_, T>, RecvError> {
+        if self.has_front.get() {
+            return Ok(RecvRef::front(self));
+        }
+        let head = self.inner_ref().peek()?;
+        Ok(RecvRef {
+            receiver: self,
+            head,
+            front: false,
+        })
+    }
+
+    /// Tries to return a reference to a pending value.
+    ///
+    /// Works like [`try_recv`](Receiver::try_recv), except the value
+    /// stays in the buffer until the [`RecvRef`] is dropped.
+    #[inline]
+    pub fn try_recv_ref(&mut self) -> Result<RecvRef<'_, T>, TryRecvError> {
+        if self.has_front.get() {
+            return Ok(RecvRef::front(self));
+        }
+        let head = self.inner_ref().try_peek()?;
+        Ok(RecvRef {
+            receiver: self,
+            head,
+            front: false,
+        })
+    }
+
+    /// Tries to borrow up to `max` pending values without moving them out of the buffer.
+    ///
+    /// The values can be processed in place, e.g. serialized straight from the
+    /// [`channel`], and are received by [`ReadChunk::commit`]. Fails like
+    /// [`try_recv`](Receiver::try_recv) if no value is pending. The [`ReadChunk`]
+    /// borrows the [`Receiver`] mutably, like [`recv_ref`](Receiver::recv_ref).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, mut sink) = bounded::channel::<u8>(4);
+    /// assert_eq!(src.try_send_slice(b"abc"), Ok(3));
+    ///
+    /// let chunk = sink.read_chunk(8).unwrap();
+    /// let (first, second) = chunk.as_slices();
+    /// assert_eq!([first, second].concat(), b"abc");
+    /// chunk.commit(2);
+    /// assert_eq!(sink.try_recv(), Ok(b'c'));
+    /// ```
+    #[cfg(not(feature = "loom"))]
+    pub fn read_chunk(&mut self, max: usize) -> Result<ReadChunk<'_, T>, TryRecvError> {
+        if self.has_front.get() {
+            return Ok(ReadChunk::front(self, max));
+        }
+        let head = self.inner_ref().try_peek()?;
+        Ok(ReadChunk::new(self, head, max))
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
+    /// methods return [`TryRecvError::Disconnected`] or [`RecvError`] only
+    /// after consuming all previously sent data, even if the [`Sender`] isn't
+    /// connected. This method doesn't take pending data into account and can
+    /// be used to avoid this behaviour.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    /// Checks if either end point panicked on a [`channel`]
+    /// built with [`Builder::poisoning`].
+    ///
+    /// Like [`sender_connected`](Receiver::sender_connected), this doesn't take
+    /// pending data into account, unlike [`TryRecvError::Poisoned`].
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.inner_ref().is_poisoned()
+    }
+
+    /// Returns the connection state of the [`channel`], along with how
+    /// many values are pending if it's disconnected.
+    ///
+    /// This tells apart the reasons [`try_recv`](Receiver::try_recv) can
+    /// fail with [`TryRecvError::Disconnected`] and whether all data was
+    /// consumed, without having to infer it from a sequence of errors.
+    /// A [`channel`] that was closed and then lost its [`Sender`] is reported
+    /// as [`Closed`](ReceiverState::Closed).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded::{self, ReceiverState};
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// assert_eq!(sink.state(), ReceiverState::Connected);
+    /// src.send(1).unwrap();
+    /// drop(src);
+    /// assert_eq!(sink.state(), ReceiverState::SenderDropped { pending: 1 });
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// assert_eq!(sink.state(), ReceiverState::SenderDropped { pending: 0 });
+    /// ```
+    pub fn state(&self) -> ReceiverState {
+        // loaded first, so the pending count includes everything sent before a disconnect.
+        let state = self.inner_ref().shared.drop_count.load(Acquire);
+        if state == 0 {
+            return ReceiverState::Connected;
+        }
+        let pending = self.len_hint();
+        if state & POISONED != 0 {
+            ReceiverState::Poisoned { pending }
+        } else if state & CLOSED != 0 {
+            ReceiverState::Closed { pending }
+        } else {
+            ReceiverState::SenderDropped { pending }
+        }
+    }
+
+    /// Checks if a value is pending, so [`try_recv`](Receiver::try_recv) would succeed.
+    ///
+    /// Unlike [`try_recv`](Receiver::try_recv), this doesn't modify the
+    /// [`channel`], so it's cheap to poll. It doesn't take disconnection
+    /// into account, see [`sender_connected`](Receiver::sender_connected).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// assert!(!sink.is_ready());
+    /// src.send(1).unwrap();
+    /// assert!(sink.is_ready());
+    /// ```
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.has_front.get() || self.inner_ref().has_pending()
+    }
+
+    /// Returns how many values are pending.
+    ///
+    /// Like [`Sender::slots_free`], the estimate can only be stale in one
+    /// direction: the [`Sender`] may send more values after the call, but
+    /// only this [`Receiver`] can take them out, so the result is a lower
+    /// bound until the next receive. Without receives in between, later
+    /// calls never return less. On a [`channel_overwriting`], values
+    /// overwritten by [`force_send`](Sender::force_send) are gone, so it's
+    /// only an estimate there.
+    #[inline]
+    pub fn len_hint(&self) -> usize {
+        self.has_front.get() as usize + self.inner_ref().len_hint()
+    }
+
+    /// Returns how many values the [`channel`] can hold.
+    ///
+    /// See [`Sender::capacity`].
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner_ref().capacity()
+    }
+
+    /// Returns the sequence number of the next value received, which
+    /// is how many values were received from the [`channel`].
+    ///
+    /// See [`Sender::tail_seq`]. A value put back with
+    /// [`push_front`](Receiver::push_front) doesn't count as received, and
+    /// on a [`channel_overwriting`], values overwritten by
+    /// [`force_send`](Sender::force_send) do.
+    #[inline]
+    pub fn head_seq(&self) -> u64 {
+        self.inner_ref()
+            .head_seq()
+            .wrapping_sub(self.has_front.get() as u64)
+    }
+
+    /// Returns a snapshot of the [`channel`]'s counters.
+    /// Enabled by the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::metrics::Stats {
+        self.inner_ref().stats()
+    }
+
+    /// Sets a callback that's called every time the [`Sender`] sends
+    /// a value or disconnects, replacing the previous callback.
+    ///
+    /// This allows waiting for the [`channel`] in other event loops, for
+    /// example by waking a [`std::task::Waker`]. The callback runs on the
+    /// [`Sender`]'s thread, so it should return quickly. Values sent
+    /// before the callback was set don't call it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// let sent = Arc::new(AtomicUsize::new(0));
+    /// let counter = sent.clone();
+    /// sink.set_notify(move || {
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// src.send(1).unwrap();
+    /// assert_eq!(sent.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn set_notify(&self, notify: impl Fn() + Send + Sync + 'static) {
+        self.inner_ref().set_receiver_notify(Some(Box::new(notify)))
+    }
+
+    /// Removes the callback set by [`set_notify`](Receiver::set_notify).
+    pub fn clear_notify(&self) {
+        self.inner_ref().set_receiver_notify(None)
+    }
+
+    /// Returns a file descriptor that becomes readable every time the
+    /// [`Sender`] sends a value or disconnects, replacing the
+    /// callback of [`set_notify`](Receiver::set_notify).
+    ///
+    /// Values sent before this call don't make the descriptor readable.
+    /// See [`NotifyFd`](crate::notify_fd::NotifyFd) for more.
+    #[cfg(all(unix, feature = "fd-notify"))]
+    pub fn notify_fd(&self) -> std::io::Result<crate::notify_fd::NotifyFd> {
+        let (fd, notify) = crate::notify_fd::NotifyFd::new()?;
+        self.inner_ref().set_receiver_notify(Some(Box::new(notify)));
+        Ok(fd)
+    }
+
+    /// Disconnects the [`channel`] without dropping the [`Receiver`].
+    ///
+    /// The [`Sender`] is woken up if it's blocked and any further sends fail.
+    /// Values sent before closing can still be received.
+    pub fn close(&self) {
+        self.inner_ref().close();
+        self.inner_ref().wake_sender();
+    }
+
+    /// Checks if the [`channel`] was closed by either end point or disconnected.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        !self.inner_ref().peer_connected()
+    }
+
+    /// Takes all pending values out of the [`channel`] at once.
+    ///
+    /// The values are copied out of the buffer in at most two contiguous
+    /// runs, which is much faster than calling [`try_recv`](Receiver::try_recv)
+    /// in a loop for small `T`. Never blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u64>(8);
+    /// for i in 0..5 {
+    ///     src.send(i).unwrap();
+    /// }
+    /// assert_eq!(sink.drain_to_vec(), vec![0, 1, 2, 3, 4]);
+    /// assert!(sink.drain_to_vec().is_empty());
+    /// ```
+    pub fn drain_to_vec(&self) -> Vec<T> {
+        let front = self.take_front();
+        let mut values = self.inner_ref().drain_to_vec();
+        if let Some(item) = front {
+            values.insert(0, item);
+        }
+        values
+    }
+
+    /// Closes the [`channel`] and takes out every pending value, so
+    /// values that weren't processed can be logged or saved on shutdown
+    /// instead of being dropped along with the [`channel`].
+    ///
+    /// A send that started before the [`Sender`] noticed the close can
+    /// still go through afterwards. That's only possible on channels
+    /// built with [`Builder::disconnect_check_interval`] or while the
+    /// [`Sender`] is in the middle of a send, and the values can be taken
+    /// out with [`drain_to_vec`](Receiver::drain_to_vec) once it's dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(8);
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// assert_eq!(sink.close_and_drain(), vec![2]);
+    /// assert!(src.send(3).is_err());
+    /// ```
+    pub fn close_and_drain(&self) -> Vec<T> {
+        self.close();
+        self.drain_to_vec()
+    }
+
+    /// Puts `item` back into the [`channel`], so it's the next value received.
+    ///
+    /// This is meant for values that were received, but can't be processed yet,
+    /// and keeps them in order without a separate buffer. Only one value can be
+    /// put back at a time, so this returns [`TrySendError::Full`] until it's
+    /// received again. The value doesn't take up a slot of the [`channel`]
+    /// and is dropped along with the [`Receiver`]. The room for it is
+    /// allocated by the first call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::error::TrySendError;
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(2);
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// let value = sink.recv().unwrap();
+    /// // downstream isn't ready for `value` yet.
+    /// assert_eq!(sink.push_front(value), Ok(()));
+    /// assert_eq!(sink.push_front(3), Err(TrySendError::Full(3)));
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// assert_eq!(sink.recv(), Ok(2));
+    /// ```
+    ///
+    /// A [`RecvRef`] or [`ReadChunk`] of the value borrows the [`Receiver`]
+    /// mutably, so it can't be taken out or replaced while they're alive:
+    ///
+    /// ```compile_fail
+    /// use concurrent_qs::spsc::bounded;
+    ///
+    /// let (src, mut sink) = bounded::channel::<u32>(2);
+    /// sink.push_front(1).unwrap();
+    /// let value = sink.recv_ref().unwrap();
+    /// let again = sink.try_recv();
+    /// drop(value);
+    /// ```
+    pub fn push_front(&self, item: T) -> Result<(), TrySendError<T>> {
+        if self.has_front.get() {
+            return Err(TrySendError::Full(item));
+        }
+        //SAFETY: `front` is uninitialised and only accessed by this !Sync Receiver.
+        let front = unsafe { &mut *self.front.get() };
+        front
+            .get_or_insert_with(|| Box::new(MaybeUninit::uninit()))
+            .write(item);
+        self.has_front.set(true);
+        Ok(())
+    }
+
+    /// Takes the value put back by [`push_front`](Receiver::push_front).
+    #[inline]
+    fn take_front(&self) -> Option<T> {
+        self.take_front_if(true)
+    }
+
+    #[inline]
+    fn take_front_if(&self, take: bool) -> Option<T> {
+        if !take || !self.has_front.get() {
+            return None;
+        }
+        Some(self.take_front_unchecked())
+    }
+
+    /// Kept out of line, so receiving costs nothing extra while
+    /// [`push_front`](Receiver::push_front) isn't used.
+    ///
+    /// Must only be called if `has_front` is set.
+    #[cold]
+    fn take_front_unchecked(&self) -> T {
+        //SAFETY: `has_front` is set, so `front` is initialised.
+        let item = unsafe { self.front_ptr().read() };
+        self.has_front.set(false);
+        item
+    }
+
+    /// Returns a pointer to the value put back by [`push_front`](Receiver::push_front).
+    ///
+    /// Must only be called if `has_front` is set.
+    #[inline]
+    fn front_ptr(&self) -> *mut T {
+        debug_assert!(self.has_front.get());
+        //SAFETY: the room is allocated before `has_front` is first set.
+        match unsafe { &mut *self.front.get() } {
+            Some(front) => front.as_mut_ptr(),
+            None => unreachable!(),
+        }
+    }
+
+    /// Returns an id of the [`channel`], shared by its [`Sender`] and [`Receiver`].
+    ///
+    /// See [`Sender::channel_id`].
+    #[inline]
+    pub fn channel_id(&self) -> usize {
+        crate::util::addr(self.inner.as_ptr())
+    }
+
+    /// Returns the name the [`channel`] was built with by [`Builder::name`].
+    #[inline]
+    pub fn name(&self) -> Option<&'static str> {
+        self.inner_ref().name()
+    }
+
+    /// Checks if `sender` belongs to the same [`channel`] as this [`Receiver`].
+    #[inline]
+    pub fn same_channel(&self, sender: &Sender<T>) -> bool {
+        sender.same_channel(self)
+    }
+
+    /// Hands the [`channel`] over to a new [`Receiver`], leaving this one behind
+    /// as a disconnected [`Receiver`] of an empty channel.
+    ///
+    /// The buffered values, including one put back by [`push_front`](Receiver::push_front),
+    /// go to the new [`Receiver`], and the [`Sender`] doesn't notice the hand-off.
+    /// This lets a consumer thread be replaced, e.g. to reload it, without tearing
+    /// down the [`channel`]: the old thread hands the new [`Receiver`] over and
+    /// sees its own receive loop end, since it only gets disconnect errors.
+    ///
+    /// Returns an [`AllocError`] if the memory for the empty channel can't
+    /// be allocated, in which case this [`Receiver`] is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::thread;
+    ///
+    /// let (src, mut sink) = bounded::channel::<u32>(4);
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// assert_eq!(sink.recv(), Ok(1));
+    ///
+    /// let takeover = sink.try_clone_takeover().unwrap();
+    /// assert!(sink.recv().is_err());
+    /// let consumer = thread::spawn(move || takeover.recv());
+    /// // the buffered value isn't lost.
+    /// assert_eq!(consumer.join().unwrap(), Ok(2));
+    /// ```
+    pub fn try_clone_takeover(&mut self) -> Result<Receiver<T>, AllocError> {
+        // a generation counter checked on each op would leave the stale Receiver
+        // pointing at the channel, which is freed once the new Receiver and the
+        // Sender are dropped, and would add a check to every receive. Moving it
+        // onto its own empty channel costs an allocation per takeover instead,
+        // but it fails just as deterministically and never touches the channel.
+        // The Sender of the empty channel is dropped right away, which disconnects it.
+        let (_, mut takeover) = try_from_inner(Inner::<T>::try_new(1)?)?;
+        std::mem::swap(self, &mut takeover);
+        Ok(takeover)
+    }
+
+    /// Sets how many times blocking operations of this [`Receiver`]
+    /// busy-wait for the [`Sender`] before parking the thread.
+    ///
+    /// A short spin avoids the cost of parking when values are expected
+    /// to arrive quickly. See also [`Builder::spin`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::thread;
+    ///
+    /// let (src, sink) = bounded::channel::<u32>(4);
+    /// sink.set_spin(1000);
+    /// thread::spawn(move || src.send(1).unwrap());
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// ```
+    pub fn set_spin(&self, count: u32) {
+        self.inner_ref().set_receiver_spin(count)
+    }
+
+    /// Sets what blocking operations of this [`Receiver`] do once they're
+    /// done spinning, replacing the strategy set by [`Builder::idle`].
+    ///
+    /// See [`IdleStrategy`] for an example.
+    pub fn set_idle(&self, idle: IdleStrategy) {
+        self.inner_ref().set_receiver_idle(idle)
+    }
+
+    /// Returns an iterator that blocks waiting for values.
+    ///
+    /// The iterator ends when the [`Sender`] disconnects and
+    /// all previously sent data is received.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator over the currently pending values.
+    ///
+    /// The iterator never blocks and ends when the [`channel`]
+    /// is empty or the [`Sender`] disconnects.
+    #[inline]
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+
+    fn inner_ref(&self) -> &Inner<T> {
+        /*SAFETY:
+         *This type and Receiver are responsible for inner's lifetime.
+         */
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+/// A slot reserved with [`Sender::reserve`].
+///
+/// Dropping it without calling [`commit`](SendSlot::commit) or
+/// [`write`](SendSlot::write) doesn't send anything. If the slot
+/// was initialised, the value is leaked instead of dropped. On a
+/// [`channel`] built with [`Builder::poisoning`], dropping it while
+/// panicking poisons the [`channel`].
+pub struct SendSlot<'a, T> {
+    sender: &'a Sender<T>,
+    tail: usize,
+}
+
+impl<'a, T> SendSlot<'a, T> {
+    /// Gives access to the reserved slot for in-place construction.
+    #[inline]
+    pub fn as_uninit(&mut self) -> &mut MaybeUninit<T> {
+        /*SAFETY:
+         *the slot is reserved and can't be read by the
+         *Receiver until it's committed.
+         */
+        unsafe { &mut *self.sender.inner_ref().slot_ptr(self.tail) }
+    }
+
+    /// Sends the value in the reserved slot.
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialised through [`as_uninit`](SendSlot::as_uninit).
+    #[inline]
+    pub unsafe fn commit(self) {
+        let this = std::mem::ManuallyDrop::new(self);
+        this.sender.inner_ref().commit(this.tail)
+    }
+
+    /// Writes `item` into the reserved slot and sends it.
+    #[inline]
+    pub fn write(mut self, item: T) {
+        self.as_uninit().write(item);
+        //SAFETY: just initialised.
+        unsafe { self.commit() }
+    }
+}
+
+impl<'a, T> Drop for SendSlot<'a, T> {
+    fn drop(&mut self) {
+        let inner = self.sender.inner_ref();
+        if inner.poison_if_panicking() {
+            inner.wake_receiver();
+        }
+    }
+}
+
+impl<'a, T> std::fmt::Debug for SendSlot<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::SendSlot<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.sender.inner
+        )
+    }
+}
+
+/// A value received with [`Receiver::recv_ref`] or [`Receiver::try_recv_ref`].
+///
+/// The value stays in the [`channel`]'s buffer until this is dropped. On a
+/// [`channel`] built with [`Builder::poisoning`], dropping it while
+/// panicking poisons the [`channel`].
+pub struct RecvRef<'a, T> {
+    receiver: &'a Receiver<T>,
+    head: usize,
+    /// Set if the value was put back by `push_front` instead of being in the buffer.
+    front: bool,
+}
+
+impl<'a, T> RecvRef<'a, T> {
+    #[cold]
+    fn front(receiver: &'a Receiver<T>) -> Self {
+        Self {
+            receiver,
+            head: 0,
+            front: true,
+        }
+    }
+
+    #[inline]
+    fn value_ptr(&self) -> *mut T {
+        match self.front {
+            true => self.receiver.front_ptr(),
+            false => self.receiver.inner_ref().slot_ptr(self.head) as *mut T,
+        }
+    }
+}
+
+impl<'a, T> std::ops::Deref for RecvRef<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        /*SAFETY:
+         *the slot was written to by the sender and
+         *can't be reused before it's released.
+         */
+        unsafe { &*self.value_ptr() }
+    }
+}
+
+impl<'a, T> std::ops::DerefMut for RecvRef<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        //SAFETY: same as deref, and only the Receiver can access the slot.
+        unsafe { &mut *self.value_ptr() }
+    }
+}
+
+impl<'a, T> Drop for RecvRef<'a, T> {
+    fn drop(&mut self) {
+        let inner = self.receiver.inner_ref();
+        if inner.poison_if_panicking() {
+            inner.wake_sender();
+        }
+        if self.front {
+            let front = self.receiver.front_ptr();
+            self.receiver.has_front.set(false);
+            //SAFETY: the value was put back by push_front and nothing else refers to it.
+            return unsafe { front.drop_in_place() };
+        }
+        let _release = ReleaseGuard {
+            inner,
+            head: self.head,
+            n: 1,
+        };
+        /*SAFETY:
+         *the value is dropped before the
+         *slot is released to the sender.
+         */
+        unsafe { (inner.slot_ptr(self.head) as *mut T).drop_in_place() };
+    }
+}
+
+/// Releases `n` slots starting at `head` when dropped, so
+/// they're freed even if dropping their values panics.
+struct ReleaseGuard<'a, T> {
+    inner: &'a Inner<T>,
+    head: usize,
+    n: usize,
+}
+
+impl<'a, T> Drop for ReleaseGuard<'a, T> {
+    fn drop(&mut self) {
+        /*SAFETY:
+         *head was returned by the last peek or try_peek call,
+         *and the values were dropped or are being dropped.
+         */
+        unsafe { self.inner.release_n(self.head, self.n) }
+    }
+}
+
+impl<'a, T: std::fmt::Debug> std::fmt::Debug for RecvRef<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A blocking iterator over the values of a [`channel`].
+///
+/// Created by [`Receiver::iter`].
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+/// A non-blocking iterator over the pending values of a [`channel`].
+///
+/// Created by [`Receiver::try_iter`].
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+/// An owning blocking iterator over the values of a [`channel`].
+///
+/// Created by [`Receiver::into_iter`].
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.inner_ref().poison_if_panicking();
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().shared.drop_count.fetch_add(1, AcqRel) & !(CLOSED | POISONED) {
+                0 => self.inner_ref().wake_receiver(),
+                1 => break,
+                2 => {
+                    break unsafe {
+                        self.inner.as_ptr().drop_in_place();
+                        dealloc(self.inner.as_ptr() as *mut u8, Inner::<T>::LAYOUT)
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        drop(self.take_front());
+        self.inner_ref().poison_if_panicking();
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().shared.drop_count.fetch_add(1, AcqRel) & !(CLOSED | POISONED) {
+                0 => self.inner_ref().wake_sender(),
+                1 => break,
+                2 => {
+                    break unsafe {
+                        self.inner.as_ptr().drop_in_place();
+                        dealloc(self.inner.as_ptr() as *mut u8, Inner::<T>::LAYOUT)
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> PartialEq for Sender<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.channel_id() == other.channel_id()
+    }
+}
+
+impl<T> Eq for Sender<T> {}
+
+impl<T> std::hash::Hash for Sender<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.channel_id().hash(state)
+    }
+}
+
+impl<T> PartialEq for Receiver<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.channel_id() == other.channel_id()
+    }
+}
+
+impl<T> Eq for Receiver<T> {}
+
+impl<T> std::hash::Hash for Receiver<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.channel_id().hash(state)
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = format!("spsc::bounded::Sender<{}>", std::any::type_name::<T>());
+        let mut debug = f.debug_struct(&name);
+        debug.field("channel", &self.inner);
+        if let Some(name) = self.name() {
+            debug.field("name", &name);
+        }
+        debug
+            .field("len", &(self.capacity() - self.slots_free()))
+            .field("capacity", &self.capacity())
+            .field("receiver_connected", &self.receiver_connected())
+            .finish()
+    }
+}
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = format!("spsc::bounded::Receiver<{}>", std::any::type_name::<T>());
+        let mut debug = f.debug_struct(&name);
+        debug.field("channel", &self.inner);
+        if let Some(name) = self.name() {
+            debug.field("name", &name);
+        }
+        debug
+            .field("len", &(self.len_hint()))
+            .field("capacity", &self.capacity())
+            .field("sender_connected", &self.sender_connected())
+            .finish()
+    }
+}
+
+impl<T> crate::traits::TrySend<T> for Sender<T> {
+    type Error = TrySendError<T>;
+
+    #[inline]
+    fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        Sender::try_send(self, item)
+    }
+}
+
+impl<T> crate::traits::BlockingSend<T> for Sender<T> {
+    #[inline]
+    fn send(&self, item: T) -> Result<(), SendError<T>> {
+        Sender::send(self, item)
+    }
+}
+
+impl<T> crate::traits::TryRecv<T> for Receiver<T> {
+    #[inline]
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        Receiver::try_recv(self)
+    }
+}
+
+impl<T> crate::traits::BlockingRecv<T> for Receiver<T> {
+    #[inline]
+    fn recv(&self) -> Result<T, RecvError> {
+        Receiver::recv(self)
+    }
+}
+
+/// Enabled by the `futures` feature.
+///
+/// While waiting for room, the task is woken every time
+/// the [`Receiver`] takes values out.
+/// Sending fails with a [`SendError`] once the [`channel`] is closed or
+/// disconnected, and [`poll_close`](futures_sink::Sink::poll_close) closes it,
+/// so a [`Receiver`] used as a [`Stream`](futures_core::Stream) ends.
+/// On a [`channel_rendezvous`], a sent value waits in the buffer and
+/// [`poll_flush`](futures_sink::Sink::poll_flush) waits until it's received.
+#[cfg(feature = "futures")]
+impl<T> futures_sink::Sink<T> for Sender<T> {
+    type Error = SendError<()>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        let ready = |sender: &Self| {
+            if sender.is_closed() {
+                Poll::Ready(Err(SendError(())))
+            } else if sender.is_full() {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        };
+        if let Poll::Ready(result) = ready(&self) {
+            return Poll::Ready(result);
+        }
+        self.inner_ref().set_sender_waker(cx.waker());
+        // a value taken before the waker was set didn't wake it.
+        ready(&self)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), SendError<()>> {
+        // on rendezvous channels the value waits in the buffer until poll_flush.
+        match self.inner_ref().try_push(item) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => panic!("start_send called before poll_ready"),
+            Err(_) => Err(SendError(())),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        self.flush();
+        let flushed = |sender: &Self| match sender.inner_ref().try_wait_handoff() {
+            Ok(true) => Poll::Ready(Ok(())),
+            Ok(false) => Poll::Pending,
+            Err(err) => Poll::Ready(Err(err)),
+        };
+        if let Poll::Ready(result) = flushed(&self) {
+            return Poll::Ready(result);
+        }
+        self.inner_ref().set_sender_waker(cx.waker());
+        // a value taken before the waker was set didn't wake it.
+        flushed(&self)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        self.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Enabled by the `futures` feature.
+///
+/// Waiting for a value replaces the callback set by
+/// [`set_notify`](Receiver::set_notify) with one that wakes the task,
+/// while the [`Sender`] can still block on another thread.
+/// The stream ends once the [`channel`] is disconnected and empty.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+/// use futures::{executor::block_on, SinkExt, StreamExt};
+/// use std::thread;
+///
+/// let (src, sink) = bounded::channel::<u32>(4);
+/// let (doubled_src, doubled_sink) = bounded::channel::<u32>(4);
+/// thread::spawn(move || {
+///     for i in 0..10 {
+///         src.send(i).unwrap();
+///     }
+/// });
+/// thread::spawn(move || block_on(sink.map(|x| Ok(x * 2)).forward(doubled_src)));
+/// // forward closes the channel once the input ends.
+/// let doubled: Vec<u32> = block_on(doubled_sink.collect());
+/// assert_eq!(doubled, (0..10).map(|x| x * 2).collect::<Vec<_>>());
+/// ```
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let ready = |receiver: &Self| match receiver.try_recv() {
+            Ok(item) => Poll::Ready(Some(item)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+            Err(_) => Poll::Ready(None),
+        };
+        if let Poll::Ready(item) = ready(&self) {
+            return Poll::Ready(item);
+        }
+        self.inner_ref().set_receiver_waker(cx.waker());
+        // a value sent before the waker was set didn't wake it.
+        ready(&self)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len_hint(), None)
+    }
+}
+
+impl<T> crate::select::Selectable for Receiver<T> {}
+
+impl<T> crate::select::sealed::Sealed for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        self.has_front.get() || self.inner_ref().is_ready()
+    }
+
+    unsafe fn register(&self, waker: crate::select::sealed::Waker) -> bool {
+        self.inner_ref().register_receiver(waker.0)
+    }
+
+    unsafe fn unregister(&self) {
+        self.inner_ref().unregister_receiver()
+    }
+}
+
+unsafe impl<T: Send> Send for Sender<T> {}
+unsafe impl<T: Send> Send for Receiver<T> {}
+
+/*
+Every operation leaves the channel in a valid state if it unwinds:
+values only become visible to the other end point once they're fully
+written, and slots are freed even if dropping their values panics.
+*/
+impl<T> UnwindSafe for Sender<T> {}
+impl<T> RefUnwindSafe for Sender<T> {}
+impl<T> UnwindSafe for Receiver<T> {}
+impl<T> RefUnwindSafe for Receiver<T> {}
 
 #[cfg(test)]
 mod tests;
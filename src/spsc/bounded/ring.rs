@@ -0,0 +1,277 @@
+use super::inner::{Inner, CLOSED, POISONED};
+use super::{from_inner, round_capacity, Receiver, Sender};
+use crate::error::{
+    RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+};
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::time::{Duration, Instant};
+
+/// A bounded ring buffer that can be used on its own, or split into
+/// the end points of a [`channel`](super::channel).
+///
+/// Owning the buffer makes it usable from one thread with [`push`](RingBuffer::push)
+/// and [`pop`](RingBuffer::pop), without splitting it first.
+/// [`split_ref`](RingBuffer::split_ref) borrows it as a pair of end points, e.g.
+/// for scoped threads, and the [`RingBuffer`] keeps the values that are still
+/// pending once they're gone. [`split`](RingBuffer::split) turns it into a
+/// [`Sender`] and a [`Receiver`] for good.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded::RingBuffer;
+/// use std::thread;
+///
+/// let mut ring = RingBuffer::new(4);
+/// ring.push(0).unwrap();
+/// thread::scope(|s| {
+///     let (src, sink) = ring.split_ref();
+///     let producer = s.spawn(move || {
+///         for i in 1..10 {
+///             src.send(i).unwrap();
+///         }
+///     });
+///     for i in 0..8 {
+///         assert_eq!(sink.recv(), Ok(i));
+///     }
+///     producer.join().unwrap();
+/// });
+/// // the values that weren't received are still there.
+/// assert_eq!(ring.pop(), Some(8));
+/// assert_eq!(ring.pop(), Some(9));
+/// assert_eq!(ring.pop(), None);
+/// ```
+pub struct RingBuffer<T> {
+    inner: Inner<T>,
+    /// Set by `split_ref`, whose end points disconnect the channel when dropped.
+    borrowed: bool,
+}
+
+/// The sending endpoint borrowed from a [`RingBuffer`].
+///
+/// Data can be sent using the [`try_send`](RingSender::try_send)
+/// and [`send`](RingSender::send) methods.
+pub struct RingSender<'a, T> {
+    inner: &'a Inner<T>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint borrowed from a [`RingBuffer`].
+///
+/// Data can be received using the [`try_recv`](RingReceiver::try_recv)
+/// and [`recv`](RingReceiver::recv) methods.
+pub struct RingReceiver<'a, T> {
+    inner: &'a Inner<T>,
+    _unsync: PhantomUnsync,
+}
+
+impl<T> RingBuffer<T> {
+    /// Creates a [`RingBuffer`] with storage for at least `min_capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// The function panics if the rounded capacity is greater than
+    /// [`MAX_CAPACITY`](super::MAX_CAPACITY) or it can't allocate the memory
+    /// needed for the buffer.
+    pub fn new(min_capacity: usize) -> Self {
+        Self {
+            inner: Inner::new(round_capacity(min_capacity)),
+            borrowed: false,
+        }
+    }
+
+    /// Appends `item` to the buffer, or returns it if the buffer is full.
+    #[inline]
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        self.inner_mut().try_send(item).map_err(TrySendError::into_inner)
+    }
+
+    /// Removes the oldest value from the buffer.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.inner_mut().try_recv().ok()
+    }
+
+    /// Returns how many values are in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len_hint()
+    }
+
+    /// Checks if the buffer has no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the buffer has no room for another value.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    /// Returns how many values the buffer can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Borrows the buffer as the end points of a channel.
+    ///
+    /// The end points work like a [`Sender`] and a [`Receiver`] until either
+    /// is dropped, which disconnects the other one. Values that weren't
+    /// received stay in the buffer.
+    pub fn split_ref(&mut self) -> (RingSender<'_, T>, RingReceiver<'_, T>) {
+        self.inner_mut();
+        self.borrowed = true;
+        let inner = &self.inner;
+        (
+            RingSender {
+                inner,
+                _unsync: PhantomUnsync {},
+            },
+            RingReceiver {
+                inner,
+                _unsync: PhantomUnsync {},
+            },
+        )
+    }
+
+    /// Turns the buffer into the end points of a channel,
+    /// which start off with the values in the buffer.
+    ///
+    /// # Panics
+    ///
+    /// The function panics if it can't allocate the memory needed for the channel.
+    pub fn split(mut self) -> (Sender<T>, Receiver<T>) {
+        self.inner_mut();
+        from_inner(self.inner)
+    }
+
+    /// Returns the channel, connecting it again if it was borrowed.
+    #[inline]
+    fn inner_mut(&mut self) -> &Inner<T> {
+        if std::mem::take(&mut self.borrowed) {
+            self.inner.reconnect();
+        }
+        &self.inner
+    }
+}
+
+impl<'a, T> RingSender<'a, T> {
+    /// Tries to send a value through the borrowed [`RingBuffer`].
+    ///
+    /// See [`Sender::try_send`].
+    #[inline]
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(item)
+    }
+
+    /// Sends a value through the borrowed [`RingBuffer`].
+    ///
+    /// See [`Sender::send`].
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.inner.send(item)
+    }
+
+    /// Like [`send`](RingSender::send), but waits at most `timeout`.
+    ///
+    /// See [`Sender::send_timeout`].
+    pub fn send_timeout(&self, item: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.inner
+            .send_deadline(item, Instant::now().checked_add(timeout))
+    }
+
+    /// Checks if the [`RingReceiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner.peer_connected()
+    }
+}
+
+impl<'a, T> RingReceiver<'a, T> {
+    /// Tries to return a pending value.
+    ///
+    /// See [`Receiver::try_recv`].
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner.try_recv()
+    }
+
+    /// Reads a value from the borrowed [`RingBuffer`].
+    ///
+    /// See [`Receiver::recv`].
+    #[inline]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner.recv()
+    }
+
+    /// Like [`recv`](RingReceiver::recv), but waits at most `timeout`.
+    ///
+    /// See [`Receiver::recv_timeout`].
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.inner
+            .recv_deadline(Instant::now().checked_add(timeout))
+    }
+
+    /// Checks if the [`RingSender`] is still connected.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner.peer_connected()
+    }
+}
+
+impl<'a, T> Drop for RingSender<'a, T> {
+    fn drop(&mut self) {
+        // the RingBuffer owns the channel, so 'drop_count' only disconnects it.
+        if self.inner.shared.drop_count.fetch_add(1, AcqRel) & !(CLOSED | POISONED) == 0 {
+            self.inner.wake_receiver();
+        }
+    }
+}
+
+impl<'a, T> Drop for RingReceiver<'a, T> {
+    fn drop(&mut self) {
+        // the RingBuffer owns the channel, so 'drop_count' only disconnects it.
+        if self.inner.shared.drop_count.fetch_add(1, AcqRel) & !(CLOSED | POISONED) == 0 {
+            self.inner.wake_sender();
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for RingBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RingBuffer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<'a, T> std::fmt::Debug for RingSender<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::RingSender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+impl<'a, T> std::fmt::Debug for RingReceiver<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::RingReceiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+unsafe impl<T: Send> Send for RingBuffer<T> {}
+unsafe impl<'a, T: Send> Send for RingSender<'a, T> {}
+unsafe impl<'a, T: Send> Send for RingReceiver<'a, T> {}
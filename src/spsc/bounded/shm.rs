@@ -0,0 +1,555 @@
+use super::MAX_CAPACITY;
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::util::cache::CachePadded;
+use crate::util::marker::PhantomUnsync;
+use std::cell::Cell;
+use std::io;
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+use std::os::fd::{AsFd, AsRawFd, RawFd};
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release, SeqCst};
+use std::sync::atomic::{fence, AtomicU32, AtomicU64, AtomicUsize};
+use std::sync::Arc;
+
+/// Creates or opens a bounded SPSC channel in the shared memory file `fd`.
+///
+/// The ring is the same as the one of [`channel`](super::channel), but the
+/// indices and values live in a `MAP_SHARED` mapping of `fd` and blocking
+/// end points wait on futexes in the mapping instead of a [`Parker`](crate::sync::Parker).
+/// Every process that calls this with the same file, e.g. one opened from
+/// `/dev/shm` or a `memfd`, gets the same channel. The first call initialises
+/// the file with storage for at least `min_capacity` values, and later calls
+/// ignore `min_capacity`. Processes that open a new file at the same time
+/// have to pass the same `min_capacity`.
+///
+/// Each end point can be taken once, by any process, with
+/// [`ShmChannel::sender`] and [`ShmChannel::receiver`].
+///
+/// # Errors
+///
+/// Returns an error if the file can't be resized or mapped, or
+/// [`io::ErrorKind::InvalidData`] if it holds a channel for another type.
+///
+/// # Safety
+///
+/// Every process must use the file as a channel of the same `T`, built from the
+/// same version of this crate for the same target. `T` can't hold pointers or
+/// references, since they aren't valid in other processes.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+/// use std::fs::File;
+///
+/// let path = std::env::temp_dir().join(format!("shm-doc-{}", std::process::id()));
+/// let file = File::options().read(true).write(true).create(true).open(&path)?;
+/// // SAFETY: the file is only used as a channel of u64.
+/// let channel = unsafe { bounded::shm_channel::<u64>(&file, 16)? };
+/// # std::fs::remove_file(&path)?;
+///
+/// // another process would take the other end point.
+/// let src = channel.sender().unwrap();
+/// let sink = channel.receiver().unwrap();
+/// assert!(channel.sender().is_none());
+/// src.send(7).unwrap();
+/// assert_eq!(sink.recv(), Ok(7));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub unsafe fn shm_channel<T: Copy>(
+    fd: impl AsFd,
+    min_capacity: usize,
+) -> io::Result<ShmChannel<T>> {
+    let fd = fd.as_fd().as_raw_fd();
+    if align_of::<T>() > MIN_PAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "alignment is greater than the page size",
+        ));
+    }
+    allocate(fd, size_of::<Header>())?;
+    let header = Mapping::new(fd, size_of::<Header>())?;
+    let h = header.header();
+
+    loop {
+        match h
+            .state
+            .compare_exchange(UNINIT, INITIALIZING, Acquire, Acquire)
+        {
+            Ok(_) => {
+                let capacity = match min_capacity.max(1).checked_next_power_of_two() {
+                    Some(capacity)
+                        if capacity <= MAX_CAPACITY && mapping_len::<T>(capacity).is_some() =>
+                    {
+                        capacity
+                    }
+                    _ => {
+                        h.state.store(UNINIT, Release);
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "capacity overflow",
+                        ));
+                    }
+                };
+                if let Err(err) = allocate(fd, mapping_len::<T>(capacity).unwrap()) {
+                    h.state.store(UNINIT, Release);
+                    return Err(err);
+                }
+                h.capacity.store(capacity, Relaxed);
+                h.value_size.store(size_of::<T>(), Relaxed);
+                h.value_align.store(align_of::<T>(), Relaxed);
+                h.header_size.store(size_of::<Header>(), Relaxed);
+                h.magic.store(MAGIC, Relaxed);
+                h.state.store(READY, Release);
+                break;
+            }
+            Err(READY) => break,
+            // another process is initialising the file.
+            Err(_) => std::thread::yield_now(),
+        }
+    }
+
+    let capacity = h.capacity.load(Relaxed);
+    let len = match mapping_len::<T>(capacity) {
+        Some(len)
+            if h.magic.load(Relaxed) == MAGIC
+                && h.value_size.load(Relaxed) == size_of::<T>()
+                && h.value_align.load(Relaxed) == align_of::<T>()
+                && h.header_size.load(Relaxed) == size_of::<Header>()
+                && capacity.is_power_of_two() =>
+        {
+            len
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file holds a different channel",
+            ))
+        }
+    };
+    drop(header);
+    Ok(ShmChannel {
+        map: Arc::new(Mapping::new(fd, len)?),
+        _marker: PhantomData,
+    })
+}
+
+/// A channel in shared memory, created by [`shm_channel`].
+///
+/// Hands out the [`ShmSender`] and [`ShmReceiver`], each of which can
+/// only be taken once across all processes.
+pub struct ShmChannel<T> {
+    map: Arc<Mapping>,
+    _marker: PhantomData<T>,
+}
+
+/// The sending endpoint of a [`ShmChannel`].
+///
+/// Data can be sent using the [`try_send`](ShmSender::try_send)
+/// and [`send`](ShmSender::send) methods.
+pub struct ShmSender<T> {
+    map: Arc<Mapping>,
+    head_cache: Cell<usize>,
+    _marker: PhantomData<T>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a [`ShmChannel`].
+///
+/// Data can be received using the [`try_recv`](ShmReceiver::try_recv)
+/// and [`recv`](ShmReceiver::recv) methods.
+pub struct ShmReceiver<T> {
+    map: Arc<Mapping>,
+    tail_cache: Cell<usize>,
+    _marker: PhantomData<T>,
+    _unsync: PhantomUnsync,
+}
+
+impl<T: Copy> ShmChannel<T> {
+    /// Takes the [`ShmSender`], unless a process already took it.
+    pub fn sender(&self) -> Option<ShmSender<T>> {
+        match self.map.header().claimed.fetch_or(SENDER, AcqRel) & SENDER {
+            0 => Some(ShmSender {
+                map: self.map.clone(),
+                head_cache: Cell::new(self.map.header().head.load(Acquire)),
+                _marker: PhantomData,
+                _unsync: PhantomUnsync {},
+            }),
+            _ => None,
+        }
+    }
+
+    /// Takes the [`ShmReceiver`], unless a process already took it.
+    pub fn receiver(&self) -> Option<ShmReceiver<T>> {
+        match self.map.header().claimed.fetch_or(RECEIVER, AcqRel) & RECEIVER {
+            0 => Some(ShmReceiver {
+                map: self.map.clone(),
+                tail_cache: Cell::new(self.map.header().tail.load(Acquire)),
+                _marker: PhantomData,
+                _unsync: PhantomUnsync {},
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns how many values the channel can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.header().capacity.load(Relaxed)
+    }
+}
+
+impl<T: Copy> ShmSender<T> {
+    /// Tries to send a value through this [`ShmChannel`].
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        let h = self.map.header();
+        if h.closed.load(Relaxed) & RECEIVER != 0 {
+            return Err(TrySendError::Disconnected(item));
+        }
+        let cap = h.capacity.load(Relaxed);
+        // tail is only modified by this end point.
+        let tail = h.tail.load(Relaxed);
+        if tail.wrapping_sub(self.head_cache.get()) == cap {
+            self.head_cache.set(h.head.load(Acquire));
+            if tail.wrapping_sub(self.head_cache.get()) == cap {
+                return Err(TrySendError::Full(item));
+            }
+        }
+        /*SAFETY:
+         *the slot is free, since tail is less than cap values ahead of head,
+         *and the receiver doesn't read it before tail is moved past it.
+         */
+        unsafe { self.map.slot::<T>(tail & (cap - 1)).write(item) };
+        h.tail.store(tail.wrapping_add(1), Release);
+        h.receiver_event.notify();
+        Ok(())
+    }
+
+    /// Sends a value through this [`ShmChannel`].
+    ///
+    /// If the [`ShmChannel`] is full, blocks and waits for the [`ShmReceiver`].
+    /// Returns a [`SendError`] if the [`ShmReceiver`] is disconnected.
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        let h = self.map.header();
+        let mut item = item;
+        loop {
+            match self.try_send(item) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(ret) | TrySendError::Poisoned(ret)) => {
+                    return Err(SendError(ret))
+                }
+                Err(TrySendError::Full(ret)) => item = ret,
+            }
+            let full = self.head_cache.get();
+            h.sender_event
+                .wait(|| h.head.load(Relaxed) != full || h.closed.load(Relaxed) & RECEIVER != 0);
+        }
+    }
+
+    /// Checks if the [`ShmReceiver`] is still connected.
+    ///
+    /// A process that exits without dropping its [`ShmReceiver`]
+    /// still counts as connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.map.header().closed.load(Relaxed) & RECEIVER == 0
+    }
+}
+
+impl<T: Copy> ShmReceiver<T> {
+    /// Tries to return a pending value.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after consuming all
+    /// sent data. To avoid this, use [`sender_connected`](ShmReceiver::sender_connected).
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let h = self.map.header();
+        // head is only modified by this end point.
+        let head = h.head.load(Relaxed);
+        if head == self.tail_cache.get() {
+            self.tail_cache.set(h.tail.load(Acquire));
+            if head == self.tail_cache.get() {
+                if h.closed.load(Acquire) & SENDER == 0 {
+                    return Err(TryRecvError::Empty);
+                }
+                // Let the receiver consume all the messages after sender disconnects.
+                self.tail_cache.set(h.tail.load(Acquire));
+                if head == self.tail_cache.get() {
+                    return Err(TryRecvError::Disconnected);
+                }
+            }
+        }
+        let cap = h.capacity.load(Relaxed);
+        /*SAFETY:
+         *everything before tail has been written to by the
+         *sender, which doesn't reuse the slot before head moves.
+         */
+        let item = unsafe { self.map.slot::<T>(head & (cap - 1)).read() };
+        h.head.store(head.wrapping_add(1), Release);
+        h.sender_event.notify();
+        Ok(item)
+    }
+
+    /// Reads a value from the [`ShmChannel`].
+    ///
+    /// If the [`ShmChannel`] is empty, blocks and waits for the [`ShmSender`].
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after consuming all sent data. To
+    /// avoid this, use [`sender_connected`](ShmReceiver::sender_connected).
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let h = self.map.header();
+        loop {
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+            let empty = self.tail_cache.get();
+            h.receiver_event
+                .wait(|| h.tail.load(Relaxed) != empty || h.closed.load(Relaxed) & SENDER != 0);
+        }
+    }
+
+    /// Checks if the [`ShmSender`] is still connected.
+    ///
+    /// A process that exits without dropping its [`ShmSender`]
+    /// still counts as connected.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.map.header().closed.load(Relaxed) & SENDER == 0
+    }
+}
+
+impl<T> Drop for ShmSender<T> {
+    fn drop(&mut self) {
+        let h = self.map.header();
+        h.closed.fetch_or(SENDER, AcqRel);
+        h.receiver_event.notify();
+    }
+}
+
+impl<T> Drop for ShmReceiver<T> {
+    fn drop(&mut self) {
+        let h = self.map.header();
+        h.closed.fetch_or(RECEIVER, AcqRel);
+        h.sender_event.notify();
+    }
+}
+
+/// The smallest page size of the supported targets, which is
+/// also the alignment of the start of the mapping.
+const MIN_PAGE_SIZE: usize = 4096;
+
+const MAGIC: u64 = u64::from_le_bytes(*b"cqs-shm1");
+
+// Header::state
+const UNINIT: u32 = 0;
+const INITIALIZING: u32 = 1;
+const READY: u32 = 2;
+
+// Header::claimed and Header::closed
+const SENDER: u32 = 1;
+const RECEIVER: u32 = 2;
+
+/// The start of the mapping, followed by the buffer.
+///
+/// A new file is filled with zeroes, which is a valid `UNINIT` header.
+/// Everything is atomic, since other processes can access it at any time.
+#[repr(C)]
+struct Header {
+    state: AtomicU32,
+    claimed: AtomicU32,
+    closed: AtomicU32,
+    magic: AtomicU64,
+    capacity: AtomicUsize,
+    value_size: AtomicUsize,
+    value_align: AtomicUsize,
+    header_size: AtomicUsize,
+    tail: CachePadded<AtomicUsize>,
+    receiver_event: CachePadded<Event>,
+    head: CachePadded<AtomicUsize>,
+    sender_event: CachePadded<Event>,
+}
+
+/// Returns the offset of the buffer in the mapping.
+const fn buffer_offset<T>() -> usize {
+    size_of::<Header>().next_multiple_of(align_of::<T>())
+}
+
+/// Returns the size of a mapping for `capacity` values.
+fn mapping_len<T>(capacity: usize) -> Option<usize> {
+    size_of::<T>()
+        .checked_mul(capacity)?
+        .checked_add(buffer_offset::<T>())
+}
+
+/// Makes the file at least `len` bytes long, without ever shrinking it.
+fn allocate(fd: RawFd, len: usize) -> io::Result<()> {
+    let len = libc::off_t::try_from(len)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "capacity overflow"))?;
+    //SAFETY: posix_fallocate only touches the file.
+    match unsafe { libc::posix_fallocate(fd, 0, len) } {
+        0 => Ok(()),
+        errno => Err(io::Error::from_raw_os_error(errno)),
+    }
+}
+
+/// A futex based replacement of [`Parker`](crate::util::park::Parker)
+/// that works across processes.
+///
+/// Only one thread may wait on an `Event` at a time.
+#[repr(C)]
+struct Event {
+    /// Incremented by every `notify` that finds a waiter, and waited on.
+    seq: AtomicU32,
+    waiting: AtomicU32,
+}
+
+impl Event {
+    /// Blocks until `ready` returns `true`.
+    ///
+    /// `ready` is checked after announcing the wait, so a `notify`
+    /// call that follows a change `ready` checks isn't missed.
+    fn wait(&self, ready: impl Fn() -> bool) {
+        loop {
+            let seq = self.seq.load(Acquire);
+            self.waiting.store(1, Relaxed);
+            // pairs with the fence in notify.
+            fence(SeqCst);
+            if ready() {
+                self.waiting.store(0, Relaxed);
+                return;
+            }
+            /*SAFETY:
+             *the futex word is in the mapping, which outlives the call.
+             *EINTR and EAGAIN are handled by checking `ready` again.
+             */
+            unsafe {
+                libc::syscall(
+                    libc::SYS_futex,
+                    self.seq.as_ptr(),
+                    libc::FUTEX_WAIT,
+                    seq,
+                    std::ptr::null::<libc::timespec>(),
+                );
+            }
+        }
+    }
+
+    /// Wakes the thread in [`wait`](Event::wait), if there's one.
+    #[inline]
+    fn notify(&self) {
+        // pairs with the fence in wait.
+        fence(SeqCst);
+        if self.waiting.load(Relaxed) != 0 {
+            self.wake();
+        }
+    }
+
+    #[inline(never)]
+    fn wake(&self) {
+        self.seq.fetch_add(1, Release);
+        //SAFETY: same as in wait.
+        unsafe { libc::syscall(libc::SYS_futex, self.seq.as_ptr(), libc::FUTEX_WAKE, 1) };
+    }
+}
+
+/// A `MAP_SHARED` mapping of a file, unmapped on drop.
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl Mapping {
+    fn new(fd: RawFd, len: usize) -> io::Result<Self> {
+        /*SAFETY:
+         *the kernel picks the address, so no existing memory is replaced,
+         *and the file is at least len bytes long, so every page is backed.
+         */
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            ptr: ptr as *mut u8,
+            len,
+        })
+    }
+
+    #[inline]
+    fn header(&self) -> &Header {
+        //SAFETY: the mapping starts with a Header and is page aligned.
+        unsafe { &*(self.ptr as *const Header) }
+    }
+
+    /// Returns a pointer to the slot at `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than the capacity and the buffer has to hold `T`s.
+    #[inline]
+    unsafe fn slot<T>(&self, index: usize) -> *mut T {
+        (self.ptr.add(buffer_offset::<T>()) as *mut T).add(index)
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        //SAFETY: the mapping isn't used after this.
+        unsafe { libc::munmap(self.ptr as *mut libc::c_void, self.len) };
+    }
+}
+
+//SAFETY: the mapping is only accessed through atomics and the SPSC protocol.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl<T> std::fmt::Debug for ShmChannel<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::ShmChannel<{}> {{ mapping: {:p} }}",
+            std::any::type_name::<T>(),
+            self.map.ptr
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for ShmSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::ShmSender<{}> {{ mapping: {:p} }}",
+            std::any::type_name::<T>(),
+            self.map.ptr
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for ShmReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::ShmReceiver<{}> {{ mapping: {:p} }}",
+            std::any::type_name::<T>(),
+            self.map.ptr
+        )
+    }
+}
+
+unsafe impl<T: Send> Send for ShmSender<T> {}
+unsafe impl<T: Send> Send for ShmReceiver<T> {}
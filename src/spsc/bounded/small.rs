@@ -0,0 +1,221 @@
+use super::inner::{Inner, Slot};
+use crate::error::{
+    RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+};
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+/// Creates a SPSC channel with an inline buffer of exactly `N` elements.
+///
+/// Unlike [`channel`](super::channel), which allocates its buffer separately,
+/// the buffer is placed in the same allocation as the rest of the channel.
+/// That saves an allocation and a pointer indirection on every operation,
+/// and keeps the values close to the indices, which helps small channels.
+/// Large `N` make the channel's allocation just as large, so
+/// [`channel`](super::channel) should be used for them instead.
+///
+/// `N` must be a power of two, which is checked at compile time.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+/// use std::thread;
+///
+/// let (src, sink) = bounded::channel_small::<u32, 4>();
+/// thread::spawn(move || {
+///     for i in 0..10 {
+///         src.send(i).unwrap();
+///     }
+/// });
+/// for i in 0..10 {
+///     assert_eq!(sink.recv(), Ok(i));
+/// }
+/// ```
+pub fn channel_small<T, const N: usize>() -> (SmallSender<T, N>, SmallReceiver<T, N>) {
+    #[cfg(not(feature = "loom"))]
+    let inner = Inner::boxed_inline();
+    #[cfg(feature = "loom")]
+    let inner = Box::new(Inner::new_inline());
+    /*SAFETY: freed by the last end point's Drop.*/
+    let inner = unsafe { NonNull::new_unchecked(Box::into_raw(inner)) };
+    (
+        SmallSender {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+        SmallReceiver {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+    )
+}
+
+/// The sending endpoint of a [`channel_small`].
+///
+/// Data can be sent using the [`try_send`](SmallSender::try_send)
+/// and [`send`](SmallSender::send) methods.
+pub struct SmallSender<T, const N: usize> {
+    inner: NonNull<Inner<T, [Slot<T>; N]>>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a [`channel_small`].
+///
+/// Data can be received using the [`try_recv`](SmallReceiver::try_recv)
+/// and [`recv`](SmallReceiver::recv) methods.
+pub struct SmallReceiver<T, const N: usize> {
+    inner: NonNull<Inner<T, [Slot<T>; N]>>,
+    _unsync: PhantomUnsync,
+}
+
+impl<T, const N: usize> SmallSender<T, N> {
+    /// Tries to send a value through this [`channel_small`].
+    ///
+    /// See [`Sender::try_send`](super::Sender::try_send).
+    #[inline]
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        self.inner_ref().try_send(item)
+    }
+
+    /// Sends a value through this [`channel_small`].
+    ///
+    /// See [`Sender::send`](super::Sender::send).
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.inner_ref().send(item)
+    }
+
+    /// Like [`send`](SmallSender::send), but waits at most `timeout`.
+    ///
+    /// See [`Sender::send_timeout`](super::Sender::send_timeout).
+    pub fn send_timeout(&self, item: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.inner_ref()
+            .send_deadline(item, Instant::now().checked_add(timeout))
+    }
+
+    /// Checks if the [`SmallReceiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    /// Returns the capacity of the channel, which is always `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<T, [Slot<T>; N]> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T, const N: usize> SmallReceiver<T, N> {
+    /// Tries to return a pending value.
+    ///
+    /// See [`Receiver::try_recv`](super::Receiver::try_recv).
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner_ref().try_recv()
+    }
+
+    /// Reads a value from the [`channel_small`].
+    ///
+    /// See [`Receiver::recv`](super::Receiver::recv).
+    #[inline]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner_ref().recv()
+    }
+
+    /// Like [`recv`](SmallReceiver::recv), but waits at most `timeout`.
+    ///
+    /// See [`Receiver::recv_timeout`](super::Receiver::recv_timeout).
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.inner_ref()
+            .recv_deadline(Instant::now().checked_add(timeout))
+    }
+
+    /// Checks if the [`SmallSender`] is still connected.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    /// Returns the capacity of the channel, which is always `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<T, [Slot<T>; N]> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T, const N: usize> Drop for SmallSender<T, N> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().shared.drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_receiver(),
+                1 => break,
+                //SAFETY: the SmallReceiver is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for SmallReceiver<T, N> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().shared.drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_sender(),
+                1 => break,
+                //SAFETY: the SmallSender is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> std::fmt::Debug for SmallSender<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::SmallSender<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            N,
+            self.inner
+        )
+    }
+}
+
+impl<T, const N: usize> std::fmt::Debug for SmallReceiver<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::SmallReceiver<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            N,
+            self.inner
+        )
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for SmallSender<T, N> {}
+unsafe impl<T: Send, const N: usize> Send for SmallReceiver<T, N> {}
@@ -0,0 +1,264 @@
+use super::inner::{Inner, Slot};
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::sync::atomic::AtomicBool;
+use crate::sync::atomic::Ordering::{AcqRel, Relaxed};
+use crate::util::marker::PhantomUnsync;
+
+/// A bounded SPSC channel with an inline buffer of exactly `N` elements.
+///
+/// Unlike [`channel`](super::channel), a [`StaticChannel`] doesn't allocate,
+/// so it can be placed in a `static`. It can be [`split`](StaticChannel::split)
+/// into a [`StaticSender`] and a [`StaticReceiver`] once. A [`StaticChannel`]
+/// on the stack can instead lend them out with [`split_ref`](StaticChannel::split_ref)
+/// as often as needed, e.g. to scoped threads.
+///
+/// `N` must be a power of two, which is checked at compile time.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded::StaticChannel;
+/// use std::thread;
+///
+/// static CHANNEL: StaticChannel<u32, 4> = StaticChannel::new();
+///
+/// let (src, sink) = CHANNEL.split().unwrap();
+/// thread::spawn(move || {
+///     for i in 0..10 {
+///         src.send(i).unwrap();
+///     }
+/// });
+/// for i in 0..10 {
+///     assert_eq!(sink.recv(), Ok(i));
+/// }
+/// assert!(CHANNEL.split().is_none());
+/// ```
+pub struct StaticChannel<T, const N: usize> {
+    inner: Inner<T, [Slot<T>; N]>,
+    split: AtomicBool,
+}
+
+/// The sending endpoint of a [`StaticChannel`].
+///
+/// Data can be sent using the [`try_send`](StaticSender::try_send)
+/// and [`send`](StaticSender::send) methods.
+pub struct StaticSender<'a, T, const N: usize> {
+    inner: &'a Inner<T, [Slot<T>; N]>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a [`StaticChannel`].
+///
+/// Data can be received using the [`try_recv`](StaticReceiver::try_recv)
+/// and [`recv`](StaticReceiver::recv) methods.
+pub struct StaticReceiver<'a, T, const N: usize> {
+    inner: &'a Inner<T, [Slot<T>; N]>,
+    _unsync: PhantomUnsync,
+}
+
+impl<T, const N: usize> StaticChannel<T, N> {
+    /// Creates a new [`StaticChannel`].
+    #[cfg(not(feature = "loom"))]
+    pub const fn new() -> Self {
+        Self {
+            inner: Inner::new_inline(),
+            split: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates a new [`StaticChannel`].
+    #[cfg(feature = "loom")]
+    pub fn new() -> Self {
+        Self {
+            inner: Inner::new_inline(),
+            split: AtomicBool::new(false),
+        }
+    }
+
+    /// Splits the channel into its endpoints.
+    ///
+    /// Returns [`None`] if the channel was already split.
+    pub fn split(&self) -> Option<(StaticSender<'_, T, N>, StaticReceiver<'_, T, N>)> {
+        if self.split.swap(true, Relaxed) {
+            return None;
+        }
+        Some((
+            StaticSender {
+                inner: &self.inner,
+                _unsync: PhantomUnsync {},
+            },
+            StaticReceiver {
+                inner: &self.inner,
+                _unsync: PhantomUnsync {},
+            },
+        ))
+    }
+
+    /// Borrows the channel's endpoints, which can't outlive the borrow.
+    ///
+    /// Unlike [`split`](StaticChannel::split), this can be called again once the
+    /// endpoints are gone, which connects the channel again. Values they left
+    /// in the channel are received by the next [`StaticReceiver`]. After this,
+    /// [`split`](StaticChannel::split) always returns [`None`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded::StaticChannel;
+    /// use std::thread;
+    ///
+    /// let mut channel = StaticChannel::<u32, 4>::new();
+    /// for round in 0..3 {
+    ///     thread::scope(|s| {
+    ///         let (src, sink) = channel.split_ref();
+    ///         s.spawn(move || src.send(round).unwrap());
+    ///         assert_eq!(sink.recv(), Ok(round));
+    ///     });
+    /// }
+    /// ```
+    pub fn split_ref(&mut self) -> (StaticSender<'_, T, N>, StaticReceiver<'_, T, N>) {
+        if self.split.swap(true, Relaxed) {
+            self.inner.reconnect();
+        }
+        (
+            StaticSender {
+                inner: &self.inner,
+                _unsync: PhantomUnsync {},
+            },
+            StaticReceiver {
+                inner: &self.inner,
+                _unsync: PhantomUnsync {},
+            },
+        )
+    }
+
+    /// Returns the capacity of the channel, which is always `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<'a, T, const N: usize> StaticSender<'a, T, N> {
+    /// Tries to send a value through this [`StaticChannel`].
+    ///
+    /// See [`Sender::try_send`](super::Sender::try_send).
+    #[inline]
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(item)
+    }
+
+    /// Sends a value through this [`StaticChannel`].
+    ///
+    /// See [`Sender::send`](super::Sender::send).
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.inner.send(item)
+    }
+
+    /// Checks if the [`StaticReceiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner.peer_connected()
+    }
+}
+
+impl<'a, T, const N: usize> StaticReceiver<'a, T, N> {
+    /// Tries to return a pending value.
+    ///
+    /// See [`Receiver::try_recv`](super::Receiver::try_recv).
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner.try_recv()
+    }
+
+    /// Reads a value from the [`StaticChannel`].
+    ///
+    /// See [`Receiver::recv`](super::Receiver::recv).
+    #[inline]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner.recv()
+    }
+
+    /// Checks if the [`StaticSender`] is still connected.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner.peer_connected()
+    }
+}
+
+impl<T, const N: usize> Default for StaticChannel<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> Drop for StaticSender<'a, T, N> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner.shared.drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner.wake_receiver(),
+                //the buffer is dropped with the StaticChannel.
+                1 | 2 => break,
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<'a, T, const N: usize> Drop for StaticReceiver<'a, T, N> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner.shared.drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner.wake_sender(),
+                //the buffer is dropped with the StaticChannel.
+                1 | 2 => break,
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> std::fmt::Debug for StaticChannel<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::StaticChannel<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            N,
+            &self.inner
+        )
+    }
+}
+
+impl<'a, T, const N: usize> std::fmt::Debug for StaticSender<'a, T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::StaticSender<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            N,
+            self.inner
+        )
+    }
+}
+
+impl<'a, T, const N: usize> std::fmt::Debug for StaticReceiver<'a, T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::bounded::StaticReceiver<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            N,
+            self.inner
+        )
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for StaticChannel<T, N> {}
+// The endpoints are the only way to access the data and there's only one of each.
+unsafe impl<T: Send, const N: usize> Sync for StaticChannel<T, N> {}
+unsafe impl<'a, T: Send, const N: usize> Send for StaticSender<'a, T, N> {}
+unsafe impl<'a, T: Send, const N: usize> Send for StaticReceiver<'a, T, N> {}
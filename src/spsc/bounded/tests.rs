@@ -45,6 +45,1206 @@ fn st_receiver_disconnect() {
     assert_eq!(src.try_send(1), Err(TrySendError::Disconnected(1)));
 }
 
+#[test]
+fn st_reconnect() {
+    let (mut src, sink) = channel::<i32>(2);
+    assert!(src.reconnect().is_none());
+    src.send(1).unwrap();
+    drop(sink);
+    assert_eq!(src.try_send(2), Err(TrySendError::Disconnected(2)));
+
+    let sink = src.reconnect().expect("Receiver was dropped.");
+    assert!(src.receiver_connected());
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    src.send(3).unwrap();
+    assert_eq!(sink.recv(), Ok(3));
+    drop(src);
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+#[test]
+fn st_slots_free_len_hint() {
+    let (src, sink) = channel_exact::<i32>(3);
+    assert_eq!((src.slots_free(), sink.len_hint()), (3, 0));
+    for i in 0..3 {
+        src.send(i).unwrap();
+    }
+    assert_eq!((src.slots_free(), sink.len_hint()), (0, 3));
+    assert_eq!(sink.recv(), Ok(0));
+    assert_eq!((src.slots_free(), sink.len_hint()), (1, 2));
+
+    let (src, sink) = channel_overwriting::<i32>(2);
+    for i in 0..5 {
+        src.force_send(i).unwrap();
+    }
+    assert_eq!((src.slots_free(), sink.len_hint()), (0, 2));
+}
+
+#[test]
+fn mt_slots_free_len_hint() {
+    let (src, sink) = channel::<u32>(8);
+    let handle = std::thread::spawn(move || {
+        for i in 0..10_000 {
+            let free = src.slots_free();
+            if free == 0 {
+                src.send(i).unwrap();
+                continue;
+            }
+            src.try_send(i).unwrap();
+            // only the sender can use the free slots up.
+            assert!(src.slots_free() + 1 >= free);
+        }
+    });
+    for i in 0..10_000 {
+        let len = sink.len_hint();
+        assert_eq!(sink.recv(), Ok(i));
+        assert!(sink.len_hint() + 1 >= len);
+    }
+    handle.join().unwrap();
+}
+
+#[test]
+fn st_disconnect_check_interval() {
+    let (mut src, sink) = Builder::new(2).disconnect_check_interval(8).build::<i32>();
+    src.send(1).unwrap();
+    drop(sink);
+    // the next send fills the channel, which is always checked.
+    src.send(2).unwrap();
+    assert_eq!(src.try_send(3), Err(TrySendError::Disconnected(3)));
+    assert_eq!(src.try_send(4), Err(TrySendError::Disconnected(4)));
+
+    let sink = src.reconnect().expect("Receiver was dropped.");
+    src.send(5).unwrap();
+    assert_eq!(sink.recv(), Ok(5));
+    src.close();
+    assert_eq!(src.try_send(6), Err(TrySendError::Disconnected(6)));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn st_static_channel() {
+    static CHANNEL: StaticChannel<i32, 2> = StaticChannel::new();
+    let (src, sink) = CHANNEL.split().unwrap();
+    assert!(CHANNEL.split().is_none());
+
+    assert_eq!(src.try_send(1), Ok(()));
+    assert_eq!(src.try_send(2), Ok(()));
+    assert_eq!(src.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(sink.try_recv(), Ok(1));
+    assert_eq!(sink.recv(), Ok(2));
+    drop(src);
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn mt_static_channel_split_ref() {
+    let mut channel = StaticChannel::<u32, 4>::new();
+    let (src, sink) = channel.split().unwrap();
+    src.send(9).unwrap();
+    drop((src, sink));
+    for round in 1..10 {
+        std::thread::scope(|s| {
+            let (src, sink) = channel.split_ref();
+            let producer = s.spawn(move || {
+                for i in 0..10 {
+                    src.send(round * 10 + i).unwrap();
+                }
+            });
+            // the last value of every round is left for the next one.
+            assert_eq!(sink.recv(), Ok(round * 10 - 1));
+            for i in 0..9 {
+                assert_eq!(sink.recv(), Ok(round * 10 + i));
+            }
+            producer.join().unwrap();
+        });
+    }
+    assert!(channel.split().is_none());
+}
+
+#[test]
+fn static_channel_drop() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    {
+        let channel = StaticChannel::<Rc<()>, 4>::new();
+        let (src, _sink) = channel.split().unwrap();
+        src.send(rc.clone()).unwrap();
+        src.send(rc.clone()).unwrap();
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn st_channel_small() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let (src, sink) = channel_small::<Rc<()>, 2>();
+    assert_eq!(src.capacity(), 2);
+    src.try_send(rc.clone()).unwrap();
+    src.try_send(rc.clone()).unwrap();
+    assert!(matches!(src.try_send(rc.clone()), Err(TrySendError::Full(_))));
+    assert!(sink.try_recv().is_ok());
+    drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(sink);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn st_channel_small_large() {
+    // 16 MiB of slots, more than a test thread's stack.
+    let (src, sink) = channel_small::<u64, { 1 << 21 }>();
+    assert_eq!(src.capacity(), 1 << 21);
+    src.try_send(1).unwrap();
+    assert_eq!(sink.try_recv(), Ok(1));
+}
+
+#[test]
+fn st_channel_const() {
+    let (src, sink) = channel_const::<u32, 2>();
+    assert_eq!(src.slots_free(), 2);
+    src.try_send(1).unwrap();
+    src.try_send(2).unwrap();
+    assert_eq!(src.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(sink.recv(), Ok(1));
+    let (src, _sink) = channel_const::<u32, 1>();
+    src.try_send(1).unwrap();
+    assert_eq!(src.try_send(2), Err(TrySendError::Full(2)));
+}
+
+#[test]
+fn st_capacity() {
+    let (src, sink, info) = channel_with_info::<u32>(5);
+    assert_eq!(info, ChannelInfo { requested: 5, capacity: 8 });
+    assert_eq!((src.capacity(), sink.capacity()), (8, 8));
+    let (src, sink) = channel_exact::<u32>(5);
+    assert_eq!((src.capacity(), sink.capacity()), (5, 5));
+    let (src, _sink) = channel_rendezvous::<u32>();
+    assert_eq!(src.capacity(), 1);
+}
+
+#[test]
+fn st_last_known_free() {
+    let (src, sink) = channel::<u32>(4);
+    assert_eq!(src.last_known_free(), 4);
+    src.try_send(1).unwrap();
+    src.try_send(2).unwrap();
+    assert_eq!(src.last_known_free(), 2);
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(src.last_known_free(), 2);
+    src.try_send(3).unwrap();
+    src.try_send(4).unwrap();
+    // refreshes the cached head when it runs out.
+    src.try_send(5).unwrap();
+    assert_eq!(src.last_known_free(), 0);
+    assert_eq!(src.try_send(6), Err(TrySendError::Full(6)));
+    assert_eq!(src.capacity() - src.last_known_free(), 4);
+}
+
+#[test]
+fn st_ring_buffer() {
+    let mut ring = RingBuffer::new(3);
+    assert_eq!(ring.capacity(), 4);
+    for i in 0..4 {
+        ring.push(i).unwrap();
+    }
+    assert!(ring.is_full());
+    assert_eq!(ring.push(4), Err(4));
+    assert_eq!(ring.pop(), Some(0));
+    {
+        let (src, sink) = ring.split_ref();
+        src.send(4).unwrap();
+        assert_eq!(sink.recv(), Ok(1));
+        drop(src);
+        assert_eq!(sink.recv(), Ok(2));
+    }
+    assert_eq!(ring.len(), 2);
+    ring.push(5).unwrap();
+    {
+        let (src, sink) = ring.split_ref();
+        assert!(src.receiver_connected());
+        drop(sink);
+        assert_eq!(src.try_send(6), Err(TrySendError::Disconnected(6)));
+    }
+    let (src, sink) = ring.split();
+    src.send(6).unwrap();
+    drop(src);
+    assert_eq!(sink.iter().collect::<Vec<_>>(), [3, 4, 5, 6]);
+}
+
+#[test]
+fn mt_ring_buffer_scoped() {
+    let mut ring = RingBuffer::new(8);
+    for round in 0..10 {
+        std::thread::scope(|s| {
+            let (src, sink) = ring.split_ref();
+            let producer = s.spawn(move || {
+                for i in 0..100 {
+                    src.send(round * 100 + i).unwrap();
+                }
+            });
+            for i in 0..96 {
+                assert_eq!(sink.recv(), Ok(round * 100 + i));
+            }
+            producer.join().unwrap();
+        });
+        for i in 96..100 {
+            assert_eq!(ring.pop(), Some(round * 100 + i));
+        }
+        assert!(ring.is_empty());
+    }
+}
+
+#[test]
+fn mt_channel_small() {
+    let (src, sink) = channel_small::<usize, 4>();
+    let handle = std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..1000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    handle.join().unwrap();
+    assert_eq!(
+        sink.recv_timeout(Duration::from_millis(1)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn st_channel_in_place() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let mut place = MaybeUninit::uninit();
+    let mut buffer = [const { MaybeUninit::uninit() }; 3];
+    let (src, sink) = Channel::init_in(&mut place, &mut buffer);
+    for _ in 0..3 {
+        src.try_send(rc.clone()).unwrap();
+    }
+    assert!(matches!(src.try_send(rc.clone()), Err(TrySendError::Full(_))));
+    assert!(sink.recv().is_ok());
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(Rc::strong_count(&rc), 3);
+    drop(src);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn mt_channel_in_place() {
+    let mut place = MaybeUninit::uninit();
+    let mut buffer = [const { MaybeUninit::uninit() }; 5];
+    let (src, sink) = Channel::init_in(&mut place, &mut buffer);
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            for i in 0..10_000 {
+                src.send(i).unwrap();
+            }
+        });
+        for i in 0..10_000 {
+            assert_eq!(sink.recv(), Ok(i));
+        }
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    });
+}
+
+#[test]
+#[cfg(feature = "spsc-bidir")]
+fn st_bidir_pair() {
+    use std::rc::Rc;
+    let (a, b) = bidir::pair::<Rc<u32>, String>(2, 3);
+    let value = Rc::new(1);
+    for _ in 0..2 {
+        a.try_send(value.clone()).unwrap();
+    }
+    assert_eq!(a.try_send(value.clone()), Err(TrySendError::Full(value.clone())));
+    // the capacity is rounded up like the one of a bounded channel.
+    for i in 0..4 {
+        b.try_send(i.to_string()).unwrap();
+    }
+    assert!(b.try_send("4".into()).is_err());
+    assert_eq!(*b.try_recv().unwrap(), 1);
+    assert_eq!(a.recv(), Ok("0".into()));
+    // dropping either end point disconnects both directions.
+    drop(b);
+    assert!(!a.peer_connected());
+    assert_eq!(a.send(value.clone()), Err(SendError(value.clone())));
+    assert_eq!(a.recv(), Ok("1".into()));
+    assert_eq!(a.recv(), Ok("2".into()));
+    assert_eq!(a.recv(), Ok("3".into()));
+    assert_eq!(a.recv(), Err(RecvError {}));
+    // the value left in the ring is dropped along with the last end point.
+    assert_eq!(Rc::strong_count(&value), 2);
+    drop(a);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+#[cfg(feature = "spsc-bidir")]
+fn mt_bidir_pair() {
+    let (client, server) = bidir::pair::<u32, u64>(4, 1);
+    let handle = std::thread::spawn(move || {
+        let mut handled = 0;
+        while let Ok(request) = server.recv() {
+            server.send(u64::from(request) * 2).unwrap();
+            handled += 1;
+        }
+        handled
+    });
+    for i in 0..10_000 {
+        client.send(i).unwrap();
+        assert_eq!(client.recv(), Ok(u64::from(i) * 2));
+    }
+    assert_eq!(
+        client.recv_timeout(std::time::Duration::from_millis(1)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    drop(client);
+    assert_eq!(handle.join().unwrap(), 10_000);
+}
+
+#[test]
+fn st_reserve() {
+    let (mut src, sink) = channel::<Box<i32>>(2);
+    src.reserve().unwrap().write(Box::new(1));
+    let mut slot = src.reserve().unwrap();
+    slot.as_uninit().write(Box::new(2));
+    unsafe { slot.commit() };
+    assert_eq!(src.reserve().unwrap_err(), TrySendError::Full(()));
+
+    assert_eq!(sink.try_recv(), Ok(Box::new(1)));
+    {
+        let _aborted = src.reserve().unwrap();
+    }
+    assert_eq!(sink.try_recv(), Ok(Box::new(2)));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    drop(sink);
+    assert_eq!(src.reserve().unwrap_err(), TrySendError::Disconnected(()));
+}
+
+#[test]
+fn st_recv_ref() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let (src, mut sink) = channel::<Rc<()>>(1);
+    src.send(rc.clone()).unwrap();
+    {
+        let value = sink.recv_ref().unwrap();
+        assert!(Rc::ptr_eq(&value, &rc));
+        assert!(matches!(src.try_send(rc.clone()), Err(TrySendError::Full(_))));
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+    assert!(sink.try_recv_ref().is_err());
+    src.send(rc.clone()).unwrap();
+    drop(src);
+    drop(sink.try_recv_ref().unwrap());
+    assert_eq!(Rc::strong_count(&rc), 1);
+    assert!(sink.recv_ref().is_err());
+}
+
+#[test]
+fn st_read_chunk() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let (src, mut sink) = channel_exact::<Rc<()>>(3);
+    assert_eq!(sink.read_chunk(2).unwrap_err(), TryRecvError::Empty);
+    for _ in 0..3 {
+        src.send(rc.clone()).unwrap();
+    }
+    sink.read_chunk(1).unwrap().commit_all();
+    src.send(rc.clone()).unwrap();
+    // the values wrap around the end of the buffer.
+    let chunk = sink.read_chunk(8).unwrap();
+    assert_eq!(chunk.len(), 3);
+    assert_eq!(chunk.as_slices().0.len(), 2);
+    assert_eq!(chunk.as_slices().1.len(), 1);
+    drop(chunk);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    sink.read_chunk(8).unwrap().commit(2);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    assert!(matches!(src.try_send(rc.clone()), Ok(())));
+    drop(src);
+    let mut chunk = sink.read_chunk(8).unwrap();
+    assert_eq!(chunk.len(), 2);
+    chunk.as_mut_slices().0[0] = rc.clone();
+    chunk.commit_all();
+    assert_eq!(Rc::strong_count(&rc), 1);
+    assert_eq!(sink.read_chunk(8).unwrap_err(), TryRecvError::Disconnected);
+
+    let (src, mut sink) = channel_overwriting::<u32>(2);
+    src.send(1).unwrap();
+    assert!(sink.read_chunk(0).unwrap().is_empty());
+    assert_eq!(src.force_send(2), Ok(None));
+    assert_eq!(src.force_send(3), Ok(Some(1)));
+    assert_eq!(sink.read_chunk(2).unwrap().as_slices(), (&[2][..], &[3][..]));
+}
+
+#[test]
+fn st_write_chunk_uninit() {
+    use std::mem::MaybeUninit;
+    let (mut src, sink) = channel_exact::<u32>(3);
+    let mut chunk = src.write_chunk_uninit(8).unwrap();
+    assert_eq!(chunk.len(), 3);
+    chunk.as_mut_slices().0[0].write(0);
+    chunk.as_mut_slices().0[1].write(1);
+    unsafe { chunk.commit(2) };
+    // dropping it doesn't send anything.
+    {
+        let _aborted = src.write_chunk_uninit(8).unwrap();
+    }
+    assert_eq!(sink.try_recv(), Ok(0));
+    // the free slots wrap around the end of the buffer.
+    let mut chunk = src.write_chunk_uninit(8).unwrap();
+    let (first, second) = chunk.as_mut_slices();
+    assert_eq!((first.len(), second.len()), (1, 1));
+    first[0] = MaybeUninit::new(2);
+    second[0] = MaybeUninit::new(3);
+    unsafe { chunk.commit(2) };
+    assert_eq!(src.write_chunk_uninit(8).unwrap_err(), TrySendError::Full(()));
+    assert!(src.write_chunk_uninit(0).is_err());
+    assert_eq!(sink.drain_to_vec(), vec![1, 2, 3]);
+    assert!(src.write_chunk_uninit(0).unwrap().is_empty());
+    drop(sink);
+    assert_eq!(
+        src.write_chunk_uninit(8).unwrap_err(),
+        TrySendError::Disconnected(())
+    );
+}
+
+#[test]
+fn st_max_capacity() {
+    assert!(try_channel::<()>(MAX_CAPACITY).is_err());
+    assert!(try_channel_exact::<()>(MAX_CAPACITY + 1).is_err());
+    assert!(Builder::new(usize::MAX).try_build::<()>().is_err());
+    assert!(std::panic::catch_unwind(|| channel::<()>(MAX_CAPACITY)).is_err());
+    // zero sized values don't need memory, so the largest capacities work.
+    let (src, sink) = try_channel::<()>(MAX_CAPACITY / 2 + 1).unwrap();
+    assert_eq!(src.slots_free(), MAX_CAPACITY / 2 + 1);
+    src.send(()).unwrap();
+    assert_eq!(sink.recv(), Ok(()));
+    let (src, sink) = channel_exact::<()>(MAX_CAPACITY);
+    assert_eq!(src.slots_free(), MAX_CAPACITY);
+    src.send(()).unwrap();
+    assert_eq!((sink.len_hint(), sink.recv()), (1, Ok(())));
+}
+
+#[test]
+fn st_index_wrap_around() {
+    // power of two capacities wrap at usize::MAX.
+    let (src, sink) = from_inner(Inner::<u32>::new(4).with_index(usize::MAX - 1));
+    for round in 0..3 {
+        for i in 0..4 {
+            src.try_send(round * 4 + i).unwrap();
+        }
+        assert!(src.is_full());
+        assert_eq!(sink.len_hint(), 4);
+        assert_eq!(sink.drain_to_vec(), (round * 4..round * 4 + 4).collect::<Vec<_>>());
+    }
+    // other capacities wrap at twice the capacity.
+    let (src, sink) = from_inner(Inner::<u32>::new(3).with_index(4));
+    for i in 0..9 {
+        src.try_send(i).unwrap();
+        assert_eq!(src.slots_free(), 2);
+        assert_eq!(sink.try_recv(), Ok(i));
+    }
+    // even when twice the capacity is close to usize::MAX.
+    let (src, sink) = from_inner(Inner::<()>::new(MAX_CAPACITY).with_index(2 * MAX_CAPACITY - 1));
+    for _ in 0..3 {
+        src.try_send(()).unwrap();
+    }
+    assert_eq!(src.slots_free(), MAX_CAPACITY - 3);
+    assert_eq!(sink.len_hint(), 3);
+    assert_eq!(sink.drain_to_vec().len(), 3);
+    assert_eq!(src.slots_free(), MAX_CAPACITY);
+}
+
+#[test]
+fn st_index_wrap_stale_caches() {
+    // the caches lag behind the other end point's index across the wrap.
+    let (src, mut sink) = from_inner(Inner::<u32>::new(4).with_index(usize::MAX - 2));
+    src.send_slice(&[0, 1, 2, 3]).unwrap();
+    assert_eq!(src.try_send(4), Err(TrySendError::Full(4)));
+    assert_eq!(sink.read_chunk(3).unwrap().len(), 3);
+    assert_eq!(sink.drain_to_vec(), [0, 1, 2, 3]);
+    // head_cache is still 4 indices behind, on the other side of usize::MAX.
+    assert_eq!(src.last_known_free(), 0);
+    src.send_slice(&[4, 5, 6]).unwrap();
+    assert_eq!(src.slots_free(), 1);
+    assert_eq!(sink.len_hint(), 3);
+    assert_eq!(sink.try_recv(), Ok(4));
+    // the same with exact capacities, which wrap at twice the capacity.
+    let (src, sink) = from_inner(Inner::<u32>::new(3).with_index(5));
+    src.send_slice(&[0, 1, 2]).unwrap();
+    assert_eq!(src.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(sink.drain_to_vec(), [0, 1, 2]);
+    assert_eq!(src.last_known_free(), 0);
+    src.send_slice(&[3, 4, 5]).unwrap();
+    assert!(src.is_full());
+    assert_eq!(sink.drain_to_vec(), [3, 4, 5]);
+}
+
+#[test]
+fn st_seq() {
+    let (mut src, mut sink) = channel_exact::<u32>(3);
+    assert_eq!((src.tail_seq(), sink.head_seq()), (0, 0));
+    src.send_slice(&[0, 1]).unwrap();
+    src.reserve_many(1).unwrap().commit();
+    src.send(2).unwrap();
+    assert_eq!(src.tail_seq(), 3);
+    assert_eq!(sink.recv(), Ok(0));
+    sink.read_chunk(2).unwrap().commit(1);
+    assert_eq!(sink.head_seq(), 2);
+    let value = sink.recv().unwrap();
+    sink.push_front(value).unwrap();
+    assert_eq!(sink.head_seq(), 2);
+    assert_eq!(sink.drain_to_vec(), [2]);
+    assert_eq!(sink.head_seq(), src.tail_seq());
+    // the numbers keep growing past the index wrap-around.
+    for i in 0..10 {
+        src.send(i).unwrap();
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    assert_eq!((src.tail_seq(), sink.head_seq()), (13, 13));
+
+    let (src, sink) = channel_overwriting::<u32>(1);
+    src.force_send(1).unwrap();
+    src.force_send(2).unwrap();
+    assert_eq!((src.tail_seq(), sink.head_seq()), (2, 1));
+}
+
+#[test]
+fn mt_seq() {
+    let (src, sink) = channel::<u32>(4);
+    let handle = std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i).unwrap();
+            assert_eq!(src.tail_seq(), u64::from(i) + 1);
+        }
+        src.tail_seq()
+    });
+    for i in 0..1000 {
+        assert_eq!(sink.head_seq(), u64::from(i));
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    assert_eq!(handle.join().unwrap(), sink.head_seq());
+}
+
+#[test]
+fn st_reserve_many() {
+    use std::rc::Rc;
+    let (mut src, sink) = channel_exact::<Rc<u32>>(3);
+    src.send(Rc::new(0)).unwrap();
+    assert_eq!(src.reserve_many(3).unwrap_err(), TrySendError::Full(()));
+    let mut group = src.reserve_many(2).unwrap();
+    assert_eq!(group.capacity(), 2);
+    let value = Rc::new(1);
+    group.push(value.clone()).unwrap();
+    assert_eq!(sink.len_hint(), 1);
+    // dropping the group drops the written values.
+    drop(group);
+    assert_eq!(Rc::strong_count(&value), 1);
+    assert_eq!(*sink.try_recv().unwrap(), 0);
+    // the group wraps around the end of the buffer.
+    let mut group = src.reserve_many(3).unwrap();
+    for i in 1..=3 {
+        group.push(Rc::new(i)).unwrap();
+    }
+    assert_eq!(*group.push(Rc::new(4)).unwrap_err(), 4);
+    group.commit();
+    // unwritten slots are given back.
+    assert_eq!(*sink.try_recv().unwrap(), 1);
+    let mut group = src.reserve_many(1).unwrap();
+    group.push(Rc::new(4)).unwrap();
+    group.commit();
+    let received: Vec<_> = sink.try_iter().map(|value| *value).collect();
+    assert_eq!(received, [2, 3, 4]);
+    src.reserve_many(2).unwrap().commit();
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    drop(sink);
+    assert_eq!(src.reserve_many(1).unwrap_err(), TrySendError::Disconnected(()));
+}
+
+#[test]
+fn mt_reserve_many() {
+    const RECORDS: u32 = 10_000;
+    let (mut src, sink) = channel_exact::<u32>(7);
+    std::thread::spawn(move || {
+        // every record is its length followed by that many values.
+        for i in 0..RECORDS {
+            let len = i % 6 + 1;
+            src.wait_for_capacity(len as usize).unwrap();
+            let mut group = src.reserve_many(len as usize).unwrap();
+            group.push(len - 1).unwrap();
+            for _ in 1..len {
+                group.push(i).unwrap();
+            }
+            group.commit();
+        }
+    });
+    for i in 0..RECORDS {
+        let len = sink.recv().unwrap();
+        assert_eq!(len, i % 6);
+        for _ in 0..len {
+            // the rest of the record was published with its length.
+            assert_eq!(sink.try_recv(), Ok(i));
+        }
+    }
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+#[test]
+fn st_force_send() {
+    let (src, mut sink) = channel_overwriting::<i32>(2);
+    assert_eq!(src.force_send(1), Ok(None));
+    assert_eq!(src.force_send(2), Ok(None));
+    assert_eq!(src.force_send(3), Ok(Some(1)));
+    assert_eq!(src.force_send(4), Ok(Some(2)));
+    assert_eq!(src.try_send(5), Err(TrySendError::Full(5)));
+    assert_eq!(sink.try_recv(), Ok(3));
+    assert_eq!(src.force_send(5), Ok(None));
+    assert_eq!(sink.recv(), Ok(4));
+    assert_eq!(*sink.recv_ref().unwrap(), 5);
+    drop(sink);
+    assert_eq!(src.force_send(6), Err(SendError(6)));
+}
+
+#[test]
+fn mt_force_send() {
+    let (src, sink) = channel_overwriting::<u32>(4);
+    std::thread::spawn(move || {
+        for i in 0..10_000 {
+            src.force_send(i).unwrap();
+        }
+    });
+    let mut last = None;
+    while let Ok(i) = sink.recv() {
+        assert!(last < Some(i), "values should stay in order");
+        last = Some(i);
+    }
+    assert_eq!(last, Some(9_999));
+}
+
+#[test]
+fn mt_drop_receiver_while_full() {
+    // a sender blocked on a full channel is woken by the receiver's drop.
+    let (src, sink) = channel::<i32>(1);
+    src.send(1).unwrap();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        drop(sink);
+    });
+    assert_eq!(src.send(2), Err(SendError(2)));
+}
+
+#[test]
+fn mt_rendezvous() {
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+    let (src, sink) = channel_rendezvous::<usize>();
+    assert_eq!(src.try_send(0), Err(TrySendError::Full(0)));
+    std::thread::spawn(move || {
+        while let Ok(i) = sink.recv() {
+            RECEIVED.store(i, SeqCst);
+        }
+    });
+    for i in 1..100 {
+        src.send(i).unwrap();
+        assert!(RECEIVED.load(SeqCst) + 1 >= i, "send returned before a handoff");
+    }
+}
+
+#[test]
+fn mt_rendezvous_disconnect() {
+    let (src, sink) = channel_rendezvous::<i32>();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        drop(sink);
+    });
+    assert_eq!(src.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn mt_rendezvous_recv_then_drop() {
+    for _ in 0..100 {
+        let (src, sink) = channel_rendezvous::<std::sync::Arc<()>>();
+        let item = std::sync::Arc::new(());
+        let handle = std::thread::spawn(move || sink.recv().is_ok());
+        assert!(src.send(item.clone()).is_ok());
+        assert!(handle.join().unwrap());
+        std::mem::drop(src);
+        assert_eq!(std::sync::Arc::strong_count(&item), 1);
+    }
+}
+
+#[test]
+fn mt_rendezvous_close() {
+    let (src, sink) = channel_rendezvous::<std::sync::Arc<()>>();
+    let item = std::sync::Arc::new(());
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        sink.close();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let first = sink.try_recv();
+        let second = sink.try_recv();
+        (first.is_ok(), second.is_err())
+    });
+    assert!(src.send(item.clone()).is_ok());
+    assert_eq!(handle.join().unwrap(), (true, true));
+    std::mem::drop(src);
+    assert_eq!(std::sync::Arc::strong_count(&item), 1);
+}
+
+#[test]
+fn st_rendezvous_disconnect() {
+    let (src, sink) = channel_rendezvous::<i32>();
+    drop(sink);
+    assert_eq!(src.send(1), Err(SendError(1)));
+    assert_eq!(src.try_send(2), Err(TrySendError::Disconnected(2)));
+}
+
+#[test]
+fn st_exact() {
+    let (src, sink) = channel_exact::<usize>(3);
+    for round in 0..10 {
+        for i in 0..3 {
+            assert_eq!(src.try_send(round * 3 + i), Ok(()));
+        }
+        assert_eq!(src.try_send(0), Err(TrySendError::Full(0)));
+        for i in 0..3 {
+            assert_eq!(sink.try_recv(), Ok(round * 3 + i));
+        }
+        assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    }
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    drop(src);
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.recv(), Ok(2));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+#[test]
+fn exact_drop() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    {
+        let (src, sink) = channel_exact(5);
+        for _ in 0..4 {
+            src.send(rc.clone()).unwrap();
+        }
+        drop(sink.recv());
+        for _ in 0..2 {
+            src.send(rc.clone()).unwrap();
+        }
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn mt_exact() {
+    let (src, sink) = channel_exact::<u32>(7);
+    std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..1000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+}
+
+#[test]
+fn mt_builder_spin() {
+    let (src, sink) = Builder::new(2).spin(100).build::<u32>();
+    std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..1000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+#[test]
+fn mt_builder_busy_wait() {
+    let (src, sink) = Builder::new(2).park(false).build::<u32>();
+    let handle = std::thread::spawn(move || {
+        for i in 0..1000 {
+            assert_eq!(sink.recv(), Ok(i));
+        }
+    });
+    for i in 0..1000 {
+        src.send(i).unwrap();
+    }
+    handle.join().unwrap();
+}
+
+#[test]
+fn st_try_channel() {
+    let (src, sink) = try_channel::<u32>(3).unwrap();
+    src.send(1).unwrap();
+    assert_eq!(sink.recv(), Ok(1));
+
+    assert_eq!(try_channel::<u64>(usize::MAX / 4).err(), Some(AllocError {}));
+    assert_eq!(try_channel::<u8>(usize::MAX).err(), Some(AllocError {}));
+    assert_eq!(
+        Builder::new(usize::MAX / 2 + 2).try_build::<()>().err(),
+        Some(AllocError {})
+    );
+}
+
+#[test]
+fn st_send_timeout() {
+    use std::time::Duration;
+    let (src, sink) = channel::<u8>(1);
+    assert_eq!(src.send_timeout(1, Duration::from_millis(1)), Ok(()));
+    assert_eq!(
+        src.send_timeout(2, Duration::from_millis(10)),
+        Err(SendTimeoutError::Timeout(2))
+    );
+    assert_eq!(
+        src.wait_for_capacity_timeout(1, Duration::from_millis(10)),
+        Err(SendTimeoutError::Timeout(()))
+    );
+    assert_eq!(src.wait_for_capacity_timeout(0, Duration::ZERO), Ok(()));
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(src.wait_for_capacity(1), Ok(()));
+    std::mem::drop(sink);
+    assert_eq!(
+        src.send_timeout(3, Duration::from_millis(10)),
+        Err(SendTimeoutError::Disconnected(3))
+    );
+    assert_eq!(src.wait_for_capacity(1), Err(SendError(())));
+}
+
+#[test]
+fn st_on_starved() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    let waits = Arc::new(Mutex::new(Vec::new()));
+    let log = waits.clone();
+    let (src, sink) = Builder::new(1)
+        .on_starved(Duration::from_millis(20), move |waited| {
+            log.lock().unwrap().push(waited)
+        })
+        .build::<u8>();
+    // a timeout before the threshold doesn't fire the callback.
+    assert_eq!(sink.recv_timeout(Duration::from_millis(5)), Err(RecvTimeoutError::Timeout));
+    assert!(waits.lock().unwrap().is_empty());
+    // past it, the callback fires once and the timeout still holds.
+    src.send(1).unwrap();
+    assert_eq!(
+        src.send_timeout(2, Duration::from_millis(60)),
+        Err(SendTimeoutError::Timeout(2))
+    );
+    assert_eq!(waits.lock().unwrap().len(), 1);
+    assert!(waits.lock().unwrap()[0] >= Duration::from_millis(20));
+    // every blocking operation is timed separately.
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.recv_timeout(Duration::from_millis(30)), Err(RecvTimeoutError::Timeout));
+    assert_eq!(waits.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn mt_on_starved() {
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use std::sync::Arc;
+    use std::time::Duration;
+    let starved = Arc::new(AtomicUsize::new(0));
+    let counter = starved.clone();
+    let (src, sink) = Builder::new(1)
+        .on_starved(Duration::from_millis(10), move |_| {
+            counter.fetch_add(1, SeqCst);
+        })
+        .build::<u32>();
+    let handle = std::thread::spawn(move || {
+        for i in 0..3 {
+            src.send(i).unwrap();
+        }
+    });
+    std::thread::sleep(Duration::from_millis(50));
+    // the sender is stuck on the second value.
+    assert_eq!(starved.load(SeqCst), 1);
+    for i in 0..3 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    handle.join().unwrap();
+}
+
+#[test]
+fn mt_send_until() {
+    use std::time::{Duration, Instant};
+    let (src, sink) = channel::<u32>(2);
+    let handle = std::thread::spawn(move || {
+        for i in 0..10_000 {
+            let deadline = Instant::now() + Duration::from_secs(60);
+            src.try_send_until(i, deadline).unwrap();
+        }
+        let past = Instant::now();
+        src.send(0).unwrap();
+        src.send(0).unwrap();
+        assert_eq!(src.try_send_until(1, past), Err(SendTimeoutError::Timeout(1)));
+    });
+    for i in 0..10_000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    handle.join().unwrap();
+    assert_eq!(sink.try_iter().count(), 2);
+}
+
+#[test]
+fn mt_wait_for_capacity() {
+    let (src, sink) = channel::<u32>(4);
+    std::thread::spawn(move || {
+        for batch in 0..100 {
+            src.wait_for_capacity(3).unwrap();
+            for i in 0..3 {
+                src.try_send(batch * 3 + i).unwrap();
+            }
+        }
+    });
+    for i in 0..300 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+}
+
+#[test]
+fn mt_rendezvous_send_timeout() {
+    use std::time::Duration;
+    let (src, sink) = channel_rendezvous::<u32>();
+    assert_eq!(
+        src.send_timeout(0, Duration::from_millis(10)),
+        Err(SendTimeoutError::Timeout(0))
+    );
+    let handle = std::thread::spawn(move || sink.recv());
+    assert_eq!(src.send_timeout(1, Duration::from_secs(60)), Ok(()));
+    assert_eq!(handle.join().unwrap(), Ok(1));
+}
+
+#[test]
+fn mt_set_spin() {
+    let (src, sink) = channel::<u32>(2);
+    src.set_spin(100);
+    sink.set_spin(100);
+    std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..1000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+}
+
+#[test]
+// the hash only depends on the channel, not on a value put back with push_front.
+#[allow(clippy::mutable_key_type)]
+fn st_channel_identity() {
+    use std::collections::HashSet;
+    let (src1, sink1) = channel::<u8>(1);
+    let (src2, sink2) = channel::<u8>(1);
+    assert_eq!(src1.channel_id(), sink1.channel_id());
+    assert_ne!(src1.channel_id(), src2.channel_id());
+    assert!(src2.same_channel(&sink2) && sink2.same_channel(&src2));
+    assert!(!sink1.same_channel(&src2));
+
+    let mut receivers = HashSet::new();
+    receivers.insert(sink1);
+    receivers.insert(sink2);
+    assert_eq!(receivers.len(), 2);
+    assert!(receivers.iter().any(|rx| rx.same_channel(&src1)));
+}
+
+#[test]
+fn st_drain_to_vec() {
+    let (src, sink) = channel::<String>(4);
+    let (exact_src, exact_sink) = channel_exact::<String>(3);
+    for round in 0..5 {
+        for i in 0..round % 4 {
+            src.send(format!("{round}:{i}")).unwrap();
+        }
+        let expected = (0..round % 4)
+            .map(|i| format!("{round}:{i}"))
+            .collect::<Vec<_>>();
+        assert_eq!(sink.drain_to_vec(), expected);
+
+        for i in 0..round % 3 + 1 {
+            exact_src.send(format!("{round}:{i}")).unwrap();
+        }
+        assert_eq!(exact_sink.try_recv(), Ok(format!("{round}:0")));
+        let expected = (1..round % 3 + 1)
+            .map(|i| format!("{round}:{i}"))
+            .collect::<Vec<_>>();
+        assert_eq!(exact_sink.drain_to_vec(), expected);
+    }
+    assert_eq!(src.try_send("last".into()), Ok(()));
+    assert_eq!(sink.try_recv(), Ok("last".into()));
+}
+
+#[test]
+fn st_close_and_drain() {
+    let (src, sink) = channel::<String>(4);
+    for i in 0..3 {
+        src.send(i.to_string()).unwrap();
+    }
+    let first = sink.recv().unwrap();
+    sink.push_front(first).unwrap();
+    assert_eq!(sink.close_and_drain(), ["0", "1", "2"]);
+    assert!(src.is_closed());
+    assert_eq!(src.send("3".into()), Err(SendError("3".into())));
+    assert!(sink.close_and_drain().is_empty());
+}
+
+#[test]
+fn st_receiver_state() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let (src, sink) = channel::<u32>(4);
+    src.send(1).unwrap();
+    assert_eq!(sink.state(), ReceiverState::Connected);
+    src.close();
+    assert_eq!(sink.state(), ReceiverState::Closed { pending: 1 });
+    drop(src);
+    assert_eq!(sink.state(), ReceiverState::Closed { pending: 1 });
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.state(), ReceiverState::Closed { pending: 0 });
+
+    let (src, sink) = Builder::new(4).poisoning().build::<u32>();
+    src.send(1).unwrap();
+    let res = catch_unwind(AssertUnwindSafe(move || {
+        let _src = src;
+        panic!("poisoned");
+    }));
+    assert!(res.is_err());
+    assert_eq!(sink.state(), ReceiverState::Poisoned { pending: 1 });
+}
+
+#[test]
+fn mt_idle_strategy() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let idle = Arc::new(AtomicUsize::new(0));
+    let counter = idle.clone();
+    let (src, sink) = Builder::new(1).idle(IdleStrategy::Yield).build::<u32>();
+    src.set_idle(IdleStrategy::park_timeout(Duration::from_millis(1), move || {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }));
+    src.send(0).unwrap();
+    let handle = std::thread::spawn(move || {
+        // the channel is full, so this waits for the receiver.
+        src.send(1).unwrap();
+        src.set_idle(IdleStrategy::Spin);
+        for i in 2..100 {
+            src.send(i).unwrap();
+        }
+    });
+    std::thread::sleep(Duration::from_millis(20));
+    for i in 0..100 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    handle.join().unwrap();
+    assert!(idle.load(Ordering::Relaxed) > 0);
+    assert_eq!(
+        sink.recv_timeout(Duration::from_millis(1)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn st_name() {
+    let (src, sink) = Builder::new(4).name("events").build::<u32>();
+    assert_eq!((src.name(), sink.name()), (Some("events"), Some("events")));
+    assert!(format!("{:?}", src).contains("name: \"events\""));
+    let (src, _sink) = channel::<u32>(4);
+    assert_eq!(src.name(), None);
+    assert!(!format!("{:?}", src).contains("name"));
+}
+
+#[test]
+fn st_clone_takeover() {
+    let (src, mut sink) = channel::<u32>(4);
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    sink.push_front(0).unwrap();
+    let takeover = sink.try_clone_takeover().unwrap();
+    assert!(takeover.same_channel(&src));
+    assert!(!sink.same_channel(&src));
+    assert!(!sink.sender_connected());
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+    drop(sink);
+    assert!(src.receiver_connected());
+    src.send(3).unwrap();
+    assert_eq!(takeover.try_iter().collect::<Vec<_>>(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn st_debug() {
+    let (src, sink) = channel::<u32>(4);
+    src.send(1).unwrap();
+    let debug = format!("{:?}", src);
+    assert!(debug.starts_with("spsc::bounded::Sender<u32> { channel: 0x"));
+    assert!(debug.ends_with("len: 1, capacity: 4, receiver_connected: true }"));
+    drop(src);
+    let debug = format!("{:?}", sink);
+    assert!(debug.ends_with("len: 1, capacity: 4, sender_connected: false }"));
+}
+
+#[test]
+fn st_watermarks() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let (highs, lows) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let (high, low) = (highs.clone(), lows.clone());
+    let (src, sink) = Builder::new(4)
+        .watermarks(
+            100,
+            0,
+            move || {
+                high.fetch_add(1, Ordering::Relaxed);
+            },
+            move || {
+                low.fetch_add(1, Ordering::Relaxed);
+            },
+        )
+        .build::<u32>();
+    for round in 1..=2 {
+        src.send_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(highs.load(Ordering::Relaxed), round - 1);
+        // the high watermark is clamped to the capacity.
+        src.send(4).unwrap();
+        assert_eq!(highs.load(Ordering::Relaxed), round);
+        assert_eq!(sink.recv(), Ok(1));
+        src.send(5).unwrap();
+        assert_eq!(highs.load(Ordering::Relaxed), round);
+        let mut buf = [0; 3];
+        assert_eq!(sink.recv_slice(&mut buf), Ok(3));
+        assert_eq!(lows.load(Ordering::Relaxed), round - 1);
+        assert_eq!(sink.drain_to_vec(), [5]);
+        assert_eq!(lows.load(Ordering::Relaxed), round);
+    }
+}
+
+#[test]
+fn mt_close_and_drain() {
+    let (src, sink) = channel::<u32>(16);
+    let handle = std::thread::spawn(move || (0..).take_while(|&i| src.send(i).is_ok()).count());
+    let mut received = Vec::new();
+    while received.len() < 100 {
+        received.push(sink.recv().unwrap());
+    }
+    received.extend(sink.close_and_drain());
+    let sent = handle.join().unwrap();
+    // values sent while the close was racing the Sender are still pending.
+    received.extend(sink.drain_to_vec());
+    assert_eq!(received, (0..sent as u32).collect::<Vec<_>>());
+}
+
 #[test]
 fn send_non_copy() {
     use std::ops::Deref;
@@ -53,6 +1253,896 @@ fn send_non_copy() {
     assert_eq!(sink.recv().unwrap().deref(), "Hello");
 }
 
+#[test]
+fn iterators() {
+    let (src, sink) = channel::<i32>(4);
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    assert_eq!(sink.try_iter().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(sink.try_iter().next(), None);
+
+    std::thread::spawn(move || {
+        for i in 0..10 {
+            src.send(i).unwrap();
+        }
+    });
+    assert_eq!(sink.into_iter().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_recv_timeout() {
+    use std::time::{Duration, Instant};
+    let (src, sink) = channel::<i32>(4);
+    let start = Instant::now();
+    assert_eq!(sink.recv_timeout(Duration::from_millis(10)), Err(RecvTimeoutError::Timeout));
+    assert!(start.elapsed() >= Duration::from_millis(10));
+    src.send(1).unwrap();
+    assert_eq!(sink.recv_timeout(Duration::ZERO), Ok(1));
+    drop(src);
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Err(RecvTimeoutError::Disconnected));
+}
+
+#[test]
+fn mt_recv_timeout() {
+    use std::time::Duration;
+    let (src, sink) = channel_rendezvous::<i32>();
+    let handle = std::thread::spawn(move || {
+        src.send(1).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        src.send(2).unwrap();
+    });
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Ok(1));
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Ok(2));
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Err(RecvTimeoutError::Disconnected));
+    handle.join().unwrap();
+}
+
+#[test]
+fn st_is_full_is_ready() {
+    let (src, sink) = channel_exact::<i32>(2);
+    assert!(!src.is_full() && !sink.is_ready());
+    src.send(1).unwrap();
+    assert!(!src.is_full() && sink.is_ready());
+    src.send(2).unwrap();
+    assert!(src.is_full());
+    assert_eq!(sink.recv(), Ok(1));
+    assert!(!src.is_full() && sink.is_ready());
+    assert_eq!(sink.recv(), Ok(2));
+    assert!(!sink.is_ready());
+    drop(src);
+    assert!(!sink.is_ready());
+
+    let (src, sink) = channel_overwriting::<i32>(1);
+    src.force_send(1).unwrap();
+    assert_eq!(src.force_send(2), Ok(Some(1)));
+    assert!(src.is_full() && sink.is_ready());
+}
+
+#[test]
+fn mt_is_ready() {
+    let (src, sink) = channel::<i32>(16);
+    let handle = std::thread::spawn(move || {
+        for i in 0..1000 {
+            while src.is_full() {
+                std::thread::yield_now();
+            }
+            src.try_send(i).unwrap();
+        }
+    });
+    for i in 0..1000 {
+        while !sink.is_ready() {
+            std::thread::yield_now();
+        }
+        assert_eq!(sink.try_recv(), Ok(i));
+    }
+    handle.join().unwrap();
+}
+
+#[test]
+fn st_wait_empty() {
+    use std::time::Duration;
+    let (src, sink) = channel::<i32>(4);
+    src.wait_empty().unwrap();
+    src.send(1).unwrap();
+    assert_eq!(
+        src.wait_empty_timeout(Duration::from_millis(10)),
+        Err(SendTimeoutError::Timeout(()))
+    );
+    assert_eq!(sink.recv(), Ok(1));
+    src.wait_empty_timeout(Duration::ZERO).unwrap();
+    src.send(2).unwrap();
+    drop(sink);
+    assert_eq!(src.wait_empty(), Err(SendError(())));
+}
+
+#[test]
+fn mt_wait_empty() {
+    let (src, sink) = channel::<i32>(16);
+    let handle = std::thread::spawn(move || {
+        for i in 0..8 {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            assert_eq!(sink.recv(), Ok(i));
+        }
+        sink
+    });
+    for i in 0..8 {
+        src.send(i).unwrap();
+    }
+    src.close();
+    // close still lets the receiver drain the channel.
+    src.wait_empty().unwrap();
+    let sink = handle.join().unwrap();
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn st_send_or_else() {
+    let (src, sink) = channel::<i32>(1);
+    src.send_or_else(1, |_| unreachable!());
+    assert_eq!(src.try_send(2).unwrap_err().into_inner(), 2);
+    drop(sink);
+    let mut lost = Vec::new();
+    src.send_or_else(3, |item| lost.push(item));
+    assert_eq!(src.send(4).unwrap_err().into_inner(), 4);
+    assert_eq!(src.try_send(5).unwrap_err().into_inner(), 5);
+    assert_eq!(lost, [3]);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn st_stats() {
+    let (src, sink) = channel_exact::<i32>(2);
+    assert_eq!(src.stats(), crate::metrics::Stats::default());
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(src.try_send_slice(&[1, 2, 3]), Ok(2));
+    assert_eq!(src.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.drain_to_vec(), [2]);
+    let stats = sink.stats();
+    assert_eq!(stats, src.stats());
+    assert_eq!((stats.sent, stats.received), (2, 2));
+    assert_eq!((stats.full, stats.empty), (1, 1));
+    assert_eq!(stats.max_depth, 2);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn mt_stats() {
+    let (src, sink) = channel::<i32>(4);
+    let handle = std::thread::spawn(move || {
+        for i in 0..10_000 {
+            src.send(i).unwrap();
+        }
+        src
+    });
+    for i in 0..10_000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    let src = handle.join().unwrap();
+    let stats = src.stats();
+    assert_eq!((stats.sent, stats.received), (10_000, 10_000));
+    assert!(stats.max_depth <= 4);
+    // time is only counted while a thread sleeps.
+    assert_eq!(stats.sender_parks == 0, stats.sender_park_time.is_zero());
+    assert_eq!(stats.receiver_parks == 0, stats.receiver_park_time.is_zero());
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn mt_tracing() {
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+    use std::sync::Arc;
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    #[derive(Default)]
+    struct Parks(AtomicUsize);
+    impl Subscriber for Parks {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            if span.metadata().name() == "park" {
+                self.0.fetch_add(1, SeqCst);
+            }
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    let parks = Arc::new(Parks::default());
+    let (src, sink) = channel::<i32>(1);
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        src.send(1).unwrap();
+    });
+    tracing::subscriber::with_default(parks.clone(), || assert_eq!(sink.recv(), Ok(1)));
+    handle.join().unwrap();
+    assert!(parks.0.load(SeqCst) > 0);
+}
+
+#[test]
+fn st_close() {
+    let (src, sink) = channel::<i32>(4);
+    src.send(1).unwrap();
+    assert!(!sink.is_closed());
+    src.close();
+    assert!(src.is_closed() && sink.is_closed());
+    assert_eq!(src.try_send(2), Err(TrySendError::Disconnected(2)));
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+    let src = src.into_pending().unwrap_err();
+    drop(sink);
+    assert_eq!(src.into_pending().unwrap(), Vec::<i32>::new());
+
+    let (src, sink) = channel::<i32>(4);
+    sink.close();
+    assert_eq!(src.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn mt_close_wakes_peer() {
+    let (src, sink) = channel::<i32>(1);
+    src.send(1).unwrap();
+    let handle = std::thread::spawn(move || assert_eq!(src.send(2), Err(SendError(2))));
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    sink.close();
+    handle.join().unwrap();
+
+    let (src, sink) = channel::<i32>(1);
+    let handle = std::thread::spawn(move || assert_eq!(sink.recv(), Err(RecvError {})));
+    src.close();
+    handle.join().unwrap();
+}
+
+#[test]
+fn st_set_notify() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let (src, sink) = channel::<i32>(4);
+    let notified = Arc::new(AtomicUsize::new(0));
+    let counter = notified.clone();
+    sink.set_notify(move || {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    assert_eq!(notified.load(Ordering::Relaxed), 2);
+    sink.clear_notify();
+    src.send(3).unwrap();
+    assert_eq!(notified.load(Ordering::Relaxed), 2);
+    assert_eq!(Arc::strong_count(&notified), 1);
+}
+
+#[test]
+fn mt_set_notify() {
+    let (src, sink) = channel::<i32>(4);
+    let (notify_tx, notify_rx) = std::sync::mpsc::sync_channel::<()>(1);
+    sink.set_notify(move || {
+        let _ = notify_tx.try_send(());
+    });
+    std::thread::spawn(move || {
+        for i in 0..100 {
+            src.send(i).unwrap();
+        }
+    });
+    let mut received = Vec::new();
+    loop {
+        match sink.try_recv() {
+            Ok(i) => received.push(i),
+            Err(TryRecvError::Empty) => notify_rx.recv().unwrap(),
+            Err(_) => break,
+        }
+    }
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn st_sink_stream() {
+    use futures::{FutureExt, SinkExt, StreamExt};
+    let (mut src, mut sink) = channel_exact::<u32>(2);
+    assert_eq!(SinkExt::send(&mut src, 1).now_or_never(), Some(Ok(())));
+    src.send(2).unwrap();
+    // the sink waits for room.
+    assert_eq!(SinkExt::send(&mut src, 3).now_or_never(), None);
+    assert_eq!(futures::Stream::size_hint(&sink), (2, None));
+    assert_eq!(sink.next().now_or_never(), Some(Some(1)));
+    assert_eq!(sink.next().now_or_never(), Some(Some(2)));
+    assert_eq!(sink.next().now_or_never(), None);
+    // closing the sink ends the stream.
+    assert_eq!(SinkExt::close(&mut src).now_or_never(), Some(Ok(())));
+    assert_eq!(SinkExt::send(&mut src, 4).now_or_never(), Some(Err(SendError(()))));
+    assert_eq!(sink.next().now_or_never(), Some(None));
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn mt_sink_stream() {
+    use futures::executor::block_on;
+    use futures::{stream, SinkExt, StreamExt};
+    let (mut src, sink) = channel::<u32>(4);
+    let handle = std::thread::spawn(move || block_on(sink.collect::<Vec<_>>()));
+    block_on(SinkExt::send_all(&mut src, &mut stream::iter(0..1000).map(Ok))).unwrap();
+    block_on(SinkExt::close(&mut src)).unwrap();
+    assert_eq!(handle.join().unwrap(), (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn st_sink_rendezvous() {
+    use futures::{FutureExt, SinkExt};
+    let (mut src, sink) = channel_rendezvous::<u32>();
+    // the value is sent, but flushing waits for the receiver.
+    assert_eq!(SinkExt::send(&mut src, 1).now_or_never(), None);
+    assert_eq!(SinkExt::feed(&mut src, 2).now_or_never(), None);
+    assert_eq!(sink.try_recv(), Ok(1));
+    assert_eq!(SinkExt::flush(&mut src).now_or_never(), Some(Ok(())));
+    drop(sink);
+    assert_eq!(SinkExt::send(&mut src, 2).now_or_never(), Some(Err(SendError(()))));
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn mt_sink_rendezvous() {
+    use futures::executor::block_on;
+    use futures::{stream, SinkExt, StreamExt};
+    let (mut src, sink) = channel_rendezvous::<u32>();
+    let handle = std::thread::spawn(move || block_on(sink.collect::<Vec<_>>()));
+    block_on(SinkExt::send_all(&mut src, &mut stream::iter(0..1000).map(Ok))).unwrap();
+    block_on(SinkExt::close(&mut src)).unwrap();
+    assert_eq!(handle.join().unwrap(), (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg(all(unix, feature = "fd-notify"))]
+fn st_notify_fd() {
+    use std::io::Read;
+    use std::os::unix::io::AsFd;
+    use std::os::unix::net::UnixStream;
+    let (src, sink) = channel::<i32>(4);
+    let fd = sink.notify_fd().unwrap();
+    let readable = {
+        let stream = UnixStream::from(fd.as_fd().try_clone_to_owned().unwrap());
+        //reads consume the notifications, like NotifyFd::clear.
+        move || matches!((&stream).read(&mut [0; 64]), Ok(1..))
+    };
+    assert!(!readable());
+    src.send(1).unwrap();
+    assert!(readable());
+    assert!(!readable());
+    src.send(2).unwrap();
+    fd.clear();
+    assert!(!readable());
+    drop(src);
+    assert!(readable());
+    assert_eq!(sink.try_iter().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[cfg(all(target_os = "linux", feature = "shm"))]
+fn shm_file(name: &str) -> std::fs::File {
+    let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let file = std::fs::File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    file
+}
+
+#[test]
+#[cfg(all(target_os = "linux", feature = "shm"))]
+fn st_shm() {
+    let file = shm_file("st_shm");
+    let channel = unsafe { shm_channel::<u64>(&file, 3).unwrap() };
+    assert_eq!(channel.capacity(), 4);
+    // a second mapping, like the one of another process.
+    let other = unsafe { shm_channel::<u64>(&file, 100).unwrap() };
+    assert_eq!(other.capacity(), 4);
+    let other_type = unsafe { shm_channel::<u32>(&file, 4) };
+    assert_eq!(other_type.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    let src = channel.sender().unwrap();
+    assert!(other.sender().is_none());
+    let sink = other.receiver().unwrap();
+    assert!(channel.receiver().is_none());
+    for round in 0..3 {
+        for i in 0..4 {
+            assert_eq!(src.try_send(round * 4 + i), Ok(()));
+        }
+        assert_eq!(src.try_send(99), Err(TrySendError::Full(99)));
+        for i in 0..4 {
+            assert_eq!(sink.try_recv(), Ok(round * 4 + i));
+        }
+        assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    }
+    src.send(1).unwrap();
+    drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+    assert!(channel.sender().is_none());
+}
+
+#[test]
+#[cfg(all(target_os = "linux", feature = "shm"))]
+fn mt_shm() {
+    let file = shm_file("mt_shm");
+    let channel = unsafe { shm_channel::<u32>(&file, 8).unwrap() };
+    let sink = channel.receiver().unwrap();
+    let handle = std::thread::spawn(move || {
+        let channel = unsafe { shm_channel::<u32>(&file, 8).unwrap() };
+        let src = channel.sender().unwrap();
+        for i in 0..100_000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..100_000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    assert_eq!(sink.recv(), Err(RecvError {}));
+    handle.join().unwrap();
+
+    let file = shm_file("mt_shm_dc");
+    let channel = unsafe { shm_channel::<u32>(&file, 1).unwrap() };
+    let src = channel.sender().unwrap();
+    let sink = channel.receiver().unwrap();
+    src.send(0).unwrap();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        drop(sink);
+    });
+    // wakes up when the receiver disconnects.
+    assert_eq!(src.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn st_try_send_slice() {
+    for (cap, (src, sink)) in [(4, channel::<u32>(4)), (3, channel_exact::<u32>(3))] {
+        let items: Vec<u32> = (0..10).collect();
+        assert_eq!(src.try_send_slice(&items), Ok(cap as usize));
+        assert_eq!(src.try_send_slice(&items), Ok(0));
+        assert_eq!(sink.recv(), Ok(0));
+        assert_eq!(sink.recv(), Ok(1));
+        // wraps around the end of the buffer.
+        assert_eq!(src.try_send_slice(&items[cap as usize..]), Ok(2));
+        assert_eq!(
+            sink.try_iter().collect::<Vec<_>>(),
+            (2..cap + 2).collect::<Vec<_>>()
+        );
+        assert_eq!(src.try_send_slice(&[]), Ok(0));
+        drop(sink);
+        assert_eq!(src.try_send_slice(&items), Err(SendError(())));
+    }
+}
+
+#[test]
+fn mt_try_send_slice() {
+    let (src, sink) = channel_exact::<u32>(7);
+    std::thread::spawn(move || {
+        let items: Vec<u32> = (0..10_000).collect();
+        let mut sent = 0;
+        while sent != items.len() {
+            match src.try_send_slice(&items[sent..]).unwrap() {
+                0 => std::thread::yield_now(),
+                n => sent += n,
+            }
+        }
+    });
+    for i in 0..10_000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+}
+
+#[test]
+fn st_send_all() {
+    for (cap, (src, sink)) in [(4, channel::<u32>(4)), (3, channel_exact::<u32>(3))] {
+        src.send_all(0..cap).unwrap();
+        assert_eq!(sink.recv(), Ok(0));
+        // wraps around the end of the buffer.
+        src.send_all([cap]).unwrap();
+        assert_eq!(
+            sink.try_iter().collect::<Vec<_>>(),
+            (1..=cap).collect::<Vec<_>>()
+        );
+        src.send_all([]).unwrap();
+        src.send_all(0..cap - 1).unwrap();
+        drop(sink);
+        // the value taken out of the iterator before noticing the disconnect is returned too.
+        let rest = src.send_all(10..15).unwrap_err().0;
+        assert_eq!(rest.collect::<Vec<_>>(), [10, 11, 12, 13, 14]);
+    }
+}
+
+#[test]
+fn mt_send_all() {
+    for (src, sink) in [channel_exact::<u32>(7), channel_rendezvous::<u32>()] {
+        std::thread::spawn(move || src.send_all(0..10_000).unwrap());
+        for i in 0..10_000 {
+            assert_eq!(sink.recv(), Ok(i));
+        }
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    }
+
+    let (src, sink) = channel_rendezvous::<u32>();
+    let handle = std::thread::spawn(move || src.send_all(0..10).unwrap_err().0);
+    assert_eq!(sink.recv(), Ok(0));
+    drop(sink);
+    let rest = handle.join().unwrap().collect::<Vec<_>>();
+    assert_eq!(rest, (1..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_recv_batch_timeout() {
+    use std::time::Duration;
+    let (src, sink) = channel::<u32>(4);
+    let mut batch = vec![0];
+    assert_eq!(sink.recv_batch_timeout(&mut batch, 4, Duration::ZERO), Ok(0));
+    src.send_all(1..=4).unwrap();
+    assert_eq!(sink.recv_batch_timeout(&mut batch, 3, Duration::ZERO), Ok(3));
+    assert_eq!(sink.recv_batch_timeout(&mut batch, 0, Duration::ZERO), Ok(0));
+    assert_eq!(batch, [0, 1, 2, 3]);
+    drop(src);
+    assert_eq!(sink.recv_batch_timeout(&mut batch, 3, Duration::MAX), Ok(1));
+    assert_eq!(batch, [0, 1, 2, 3, 4]);
+    assert_eq!(
+        sink.recv_batch_timeout(&mut batch, 3, Duration::MAX),
+        Err(RecvError {})
+    );
+}
+
+#[test]
+fn mt_recv_batch_timeout() {
+    use std::time::Duration;
+    let (src, sink) = channel_exact::<u32>(7);
+    std::thread::spawn(move || src.send_all(0..10_000).unwrap());
+    let mut batch = Vec::new();
+    while let Ok(n) = sink.recv_batch_timeout(&mut batch, 100, Duration::from_millis(1)) {
+        assert!(n <= 100);
+    }
+    assert_eq!(batch, (0..10_000).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_wake_threshold() {
+    use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+    use std::sync::Arc;
+    let (src, sink) = Builder::new(8).wake_threshold(4).build::<u32>();
+    let wakes = Arc::new(AtomicUsize::new(0));
+    let counter = wakes.clone();
+    sink.set_notify(move || {
+        counter.fetch_add(1, Relaxed);
+    });
+    for i in 0..3 {
+        src.send(i).unwrap();
+    }
+    assert_eq!(wakes.load(Relaxed), 0);
+    src.send(3).unwrap();
+    assert_eq!(wakes.load(Relaxed), 1);
+    src.flush();
+    assert_eq!(wakes.load(Relaxed), 2);
+    assert_eq!(sink.try_iter().count(), 4);
+    // the stale head_cache is refreshed before waking.
+    src.send_all(4..6).unwrap();
+    assert_eq!(wakes.load(Relaxed), 2);
+    assert_eq!(src.try_send_slice(&[6, 7]), Ok(2));
+    assert_eq!(wakes.load(Relaxed), 3);
+    drop(src);
+    assert_eq!(wakes.load(Relaxed), 4);
+    assert_eq!(sink.try_iter().collect::<Vec<_>>(), [4, 5, 6, 7]);
+}
+
+#[test]
+fn mt_wake_threshold() {
+    let (src, sink) = Builder::new(8).wake_threshold(4).build::<u32>();
+    let handle = std::thread::spawn(move || sink.iter().collect::<Vec<_>>());
+    src.send_all(0..1000).unwrap();
+    for i in 1000..1002 {
+        src.send(i).unwrap();
+    }
+    drop(src);
+    assert_eq!(handle.join().unwrap(), (0..1002).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_try_recv_slice() {
+    for (cap, (src, sink)) in [(4, channel::<u32>(4)), (3, channel_exact::<u32>(3))] {
+        let mut buf = [0; 8];
+        assert_eq!(sink.try_recv_slice(&mut buf), Ok(0));
+        src.send(0).unwrap();
+        src.send(1).unwrap();
+        assert_eq!(sink.try_recv_slice(&mut buf[..1]), Ok(1));
+        assert_eq!(buf[0], 0);
+        // wraps around the end of the buffer.
+        for i in 2..cap + 1 {
+            src.send(i).unwrap();
+        }
+        assert_eq!(sink.try_recv_slice(&mut buf), Ok(cap as usize));
+        assert_eq!(buf[..cap as usize], (1..cap + 1).collect::<Vec<_>>()[..]);
+        src.send(7).unwrap();
+        drop(src);
+        assert_eq!(sink.try_recv_slice(&mut buf), Ok(1));
+        assert_eq!(buf[0], 7);
+        assert_eq!(sink.try_recv_slice(&mut buf), Err(RecvError {}));
+    }
+}
+
+#[test]
+fn mt_read_chunk() {
+    const ITEMS: u32 = 100_000;
+    let (src, mut sink) = channel_exact::<u32>(7);
+    std::thread::spawn(move || {
+        for i in 0..ITEMS {
+            src.send(i).unwrap();
+        }
+    });
+    let mut expected = 0;
+    while expected < ITEMS {
+        let Ok(chunk) = sink.read_chunk(5) else {
+            continue;
+        };
+        let (first, second) = chunk.as_slices();
+        for &item in first.iter().chain(second) {
+            assert_eq!(item, expected);
+            expected += 1;
+        }
+        chunk.commit_all();
+    }
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+#[test]
+fn mt_write_chunk_uninit() {
+    const ITEMS: u32 = 100_000;
+    let (mut src, sink) = channel_exact::<u32>(7);
+    std::thread::spawn(move || {
+        let mut next = 0;
+        while next < ITEMS {
+            let Ok(mut chunk) = src.write_chunk_uninit(5) else {
+                continue;
+            };
+            let len = chunk.len().min((ITEMS - next) as usize);
+            let (first, second) = chunk.as_mut_slices();
+            for slot in first.iter_mut().chain(second).take(len) {
+                slot.write(next);
+                next += 1;
+            }
+            unsafe { chunk.commit(len) };
+        }
+    });
+    for i in 0..ITEMS {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+#[test]
+fn st_poisoning() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let (mut src, sink) = Builder::new(4).poisoning().build::<u32>();
+    src.send(1).unwrap();
+    // a panic while holding a reservation poisons the channel, even if the Sender survives.
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let _slot = src.reserve().unwrap();
+        panic!("poisoned");
+    }));
+    assert!(res.is_err());
+    assert!(src.is_poisoned() && sink.is_poisoned());
+    assert_eq!(src.try_send(2), Err(TrySendError::Poisoned(2)));
+    assert_eq!(
+        src.send_timeout(2, Duration::ZERO),
+        Err(SendTimeoutError::Poisoned(2))
+    );
+    assert_eq!(sink.try_recv(), Ok(1));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Poisoned));
+    assert_eq!(sink.recv_timeout(Duration::ZERO), Err(RecvTimeoutError::Poisoned));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+
+    // without poisoning, the same panic only gives up the reservation.
+    let (mut src, sink) = channel::<u32>(4);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        let _slot = src.reserve().unwrap();
+        panic!("not poisoned");
+    }))
+    .is_err());
+    assert!(!src.is_poisoned());
+    assert_eq!(src.try_send(1), Ok(()));
+    assert_eq!(sink.try_recv(), Ok(1));
+
+    // the slot of a value whose drop panics is still freed.
+    struct PanicOnDrop(bool);
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            if self.0 {
+                panic!("drop");
+            }
+        }
+    }
+    let (src, mut sink) = Builder::new(1).poisoning().build::<PanicOnDrop>();
+    src.send(PanicOnDrop(true)).unwrap();
+    assert!(catch_unwind(AssertUnwindSafe(|| drop(sink.try_recv_ref()))).is_err());
+    assert!(!src.is_poisoned());
+    assert!(src.try_send(PanicOnDrop(false)).is_ok());
+}
+
+#[test]
+fn mt_poisoning() {
+    let (src, mut sink) = Builder::new(4).poisoning().build::<u32>();
+    let handle = std::thread::spawn(move || {
+        let _recv = sink.recv_ref().unwrap();
+        panic!("poisoned");
+    });
+    src.send(1).unwrap();
+    assert!(handle.join().is_err());
+    // the Receiver was dropped while panicking, so the Sender only sees the poison.
+    assert_eq!(src.try_send(2), Err(TrySendError::Poisoned(2)));
+    assert_eq!(src.send(3), Err(SendError(3)));
+
+    let (src, sink) = Builder::new(4).poisoning().build::<u32>();
+    let handle = std::thread::spawn(move || sink.recv_timeout(Duration::from_secs(10)));
+    std::thread::spawn(move || {
+        let _src = src;
+        panic!("poisoned");
+    });
+    assert_eq!(handle.join().unwrap(), Err(RecvTimeoutError::Poisoned));
+}
+
+#[test]
+fn st_recv_cancellable() {
+    let (src, sink) = channel::<u32>(2);
+    let token = CancellationToken::new();
+    src.send(1).unwrap();
+    assert_eq!(sink.recv_cancellable(&token), Ok(1));
+    token.cancel();
+    // pending values are left for the other methods.
+    src.send(2).unwrap();
+    assert_eq!(sink.recv_cancellable(&token), Err(RecvCancelError::Cancelled));
+    assert_eq!(sink.try_recv(), Ok(2));
+    drop(src);
+    assert_eq!(
+        sink.recv_cancellable(&CancellationToken::new()),
+        Err(RecvCancelError::Disconnected)
+    );
+}
+
+#[test]
+fn mt_recv_cancellable() {
+    let (src, sink) = channel::<u32>(4);
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    let handle = std::thread::spawn(move || {
+        let mut received = 0;
+        while sink.recv_cancellable(&token).is_ok() {
+            received += 1;
+        }
+        received
+    });
+    for i in 0..1000 {
+        src.send(i).unwrap();
+    }
+    src.wait_empty().unwrap();
+    // the Sender is still connected, so only the token unblocks the Receiver.
+    cancel.cancel();
+    assert_eq!(handle.join().unwrap(), 1000);
+}
+
+#[test]
+fn st_push_front() {
+    let (src, mut sink) = channel_exact::<u32>(2);
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    assert_eq!(sink.push_front(0), Ok(()));
+    assert_eq!(sink.push_front(9), Err(TrySendError::Full(9)));
+    // the value doesn't take up a slot.
+    assert!(src.is_full());
+    assert_eq!(sink.len_hint(), 3);
+    assert_eq!(*sink.try_recv_ref().unwrap(), 0);
+    sink.push_front(0).unwrap();
+    let chunk = sink.read_chunk(usize::MAX).unwrap();
+    assert_eq!(chunk.as_slices(), (&[0][..], &[][..]));
+    chunk.commit_all();
+    assert_eq!(sink.try_recv(), Ok(1));
+    sink.push_front(1).unwrap();
+    let mut buf = [0; 3];
+    assert_eq!(sink.try_recv_slice(&mut buf), Ok(2));
+    assert_eq!(buf[..2], [1, 2]);
+    sink.push_front(3).unwrap();
+    drop(src);
+    assert!(sink.is_ready());
+    assert_eq!(sink.recv(), Ok(3));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+    // a value that's never received again is dropped with the Receiver.
+    let (src, sink) = channel::<std::rc::Rc<()>>(1);
+    let value = std::rc::Rc::new(());
+    sink.push_front(value.clone()).unwrap();
+    drop((src, sink));
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn st_push_front_guards() {
+    use std::rc::Rc;
+    let (src, mut sink) = channel::<Rc<u32>>(2);
+    let values: Vec<_> = (0..3).map(Rc::new).collect();
+    src.send(values[1].clone()).unwrap();
+    src.send(values[2].clone()).unwrap();
+    sink.push_front(values[0].clone()).unwrap();
+    // the guard only refers to the value put back, and drops it once.
+    let value = sink.recv_ref().unwrap();
+    assert_eq!(**value, 0);
+    drop(value);
+    assert_eq!(Rc::strong_count(&values[0]), 1);
+    // a chunk of it that isn't committed leaves it in place.
+    sink.push_front(values[0].clone()).unwrap();
+    drop(sink.read_chunk(2).unwrap());
+    assert_eq!(sink.push_front(values[0].clone()), Err(TrySendError::Full(values[0].clone())));
+    sink.read_chunk(2).unwrap().commit_all();
+    assert_eq!(Rc::strong_count(&values[0]), 1);
+    // then the buffer is read again, behind another value put back.
+    let value = sink.try_recv_ref().unwrap();
+    assert_eq!(**value, 1);
+    drop(value);
+    sink.push_front(values[1].clone()).unwrap();
+    assert_eq!(sink.try_recv(), Ok(values[1].clone()));
+    assert_eq!(sink.try_recv(), Ok(values[2].clone()));
+    drop((src, sink));
+    assert!(values.iter().all(|value| Rc::strong_count(value) == 1));
+}
+
+#[test]
+fn mt_push_front() {
+    let (src, sink) = channel::<u32>(4);
+    std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i).unwrap();
+        }
+    });
+    let mut expected = 0;
+    let mut put_back = false;
+    while let Ok(value) = sink.recv() {
+        assert_eq!(value, expected);
+        // every third value is put back once before it's accepted.
+        put_back = value % 3 == 0 && !put_back;
+        match put_back {
+            true => sink.push_front(value).unwrap(),
+            false => expected += 1,
+        }
+    }
+    assert_eq!(expected, 1000);
+}
+
+#[test]
+fn mt_slices() {
+    let (src, sink) = channel_exact::<u32>(7);
+    std::thread::spawn(move || {
+        let items: Vec<u32> = (0..10_000).collect();
+        let mut sent = 0;
+        while sent != items.len() {
+            match src.try_send_slice(&items[sent..]).unwrap() {
+                0 => std::thread::yield_now(),
+                n => sent += n,
+            }
+        }
+    });
+    let mut received = Vec::new();
+    let mut buf = [0; 5];
+    while let Ok(n) = sink.try_recv_slice(&mut buf) {
+        if n == 0 {
+            std::thread::yield_now();
+        }
+        received.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(received, (0..10_000).collect::<Vec<_>>());
+}
+
 }
 
 cfg_loom! {
@@ -68,7 +2158,7 @@ fn mt_sender_disconnect() {
             match sink.try_recv() {
                 Ok(_) => panic!("No data was sent, but some was received."),
                 Err(TryRecvError::Empty) => thread::yield_now(),
-                Err(TryRecvError::Disconnected) => break,
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => break,
             }
         }
     });
@@ -83,12 +2173,33 @@ fn mt_receiver_disconnect() {
             match src.try_send(0) {
                 Ok(_) => thread::yield_now(),
                 Err(TrySendError::Full(_)) => thread::yield_now(),
-                Err(TrySendError::Disconnected(_)) => break,
+                Err(TrySendError::Disconnected(_) | TrySendError::Poisoned(_)) => break,
             }
         }
     });
 }
 
+#[test]
+fn drain_to_vec() {
+    let mut model = loom::model::Builder::new();
+    model.max_threads = 2;
+    model.preemption_bound = Some(4);
+    model.check(|| {
+        let (src, sink) = channel::<u8>(2);
+        thread::spawn(move || {
+            for i in 0..3 {
+                src.send(i).unwrap();
+            }
+        });
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            received.extend(sink.drain_to_vec());
+            thread::yield_now();
+        }
+        assert_eq!(received, [0, 1, 2]);
+    });
+}
+
 const CHANNEL_SIZE: u8 = 2;
 
 #[test]
@@ -150,7 +2261,9 @@ fn try_insert(src: Sender<u8>) {
                 match src.try_send(i) {
                     Ok(()) => break,
                     Err(TrySendError::Full(_)) => thread::yield_now(),
-                    Err(TrySendError::Disconnected(_)) => panic!("Receiver dropped early."),
+                    Err(TrySendError::Disconnected(_) | TrySendError::Poisoned(_)) => {
+                        panic!("Receiver dropped early.")
+                    }
                 }
             }
         }
@@ -168,7 +2281,7 @@ fn try_remove(sink: Receiver<u8>) {
                     );
                 }
                 Err(TryRecvError::Empty) => thread::yield_now(),
-                Err(TryRecvError::Disconnected) => {
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
                     panic!("Sender dropped before sending all data.")
                 }
             }
@@ -184,6 +2297,45 @@ fn block_insert(src: Sender<u8>) {
     });
 }
 
+#[test]
+fn rendezvous() {
+    loom::model(|| {
+        let (src, sink) = channel_rendezvous::<u8>();
+        let handle = thread::spawn(move || sink.recv());
+        match src.send(1) {
+            Ok(()) => assert_eq!(handle.join().unwrap(), Ok(1)),
+            Err(_) => unreachable!("the receiver can't disconnect early"),
+        }
+    });
+}
+
+#[test]
+fn force_send_try_recv() {
+    let mut model = loom::model::Builder::new();
+    model.max_threads = 2;
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (src, sink) = channel_overwriting::<u8>(1);
+        thread::spawn(move || {
+            for i in 0..=CHANNEL_SIZE {
+                src.force_send(i).unwrap();
+            }
+        });
+        let mut last = None;
+        loop {
+            match sink.try_recv() {
+                Ok(i) => {
+                    assert!(last < Some(i), "values should stay in order");
+                    last = Some(i);
+                }
+                Err(TryRecvError::Empty) => thread::yield_now(),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => break,
+            }
+        }
+        assert_eq!(last, Some(CHANNEL_SIZE));
+    });
+}
+
 fn block_remove(sink: Receiver<u8>) {
     for i in 0..=CHANNEL_SIZE {
         assert_eq!(
@@ -194,4 +2346,135 @@ fn block_remove(sink: Receiver<u8>) {
         );
     }
 }
+
+#[test]
+fn try_send_slice() {
+    model(|| {
+        let (src, sink) = channel::<i32>(2);
+        thread::spawn(move || {
+            let items = [1, 2, 3];
+            let mut sent = 0;
+            while sent != items.len() {
+                match src.try_send_slice(&items[sent..]).unwrap() {
+                    0 => thread::yield_now(),
+                    n => sent += n,
+                }
+            }
+        });
+        for i in 1..=3 {
+            assert_eq!(sink.recv(), Ok(i));
+        }
+    });
+}
+
+#[test]
+fn send_all() {
+    let mut model = loom::model::Builder::new();
+    model.max_threads = 2;
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (src, sink) = channel::<i32>(2);
+        thread::spawn(move || src.send_all(1..=3).unwrap());
+        for i in 1..=3 {
+            assert_eq!(sink.recv(), Ok(i));
+        }
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    });
+}
+
+#[test]
+fn wake_threshold() {
+    let mut model = loom::model::Builder::new();
+    model.max_threads = 2;
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (src, sink) = Builder::new(2).wake_threshold(2).build::<i32>();
+        thread::spawn(move || {
+            for i in 1..=3 {
+                src.send(i).unwrap();
+            }
+        });
+        for i in 1..=3 {
+            assert_eq!(sink.recv(), Ok(i));
+        }
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    });
+}
+
+#[test]
+fn disconnect_is_permanent() {
+    loom::model(|| {
+        let (src, sink) = channel::<u8>(2);
+        loom::thread::spawn(move || drop(sink));
+        let mut disconnected = false;
+        for i in 0..3 {
+            match src.try_send(i) {
+                Ok(()) => assert!(!disconnected, "sent after a disconnect"),
+                Err(TrySendError::Disconnected(item)) => {
+                    assert_eq!(item, i);
+                    disconnected = true;
+                }
+                Err(TrySendError::Full(_)) => {}
+                Err(TrySendError::Poisoned(_)) => unreachable!(),
+            }
+        }
+    });
+}
+
+#[test]
+fn wait_empty() {
+    loom::model(|| {
+        let (src, sink) = channel::<u8>(2);
+        src.send(1).unwrap();
+        src.send(2).unwrap();
+        let handle = loom::thread::spawn(move || {
+            assert_eq!(sink.recv(), Ok(1));
+            assert_eq!(sink.recv(), Ok(2));
+            sink
+        });
+        src.wait_empty().unwrap();
+        drop(handle.join().unwrap());
+        assert_eq!(src.wait_empty(), Ok(()));
+    });
+}
+
+#[test]
+fn is_ready() {
+    loom::model(|| {
+        let (src, sink) = channel_exact::<u8>(1);
+        let handle = loom::thread::spawn(move || {
+            if sink.is_ready() {
+                assert_eq!(sink.try_recv(), Ok(1));
+            }
+            sink
+        });
+        src.send(1).unwrap();
+        let sink = handle.join().unwrap();
+        assert_eq!(src.is_full(), sink.is_ready());
+    });
+}
+
+#[test]
+fn try_recv_slice() {
+    let mut model = loom::model::Builder::new();
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (src, sink) = channel::<i32>(2);
+        thread::spawn(move || {
+            for i in 1..=3 {
+                src.send(i).unwrap();
+            }
+        });
+        let mut received = Vec::new();
+        let mut buf = [0; 2];
+        while let Ok(n) = sink.try_recv_slice(&mut buf) {
+            match n {
+                0 => thread::yield_now(),
+                n => received.extend_from_slice(&buf[..n]),
+            }
+        }
+        assert_eq!(received, [1, 2, 3]);
+    });
+}
+
 }
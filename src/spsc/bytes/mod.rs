@@ -0,0 +1,183 @@
+use crate::error::{RecvError, SendError};
+use crate::spsc::bounded;
+use std::io::{self, ErrorKind, Read, Write};
+
+/// Creates a byte stream with a buffer of at least `min_capacity` bytes.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the stream.
+pub fn channel(min_capacity: usize) -> (Sender, Receiver) {
+    let (tx, rx) = bounded::channel(min_capacity);
+    (Sender { ring: tx }, Receiver { ring: rx })
+}
+
+/// The writing end of a byte stream [`channel`].
+///
+/// Bytes are written through the [`Write`] implementation,
+/// which blocks while the buffer is full, or with
+/// [`try_write`](Sender::try_write), which doesn't.
+pub struct Sender {
+    ring: bounded::Sender<u8>,
+}
+
+/// The reading end of a byte stream [`channel`].
+///
+/// Bytes are read through the [`Read`] implementation,
+/// which blocks while the buffer is empty, or with
+/// [`try_read`](Receiver::try_read), which doesn't.
+pub struct Receiver {
+    ring: bounded::Receiver<u8>,
+}
+
+impl Sender {
+    /// Writes as many bytes from `buf` as fit into the buffer without blocking.
+    ///
+    /// Returns an error of kind [`WouldBlock`](ErrorKind::WouldBlock) if the
+    /// buffer is full, or [`BrokenPipe`](ErrorKind::BrokenPipe) if the
+    /// [`Receiver`] is disconnected.
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        match self.ring.try_send_slice(buf) {
+            Ok(0) if !buf.is_empty() => Err(ErrorKind::WouldBlock.into()),
+            Ok(n) => Ok(n),
+            Err(SendError(())) => Err(ErrorKind::BrokenPipe.into()),
+        }
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.ring.receiver_connected()
+    }
+
+    /// Returns the underlying [`bounded::Sender`].
+    pub fn into_inner(self) -> bounded::Sender<u8> {
+        self.ring
+    }
+}
+
+impl bounded::Sender<u8> {
+    /// Converts this [`Sender`](bounded::Sender) into a
+    /// [`Write`] adapter that sends bytes in bulk.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::bounded;
+    /// use std::io::{Read, Write};
+    ///
+    /// let (src, sink) = bounded::channel::<u8>(16);
+    /// let (mut writer, mut reader) = (src.into_writer(), sink.into_reader());
+    /// writer.write_all(b"piped").unwrap();
+    /// drop(writer);
+    ///
+    /// let mut text = String::new();
+    /// reader.read_to_string(&mut text).unwrap();
+    /// assert_eq!(text, "piped");
+    /// ```
+    pub fn into_writer(self) -> Sender {
+        Sender { ring: self }
+    }
+}
+
+impl Write for Sender {
+    /// Writes as many bytes from `buf` as fit into the buffer, blocking
+    /// until at least one byte fits.
+    ///
+    /// Returns an error of kind [`BrokenPipe`](ErrorKind::BrokenPipe)
+    /// if the [`Receiver`] is disconnected.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.ring.send_slice(buf) {
+            Ok(n) => Ok(n),
+            Err(SendError(())) => Err(ErrorKind::BrokenPipe.into()),
+        }
+    }
+
+    /// Does nothing, since written bytes are immediately visible to the [`Receiver`].
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Receiver {
+    /// Reads as many bytes into `buf` as are available without blocking.
+    ///
+    /// Returns an error of kind [`WouldBlock`](ErrorKind::WouldBlock) if the
+    /// buffer is empty. Returns `Ok(0)` once the [`Sender`] disconnected
+    /// and all written bytes were read.
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.ring.try_recv_slice(buf) {
+            Ok(0) if !buf.is_empty() => Err(ErrorKind::WouldBlock.into()),
+            Ok(n) => Ok(n),
+            Err(RecvError {}) => Ok(0),
+        }
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// Reads only return `Ok(0)` after all written bytes were read, even
+    /// if the [`Sender`] isn't connected. This method doesn't take pending
+    /// bytes into account and can be used to avoid this behaviour.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.ring.sender_connected()
+    }
+
+    /// Returns the underlying [`bounded::Receiver`].
+    pub fn into_inner(self) -> bounded::Receiver<u8> {
+        self.ring
+    }
+}
+
+impl bounded::Receiver<u8> {
+    /// Converts this [`Receiver`](bounded::Receiver) into a
+    /// [`Read`] adapter that receives bytes in bulk.
+    ///
+    /// See [`Sender::into_writer`](bounded::Sender::into_writer) for an example.
+    pub fn into_reader(self) -> Receiver {
+        Receiver { ring: self }
+    }
+}
+
+impl Read for Receiver {
+    /// Reads as many bytes into `buf` as are available, blocking
+    /// until at least one byte is available.
+    ///
+    /// Returns `Ok(0)` once the [`Sender`] disconnected
+    /// and all written bytes were read.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.ring.recv_slice(buf) {
+            Ok(n) => Ok(n),
+            Err(RecvError {}) => Ok(0),
+        }
+    }
+}
+
+impl From<bounded::Sender<u8>> for Sender {
+    fn from(ring: bounded::Sender<u8>) -> Self {
+        ring.into_writer()
+    }
+}
+
+impl From<bounded::Receiver<u8>> for Receiver {
+    fn from(ring: bounded::Receiver<u8>) -> Self {
+        ring.into_reader()
+    }
+}
+
+impl std::fmt::Debug for Sender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "spsc::bytes::Sender {{ channel: {:#x} }}", self.ring.channel_id())
+    }
+}
+
+impl std::fmt::Debug for Receiver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "spsc::bytes::Receiver {{ channel: {:#x} }}", self.ring.channel_id())
+    }
+}
+
+#[cfg(test)]
+mod tests;
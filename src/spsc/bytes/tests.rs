@@ -0,0 +1,80 @@
+use super::*;
+
+cfg_not_loom! {
+
+#[test]
+fn st_try_write_read() {
+    let (src, sink) = channel(4);
+    let mut buf = [0; 8];
+    assert_eq!(sink.try_read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(src.try_write(b"Hello").unwrap(), 4);
+    assert_eq!(src.try_write(b"o").unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(sink.try_read(&mut buf[..3]).unwrap(), 3);
+    assert_eq!(src.try_write(b"o").unwrap(), 1);
+    assert_eq!(sink.try_read(&mut buf[3..]).unwrap(), 2);
+    assert_eq!(&buf[..5], b"Hello");
+    drop(src);
+    assert_eq!(sink.try_read(&mut buf).unwrap(), 0);
+
+    let (src, sink) = channel(4);
+    drop(sink);
+    assert_eq!(src.try_write(b"!").unwrap_err().kind(), ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn mt_copy() {
+    let data: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+    let (mut src, mut sink) = channel(64);
+    let expected = data.clone();
+    let handle = std::thread::spawn(move || src.write_all(&data).unwrap());
+    let mut received = Vec::new();
+    sink.read_to_end(&mut received).unwrap();
+    handle.join().unwrap();
+    assert_eq!(received, expected);
+}
+
+#[test]
+fn mt_adapters() {
+    let (src, sink) = bounded::channel_exact::<u8>(5);
+    let (mut writer, mut reader) = (src.into_writer(), sink.into_reader());
+    let handle = std::thread::spawn(move || {
+        for _ in 0..100 {
+            writer.write_all(b"Hello, World!").unwrap();
+        }
+    });
+    let mut received = Vec::new();
+    reader.read_to_end(&mut received).unwrap();
+    handle.join().unwrap();
+    assert_eq!(received, b"Hello, World!".repeat(100));
+
+    let (src, sink) = bounded::channel::<u8>(4);
+    let (writer, reader) = (Sender::from(src), Receiver::from(sink));
+    assert!(writer.into_inner().same_channel(&reader.into_inner()));
+}
+
+#[test]
+fn receiver_disconnect() {
+    let (mut src, sink) = channel(4);
+    let handle = std::thread::spawn(move || src.write_all(&[0; 64]).unwrap_err().kind());
+    drop(sink);
+    assert_eq!(handle.join().unwrap(), ErrorKind::BrokenPipe);
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn copy() {
+    let mut model = loom::model::Builder::new();
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (mut src, mut sink) = channel(2);
+        loom::thread::spawn(move || src.write_all(b"abc").unwrap());
+        let mut received = Vec::new();
+        sink.read_to_end(&mut received).unwrap();
+        assert_eq!(received, b"abc");
+    });
+}
+
+}
@@ -0,0 +1,157 @@
+use crate::error::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+use crate::sync::atomic::AtomicUsize;
+use crate::sync::atomic::Ordering::Acquire;
+use crate::sync::{Mutex, MutexGuard};
+use crate::util::park::Parker;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::time::Instant;
+
+pub(super) struct Inner<K, V> {
+    pending: Mutex<Pending<K, V>>,
+    park: Parker,
+    /*
+    starts off as 0, incremented when entering Sender/Receiver drop.
+    match 'previous value' {
+        0 => {
+            Now the channel is disconnected. We try to wake the other end point.
+            If the other end point was asleep, it will detect the disconnect and unblock.
+            Then, we increment 'drop_count' again and repeat this decision tree with the
+            new 'previous value'.
+        }
+        1 => just fall off drop.
+        2 => deallocate the inner state.
+    }
+    */
+    pub(super) drop_count: AtomicUsize,
+}
+
+/// The undelivered messages, at most one per key.
+struct Pending<K, V> {
+    /// The keys in the order their oldest undelivered message was sent.
+    order: VecDeque<K>,
+    values: HashMap<K, V>,
+}
+
+impl<K: Hash + Eq + Clone, V> Inner<K, V> {
+    pub(super) fn new() -> Self {
+        Self {
+            pending: Mutex::new(Pending {
+                order: VecDeque::new(),
+                values: HashMap::new(),
+            }),
+            park: Parker::new(),
+            drop_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Queues `value` under `key`, or merges it into the undelivered
+    /// value of `key` with `merge`.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn send(
+        &self,
+        key: K,
+        value: V,
+        merge: &dyn Fn(&mut V, V),
+    ) -> Result<(), SendError<(K, V)>> {
+        if self.drop_count.load(Acquire) != 0 {
+            return Err(SendError((key, value)));
+        }
+        {
+            let mut pending = self.lock();
+            match pending.values.get_mut(&key) {
+                Some(old) => merge(old, value),
+                None => {
+                    pending.order.push_back(key.clone());
+                    pending.values.insert(key, value);
+                }
+            }
+        }
+        self.wake_receiver();
+        Ok(())
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn try_recv(&self) -> Result<(K, V), TryRecvError> {
+        if let Some(message) = self.pop() {
+            return Ok(message);
+        }
+        if self.drop_count.load(Acquire) == 0 {
+            return Err(TryRecvError::Empty);
+        }
+        // the sender might have sent before disconnecting.
+        self.pop().ok_or(TryRecvError::Disconnected)
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn recv(&self) -> Result<(K, V), RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(message) => return Ok(message),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                Err(TryRecvError::Empty) => unsafe { self.park.park() },
+            }
+        }
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn recv_deadline(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<(K, V), RecvTimeoutError> {
+        let deadline = match deadline {
+            Some(deadline) => deadline,
+            None => return self.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        loop {
+            match self.try_recv() {
+                Ok(message) => return Ok(message),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Poisoned) => return Err(RecvTimeoutError::Poisoned),
+                Err(TryRecvError::Empty) => {
+                    if Instant::now() >= deadline {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                    //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                    unsafe { self.park.park_deadline(deadline) };
+                }
+            }
+        }
+    }
+
+    /// Returns how many keys have an undelivered message.
+    pub(super) fn pending(&self) -> usize {
+        self.lock().order.len()
+    }
+
+    fn pop(&self) -> Option<(K, V)> {
+        let mut pending = self.lock();
+        let key = pending.order.pop_front()?;
+        let value = pending
+            .values
+            .remove(&key)
+            .expect("every queued key has a value");
+        Some((key, value))
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Pending<K, V>> {
+        //only a panicking merge can poison it, which leaves the queue consistent.
+        self.pending.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<K, V> Inner<K, V> {
+    pub(super) fn peer_connected(&self) -> bool {
+        self.drop_count.load(Acquire) == 0
+    }
+
+    #[inline]
+    pub(super) fn wake_receiver(&self) {
+        self.park.unpark();
+    }
+}
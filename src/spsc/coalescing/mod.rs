@@ -0,0 +1,227 @@
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::hash::Hash;
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+pub use crate::error::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+
+mod inner;
+use inner::Inner;
+
+/// Creates a coalescing channel, where a message replaces the undelivered
+/// message with the same key.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+pub fn channel<K: Hash + Eq + Clone, V>() -> (Sender<K, V>, Receiver<K, V>) {
+    channel_with_merge(|old, new| *old = new)
+}
+
+/// Creates a coalescing channel, where a message is merged into the
+/// undelivered message with the same key by calling `merge(old, new)`.
+///
+/// `merge` is called by the [`Sender`] while the [`Receiver`] can't take
+/// messages out, so it should be quick.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::coalescing;
+///
+/// // sums up the deltas of every counter until they're received.
+/// let (src, sink) = coalescing::channel_with_merge(|old: &mut i64, new| *old += new);
+/// src.send("likes", 1).unwrap();
+/// src.send("views", 10).unwrap();
+/// src.send("likes", 2).unwrap();
+///
+/// assert_eq!(sink.try_recv(), Ok(("likes", 3)));
+/// assert_eq!(sink.try_recv(), Ok(("views", 10)));
+/// ```
+pub fn channel_with_merge<K: Hash + Eq + Clone, V>(
+    merge: impl Fn(&mut V, V) + Send + 'static,
+) -> (Sender<K, V>, Receiver<K, V>) {
+    /*SAFETY: freed by the last end point's Drop.*/
+    let inner = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Inner::new()))) };
+    (
+        Sender {
+            inner,
+            merge: Box::new(merge),
+            _unsync: PhantomUnsync {},
+        },
+        Receiver {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+    )
+}
+
+/// Merges a new value into the undelivered value with the same key.
+type Merge<V> = dyn Fn(&mut V, V) + Send;
+
+/// The sending endpoint of a coalescing [`channel`].
+///
+/// Messages are sent with [`send`](Sender::send), which never blocks.
+///
+/// # Disconnection
+///
+/// When a [`Sender`] is dropped, the [`Receiver`] can still
+/// receive the undelivered messages before seeing the disconnect.
+pub struct Sender<K, V> {
+    inner: NonNull<Inner<K, V>>,
+    merge: Box<Merge<V>>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a coalescing [`channel`].
+///
+/// Messages can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<K, V> {
+    inner: NonNull<Inner<K, V>>,
+    _unsync: PhantomUnsync,
+}
+
+impl<K: Hash + Eq + Clone, V> Sender<K, V> {
+    /// Sends `value` under `key` through this [`channel`].
+    ///
+    /// If a message with the same key wasn't received yet, `value` is
+    /// merged into it instead, and the message keeps its place in the queue.
+    ///
+    /// Never blocks. Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    #[inline]
+    pub fn send(&self, key: K, value: V) -> Result<(), SendError<(K, V)>> {
+        self.inner_ref().send(key, value, &*self.merge)
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+}
+
+impl<K, V> Sender<K, V> {
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<K, V> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Receiver<K, V> {
+    /// Tries to receive the oldest undelivered message.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after receiving all
+    /// undelivered messages. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    #[inline]
+    pub fn try_recv(&self) -> Result<(K, V), TryRecvError> {
+        self.inner_ref().try_recv()
+    }
+
+    /// Waits for a message and receives it.
+    ///
+    /// Returns a [`RecvError`] once the [`Sender`] is disconnected
+    /// and all undelivered messages were received.
+    #[inline]
+    pub fn recv(&self) -> Result<(K, V), RecvError> {
+        self.inner_ref().recv()
+    }
+
+    /// Like [`recv`](Receiver::recv), but waits at most `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<(K, V), RecvTimeoutError> {
+        self.inner_ref()
+            .recv_deadline(Instant::now().checked_add(timeout))
+    }
+
+    /// Returns how many keys have an undelivered message.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.inner_ref().pending()
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// This doesn't take the undelivered messages into account.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+}
+
+impl<K, V> Receiver<K, V> {
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<K, V> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<K, V> Drop for Sender<K, V> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_receiver(),
+                1 => break,
+                //SAFETY: the Receiver is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<K, V> Drop for Receiver<K, V> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => { /*the Sender never blocks*/ }
+                1 => break,
+                //SAFETY: the Sender is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<K, V> std::fmt::Debug for Sender<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::coalescing::Sender<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<K>(),
+            std::any::type_name::<V>(),
+            self.inner
+        )
+    }
+}
+
+impl<K, V> std::fmt::Debug for Receiver<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::coalescing::Receiver<{}, {}> {{ channel: {:p} }}",
+            std::any::type_name::<K>(),
+            std::any::type_name::<V>(),
+            self.inner
+        )
+    }
+}
+
+unsafe impl<K: Send, V: Send> Send for Sender<K, V> {}
+unsafe impl<K: Send, V: Send> Send for Receiver<K, V> {}
+
+#[cfg(test)]
+mod tests;
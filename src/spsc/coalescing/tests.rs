@@ -0,0 +1,64 @@
+cfg_not_loom! {
+use super::*;
+
+#[test]
+fn st_coalesce() {
+    let (src, sink) = channel();
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    src.send('a', 1).unwrap();
+    src.send('b', 2).unwrap();
+    src.send('a', 3).unwrap();
+    assert_eq!(sink.pending(), 2);
+    // the update keeps the place of the message it replaced.
+    assert_eq!(sink.try_recv(), Ok(('a', 3)));
+    src.send('a', 4).unwrap();
+    assert_eq!(sink.recv(), Ok(('b', 2)));
+    assert_eq!(sink.recv(), Ok(('a', 4)));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn st_merge() {
+    let (src, sink) = channel_with_merge(|old: &mut Vec<u32>, new| old.extend(new));
+    src.send(0, vec![1]).unwrap();
+    src.send(0, vec![2, 3]).unwrap();
+    src.send(1, vec![4]).unwrap();
+    assert_eq!(sink.recv(), Ok((0, vec![1, 2, 3])));
+    assert_eq!(sink.recv(), Ok((1, vec![4])));
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, sink) = channel();
+    src.send(1, "one").unwrap();
+    drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(sink.try_recv(), Ok((1, "one")));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(
+        sink.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+
+    let (src, sink) = channel();
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.send(2, "two"), Err(SendError((2, "two"))));
+}
+
+#[test]
+fn mt_coalesce() {
+    let (src, sink) = channel_with_merge(|old: &mut u64, new| *old += new);
+    std::thread::spawn(move || {
+        for i in 0..10_000 {
+            src.send(i % 8, 1).unwrap();
+        }
+    });
+    let mut totals = [0; 8];
+    while let Ok((key, count)) = sink.recv() {
+        totals[key as usize] += count;
+    }
+    assert_eq!(totals, [1250; 8]);
+}
+
+}
@@ -0,0 +1,302 @@
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::spsc::bounded;
+use std::cell::{Cell, UnsafeCell};
+use std::sync::{Arc, Weak};
+
+/// Creates a growable SPSC channel with storage for `min_capacity` elements,
+/// which grows up to `max_capacity` elements.
+///
+/// A `min_capacity` of 0 is treated as 1.
+///
+/// # Panics
+///
+/// The function panics if `max_capacity` is less than `min_capacity`
+/// or it can't allocate the memory needed for the channel.
+pub fn channel<T>(min_capacity: usize, max_capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = min_capacity.max(1);
+    assert!(
+        capacity <= max_capacity,
+        "max_capacity is less than min_capacity"
+    );
+    let (tx, rx) = ring(capacity, max_capacity);
+    let alive = Arc::new(());
+    (
+        Sender {
+            ring: UnsafeCell::new(tx),
+            capacity: Cell::new(capacity),
+            max_capacity,
+            alive: alive.clone(),
+        },
+        Receiver {
+            ring: UnsafeCell::new(rx),
+            sender: Arc::downgrade(&alive),
+        },
+    )
+}
+
+/// A value or the ring that replaces the one it's sent on.
+enum Msg<T> {
+    Value(T),
+    Grow(bounded::Receiver<Msg<T>>),
+}
+
+impl<T> Msg<T> {
+    fn into_value(self) -> T {
+        match self {
+            Msg::Value(item) => item,
+            Msg::Grow(_) => unreachable!("only values are returned to the sender"),
+        }
+    }
+}
+
+/// Creates a ring for `capacity` values.
+///
+/// Rings that can still grow have an extra slot, which is
+/// kept free for handing over the next ring.
+fn ring<T>(
+    capacity: usize,
+    max_capacity: usize,
+) -> (bounded::Sender<Msg<T>>, bounded::Receiver<Msg<T>>) {
+    match capacity < max_capacity {
+        true => bounded::channel_exact(capacity + 1),
+        false => bounded::channel_exact(capacity),
+    }
+}
+
+/// The sending endpoint of a [`channel`].
+///
+/// Data can be sent using the [`try_send`](Sender::try_send)
+/// and [`send`](Sender::send) methods.
+///
+/// # Growing
+///
+/// When a send finds the [`channel`] full, the [`Sender`] allocates a new
+/// ring with twice the capacity, up to the maximum, and sends the rest of
+/// the values through it. The [`Receiver`] switches to the new ring after
+/// taking every value from the old one, which is then deallocated.
+/// Only once the maximum capacity is reached do sends block or fail with
+/// [`TrySendError::Full`].
+///
+/// The old rings keep their values until they're received, so up to
+/// twice the maximum capacity values can be pending.
+pub struct Sender<T> {
+    /// The newest ring, only replaced by `grow_to`.
+    ring: UnsafeCell<bounded::Sender<Msg<T>>>,
+    /// How many values fit into `ring`.
+    capacity: Cell<usize>,
+    max_capacity: usize,
+    /// Lets the [`Receiver`] tell a disconnect apart from a ring that was replaced.
+    alive: Arc<()>,
+}
+
+/// The receiving endpoint of a [`channel`].
+///
+/// Data can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<T> {
+    /// The oldest ring, only replaced by `switch`.
+    ring: UnsafeCell<bounded::Receiver<Msg<T>>>,
+    sender: Weak<()>,
+}
+
+impl<T> Sender<T> {
+    /// Tries to send a value through this [`channel`].
+    ///
+    /// Grows the [`channel`] if it's full, so this only fails
+    /// with [`TrySendError::Full`] at the maximum capacity.
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        // the sender's estimate never overestimates the free slots.
+        if self.capacity.get() < self.max_capacity && self.ring().slots_free() <= 1 {
+            let capacity = self.capacity.get().saturating_mul(2).min(self.max_capacity);
+            if self.grow_to(capacity).is_err() {
+                return Err(TrySendError::Disconnected(item));
+            }
+        }
+        self.ring()
+            .try_send(Msg::Value(item))
+            .map_err(|err| err.map(Msg::into_value))
+    }
+
+    /// Sends a value through this [`channel`].
+    ///
+    /// Grows the [`channel`] if it's full, so this only blocks at the maximum
+    /// capacity. Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        match self.try_send(item) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(item)) => self
+                .ring()
+                .send(Msg::Value(item))
+                .map_err(|err| err.map(Msg::into_value)),
+            Err(TrySendError::Disconnected(item) | TrySendError::Poisoned(item)) => {
+                Err(SendError(item))
+            }
+        }
+    }
+
+    /// Grows the [`channel`] to hold at least `new_min_capacity` values,
+    /// but no more than the maximum capacity.
+    ///
+    /// Does nothing if the [`channel`] is already large enough. Returns
+    /// a [`SendError`] if the [`Receiver`] is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::growable;
+    ///
+    /// let (src, sink) = growable::channel::<u32>(2, 64);
+    /// src.send(1).unwrap();
+    /// src.grow(40).unwrap();
+    /// assert_eq!(src.capacity(), 40);
+    /// src.grow(100).unwrap();
+    /// assert_eq!(src.capacity(), 64);
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// ```
+    pub fn grow(&self, new_min_capacity: usize) -> Result<(), SendError<()>> {
+        let capacity = new_min_capacity.min(self.max_capacity);
+        match capacity > self.capacity.get() {
+            true => self.grow_to(capacity),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns how many values the [`channel`] can hold before it grows.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity.get()
+    }
+
+    /// Returns the capacity the [`channel`] can grow to.
+    #[inline]
+    pub fn max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        // a Receiver that didn't switch to the newest ring yet
+        // keeps its Receiver in the older rings alive.
+        self.ring().receiver_connected()
+    }
+
+    /// Replaces the ring with one for `capacity` values.
+    ///
+    /// `capacity` must be greater than the current capacity.
+    fn grow_to(&self, capacity: usize) -> Result<(), SendError<()>> {
+        debug_assert!(capacity > self.capacity.get());
+        let (tx, rx) = ring(capacity, self.max_capacity);
+        match self.ring().try_send(Msg::Grow(rx)) {
+            Ok(()) => {}
+            Err(TrySendError::Disconnected(_) | TrySendError::Poisoned(_)) => {
+                return Err(SendError(()))
+            }
+            Err(TrySendError::Full(_)) => unreachable!("the last slot is kept free for growing"),
+        }
+        /*SAFETY:
+         *Sender is !Sync and no reference from `ring` is alive. Dropping the
+         *old Sender disconnects the old ring after the Receiver takes `rx`.
+         */
+        unsafe { *self.ring.get() = tx };
+        self.capacity.set(capacity);
+        Ok(())
+    }
+
+    #[inline]
+    fn ring(&self) -> &bounded::Sender<Msg<T>> {
+        //SAFETY: only `grow_to` writes to `ring`, and never while it's borrowed.
+        unsafe { &*self.ring.get() }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to return a pending value.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after consuming all
+    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        loop {
+            match self.ring().try_recv()? {
+                Msg::Value(item) => return Ok(item),
+                Msg::Grow(next) => self.switch(next),
+            }
+        }
+    }
+
+    /// Reads a value from the [`channel`].
+    ///
+    /// If the [`channel`] is empty, blocks and waits for the [`Sender`].
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after consuming all sent data. To
+    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.ring().recv()? {
+                Msg::Value(item) => return Ok(item),
+                Msg::Grow(next) => self.switch(next),
+            }
+        }
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
+    /// methods return [`TryRecvError::Disconnected`] or [`RecvError`] only
+    /// after consuming all previously sent data, even if the [`Sender`] isn't
+    /// connected. This method doesn't take pending data into account and can
+    /// be used to avoid this behaviour.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        // the old rings are disconnected as soon as they're replaced.
+        self.sender.strong_count() != 0
+    }
+
+    /// Replaces the ring with `next`, which was the last message in it.
+    fn switch(&self, next: bounded::Receiver<Msg<T>>) {
+        /*SAFETY:
+         *Receiver is !Sync and no reference from `ring` is alive.
+         *The Sender doesn't send anything after `next`.
+         */
+        unsafe { *self.ring.get() = next };
+    }
+
+    #[inline]
+    fn ring(&self) -> &bounded::Receiver<Msg<T>> {
+        //SAFETY: only `switch` writes to `ring`, and never while it's borrowed.
+        unsafe { &*self.ring.get() }
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::growable::Sender<{}> {{ channel: {:p}, capacity: {}, max_capacity: {} }}",
+            std::any::type_name::<T>(),
+            Arc::as_ptr(&self.alive),
+            self.capacity.get(),
+            self.max_capacity
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::growable::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.sender.as_ptr()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests;
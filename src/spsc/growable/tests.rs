@@ -0,0 +1,87 @@
+cfg_not_loom! {
+
+use super::*;
+
+#[test]
+fn st_grows_when_full() {
+    let (src, sink) = channel::<u32>(2, 8);
+    // the old rings keep their values, so 2 + 4 + 8 values fit.
+    for i in 0..14 {
+        assert_eq!(src.try_send(i), Ok(()));
+    }
+    assert_eq!(src.capacity(), 8);
+    assert_eq!(src.try_send(14), Err(TrySendError::Full(14)));
+    for i in 0..14 {
+        assert_eq!(sink.try_recv(), Ok(i));
+    }
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(src.try_send(14), Ok(()));
+    assert_eq!(sink.try_recv(), Ok(14));
+}
+
+#[test]
+fn st_grow() {
+    let (src, sink) = channel::<u32>(0, 16);
+    assert_eq!(src.capacity(), 1);
+    src.send(0).unwrap();
+    src.grow(3).unwrap();
+    assert_eq!(src.capacity(), 3);
+    src.grow(2).unwrap();
+    assert_eq!(src.capacity(), 3);
+    src.send(1).unwrap();
+    src.grow(usize::MAX).unwrap();
+    assert_eq!(src.capacity(), src.max_capacity());
+    src.send(2).unwrap();
+    for i in 0..3 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+}
+
+#[test]
+#[should_panic(expected = "max_capacity is less than min_capacity")]
+fn max_less_than_min() {
+    let _ = channel::<u32>(8, 4);
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, sink) = channel::<u32>(1, 4);
+    for i in 0..4 {
+        assert_eq!(src.try_send(i), Ok(()));
+    }
+    drop(src);
+    // the old rings are disconnected, but the Receiver still gets every value.
+    assert!(!sink.sender_connected());
+    for i in 0..4 {
+        assert_eq!(sink.try_recv(), Ok(i));
+    }
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+
+    let (src, sink) = channel::<u32>(1, 4);
+    src.send(0).unwrap();
+    src.send(1).unwrap();
+    assert!(src.receiver_connected());
+    assert!(sink.sender_connected());
+    // the newest ring is only reachable through the pending values.
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.try_send(2), Err(TrySendError::Disconnected(2)));
+    assert_eq!(src.grow(4), Err(SendError(())));
+}
+
+#[test]
+fn mt_send_recv() {
+    let (src, sink) = channel::<u32>(1, 64);
+    std::thread::spawn(move || {
+        for i in 0..10_000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..10_000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    assert_eq!(sink.recv(), Err(RecvError {}));
+}
+
+}
@@ -98,3 +98,237 @@ pub mod bounded;
 /// ```
 #[cfg(any(doc, feature = "spsc-unbounded"))]
 pub mod unbounded;
+
+/// An unbounded lock-free Single Producer Single Consumer queue
+/// that stores values in blocks. Enabled by the `spsc-unbounded-chunked` feature.
+///
+/// Works like [`unbounded`], except that values are stored in blocks
+/// of several slots instead of one node per value. This makes for fewer
+/// allocations and better cache locality when sending many values.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::unbounded_chunked;
+/// use std::thread;
+///
+/// fn main() {
+///     let (src, sink) = unbounded_chunked::channel::<u32>();
+///
+///     thread::spawn(move || {
+///         for i in 0..1000 {
+///             src.send(i).unwrap();
+///         }
+///     });
+///     let mut sum = 0;
+///     while let Ok(i) = sink.recv() {
+///         sum += i;
+///     }
+///
+///     assert_eq!(sum, 499500);
+/// }
+/// ```
+#[cfg(any(doc, feature = "spsc-unbounded-chunked"))]
+pub mod unbounded_chunked;
+
+/// A bounded Single Producer Single Consumer queue with priority levels.
+/// Enabled by the `spsc-priority` feature.
+///
+/// Every priority level is a separate [`bounded`] ring, and the receiver
+/// always takes the pending value with the highest priority. Values with
+/// the same priority are received in the order they were sent.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::priority;
+///
+/// let (src, sink) = priority::channel::<&'static str>(4, 2);
+/// src.send("routine", 0).unwrap();
+/// src.send("urgent", 1).unwrap();
+///
+/// assert_eq!(sink.recv(), Ok("urgent"));
+/// assert_eq!(sink.recv(), Ok("routine"));
+/// ```
+#[cfg(any(doc, feature = "spsc-priority"))]
+pub mod priority;
+
+/// A Single Producer Single Consumer byte stream.
+/// Enabled by the `spsc-bytes` feature.
+///
+/// The stream implements [`Write`](std::io::Write) on the sender and
+/// [`Read`](std::io::Read) on the receiver over a [`bounded`] ring, and
+/// bytes are copied in and out of it in bulk instead of one by one.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bytes;
+/// use std::io::{Read, Write};
+/// use std::thread;
+///
+/// let (mut src, mut sink) = bytes::channel(64);
+/// thread::spawn(move || src.write_all(b"Hello, World!").unwrap());
+///
+/// let mut text = String::new();
+/// sink.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "Hello, World!");
+/// ```
+#[cfg(any(doc, feature = "spsc-bytes"))]
+pub mod bytes;
+
+/// A Single Producer Single Consumer channel for signals without a payload.
+/// Enabled by the `spsc-signal` feature.
+///
+/// Instead of storing values, the channel only counts the signals that
+/// weren't received yet, so sending is an atomic increment and a wake-up.
+/// This makes it a cheap notification primitive, or a semaphore where
+/// the [`Sender`](signal::Sender) releases permits.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::signal;
+/// use std::thread;
+///
+/// let (src, sink) = signal::channel();
+/// thread::spawn(move || {
+///     for _ in 0..10 {
+///         src.send().unwrap();
+///     }
+/// });
+///
+/// let mut received = 0;
+/// while let Ok(n) = sink.recv_all() {
+///     received += n;
+/// }
+/// assert_eq!(received, 10);
+/// ```
+#[cfg(any(doc, feature = "spsc-signal"))]
+pub mod signal;
+
+/// A Single Producer Single Consumer channel for whole buffers.
+/// Enabled by the `spsc-swap` feature.
+///
+/// The sender fills a [`Vec`] and [`publish`](swap::Sender::publish)es it,
+/// getting back the buffer the receiver finished with. Only two buffers
+/// are ever in use, so frame-based workloads like rendering or audio blocks
+/// hand off whole frames without allocating or queueing every value.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::swap;
+/// use std::thread;
+///
+/// let (src, mut sink) = swap::channel::<f32>(4);
+/// thread::spawn(move || {
+///     let block = vec![0.5; 4];
+///     let recycled = src.publish(block).unwrap();
+///     assert_eq!(recycled.capacity(), 4);
+/// });
+///
+/// let frame = sink.recv().unwrap();
+/// assert_eq!(*frame, [0.5; 4]);
+/// ```
+#[cfg(any(doc, feature = "spsc-swap"))]
+pub mod swap;
+
+/// A Single Producer Single Consumer channel that coalesces messages by key.
+/// Enabled by the `spsc-coalescing` feature.
+///
+/// Every message carries a key, and a message whose key already has an
+/// undelivered message replaces it, or is merged into it, instead of being
+/// queued. This keeps producers of state updates, like UIs or state sync,
+/// from flooding a slow consumer with updates it would skip anyway.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::coalescing;
+///
+/// let (src, sink) = coalescing::channel();
+/// src.send("cursor", (0, 0)).unwrap();
+/// src.send("scroll", (0, 120)).unwrap();
+/// src.send("cursor", (4, 2)).unwrap();
+///
+/// assert_eq!(sink.recv(), Ok(("cursor", (4, 2))));
+/// assert_eq!(sink.recv(), Ok(("scroll", (0, 120))));
+/// ```
+#[cfg(any(doc, feature = "spsc-coalescing"))]
+pub mod coalescing;
+
+/// A Single Producer Single Consumer channel for [`Arc`](std::sync::Arc)s.
+/// Enabled by the `spsc-arc` feature.
+///
+/// Instead of copying values in and out of [`MaybeUninit`](std::mem::MaybeUninit)
+/// slots, like [`bounded`] does, every slot is an [`AtomicPtr`](std::sync::atomic::AtomicPtr)
+/// that holds the [`Arc`](std::sync::Arc)'s pointer. The end points don't share
+/// any indices, since an empty slot is null, so sending large immutable
+/// payloads costs a pointer store and a reference count that's handed over as is.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::arc;
+/// use std::sync::Arc;
+///
+/// let (src, sink) = arc::channel(4);
+/// let frame = Arc::new([0u8; 4096]);
+/// src.send(frame.clone()).unwrap();
+///
+/// let received = sink.recv().unwrap();
+/// assert!(Arc::ptr_eq(&received, &frame));
+/// ```
+#[cfg(any(doc, feature = "spsc-arc"))]
+pub mod arc;
+
+/// A Single Producer Single Consumer queue that grows when it's full.
+/// Enabled by the `spsc-growable` feature.
+///
+/// Starts out as a small [`bounded`] ring, and instead of blocking when it's
+/// full, the sender moves on to a ring with twice the capacity, until the
+/// maximum capacity is reached. This saves guessing the worst case capacity
+/// up front, while still bounding memory use.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::growable;
+///
+/// let (src, sink) = growable::channel::<u32>(2, 1024);
+/// for i in 0..100 {
+///     src.try_send(i).unwrap();
+/// }
+/// assert_eq!(src.capacity(), 64);
+/// assert_eq!(sink.recv(), Ok(0));
+/// ```
+#[cfg(any(doc, feature = "spsc-growable"))]
+pub mod growable;
+
+/// A bidirectional Single Producer Single Consumer channel.
+/// Enabled by the `spsc-bidir` feature.
+///
+/// A [`pair`](bidir::pair) of [`Endpoint`](bidir::Endpoint)s that can both
+/// send and receive, made of two [`bounded`] rings in one allocation. This
+/// suits request/response workers, which would otherwise need two channels.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bidir;
+/// use std::thread;
+///
+/// let (client, worker) = bidir::pair::<u32, u32>(4, 4);
+/// thread::spawn(move || {
+///     while let Ok(n) = worker.recv() {
+///         worker.send(n * n).unwrap();
+///     }
+/// });
+/// client.send(3).unwrap();
+/// assert_eq!(client.recv(), Ok(9));
+/// ```
+#[cfg(all(any(doc, feature = "spsc-bidir"), not(feature = "loom")))]
+pub mod bidir {
+    pub use super::bounded::bidir::{pair, Endpoint};
+}
@@ -0,0 +1,191 @@
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::spsc::bounded;
+use crate::sync::Arc;
+use crate::util::park::Parker;
+
+/// Creates a priority SPSC channel with `levels` priority levels,
+/// each with storage for at least `min_capacity` elements.
+///
+/// Valid priorities are `0..levels`, and higher ones are received first.
+///
+/// # Panics
+///
+/// The function panics if `levels` is 0 or it can't
+/// allocate the memory needed for the channel.
+pub fn channel<T>(min_capacity: usize, levels: u8) -> (Sender<T>, Receiver<T>) {
+    assert!(levels != 0, "a priority channel needs at least one level");
+    let parker = Arc::new(Parker::new());
+    let (rings_tx, rings_rx) = (0..levels).map(|_| bounded::channel(min_capacity)).unzip();
+    (
+        Sender {
+            rings: rings_tx,
+            parker: parker.clone(),
+        },
+        Receiver {
+            rings: rings_rx,
+            parker,
+        },
+    )
+}
+
+/// The sending endpoint of a [`channel`].
+///
+/// Data can be sent using the [`try_send`](Sender::try_send)
+/// and [`send`](Sender::send) methods.
+pub struct Sender<T> {
+    /// One ring per priority level.
+    rings: Vec<bounded::Sender<T>>,
+    /// Woken after every send, so that the receiver can wait on all rings at once.
+    parker: Arc<Parker>,
+}
+
+/// The receiving endpoint of a [`channel`].
+///
+/// Data can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<T> {
+    rings: Vec<bounded::Receiver<T>>,
+    parker: Arc<Parker>,
+}
+
+impl<T> Sender<T> {
+    /// Tries to send a value with the given `priority`.
+    ///
+    /// Returns [`TrySendError::Full`] if the level of `priority` is full,
+    /// regardless of the other levels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `priority` isn't less than the number of levels.
+    pub fn try_send(&self, item: T, priority: u8) -> Result<(), TrySendError<T>> {
+        self.ring(priority).try_send(item)?;
+        self.parker.unpark();
+        Ok(())
+    }
+
+    /// Sends a value with the given `priority`.
+    ///
+    /// If the level of `priority` is full, blocks and waits for the [`Receiver`].
+    /// Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `priority` isn't less than the number of levels.
+    pub fn send(&self, item: T, priority: u8) -> Result<(), SendError<T>> {
+        self.ring(priority).send(item)?;
+        self.parker.unpark();
+        Ok(())
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.rings[0].receiver_connected()
+    }
+
+    #[inline]
+    fn ring(&self, priority: u8) -> &bounded::Sender<T> {
+        match self.rings.get(priority as usize) {
+            Some(ring) => ring,
+            None => panic!(
+                "priority {priority} is out of range for a channel with {} levels",
+                self.rings.len()
+            ),
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to return the pending value with the highest priority.
+    ///
+    /// Values with the same priority are received in the order they were sent.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after consuming all
+    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut disconnected = true;
+        for ring in self.rings.iter().rev() {
+            match ring.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Empty) => disconnected = false,
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {}
+            }
+        }
+        match disconnected {
+            true => Err(TryRecvError::Disconnected),
+            false => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Reads the pending value with the highest priority.
+    ///
+    /// If the [`channel`] is empty, blocks and waits for the [`Sender`].
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after consuming all sent data. To
+    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                //SAFETY: only the Receiver parks and it's !Sync.
+                Err(TryRecvError::Empty) => unsafe { self.parker.park() },
+            }
+        }
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
+    /// methods return [`TryRecvError::Disconnected`] or [`RecvError`] only
+    /// after consuming all previously sent data, even if the [`Sender`] isn't
+    /// connected. This method doesn't take pending data into account and can
+    /// be used to avoid this behaviour.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.rings.iter().all(|ring| ring.sender_connected())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        // disconnect all levels before waking the receiver.
+        self.rings.clear();
+        self.parker.unpark();
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::priority::Sender<{}> {{ channel: {:p}, levels: {} }}",
+            std::any::type_name::<T>(),
+            Arc::as_ptr(&self.parker),
+            self.rings.len()
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::priority::Receiver<{}> {{ channel: {:p}, levels: {} }}",
+            std::any::type_name::<T>(),
+            Arc::as_ptr(&self.parker),
+            self.rings.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests;
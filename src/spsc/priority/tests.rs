@@ -0,0 +1,84 @@
+use super::*;
+
+cfg_not_loom! {
+
+#[test]
+fn st_priority_order() {
+    let (src, sink) = channel::<&'static str>(4, 3);
+    src.send("low 1", 0).unwrap();
+    src.send("high 1", 2).unwrap();
+    src.send("mid", 1).unwrap();
+    src.send("low 2", 0).unwrap();
+    src.send("high 2", 2).unwrap();
+    assert_eq!(sink.try_recv(), Ok("high 1"));
+    assert_eq!(sink.try_recv(), Ok("high 2"));
+    assert_eq!(sink.try_recv(), Ok("mid"));
+    assert_eq!(sink.try_recv(), Ok("low 1"));
+    src.send("high 3", 2).unwrap();
+    assert_eq!(sink.try_recv(), Ok("high 3"));
+    assert_eq!(sink.try_recv(), Ok("low 2"));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    drop(src);
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn st_levels_are_separate() {
+    let (src, sink) = channel::<u8>(1, 2);
+    assert_eq!(src.try_send(1, 0), Ok(()));
+    assert_eq!(src.try_send(2, 0), Err(TrySendError::Full(2)));
+    assert_eq!(src.try_send(3, 1), Ok(()));
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.try_send(4, 1), Err(TrySendError::Disconnected(4)));
+}
+
+#[test]
+#[should_panic]
+fn priority_out_of_range() {
+    let (src, _sink) = channel::<u8>(1, 2);
+    let _ = src.try_send(0, 2);
+}
+
+#[test]
+fn mt_recv() {
+    let (src, sink) = channel::<u32>(4, 4);
+    std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i, (i % 4) as u8).unwrap();
+        }
+    });
+    let mut received = Vec::new();
+    while let Ok(i) = sink.recv() {
+        received.push(i);
+    }
+    for level in 0..4 {
+        let level = received.iter().filter(|&&i| i % 4 == level);
+        assert!(level.clone().zip(level.skip(1)).all(|(a, b)| a < b));
+    }
+    assert_eq!(received.len(), 1000);
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn recv_disconnect() {
+    let mut model = loom::model::Builder::new();
+    model.max_threads = 2;
+    model.preemption_bound = Some(3);
+    model.check(|| {
+        let (src, sink) = channel::<u8>(1, 2);
+        loom::thread::spawn(move || {
+            src.send(0, 0).unwrap();
+            src.send(1, 1).unwrap();
+        });
+        let mut received = [sink.recv().unwrap(), sink.recv().unwrap()];
+        received.sort();
+        assert_eq!(received, [0, 1]);
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    });
+}
+
+}
@@ -0,0 +1,172 @@
+use crate::alloc::Layout;
+use crate::error::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+use crate::sync::atomic::AtomicUsize;
+use crate::sync::atomic::Ordering::{Acquire, Release};
+use crate::sync::Arc;
+use crate::util::park::Parker;
+use std::time::Instant;
+
+pub(super) struct Inner {
+    /// Signals sent, but not received yet.
+    ///
+    /// Only the sender increments it and only the receiver
+    /// decrements it, so a non-zero count the receiver sees
+    /// can't drop to zero behind its back.
+    count: AtomicUsize,
+    park: Parker,
+    /*
+    starts off as 0, incremented when entering Sender/Receiver drop.
+    match 'previous value' {
+        0 => {
+            Now the channel is disconnected. We try to wake the other end point.
+            If the other end point was asleep, it will detect the disconnect and unblock.
+            Then, we increment 'drop_count' again and repeat this decision tree with the
+            new 'previous value'.
+        }
+        1 => just fall off drop.
+        2 => deallocate the inner state.
+    }
+    */
+    pub(super) drop_count: AtomicUsize,
+}
+
+impl Inner {
+    pub(super) const LAYOUT: Layout = Layout::new::<Inner>();
+
+    pub(super) fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            park: Parker::new(),
+            drop_count: AtomicUsize::new(0),
+        }
+    }
+
+    pub(super) fn send(&self) -> Result<(), SendError<()>> {
+        // send_many can leave usize::MAX pending signals, so this has to check too.
+        self.send_many(1)
+    }
+
+    pub(super) fn send_many(&self, n: usize) -> Result<(), SendError<()>> {
+        if self.drop_count.load(Acquire) != 0 {
+            return Err(SendError(()));
+        }
+        if n == 0 {
+            return Ok(());
+        }
+        if self
+            .count
+            .fetch_update(Release, Acquire, |count| count.checked_add(n))
+            .is_err()
+        {
+            panic!("signal count overflowed usize");
+        }
+        self.wake_receiver();
+        Ok(())
+    }
+
+    pub(super) fn try_recv(&self) -> Result<(), TryRecvError> {
+        self.try_take(|_| 1).map(|_| ())
+    }
+
+    pub(super) fn try_recv_all(&self) -> Result<usize, TryRecvError> {
+        self.try_take(|count| count)
+    }
+
+    /// Takes `amount(count)` signals, where `count` is the non-zero number of
+    /// pending signals and `amount(count) <= count`.
+    #[inline(always)]
+    fn try_take(&self, amount: impl FnOnce(usize) -> usize) -> Result<usize, TryRecvError> {
+        let mut count = self.count.load(Acquire);
+        if count == 0 {
+            if self.drop_count.load(Acquire) == 0 {
+                return Err(TryRecvError::Empty);
+            }
+            // the sender might have signalled before disconnecting.
+            count = self.count.load(Acquire);
+            if count == 0 {
+                return Err(TryRecvError::Disconnected);
+            }
+        }
+        let taken = amount(count);
+        self.count.fetch_sub(taken, Acquire);
+        Ok(taken)
+    }
+
+    pub(super) fn recv(&self) -> Result<(), RecvError> {
+        self.take_blocking(|_| 1).map(|_| ())
+    }
+
+    pub(super) fn recv_all(&self) -> Result<usize, RecvError> {
+        self.take_blocking(|count| count)
+    }
+
+    #[inline(always)]
+    fn take_blocking(&self, amount: impl Fn(usize) -> usize) -> Result<usize, RecvError> {
+        loop {
+            match self.try_take(&amount) {
+                Ok(taken) => return Ok(taken),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                Err(TryRecvError::Empty) => unsafe { self.park.park() },
+            }
+        }
+    }
+
+    pub(super) fn recv_deadline(&self, deadline: Option<Instant>) -> Result<(), RecvTimeoutError> {
+        let deadline = match deadline {
+            Some(deadline) => deadline,
+            None => return self.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        loop {
+            match self.try_recv() {
+                Ok(()) => return Ok(()),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Poisoned) => return Err(RecvTimeoutError::Poisoned),
+                Err(TryRecvError::Empty) => {
+                    if Instant::now() >= deadline {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                    //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                    unsafe { self.park.park_deadline(deadline) };
+                }
+            }
+        }
+    }
+
+    /// Returns how many signals are waiting to be received.
+    pub(super) fn pending(&self) -> usize {
+        self.count.load(Acquire)
+    }
+
+    pub(super) fn peer_connected(&self) -> bool {
+        self.drop_count.load(Acquire) == 0
+    }
+
+    /// Checks if `try_recv` would return something other than [`TryRecvError::Empty`].
+    pub(super) fn is_ready(&self) -> bool {
+        self.count.load(Acquire) != 0 || self.drop_count.load(Acquire) != 0
+    }
+
+    /// Makes [`wake_receiver`](Inner::wake_receiver) also wake `waker`.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::register`.
+    pub(super) unsafe fn register_receiver(&self, waker: Arc<Parker>) -> bool {
+        self.park.register(waker)
+    }
+
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::unregister`.
+    pub(super) unsafe fn unregister_receiver(&self) {
+        self.park.unregister()
+    }
+
+    #[inline]
+    pub(super) fn wake_receiver(&self) {
+        self.park.unpark();
+    }
+}
@@ -0,0 +1,261 @@
+use crate::alloc::{alloc, dealloc};
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::ptr::NonNull;
+
+pub use crate::error::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+
+mod inner;
+use inner::Inner;
+
+/// Creates a signal channel.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+pub fn channel() -> (Sender, Receiver) {
+    //order is important: Inner is RAII, but NonNull isn't.
+    let inner = {
+        /*SAFETY: deallocated in either Sender's or Receiver's Drop*/
+        let inner_uninit = NonNull::new(unsafe { alloc(Inner::LAYOUT) as *mut Inner })
+            .expect("failed to allocate memory for the shared state");
+        /*SAFETY: this is a safe way to write to _uninitialised memory_.*/
+        unsafe { inner_uninit.as_ptr().write(Inner::new()) };
+        inner_uninit
+    };
+    (
+        Sender {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+        Receiver {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+    )
+}
+
+/// The sending endpoint of a signal [`channel`].
+///
+/// Signals are sent with [`send`](Sender::send) and [`send_many`](Sender::send_many).
+///
+/// # Disconnection
+///
+/// When a [`Sender`] is dropped, the [`Receiver`] can still
+/// receive the pending signals before seeing the disconnect.
+pub struct Sender {
+    inner: NonNull<Inner>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a signal [`channel`].
+///
+/// Signals can be received one at a time using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods, or all at once using
+/// [`try_recv_all`](Receiver::try_recv_all) and [`recv_all`](Receiver::recv_all).
+pub struct Receiver {
+    inner: NonNull<Inner>,
+    _unsync: PhantomUnsync,
+}
+
+impl Sender {
+    /// Sends a signal through this [`channel`].
+    ///
+    /// Never blocks. Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of pending signals would overflow [`usize`],
+    /// which takes [`send_many`](Sender::send_many) with a huge `n`.
+    #[inline]
+    pub fn send(&self) -> Result<(), SendError<()>> {
+        self.inner_ref().send()
+    }
+
+    /// Sends `n` signals at once, waking the [`Receiver`] only once.
+    ///
+    /// Never blocks. Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of pending signals would overflow [`usize`].
+    #[inline]
+    pub fn send_many(&self, n: usize) -> Result<(), SendError<()>> {
+        self.inner_ref().send_many(n)
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    fn inner_ref(&self) -> &Inner {
+        /*SAFETY:
+         *This type and Receiver are responsible for inner's lifetime.
+         */
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl Receiver {
+    /// Tries to receive a single signal.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after receiving all
+    /// sent signals. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    #[inline]
+    pub fn try_recv(&self) -> Result<(), TryRecvError> {
+        self.inner_ref().try_recv()
+    }
+
+    /// Waits for a single signal.
+    ///
+    /// Returns a [`RecvError`] once the [`Sender`] is disconnected
+    /// and all sent signals were received.
+    #[inline]
+    pub fn recv(&self) -> Result<(), RecvError> {
+        self.inner_ref().recv()
+    }
+
+    /// Waits for a single signal, for at most `timeout`.
+    ///
+    /// Returns [`RecvTimeoutError::Timeout`] if no signal arrived in time
+    /// and [`RecvTimeoutError::Disconnected`] like [`recv`](Receiver::recv)
+    /// returns [`RecvError`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::signal;
+    /// use std::time::Duration;
+    ///
+    /// let (src, sink) = signal::channel();
+    /// assert_eq!(
+    ///     sink.recv_timeout(Duration::from_millis(10)),
+    ///     Err(signal::RecvTimeoutError::Timeout)
+    /// );
+    /// src.send().unwrap();
+    /// assert_eq!(sink.recv_timeout(Duration::from_millis(10)), Ok(()));
+    /// ```
+    #[inline]
+    pub fn recv_timeout(&self, timeout: std::time::Duration) -> Result<(), RecvTimeoutError> {
+        self.inner_ref()
+            .recv_deadline(std::time::Instant::now().checked_add(timeout))
+    }
+
+    /// Tries to receive every pending signal, returning how many were received.
+    ///
+    /// Never returns `Ok(0)`: with no pending signals, this fails like
+    /// [`try_recv`](Receiver::try_recv).
+    #[inline]
+    pub fn try_recv_all(&self) -> Result<usize, TryRecvError> {
+        self.inner_ref().try_recv_all()
+    }
+
+    /// Waits for at least one signal, then receives every pending
+    /// signal and returns how many were received.
+    ///
+    /// Returns a [`RecvError`] once the [`Sender`] is disconnected
+    /// and all sent signals were received.
+    #[inline]
+    pub fn recv_all(&self) -> Result<usize, RecvError> {
+        self.inner_ref().recv_all()
+    }
+
+    /// Returns how many signals are waiting to be received.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.inner_ref().pending()
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// This doesn't take the pending signals into account.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    fn inner_ref(&self) -> &Inner {
+        /*SAFETY:
+         *This type and Sender are responsible for inner's lifetime.
+         */
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_receiver(),
+                1 => break,
+                2 => {
+                    break unsafe {
+                        self.inner.as_ptr().drop_in_place();
+                        dealloc(self.inner.as_ptr() as *mut u8, Inner::LAYOUT)
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => { /*the Sender never blocks*/ }
+                1 => break,
+                2 => {
+                    break unsafe {
+                        self.inner.as_ptr().drop_in_place();
+                        dealloc(self.inner.as_ptr() as *mut u8, Inner::LAYOUT)
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Sender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "signal::Sender {{ channel: {:p} }}", self.inner)
+    }
+}
+
+impl std::fmt::Debug for Receiver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "signal::Receiver {{ channel: {:p} }}", self.inner)
+    }
+}
+
+impl crate::select::Selectable for Receiver {}
+
+impl crate::select::sealed::Sealed for Receiver {
+    fn is_ready(&self) -> bool {
+        self.inner_ref().is_ready()
+    }
+
+    unsafe fn register(&self, waker: crate::select::sealed::Waker) -> bool {
+        self.inner_ref().register_receiver(waker.0)
+    }
+
+    unsafe fn unregister(&self) {
+        self.inner_ref().unregister_receiver()
+    }
+}
+
+unsafe impl Send for Sender {}
+unsafe impl Send for Receiver {}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,160 @@
+use super::*;
+cfg_not_loom! {
+
+#[test]
+fn st_send_recv() {
+    let (src, sink) = channel();
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    src.send().unwrap();
+    src.send().unwrap();
+    assert_eq!(sink.pending(), 2);
+    assert_eq!(sink.try_recv(), Ok(()));
+    assert_eq!(sink.recv(), Ok(()));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn st_recv_all() {
+    let (src, sink) = channel();
+    assert_eq!(sink.try_recv_all(), Err(TryRecvError::Empty));
+    src.send_many(5).unwrap();
+    src.send().unwrap();
+    src.send_many(0).unwrap();
+    assert_eq!(sink.try_recv_all(), Ok(6));
+    assert_eq!(sink.pending(), 0);
+    src.send_many(3).unwrap();
+    assert_eq!(sink.recv_all(), Ok(3));
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, sink) = channel();
+    src.send_many(2).unwrap();
+    drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(sink.try_recv(), Ok(()));
+    assert_eq!(sink.recv_all(), Ok(1));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(sink.recv(), Err(RecvError {}));
+
+    let (src, sink) = channel();
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.send(), Err(SendError(())));
+    assert_eq!(src.send_many(2), Err(SendError(())));
+}
+
+#[test]
+#[should_panic(expected = "signal count overflowed usize")]
+fn st_send_many_overflow() {
+    let (src, _sink) = channel();
+    src.send().unwrap();
+    let _ = src.send_many(usize::MAX);
+}
+
+#[test]
+#[should_panic(expected = "signal count overflowed usize")]
+fn st_send_overflow() {
+    let (src, _sink) = channel();
+    src.send_many(usize::MAX).unwrap();
+    let _ = src.send();
+}
+
+#[test]
+fn mt_send_recv() {
+    const SIGNALS: usize = 100_000;
+    let (src, sink) = channel();
+    std::thread::spawn(move || {
+        for _ in 0..SIGNALS {
+            src.send().unwrap();
+        }
+    });
+    let mut received = 0;
+    while sink.recv().is_ok() {
+        received += 1;
+    }
+    assert_eq!(received, SIGNALS);
+}
+
+#[test]
+fn mt_recv_all() {
+    const SIGNALS: usize = 100_000;
+    let (src, sink) = channel();
+    std::thread::spawn(move || {
+        for i in 0..SIGNALS {
+            match i % 3 {
+                0 => src.send_many(2).unwrap(),
+                _ => src.send().unwrap(),
+            }
+        }
+    });
+    let mut received = 0;
+    while let Ok(n) = sink.recv_all() {
+        assert_ne!(n, 0);
+        received += n;
+    }
+    assert_eq!(received, SIGNALS + SIGNALS.div_ceil(3));
+}
+
+#[test]
+fn mt_recv_timeout() {
+    use std::time::Duration;
+    let (src, sink) = channel();
+    assert_eq!(
+        sink.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        src.send().unwrap();
+    });
+    assert_eq!(sink.recv_timeout(Duration::from_secs(10)), Ok(()));
+    assert_eq!(
+        sink.recv_timeout(Duration::from_secs(10)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn send_recv() {
+    loom::model(|| {
+        let (src, sink) = channel();
+        loom::thread::spawn(move || {
+            src.send().unwrap();
+            src.send_many(2).unwrap();
+        });
+        assert_eq!(sink.recv(), Ok(()));
+        let mut received = 1;
+        while let Ok(n) = sink.recv_all() {
+            received += n;
+        }
+        assert_eq!(received, 3);
+    });
+}
+
+#[test]
+fn sender_dc() {
+    loom::model(|| {
+        let (src, sink) = channel();
+        loom::thread::spawn(move || drop(src));
+        assert_eq!(sink.recv(), Err(RecvError {}));
+    });
+}
+
+#[test]
+fn receiver_dc() {
+    loom::model(|| {
+        let (src, sink) = channel();
+        let handle = loom::thread::spawn(move || {
+            let _ = src.send();
+        });
+        drop(sink);
+        handle.join().unwrap();
+    });
+}
+
+}
@@ -0,0 +1,175 @@
+use crate::cell::UnsafeCell;
+use crate::error::{SendTimeoutError, TryRecvError, TrySendError};
+use crate::sync::atomic::AtomicUsize;
+use crate::sync::atomic::Ordering::{Acquire, Release};
+use crate::util::park::Parker;
+use std::time::Instant;
+
+/// `buffer` holds a cleared buffer, which belongs to the sender.
+const EMPTY: usize = 0;
+/// `buffer` holds a published frame, which belongs to the receiver.
+const FULL: usize = 1;
+
+pub(super) struct Inner<T> {
+    /// Either [`EMPTY`] or [`FULL`].
+    ///
+    /// Only the sender changes [`EMPTY`] to [`FULL`] and only the
+    /// receiver changes [`FULL`] to [`EMPTY`], so the end point
+    /// that sees its state owns `buffer` until it changes the state.
+    state: AtomicUsize,
+    buffer: UnsafeCell<Vec<T>>,
+    sender_park: Parker,
+    receiver_park: Parker,
+    /*
+    starts off as 0, incremented when entering Sender/Receiver drop.
+    match 'previous value' {
+        0 => {
+            Now the channel is disconnected. We try to wake the other end point.
+            If the other end point was asleep, it will detect the disconnect and unblock.
+            Then, we increment 'drop_count' again and repeat this decision tree with the
+            new 'previous value'.
+        }
+        1 => just fall off drop.
+        2 => deallocate the inner state.
+    }
+    */
+    pub(super) drop_count: AtomicUsize,
+}
+
+impl<T> Inner<T> {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            state: AtomicUsize::new(EMPTY),
+            buffer: UnsafeCell::new(Vec::with_capacity(capacity)),
+            sender_park: Parker::new(),
+            receiver_park: Parker::new(),
+            drop_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Can only be called by the sender.
+    pub(super) fn try_publish(&self, frame: Vec<T>) -> Result<Vec<T>, TrySendError<Vec<T>>> {
+        if self.drop_count.load(Acquire) != 0 {
+            return Err(TrySendError::Disconnected(frame));
+        }
+        if self.state.load(Acquire) != EMPTY {
+            return Err(TrySendError::Full(frame));
+        }
+        //SAFETY: the state is EMPTY, so the receiver doesn't touch the buffer.
+        let recycled = self
+            .buffer
+            .with_mut(|ptr| unsafe { std::mem::replace(&mut *ptr, frame) });
+        self.state.store(FULL, Release);
+        self.wake_receiver();
+        Ok(recycled)
+    }
+
+    /// Can only be called by the sender.
+    pub(super) fn publish_deadline(
+        &self,
+        mut frame: Vec<T>,
+        deadline: Option<Instant>,
+    ) -> Result<Vec<T>, SendTimeoutError<Vec<T>>> {
+        loop {
+            match self.try_publish(frame) {
+                Ok(recycled) => return Ok(recycled),
+                Err(TrySendError::Full(returned)) => frame = returned,
+                Err(TrySendError::Disconnected(frame) | TrySendError::Poisoned(frame)) => {
+                    return Err(SendTimeoutError::Disconnected(frame))
+                }
+            }
+            match deadline {
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Err(SendTimeoutError::Timeout(frame));
+                    }
+                    //SAFETY: park can't be called by different threads, since Sender is !Sync.
+                    unsafe { self.sender_park.park_deadline(deadline) };
+                }
+                //SAFETY: park can't be called by different threads, since Sender is !Sync.
+                None => unsafe { self.sender_park.park() },
+            }
+        }
+    }
+
+    /// Checks for a published frame, which the caller owns on success
+    /// until it calls [`recycle`](Inner::recycle).
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn try_acquire(&self) -> Result<(), TryRecvError> {
+        if self.state.load(Acquire) == FULL {
+            return Ok(());
+        }
+        if self.drop_count.load(Acquire) == 0 {
+            return Err(TryRecvError::Empty);
+        }
+        // the sender might have published before disconnecting.
+        match self.state.load(Acquire) {
+            FULL => Ok(()),
+            _ => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn acquire_deadline(&self, deadline: Option<Instant>) -> Result<(), TryRecvError> {
+        loop {
+            match self.try_acquire() {
+                Err(TryRecvError::Empty) => {}
+                result => return result,
+            }
+            match deadline {
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Err(TryRecvError::Empty);
+                    }
+                    //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                    unsafe { self.receiver_park.park_deadline(deadline) };
+                }
+                //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                None => unsafe { self.receiver_park.park() },
+            }
+        }
+    }
+
+    /// Returns the published frame.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, after a successful `try_acquire`
+    /// and before the matching `recycle`.
+    #[allow(clippy::mut_from_ref)]
+    pub(super) unsafe fn frame(&self) -> &mut Vec<T> {
+        self.buffer.with_mut(|ptr| &mut *ptr)
+    }
+
+    /// Clears the published frame and hands it back to the sender.
+    ///
+    /// # Safety
+    ///
+    /// Same as `frame`, and the frame can't be used afterwards.
+    pub(super) unsafe fn recycle(&self) {
+        // the values are dropped by the receiver, before the sender can reuse the buffer.
+        self.buffer.with_mut(|ptr| (*ptr).clear());
+        self.state.store(EMPTY, Release);
+        self.wake_sender();
+    }
+
+    /// Checks if a frame was published and not received yet.
+    pub(super) fn is_full(&self) -> bool {
+        self.state.load(Acquire) == FULL
+    }
+
+    pub(super) fn peer_connected(&self) -> bool {
+        self.drop_count.load(Acquire) == 0
+    }
+
+    #[inline]
+    pub(super) fn wake_sender(&self) {
+        self.sender_park.unpark();
+    }
+
+    #[inline]
+    pub(super) fn wake_receiver(&self) {
+        self.receiver_park.unpark();
+    }
+}
@@ -0,0 +1,293 @@
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+pub use crate::error::{
+    RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+};
+
+mod inner;
+use inner::Inner;
+
+/// Creates a swap channel, whose first recycled buffer has room for
+/// `capacity` values.
+///
+/// Only two buffers are in use at a time: the one the [`Sender`] fills and
+/// the one the channel holds. The [`Sender`] can start off with an empty
+/// [`Vec`], since [`publish`](Sender::publish) hands it a buffer to reuse.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::swap;
+/// use std::thread;
+///
+/// let (src, mut sink) = swap::channel::<u32>(16);
+/// thread::spawn(move || {
+///     let mut buffer = Vec::new();
+///     for block in 0..4 {
+///         buffer.extend(block * 16..(block + 1) * 16);
+///         buffer = src.publish(buffer).unwrap();
+///     }
+/// });
+///
+/// let mut sum = 0;
+/// while let Ok(frame) = sink.recv() {
+///     assert_eq!(frame.len(), 16);
+///     sum += frame.iter().sum::<u32>();
+/// }
+/// assert_eq!(sum, (0..64).sum::<u32>());
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    /*SAFETY: freed by the last end point's Drop.*/
+    let inner = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Inner::new(capacity)))) };
+    (
+        Sender {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+        Receiver {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+    )
+}
+
+/// The sending endpoint of a swap [`channel`].
+///
+/// Whole buffers are sent with [`try_publish`](Sender::try_publish)
+/// and [`publish`](Sender::publish).
+///
+/// # Disconnection
+///
+/// When a [`Sender`] is dropped, the [`Receiver`] can still
+/// receive the last published frame before seeing the disconnect.
+pub struct Sender<T> {
+    inner: NonNull<Inner<T>>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a swap [`channel`].
+///
+/// Frames are received with [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv). They borrow the [`Receiver`] mutably,
+/// since the next frame can't be received before the current one
+/// is handed back.
+pub struct Receiver<T> {
+    inner: NonNull<Inner<T>>,
+    _unsync: PhantomUnsync,
+}
+
+/// A frame published by the [`Sender`], returned by [`Receiver::recv`].
+///
+/// Dereferences to the published [`Vec`]. Dropping the [`Frame`] clears
+/// the buffer and hands it back to the [`Sender`], which gets it from its
+/// next [`publish`](Sender::publish) call.
+pub struct Frame<'a, T> {
+    inner: &'a Inner<T>,
+}
+
+impl<T> Sender<T> {
+    /// Tries to publish `frame`, returning a cleared buffer to fill next.
+    ///
+    /// Returns [`TrySendError::Full`] if the [`Receiver`] didn't finish with
+    /// the previous frame yet, and [`TrySendError::Disconnected`] if it's gone.
+    #[inline]
+    pub fn try_publish(&self, frame: Vec<T>) -> Result<Vec<T>, TrySendError<Vec<T>>> {
+        self.inner_ref().try_publish(frame)
+    }
+
+    /// Publishes `frame`, returning a cleared buffer to fill next.
+    ///
+    /// Waits until the [`Receiver`] is done with the previous frame, so no
+    /// frame is ever skipped. Returns a [`SendError`] if the [`Receiver`]
+    /// is disconnected.
+    ///
+    /// The returned buffer is the one the previous frame was published in,
+    /// so its capacity can be reused without allocating.
+    pub fn publish(&self, frame: Vec<T>) -> Result<Vec<T>, SendError<Vec<T>>> {
+        self.inner_ref()
+            .publish_deadline(frame, None)
+            .map_err(|err| SendError(err.into_inner()))
+    }
+
+    /// Like [`publish`](Sender::publish), but waits at most `timeout`.
+    pub fn publish_timeout(
+        &self,
+        frame: Vec<T>,
+        timeout: Duration,
+    ) -> Result<Vec<T>, SendTimeoutError<Vec<T>>> {
+        self.inner_ref()
+            .publish_deadline(frame, Instant::now().checked_add(timeout))
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<T> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to receive the published frame.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after receiving the last
+    /// published frame. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    #[inline]
+    pub fn try_recv(&mut self) -> Result<Frame<'_, T>, TryRecvError> {
+        self.inner_ref().try_acquire()?;
+        Ok(self.frame())
+    }
+
+    /// Waits for the [`Sender`] to publish a frame.
+    ///
+    /// Returns a [`RecvError`] once the [`Sender`] is disconnected
+    /// and the last published frame was received.
+    pub fn recv(&mut self) -> Result<Frame<'_, T>, RecvError> {
+        match self.inner_ref().acquire_deadline(None) {
+            Ok(()) => Ok(self.frame()),
+            Err(_) => Err(RecvError {}),
+        }
+    }
+
+    /// Like [`recv`](Receiver::recv), but waits at most `timeout`.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<Frame<'_, T>, RecvTimeoutError> {
+        match self
+            .inner_ref()
+            .acquire_deadline(Instant::now().checked_add(timeout))
+        {
+            Ok(()) => Ok(self.frame()),
+            Err(TryRecvError::Empty) => Err(RecvTimeoutError::Timeout),
+            Err(TryRecvError::Disconnected) => Err(RecvTimeoutError::Disconnected),
+            Err(TryRecvError::Poisoned) => Err(RecvTimeoutError::Poisoned),
+        }
+    }
+
+    /// Checks if a frame was published and not received yet.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.inner_ref().is_full()
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// This doesn't take the published frame into account.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    fn frame(&self) -> Frame<'_, T> {
+        Frame {
+            inner: self.inner_ref(),
+        }
+    }
+
+    #[inline(always)]
+    fn inner_ref(&self) -> &Inner<T> {
+        //SAFETY: the channel is only freed by the last end point's Drop.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T> std::ops::Deref for Frame<'_, T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        //SAFETY: the Frame exists only between acquiring and recycling the buffer.
+        unsafe { self.inner.frame() }
+    }
+}
+
+impl<T> std::ops::DerefMut for Frame<'_, T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        //SAFETY: the Frame exists only between acquiring and recycling the buffer.
+        unsafe { self.inner.frame() }
+    }
+}
+
+impl<T> Drop for Frame<'_, T> {
+    fn drop(&mut self) {
+        //SAFETY: the Frame holds the Receiver's exclusive borrow, and isn't used anymore.
+        unsafe { self.inner.recycle() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_receiver(),
+                1 => break,
+                //SAFETY: the Receiver is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_sender(),
+                1 => break,
+                //SAFETY: the Sender is gone, so this is the last user.
+                2 => break unsafe { drop(Box::from_raw(self.inner.as_ptr())) },
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::swap::Sender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::swap::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Frame<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Frame").field(&**self).finish()
+    }
+}
+
+unsafe impl<T: Send> Send for Sender<T> {}
+unsafe impl<T: Send> Send for Receiver<T> {}
+
+#[cfg(test)]
+mod tests;
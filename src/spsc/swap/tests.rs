@@ -0,0 +1,101 @@
+cfg_not_loom! {
+use super::*;
+
+#[test]
+fn st_publish_recv() {
+    let (src, mut sink) = channel::<u32>(8);
+    assert!(matches!(sink.try_recv(), Err(TryRecvError::Empty)));
+    let recycled = src.publish(vec![1, 2, 3]).unwrap();
+    assert!(recycled.is_empty());
+    assert_eq!(recycled.capacity(), 8);
+    assert!(sink.is_ready());
+    assert!(matches!(
+        src.try_publish(vec![4]),
+        Err(TrySendError::Full(frame)) if frame == [4]
+    ));
+    {
+        let mut frame = sink.try_recv().unwrap();
+        assert_eq!(*frame, [1, 2, 3]);
+        frame.push(4);
+    }
+    assert!(!sink.is_ready());
+    let recycled = src.try_publish(vec![5]).unwrap();
+    assert!(recycled.is_empty());
+    assert!(recycled.capacity() >= 4);
+    assert_eq!(*sink.recv().unwrap(), [5]);
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, mut sink) = channel::<u32>(1);
+    src.publish(vec![1]).unwrap();
+    drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(*sink.recv().unwrap(), [1]);
+    assert!(matches!(sink.try_recv(), Err(TryRecvError::Disconnected)));
+    assert!(matches!(
+        sink.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Disconnected)
+    ));
+
+    let (src, sink) = channel::<u32>(1);
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.publish(vec![2]), Err(SendError(vec![2])));
+}
+
+#[test]
+fn st_timeout() {
+    let (src, mut sink) = channel::<u32>(1);
+    assert!(matches!(
+        sink.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    ));
+    src.publish(vec![1]).unwrap();
+    assert_eq!(
+        src.publish_timeout(vec![2], Duration::from_millis(10)),
+        Err(SendTimeoutError::Timeout(vec![2]))
+    );
+}
+
+#[test]
+fn mt_publish_recv() {
+    let (src, mut sink) = channel::<usize>(64);
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        for frame in 0..100 {
+            buffer.resize(64, frame);
+            buffer = src.publish(buffer).unwrap();
+        }
+    });
+    let mut expected = 0;
+    while let Ok(frame) = sink.recv() {
+        assert_eq!(frame.len(), 64);
+        assert!(frame.iter().all(|&i| i == expected));
+        expected += 1;
+    }
+    assert_eq!(expected, 100);
+}
+
+#[test]
+fn mt_drop_values() {
+    use std::sync::Arc;
+    let item = Arc::new(());
+    let (src, mut sink) = channel(4);
+    let sent = item.clone();
+    let handle = std::thread::spawn(move || {
+        let mut buffer = vec![sent.clone(), sent.clone()];
+        for _ in 0..10 {
+            buffer = src.publish(buffer).unwrap();
+            buffer.push(sent.clone());
+        }
+    });
+    while let Ok(frame) = sink.recv() {
+        assert!(!frame.is_empty());
+    }
+    handle.join().unwrap();
+    drop(sink);
+    assert_eq!(Arc::strong_count(&item), 1);
+}
+
+}
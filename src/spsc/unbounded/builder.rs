@@ -0,0 +1,120 @@
+use super::{inner::Inner, Receiver, Sender};
+use crate::error::AllocError;
+use crate::util::{marker::PhantomUnsync, park::{Idle, WaitStrategy}};
+
+/// Configures and creates an unbounded [`channel`](super::channel).
+///
+/// By default, [`recv`](Receiver::recv) parks the thread as soon as the
+/// channel is empty. [`spin`](Builder::spin) and [`park`](Builder::park)
+/// trade CPU time for latency by busy-waiting instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::unbounded::Builder;
+/// use std::thread;
+///
+/// let (src, sink) = Builder::new().park(false).build();
+/// thread::spawn(move || {
+///     for i in 0..100 {
+///         src.send(i).unwrap();
+///     }
+/// });
+/// for i in 0..100 {
+///     assert_eq!(sink.recv(), Ok(i));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Builder {
+    wait: WaitStrategy,
+    max_cached_nodes: usize,
+    max_len: usize,
+}
+
+impl Builder {
+    /// Creates a [`Builder`] with the default configuration.
+    pub fn new() -> Self {
+        Self {
+            wait: WaitStrategy::DEFAULT,
+            max_cached_nodes: usize::MAX,
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Sets how many times [`recv`](Receiver::recv) busy-waits before parking.
+    ///
+    /// Every try waits a bit longer, like [`Backoff::snooze`], and later
+    /// ones yield the thread instead of spinning. Defaults to 0.
+    ///
+    /// [`Backoff::snooze`]: crate::sync::Backoff::snooze
+    pub fn spin(mut self, count: u32) -> Self {
+        self.wait.spins = count;
+        self
+    }
+
+    /// Sets whether [`recv`](Receiver::recv) parks at all.
+    ///
+    /// If `false`, it busy-waits until a value arrives, which is only sensible
+    /// when the receiving thread has a core to itself. Defaults to `true`.
+    pub fn park(mut self, park: bool) -> Self {
+        self.wait.idle = if park { Idle::Park } else { Idle::Spin };
+        self
+    }
+
+    /// Sets how many received nodes are kept around for reuse.
+    ///
+    /// The channel recycles the nodes of received values, so by default a burst
+    /// of sends keeps its memory allocated until the channel is dropped. With a
+    /// limit, the [`Sender`] deallocates the excess nodes the next time it runs
+    /// out of recycled ones. Defaults to no limit.
+    pub fn max_cached_nodes(mut self, count: usize) -> Self {
+        self.max_cached_nodes = count;
+        self
+    }
+
+    /// Sets a soft bound on how many values can be pending.
+    ///
+    /// Once `n` values are pending, [`try_send`](Sender::try_send) fails with
+    /// [`TrySendAllocError::Full`](crate::error::TrySendAllocError::Full), which
+    /// lets a producer apply backpressure. [`send`](Sender::send) ignores the
+    /// bound, so values that can't be dropped still get through. Defaults to no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded::{Builder, TrySendAllocError};
+    ///
+    /// let (src, sink) = Builder::new().max_len(1).build::<u32>();
+    /// src.try_send(1).unwrap();
+    /// assert_eq!(src.try_send(2), Err(TrySendAllocError::Full(2)));
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// src.try_send(2).unwrap();
+    /// ```
+    pub fn max_len(mut self, n: usize) -> Self {
+        self.max_len = n;
+        self
+    }
+
+    /// Creates the channel.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if it can't allocate the inner state of the channel.
+    pub fn build<T>(self) -> (Sender<T>, Receiver<T>) {
+        let (h1, h2) = Inner::<T>::allocate(self.wait, self.max_cached_nodes, self.max_len);
+        (Sender(h1, PhantomUnsync {}), Receiver(h2, PhantomUnsync {}))
+    }
+
+    /// Like [`build`](Builder::build), but returns an [`AllocError`] instead of
+    /// panicking if the inner state of the channel can't be allocated.
+    pub fn try_build<T>(self) -> Result<(Sender<T>, Receiver<T>), AllocError> {
+        let (h1, h2) = Inner::<T>::try_allocate(self.wait, self.max_cached_nodes, self.max_len)?;
+        Ok((Sender(h1, PhantomUnsync {}), Receiver(h2, PhantomUnsync {})))
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,16 +1,28 @@
 use crate::alloc::{self, Layout};
 use crate::cell::UnsafeCell;
-use crate::error::{RecvError, SendError, TryRecvError};
+use crate::error::{
+    AllocError, RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendAllocError,
+};
 use crate::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
 use crate::sync::atomic::{AtomicPtr, AtomicUsize};
+use crate::sync::Arc;
 
 use crate::util::ann::AtomicNonNull;
-use crate::util::cache::CacheAligned;
-use crate::util::park::Parker;
+use crate::util::cache::CachePadded;
+use crate::util::park::{Notify, Parker, WaitStrategy};
 
 use std::cell::Cell;
 use std::mem::MaybeUninit;
 use std::ptr::{self, NonNull};
+use std::time::Instant;
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{Counter, Stats};
+
+/// The bit of `drop_count` set by `close`.
+const CLOSED: usize = 1 << (usize::BITS - 1);
 
 /*
  * unbounded::channel uses a singly-linked list structured like this:
@@ -19,17 +31,46 @@ use std::ptr::{self, NonNull};
  * `sender.next_for_reuse` -> ... ->  `tail` -> ... -> `sender.head`
  */
 pub(super) struct Inner<T> {
-    sender: CacheAligned<SenderData<T>>,
-    tail: CacheAligned<AtomicNonNull<Node<T>>>,
+    sender: CachePadded<SenderData<T>>,
+    tail: CachePadded<AtomicNonNull<Node<T>>>,
     // Sender "drops" twice, to allow unpark with drop_count != 0.
+    // The CLOSED bit is set by `close` and ignored when counting drops.
     pub(super) drop_count: AtomicUsize,
+    /// How the receiver blocks.
+    wait: Cell<WaitStrategy>,
+    /// Recycled nodes beyond this are deallocated by `refresh_tail_cache`.
+    max_cached: usize,
+    /// `try_send` fails once this many values are pending.
+    max_len: usize,
+    /// How many values were received.
+    received: AtomicUsize,
+    #[cfg(feature = "metrics")]
+    metrics_received: Counter,
+    #[cfg(feature = "metrics")]
+    empty: Counter,
 }
 
 struct SenderData<T> {
     head: Cell<NonNull<Node<T>>>,
     next_for_reuse: Cell<NonNull<Node<T>>>,
     tail_cache: Cell<NonNull<Node<T>>>,
+    /// How many values were sent.
+    ///
+    /// Only written by the sender, but read by the receiver for `len_hint`.
+    sent: AtomicUsize,
+    /// How many nodes are allocated, including recycled ones.
+    nodes: Cell<usize>,
     park_receiver: Parker, //Parkers are accessed by wakers more often than the parked thread
+    /// Makes the sender refresh `tail_cache` before every reuse attempt.
+    #[cfg(feature = "loom")]
+    force_refresh: Cell<bool>,
+    #[cfg(feature = "metrics")]
+    metrics_sent: Counter,
+    #[cfg(feature = "metrics")]
+    full: Counter,
+    /// The most values pending after a send.
+    #[cfg(feature = "metrics")]
+    max_depth: Counter,
 }
 
 struct Node<T> {
@@ -42,32 +83,96 @@ impl<T> Inner<T> {
         self.drop_count.load(Acquire) == 0
     }
 
+    /// Checks if the other end point was dropped, ignoring [`close`](Inner::close).
+    pub(super) fn peer_dropped(&self) -> bool {
+        self.drop_count.load(Acquire) & !CLOSED != 0
+    }
+
+    /// Disconnects the channel without dropping an end point.
+    pub(super) fn close(&self) {
+        self.drop_count.fetch_or(CLOSED, AcqRel);
+    }
+
     pub(super) fn send(&self, item: T) -> Result<(), SendError<T>> {
         if self.drop_count.load(Relaxed) != 0 {
             Err(SendError(item))
         } else {
-            //SAFETY: nodes live until Inner::drop
-            let node = unsafe { self.next_node().as_ref() };
-
-            node.value.with_mut(|val| unsafe {
-                /*SAFETY:
-                 * - nodes from `self.next_node()` always have uninit values
-                 * - MaybeUninit<T> has the same layout as T
-                 */
-                (val as *mut T).write(item)
-            });
-
-            let old = self.sender.head.replace(node.into());
-            // SAFETY: nodes live until Inner::drop
-            unsafe { old.as_ref() }
-                .next
-                .store(node as *const _ as *mut _, Release);
-
-            self.unpark_receiver();
+            //SAFETY: nodes from `next_node` have uninit values.
+            unsafe { self.push(self.next_node(), item) };
             Ok(())
         }
     }
 
+    pub(super) fn try_send(&self, item: T) -> Result<(), TrySendAllocError<T>> {
+        if self.drop_count.load(Relaxed) != 0 {
+            Err(TrySendAllocError::Disconnected(item))
+        } else if self.max_len != usize::MAX && self.len() >= self.max_len {
+            metrics! { self.sender.full.add(1); }
+            Err(TrySendAllocError::Full(item))
+        } else {
+            match self.try_next_node() {
+                //SAFETY: nodes from `try_next_node` have uninit values.
+                Some(node) => unsafe { self.push(node, item) },
+                None => return Err(TrySendAllocError::OutOfMemory(item)),
+            }
+            Ok(())
+        }
+    }
+
+    /// Returns how many values are pending.
+    ///
+    /// Can only be called by the sender.
+    #[inline]
+    pub(super) fn len(&self) -> usize {
+        self.sender
+            .sent
+            .load(Relaxed)
+            .wrapping_sub(self.received.load(Acquire))
+    }
+
+    /// Returns a lower bound of how many values are pending.
+    ///
+    /// Can only be called by the receiver.
+    #[inline]
+    pub(super) fn len_hint(&self) -> usize {
+        // the sender counts a value before publishing it, so this can't be negative.
+        self.sender
+            .sent
+            .load(Relaxed)
+            .wrapping_sub(self.received.load(Relaxed))
+    }
+
+    /// Writes `item` to `node` and appends it to the list.
+    ///
+    /// # Safety
+    ///
+    /// `node` must come from `next_node` or `try_next_node`.
+    #[inline(always)]
+    unsafe fn push(&self, node: NonNull<Node<T>>, item: T) {
+        //SAFETY: nodes live until Inner::drop
+        let node = node.as_ref();
+
+        /*SAFETY:
+         * - nodes from `self.next_node()` always have uninit values
+         * - MaybeUninit<T> has the same layout as T
+         */
+        (node.value_ptr() as *mut T).write(item);
+
+        // counted before publishing, so a received value was always counted.
+        let sent = self.sender.sent.load(Relaxed).wrapping_add(1);
+        self.sender.sent.store(sent, Relaxed);
+        let old = self.sender.head.replace(node.into());
+        // SAFETY: nodes live until Inner::drop
+        old.as_ref().next.store(node as *const _ as *mut _, Release);
+        metrics! {
+            self.sender.metrics_sent.add(1);
+            let depth = sent.wrapping_sub(self.received.load(Relaxed));
+            self.sender.max_depth.max(depth as u64);
+        }
+
+        self.unpark_receiver();
+    }
+
     pub(super) fn try_recv(&self) -> Result<T, TryRecvError> {
         //SAFETY: nodes live until Inner::drop
         let tail = unsafe { self.tail.load(Relaxed).as_ref() };
@@ -75,7 +180,10 @@ impl<T> Inner<T> {
         let new_tail = match NonNull::new(tail.next.load(Acquire)) {
             Some(p) => p,
             None => match self.drop_count.load(Acquire) {
-                0 => return Err(TryRecvError::Empty),
+                0 => {
+                    metrics! { self.empty.add(1); }
+                    return Err(TryRecvError::Empty);
+                }
                 _ => match NonNull::new(tail.next.load(Acquire)) {
                     Some(p) => p,
                     None => return Err(TryRecvError::Disconnected),
@@ -85,59 +193,190 @@ impl<T> Inner<T> {
 
         //SAFETY: nodes live until Inner::drop
         let new_tail = unsafe { new_tail.as_ref() };
-        let ret = new_tail.value.with_mut(|x| unsafe {
-            /*SAFETY: inserted nodes have initialised values*/
-            x.read().assume_init()
-        });
+        /*SAFETY: inserted nodes have initialised values*/
+        let ret = unsafe { new_tail.value_ptr().read().assume_init() };
 
         self.tail.store(new_tail.into(), Release);
+        // only the receiver writes `received`.
+        let received = self.received.load(Relaxed).wrapping_add(1);
+        self.received.store(received, Release);
+        metrics! { self.metrics_received.add(1); }
 
         Ok(ret)
     }
 
     pub(super) fn recv(&self) -> Result<T, RecvError> {
+        let mut spun = 0;
         loop {
             match self.try_recv() {
                 Ok(t) => return Ok(t),
-                Err(TryRecvError::Disconnected) => return Err(RecvError {}),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
                 Err(TryRecvError::Empty) => unsafe {
                     //SAFETY: only Receiver parks and it's !Copy + !Clone + !Sync
-                    self.sender.park_receiver.park()
+                    self.park(&mut spun, None);
                 },
             }
         }
     }
 
+    /// Like [`recv`](Inner::recv), but gives up at `deadline`.
+    pub(super) fn recv_deadline(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        let mut spun = 0;
+        loop {
+            match self.try_recv() {
+                Ok(t) => return Ok(t),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Poisoned) => return Err(RecvTimeoutError::Poisoned),
+                Err(TryRecvError::Empty) => {}
+            }
+            //SAFETY: only Receiver parks and it's !Copy + !Clone + !Sync
+            if !unsafe { self.park(&mut spun, deadline) } {
+                return Err(RecvTimeoutError::Timeout);
+            }
+        }
+    }
+
+    /// Waits for the sender, see [`WaitStrategy::wait_deadline`].
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver.
+    #[inline(always)]
+    unsafe fn park(&self, spun: &mut u32, deadline: Option<Instant>) -> bool {
+        let wait = self.wait.get();
+        #[cfg(feature = "tracing")]
+        if wait.will_park(*spun) {
+            let park = || wait.wait_deadline(&self.sender.park_receiver, spun, deadline);
+            // the receiver only parks on an empty queue.
+            return crate::util::trace::parked("recv", crate::util::addr(self), None, 0, park);
+        }
+        wait.wait_deadline(&self.sender.park_receiver, spun, deadline)
+    }
+
+    /// Sets how many times the receiver busy-waits before parking.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn set_receiver_spin(&self, spins: u32) {
+        let mut wait = self.wait.get();
+        wait.spins = spins;
+        self.wait.set(wait);
+    }
+
+    /// Returns a snapshot of the channel's counters.
+    #[cfg(feature = "metrics")]
+    pub(super) fn stats(&self) -> Stats {
+        Stats {
+            sent: self.sender.metrics_sent.get(),
+            received: self.metrics_received.get(),
+            full: self.sender.full.get(),
+            empty: self.empty.get(),
+            // the sender never blocks.
+            sender_parks: 0,
+            receiver_parks: self.sender.park_receiver.parks(),
+            max_depth: self.sender.max_depth.get(),
+            sender_park_time: Duration::ZERO,
+            receiver_park_time: self.sender.park_receiver.park_time(),
+        }
+    }
+
     pub(super) fn unpark_receiver(&self) {
         self.sender.park_receiver.unpark();
     }
 
-    pub(super) fn allocate() -> (InnerHolder<T>, InnerHolder<T>) {
-        let this = Self::new();
+    /// Makes [`unpark_receiver`](Inner::unpark_receiver) also call `notify`.
+    pub(super) fn set_receiver_notify(&self, notify: Option<Notify>) {
+        self.sender.park_receiver.set_notify(notify);
+    }
+
+    /// Makes [`unpark_receiver`](Inner::unpark_receiver) also wake `waker`,
+    /// replacing the callback set by [`set_receiver_notify`](Inner::set_receiver_notify).
+    #[cfg(feature = "futures")]
+    pub(super) fn set_receiver_waker(&self, waker: &std::task::Waker) {
+        self.sender.park_receiver.set_waker(waker);
+    }
+
+    /// Checks if `try_recv` would return something other than [`TryRecvError::Empty`].
+    pub(super) fn is_ready(&self) -> bool {
+        //SAFETY: nodes live until Inner::drop
+        let tail = unsafe { self.tail.load(Relaxed).as_ref() };
+        !tail.next.load(Acquire).is_null() || self.drop_count.load(Acquire) != 0
+    }
+
+    /// Makes [`unpark_receiver`](Inner::unpark_receiver) also wake `waker`.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::register`.
+    pub(super) unsafe fn register_receiver(&self, waker: Arc<Parker>) -> bool {
+        self.sender.park_receiver.register(waker)
+    }
+
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::unregister`.
+    pub(super) unsafe fn unregister_receiver(&self) {
+        self.sender.park_receiver.unregister()
+    }
+
+    pub(super) fn allocate(
+        wait: WaitStrategy,
+        max_cached: usize,
+        max_len: usize,
+    ) -> (InnerHolder<T>, InnerHolder<T>) {
+        Self::try_allocate(wait, max_cached, max_len).expect("failed to allocate memory")
+    }
+
+    pub(super) fn try_allocate(
+        wait: WaitStrategy,
+        max_cached: usize,
+        max_len: usize,
+    ) -> Result<(InnerHolder<T>, InnerHolder<T>), AllocError> {
+        let this = Self::try_new(wait, max_cached, max_len)?;
         //SAFETY: deallocated in InnerHolder::drop
         let store_self = unsafe { alloc::alloc(Layout::new::<Self>()) as *mut Self };
-        let store_self = NonNull::new(store_self).expect("failed to allocate memory");
+        let store_self = NonNull::new(store_self).ok_or(AllocError {})?;
         //SAFETY: checked for null and uninit
         unsafe { store_self.as_ptr().write(this) };
 
-        (InnerHolder(store_self), InnerHolder(store_self))
+        Ok((InnerHolder(store_self), InnerHolder(store_self)))
     }
 
-    fn new() -> Self {
+    fn try_new(wait: WaitStrategy, max_cached: usize, max_len: usize) -> Result<Self, AllocError> {
         let node = unsafe {
             //SAFETY: released in Drop
-            Node::create()
-        };
-        Self {
-            sender: CacheAligned::new(SenderData {
+            Node::try_create()
+        }
+        .ok_or(AllocError {})?;
+        Ok(Self {
+            sender: CachePadded::new(SenderData {
                 head: Cell::new(node),
                 next_for_reuse: Cell::new(node),
                 tail_cache: Cell::new(node),
+                sent: AtomicUsize::new(0),
+                nodes: Cell::new(1),
                 park_receiver: Parker::new(),
+                #[cfg(feature = "loom")]
+                force_refresh: Cell::new(false),
+                #[cfg(feature = "metrics")]
+                metrics_sent: Counter::new(),
+                #[cfg(feature = "metrics")]
+                full: Counter::new(),
+                #[cfg(feature = "metrics")]
+                max_depth: Counter::new(),
             }),
-            tail: CacheAligned::new(AtomicNonNull::new(node)),
+            tail: CachePadded::new(AtomicNonNull::new(node)),
             drop_count: AtomicUsize::new(0),
-        }
+            wait: Cell::new(wait),
+            max_cached,
+            max_len,
+            received: AtomicUsize::new(0),
+            #[cfg(feature = "metrics")]
+            metrics_received: Counter::new(),
+            #[cfg(feature = "metrics")]
+            empty: Counter::new(),
+        })
     }
 
     fn next_node_fast(&self) -> Option<NonNull<Node<T>>> {
@@ -164,19 +403,104 @@ impl<T> Inner<T> {
         }
     }
 
+    /// The first attempt of `next_node` and `try_next_node`.
+    ///
+    /// Models that called [`force_refresh`](Inner::force_refresh) always fail it,
+    /// so every new node goes through `refresh_tail_cache`.
+    #[inline(always)]
+    fn next_node_cached(&self) -> Option<NonNull<Node<T>>> {
+        #[cfg(feature = "loom")]
+        if self.sender.force_refresh.get() {
+            return None;
+        }
+        self.next_node_fast()
+    }
+
+    /// Makes the sender take the slow path of `next_node` on every send.
+    ///
+    /// Loom models rarely send enough values to use up the recycled
+    /// nodes, so without this they'd hardly ever cover node reuse.
+    /// Can only be called by the sender.
+    #[cfg(all(test, feature = "loom"))]
+    pub(super) fn force_refresh(&self) {
+        self.sender.force_refresh.set(true);
+    }
+
     fn next_node(&self) -> NonNull<Node<T>> {
-        match self.next_node_fast() {
+        match self.next_node_cached() {
             Some(p) => p,
             None => {
-                self.sender.tail_cache.set(self.tail.load(Acquire));
+                self.refresh_tail_cache();
                 match self.next_node_fast() {
                     Some(p) => p,
-                    //SAFETY: deallocated in `drop`
-                    None => unsafe { Node::create() },
+                    None => {
+                        self.sender.nodes.set(self.sender.nodes.get() + 1);
+                        //SAFETY: deallocated in `drop`
+                        unsafe { Node::create() }
+                    }
                 }
             }
         }
     }
+
+    /// Reloads `tail_cache` and deallocates recycled nodes beyond `max_cached`.
+    ///
+    /// Only called once the recycled nodes before the old `tail_cache` are used up,
+    /// unless a loom model called [`force_refresh`](Inner::force_refresh). Calling
+    /// it earlier is fine too, since `tail` never moves back.
+    fn refresh_tail_cache(&self) {
+        self.sender.tail_cache.set(self.tail.load(Acquire));
+        if self.max_cached == usize::MAX {
+            return;
+        }
+
+        let tail = self.sender.tail_cache.get();
+        let mut cached = 0usize;
+        let mut current = self.sender.next_for_reuse.get();
+        while current != tail {
+            cached += 1;
+            /*SAFETY:
+             * nodes before `tail_cache` have passed Receiver, and
+             * only the Sender has access to them. They have non null next.
+             */
+            current = unsafe { NonNull::new_unchecked(Node::with_mut_next(current, |x| *x)) };
+        }
+
+        let mut current = self.sender.next_for_reuse.get();
+        for _ in self.max_cached..cached {
+            //SAFETY: same as above.
+            let next = unsafe { NonNull::new_unchecked(Node::with_mut_next(current, |x| *x)) };
+            /*SAFETY:
+             * - nodes are only created with `create`
+             * - the node is unlinked from the list before being released
+             * - nodes before `tail_cache` have uninit values
+             */
+            unsafe { Node::release(current) };
+            self.sender.nodes.set(self.sender.nodes.get() - 1);
+            current = next;
+        }
+        self.sender.next_for_reuse.set(current);
+    }
+
+    /// Like `next_node`, but returns `None` if a new node can't be allocated.
+    fn try_next_node(&self) -> Option<NonNull<Node<T>>> {
+        self.next_node_cached().or_else(|| {
+            self.refresh_tail_cache();
+            self.next_node_fast().or_else(|| {
+                //SAFETY: deallocated in `drop`
+                let node = unsafe { Node::try_create() }?;
+                self.sender.nodes.set(self.sender.nodes.get() + 1);
+                Some(node)
+            })
+        })
+    }
+
+    /// Returns how many nodes are allocated, including recycled ones.
+    ///
+    /// Can only be called by the sender.
+    pub(super) fn allocated_nodes(&self) -> usize {
+        self.sender.nodes.get()
+    }
 }
 
 impl<T> Drop for Inner<T> {
@@ -217,10 +541,8 @@ impl<T> Drop for Inner<T> {
                 //SAFETY: current is still alive
                 let node = unsafe { current.as_mut() };
 
-                node.value.with_mut(|x| unsafe {
-                    //SAFETY: all values past tail have values
-                    (x as *mut T).drop_in_place()
-                });
+                //SAFETY: all values past tail have values
+                unsafe { (node.value_ptr() as *mut T).drop_in_place() };
 
                 #[cfg(not(feature = "loom"))]
                 break *node.next.get_mut();
@@ -245,6 +567,16 @@ impl<T> Drop for Inner<T> {
 
 impl<T> Node<T> {
     const LAYOUT: Layout = Layout::new::<Self>();
+
+    /// Returns a pointer to the node's value.
+    #[inline(always)]
+    fn value_ptr(&self) -> *mut MaybeUninit<T> {
+        #[cfg(not(feature = "loom"))]
+        return self.value.get();
+        #[cfg(feature = "loom")]
+        return self.value.with_mut(|ptr| ptr);
+    }
+
     /// Creates a new heap allocated node.
     ///
     /// # Safety
@@ -252,8 +584,17 @@ impl<T> Node<T> {
     /// If the returned node isn't later passed to
     /// `release`, the memory leaks.
     unsafe fn create() -> NonNull<Self> {
-        let res =
-            NonNull::new(alloc::alloc(Self::LAYOUT) as *mut Node<T>).expect("allocation failed");
+        Self::try_create().expect("allocation failed")
+    }
+
+    /// Like `create`, but returns `None` if allocation fails.
+    ///
+    /// # Safety
+    ///
+    /// If the returned node isn't later passed to
+    /// `release`, the memory leaks.
+    unsafe fn try_create() -> Option<NonNull<Self>> {
+        let res = NonNull::new(alloc::alloc(Self::LAYOUT) as *mut Node<T>)?;
         //SAFETY: allocated with correct layout and checked for null
         ptr::write(
             res.as_ptr(),
@@ -263,7 +604,7 @@ impl<T> Node<T> {
             },
         );
 
-        res
+        Some(res)
     }
 
     /// Releases the node.
@@ -301,7 +642,7 @@ impl<T> core::ops::Deref for InnerHolder<T> {
 
 impl<T> Drop for InnerHolder<T> {
     fn drop(&mut self) {
-        match self.drop_count.fetch_add(1, AcqRel) {
+        match self.drop_count.fetch_add(1, AcqRel) & !CLOSED {
             0 | 1 => { /*some references still exist*/ }
             2 => {
                 //happens only once, since drop count never decrements
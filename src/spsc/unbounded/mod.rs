@@ -1,10 +1,20 @@
+use crate::sync::CancellationToken;
 use crate::util::marker::PhantomUnsync;
 
 use std::{fmt::Debug, ops::Deref};
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll};
 
+mod builder;
 mod inner;
+pub use builder::Builder;
 
-pub use crate::error::{RecvError, SendError, TryRecvError};
+pub use crate::error::{
+    AllocError, RecvCancelError, RecvError, RecvTimeoutError, SendError, TryRecvError,
+    TrySendAllocError,
+};
 
 /// Creates an SPSC channel with unbounded capacity.
 ///
@@ -16,13 +26,37 @@ pub use crate::error::{RecvError, SendError, TryRecvError};
 ///
 /// This function panics if it can't allocate the inner state of the channel.
 pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
-    let (h1, h2) = inner::Inner::<T>::allocate();
-    (Sender(h1, PhantomUnsync {}), Receiver(h2, PhantomUnsync {}))
+    Builder::new().build()
+}
+
+/// Creates an unbounded SPSC channel that keeps at most `n` received nodes for reuse.
+///
+/// See [`Builder::max_cached_nodes`] for details.
+///
+/// # Panics
+///
+/// This function panics if it can't allocate the inner state of the channel.
+pub fn channel_with_max_cached_nodes<T>(n: usize) -> (Sender<T>, Receiver<T>) {
+    Builder::new().max_cached_nodes(n).build()
+}
+
+/// Like [`channel`], but returns an [`AllocError`] instead of panicking
+/// if the inner state of the channel can't be allocated.
+///
+/// To also handle allocation failures when sending, use [`Sender::try_send`].
+pub fn try_channel<T>() -> Result<(Sender<T>, Receiver<T>), AllocError> {
+    Builder::new().try_build()
 }
 
 /// The sending endpoint of a [`channel`].
 ///
 /// Data can be sent using the [`send`](Sender::send) method.
+///
+/// # Disconnection
+///
+/// Once a send fails because the [`Receiver`] disconnected, every later
+/// send fails the same way and gives the value back, so no value is
+/// silently lost.
 pub struct Sender<T>(inner::InnerHolder<T>, PhantomUnsync);
 
 /// The receiving endpoint of a [`channel`].
@@ -40,16 +74,209 @@ impl<T> Sender<T> {
     /// # Panics
     ///
     /// This function may panic if no more memory is available.
+    /// [`try_send`](Sender::try_send) reports that as
+    /// [`TrySendAllocError::OutOfMemory`] instead, giving the value back.
     #[inline]
     pub fn send(&self, item: T) -> Result<(), SendError<T>> {
         self.0.send(item)
     }
 
+    /// Sends a value through this [`channel`], passing it to `f`
+    /// instead if the [`Receiver`] is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    ///
+    /// let (src, sink) = unbounded::channel::<u32>();
+    /// drop(sink);
+    /// let mut lost = Vec::new();
+    /// src.send_or_else(1, |item| lost.push(item));
+    /// src.send_or_else(2, |item| lost.push(item));
+    /// assert_eq!(lost, [1, 2]);
+    /// ```
+    #[inline]
+    pub fn send_or_else(&self, item: T, f: impl FnOnce(T)) {
+        if let Err(SendError(item)) = self.send(item) {
+            f(item)
+        }
+    }
+
+    /// Like [`send`](Sender::send), but returns [`TrySendAllocError::OutOfMemory`]
+    /// instead of panicking if no more memory is available.
+    ///
+    /// If the [`channel`] has a soft bound set by [`Builder::max_len`],
+    /// returns [`TrySendAllocError::Full`] once it's reached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded::{try_channel, TrySendAllocError};
+    ///
+    /// let (src, sink) = try_channel::<u32>().expect("out of memory");
+    /// match src.try_send(1) {
+    ///     Ok(()) => assert_eq!(sink.recv(), Ok(1)),
+    ///     Err(TrySendAllocError::OutOfMemory(_) | TrySendAllocError::Full(_)) => { /*shed load*/ }
+    ///     Err(TrySendAllocError::Disconnected(_)) => unreachable!(),
+    /// }
+    /// ```
+    #[inline]
+    pub fn try_send(&self, item: T) -> Result<(), TrySendAllocError<T>> {
+        self.0.try_send(item)
+    }
+
+    /// Checks if `receiver` belongs to the same [`channel`] as this [`Sender`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    ///
+    /// let (src1, sink1) = unbounded::channel::<u32>();
+    /// let (_src2, sink2) = unbounded::channel::<u32>();
+    /// assert!(src1.same_channel(&sink1));
+    /// assert!(!src1.same_channel(&sink2));
+    /// ```
+    #[inline]
+    pub fn same_channel(&self, receiver: &Receiver<T>) -> bool {
+        std::ptr::eq(self.0.deref(), receiver.0.deref())
+    }
+
+    /// Returns an id of the [`channel`], shared by its [`Sender`] and [`Receiver`].
+    ///
+    /// The id is unique among live channels, but may be reused
+    /// once both end points of the [`channel`] are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    /// use std::collections::HashMap;
+    ///
+    /// let (src, sink) = unbounded::channel::<u32>();
+    /// let mut names = HashMap::new();
+    /// names.insert(sink.channel_id(), "events");
+    /// assert_eq!(names[&src.channel_id()], "events");
+    /// ```
+    #[inline]
+    pub fn channel_id(&self) -> usize {
+        crate::util::addr(self.0.deref())
+    }
+
     /// Checks if the [`channel`]'s [`Receiver`] is still connected.
     #[inline]
     pub fn receiver_connected(&self) -> bool {
         self.0.peer_connected()
     }
+
+    /// Returns a snapshot of the [`channel`]'s counters.
+    /// Enabled by the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::metrics::Stats {
+        self.0.stats()
+    }
+
+    /// Disconnects the [`channel`] without dropping the [`Sender`].
+    ///
+    /// The [`Receiver`] is woken up if it's blocked and can still receive
+    /// the values sent before closing. Any further sends fail like
+    /// they would if the [`Receiver`] disconnected.
+    pub fn close(&self) {
+        self.0.close();
+        self.0.unpark_receiver();
+    }
+
+    /// Checks if the [`channel`] was closed by either end point or disconnected.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        !self.0.peer_connected()
+    }
+
+    /// Returns how many sent values the [`Receiver`] didn't take yet.
+    ///
+    /// Along with [`allocated_nodes`](Sender::allocated_nodes), this lets an
+    /// application notice a consumer that can't keep up and shed load before
+    /// running out of memory. The count is exact when it's read, but the
+    /// [`Receiver`] may take more values right after.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    ///
+    /// let (src, sink) = unbounded::channel::<u32>();
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// assert_eq!(src.pending(), 2);
+    /// assert_eq!(sink.recv(), Ok(1));
+    /// assert_eq!(src.pending(), 1);
+    /// ```
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns how many nodes of the [`channel`]'s linked list are allocated.
+    ///
+    /// Every node holds one value, so the memory used by the [`channel`] grows
+    /// with the most values that were ever pending at once. Nodes of received
+    /// values are reused by later sends, and the ones beyond the limit set by
+    /// [`channel_with_max_cached_nodes`] or [`Builder::max_cached_nodes`] are
+    /// deallocated. There's always at least one node.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    ///
+    /// let (src, sink) = unbounded::channel::<u32>();
+    /// for i in 0..4 {
+    ///     src.send(i).unwrap();
+    /// }
+    /// assert_eq!(src.allocated_nodes(), 5);
+    /// assert_eq!(sink.try_iter().count(), 4);
+    /// // received nodes are reused.
+    /// src.send(4).unwrap();
+    /// assert_eq!(src.allocated_nodes(), 5);
+    /// ```
+    #[inline]
+    pub fn allocated_nodes(&self) -> usize {
+        self.0.allocated_nodes()
+    }
+
+    /// Takes back the values the [`Receiver`] didn't receive before disconnecting.
+    ///
+    /// Returns the [`Sender`] unchanged if the [`Receiver`] is still connected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    ///
+    /// let (src, sink) = unbounded::channel::<u32>();
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// assert_eq!(sink.recv(), Ok(1));
+    ///
+    /// let src = src.into_pending().unwrap_err();
+    /// drop(sink);
+    /// assert_eq!(src.into_pending().unwrap(), vec![2]);
+    /// ```
+    pub fn into_pending(self) -> Result<Vec<T>, Sender<T>> {
+        if !self.0.peer_dropped() {
+            return Err(self);
+        }
+        /*
+        The Receiver only disconnects in its last InnerHolder::drop call,
+        so it's gone and the Sender can take its place.
+        */
+        let mut pending = Vec::new();
+        while let Ok(item) = self.0.try_recv() {
+            pending.push(item);
+        }
+        Ok(pending)
+    }
 }
 
 impl<T> Receiver<T> {
@@ -65,6 +292,50 @@ impl<T> Receiver<T> {
         self.0.recv()
     }
 
+    /// Reads a value from the [`channel`], waiting at most `timeout` for the [`Sender`].
+    ///
+    /// Returns [`RecvTimeoutError::Timeout`] if the [`channel`] is still empty
+    /// after `timeout` and [`RecvTimeoutError::Disconnected`] like
+    /// [`recv`](Receiver::recv) returns [`RecvError`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    /// use std::time::Duration;
+    ///
+    /// let (src, sink) = unbounded::channel::<u32>();
+    /// assert_eq!(
+    ///     sink.recv_timeout(Duration::from_millis(10)),
+    ///     Err(unbounded::RecvTimeoutError::Timeout)
+    /// );
+    /// src.send(1).unwrap();
+    /// assert_eq!(sink.recv_timeout(Duration::from_millis(10)), Ok(1));
+    /// ```
+    pub fn recv_timeout(&self, timeout: std::time::Duration) -> Result<T, RecvTimeoutError> {
+        self.0
+            .recv_deadline(std::time::Instant::now().checked_add(timeout))
+    }
+
+    /// Reads a value from the [`channel`], blocking until one is sent,
+    /// the [`Sender`] disconnects or `token` is cancelled.
+    ///
+    /// Once `token` is cancelled, this returns [`RecvCancelError::Cancelled`]
+    /// right away, even if values are pending. They stay in the [`channel`] and
+    /// can still be received with the other methods.
+    pub fn recv_cancellable(&self, token: &CancellationToken) -> Result<T, RecvCancelError> {
+        loop {
+            if token.is_cancelled() {
+                return Err(RecvCancelError::Cancelled);
+            }
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Empty) => token.wait(self),
+                Err(_) => return Err(RecvCancelError::Disconnected),
+            }
+        }
+    }
+
     /// Tries to return a pending value.
     ///
     /// # Note
@@ -86,11 +357,293 @@ impl<T> Receiver<T> {
     pub fn sender_connected(&self) -> bool {
         self.0.peer_connected()
     }
+
+    /// Returns a snapshot of the [`channel`]'s counters.
+    /// Enabled by the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::metrics::Stats {
+        self.0.stats()
+    }
+
+    /// Sets a callback that's called every time the [`Sender`] sends
+    /// a value or disconnects, replacing the previous callback.
+    ///
+    /// This allows waiting for the [`channel`] in other event loops, for
+    /// example by waking a [`std::task::Waker`]. The callback runs on the
+    /// [`Sender`]'s thread, so it should return quickly. Values sent
+    /// before the callback was set don't call it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let (src, sink) = unbounded::channel::<u32>();
+    /// let sent = Arc::new(AtomicUsize::new(0));
+    /// let counter = sent.clone();
+    /// sink.set_notify(move || {
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// src.send(1).unwrap();
+    /// assert_eq!(sent.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn set_notify(&self, notify: impl Fn() + Send + Sync + 'static) {
+        self.0.set_receiver_notify(Some(Box::new(notify)))
+    }
+
+    /// Removes the callback set by [`set_notify`](Receiver::set_notify).
+    pub fn clear_notify(&self) {
+        self.0.set_receiver_notify(None)
+    }
+
+    /// Returns a file descriptor that becomes readable every time the
+    /// [`Sender`] sends a value or disconnects, replacing the
+    /// callback of [`set_notify`](Receiver::set_notify).
+    ///
+    /// Values sent before this call don't make the descriptor readable.
+    /// See [`NotifyFd`](crate::notify_fd::NotifyFd) for more.
+    #[cfg(all(unix, feature = "fd-notify"))]
+    pub fn notify_fd(&self) -> std::io::Result<crate::notify_fd::NotifyFd> {
+        let (fd, notify) = crate::notify_fd::NotifyFd::new()?;
+        self.0.set_receiver_notify(Some(Box::new(notify)));
+        Ok(fd)
+    }
+
+    /// Disconnects the [`channel`] without dropping the [`Receiver`].
+    ///
+    /// Any further sends fail, but values sent
+    /// before closing can still be received.
+    pub fn close(&self) {
+        //the Sender never blocks, so there's no one to wake.
+        self.0.close();
+    }
+
+    /// Checks if the [`channel`] was closed by either end point or disconnected.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        !self.0.peer_connected()
+    }
+
+    /// Sets how many times [`recv`](Receiver::recv) busy-waits
+    /// for the [`Sender`] before parking the thread.
+    ///
+    /// A short spin avoids the cost of parking when values are expected
+    /// to arrive quickly. See also [`Builder::spin`].
+    pub fn set_spin(&self, count: u32) {
+        self.0.set_receiver_spin(count)
+    }
+
+    /// Returns an id of the [`channel`], shared by its [`Sender`] and [`Receiver`].
+    ///
+    /// See [`Sender::channel_id`].
+    #[inline]
+    pub fn channel_id(&self) -> usize {
+        crate::util::addr(self.0.deref())
+    }
+
+    /// Checks if `sender` belongs to the same [`channel`] as this [`Receiver`].
+    #[inline]
+    pub fn same_channel(&self, sender: &Sender<T>) -> bool {
+        sender.same_channel(self)
+    }
+
+    /// Returns an iterator that blocks waiting for values.
+    ///
+    /// The iterator ends when the [`Sender`] disconnects and
+    /// all previously sent data is received.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator over the currently pending values.
+    ///
+    /// The iterator never blocks and ends when the [`channel`]
+    /// is empty or the [`Sender`] disconnects.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+}
+
+/// A blocking iterator over the values of a [`channel`].
+///
+/// Created by [`Receiver::iter`].
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+/// A non-blocking iterator over the pending values of a [`channel`].
+///
+/// Created by [`Receiver::try_iter`].
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+/// An owning blocking iterator over the values of a [`channel`].
+///
+/// Created by [`Receiver::into_iter`].
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+impl<T> crate::traits::TrySend<T> for Sender<T> {
+    type Error = TrySendAllocError<T>;
+
+    #[inline]
+    fn try_send(&self, item: T) -> Result<(), TrySendAllocError<T>> {
+        Sender::try_send(self, item)
+    }
+}
+
+impl<T> crate::traits::BlockingSend<T> for Sender<T> {
+    #[inline]
+    fn send(&self, item: T) -> Result<(), SendError<T>> {
+        Sender::send(self, item)
+    }
+}
+
+impl<T> crate::traits::TryRecv<T> for Receiver<T> {
+    #[inline]
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        Receiver::try_recv(self)
+    }
+}
+
+impl<T> crate::traits::BlockingRecv<T> for Receiver<T> {
+    #[inline]
+    fn recv(&self) -> Result<T, RecvError> {
+        Receiver::recv(self)
+    }
+}
+
+/// Enabled by the `futures` feature.
+///
+/// The [`Sender`] is always ready, since sending never blocks. Sending fails
+/// with a [`SendError`] once the [`channel`] is closed or disconnected, and
+/// [`poll_close`](futures_sink::Sink::poll_close) closes it, so a [`Receiver`]
+/// used as a [`Stream`](futures_core::Stream) ends.
+#[cfg(feature = "futures")]
+impl<T> futures_sink::Sink<T> for Sender<T> {
+    type Error = SendError<()>;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        if self.is_closed() {
+            Poll::Ready(Err(SendError(())))
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), SendError<()>> {
+        self.send(item).map_err(|_| SendError(()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        self.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Enabled by the `futures` feature.
+///
+/// Waiting for a value replaces the callback set by
+/// [`set_notify`](Receiver::set_notify) with one that wakes the task,
+/// while the [`Sender`] can still block on another thread.
+/// The stream ends once the [`channel`] is disconnected and empty.
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let ready = |receiver: &Self| match receiver.try_recv() {
+            Ok(item) => Poll::Ready(Some(item)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+            Err(_) => Poll::Ready(None),
+        };
+        if let Poll::Ready(item) = ready(&self) {
+            return Poll::Ready(item);
+        }
+        self.0.set_receiver_waker(cx.waker());
+        // a value sent before the waker was set didn't wake it.
+        ready(&self)
+    }
+}
+
+impl<T> crate::select::Selectable for Receiver<T> {}
+
+impl<T> crate::select::sealed::Sealed for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        self.0.is_ready()
+    }
+
+    unsafe fn register(&self, waker: crate::select::sealed::Waker) -> bool {
+        self.0.register_receiver(waker.0)
+    }
+
+    unsafe fn unregister(&self) {
+        self.0.unregister_receiver()
+    }
 }
 
 unsafe impl<T: Send> Send for Sender<T> {}
 unsafe impl<T: Send> Send for Receiver<T> {}
 
+// values are moved in and out whole, so an unwinding operation leaves the channel valid.
+impl<T> std::panic::UnwindSafe for Sender<T> {}
+impl<T> std::panic::RefUnwindSafe for Sender<T> {}
+impl<T> std::panic::UnwindSafe for Receiver<T> {}
+impl<T> std::panic::RefUnwindSafe for Receiver<T> {}
+
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
         use crate::sync::atomic::Ordering::AcqRel;
@@ -100,25 +653,53 @@ impl<T> Drop for Sender<T> {
     }
 }
 
+impl<T> PartialEq for Sender<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.channel_id() == other.channel_id()
+    }
+}
+
+impl<T> Eq for Sender<T> {}
+
+impl<T> std::hash::Hash for Sender<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.channel_id().hash(state)
+    }
+}
+
+impl<T> PartialEq for Receiver<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.channel_id() == other.channel_id()
+    }
+}
+
+impl<T> Eq for Receiver<T> {}
+
+impl<T> std::hash::Hash for Receiver<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.channel_id().hash(state)
+    }
+}
+
 impl<T> Debug for Sender<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "spsc::unbounded::Sender<{}> {{ channel: {:p} }}",
-            std::any::type_name::<T>(),
-            self.0.deref() as *const _
-        )
+        let name = format!("spsc::unbounded::Sender<{}>", std::any::type_name::<T>());
+        f.debug_struct(&name)
+            .field("channel", &(self.0.deref() as *const inner::Inner<T>))
+            .field("len", &self.pending())
+            .field("receiver_connected", &self.receiver_connected())
+            .finish()
     }
 }
 
 impl<T> Debug for Receiver<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "spsc::unbounded::Receiver<{}> {{ channel: {:p} }}",
-            std::any::type_name::<T>(),
-            self.0.deref() as *const _
-        )
+        let name = format!("spsc::unbounded::Receiver<{}>", std::any::type_name::<T>());
+        f.debug_struct(&name)
+            .field("channel", &(self.0.deref() as *const inner::Inner<T>))
+            .field("len", &self.0.len_hint())
+            .field("sender_connected", &self.sender_connected())
+            .finish()
     }
 }
 
@@ -28,6 +28,57 @@ fn order() {
     }
 }
 
+#[test]
+fn builder_busy_wait() {
+    let (src, sink) = super::Builder::new().park(false).build::<u32>();
+    std::thread::spawn(move || {
+        for i in 0..1000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..1000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    assert!(sink.recv().is_err());
+}
+
+#[test]
+fn try_send() {
+    let (src, sink) = super::try_channel::<u8>().unwrap();
+    assert_eq!(src.try_send(1), Ok(()));
+    assert_eq!(src.try_send(2), Ok(()));
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(src.try_send(3), Ok(()));
+    assert_eq!(sink.recv(), Ok(2));
+    assert_eq!(sink.recv(), Ok(3));
+    std::mem::drop(sink);
+    assert_eq!(src.try_send(4), Err(super::TrySendAllocError::Disconnected(4)));
+}
+
+#[test]
+fn max_cached_nodes() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    {
+        let (src, sink) = super::channel_with_max_cached_nodes(2);
+        for round in 0..5 {
+            for _ in 0..100 * round {
+                src.send(rc.clone()).unwrap();
+            }
+            for _ in 0..100 * round {
+                sink.recv().unwrap();
+            }
+        }
+        for _ in 0..10 {
+            src.send(rc.clone()).unwrap();
+        }
+        for _ in 0..5 {
+            sink.recv().unwrap();
+        }
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
 #[test]
 fn sender_dc() {
     let (src, sink) = super::channel::<()>();
@@ -53,6 +104,170 @@ fn receiver_dc() {
     src.send(()).unwrap_err();
 }
 
+#[test]
+fn iterators() {
+    let (src, sink) = super::channel::<i32>();
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    assert_eq!(sink.try_iter().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(sink.try_iter().next(), None);
+
+    std::thread::spawn(move || {
+        for i in 0..10 {
+            src.send(i).unwrap();
+        }
+    });
+    assert_eq!(sink.iter().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_recv_timeout() {
+    use super::RecvTimeoutError;
+    use std::time::Duration;
+    let (src, sink) = super::channel::<i32>();
+    assert_eq!(sink.recv_timeout(Duration::from_millis(10)), Err(RecvTimeoutError::Timeout));
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        src.send(1).unwrap();
+    });
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Ok(1));
+    handle.join().unwrap();
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Err(RecvTimeoutError::Disconnected));
+}
+
+#[test]
+fn st_max_len() {
+    use super::TrySendAllocError;
+    let (src, sink) = super::Builder::new().max_len(2).build::<i32>();
+    src.try_send(1).unwrap();
+    src.try_send(2).unwrap();
+    assert_eq!(src.try_send(3), Err(TrySendAllocError::Full(3)));
+    // send ignores the soft bound.
+    src.send(3).unwrap();
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(src.try_send(4), Err(TrySendAllocError::Full(4)));
+    assert_eq!(sink.recv(), Ok(2));
+    assert_eq!(sink.recv(), Ok(3));
+    src.try_send(4).unwrap();
+    std::mem::drop(sink);
+    assert_eq!(src.try_send(5), Err(TrySendAllocError::Disconnected(5)));
+}
+
+#[test]
+fn st_pending_and_nodes() {
+    let (src, sink) = super::channel_with_max_cached_nodes::<i32>(1);
+    assert_eq!((src.pending(), src.allocated_nodes()), (0, 1));
+    for i in 0..4 {
+        src.send(i).unwrap();
+    }
+    assert_eq!((src.pending(), src.allocated_nodes()), (4, 5));
+    assert_eq!(sink.try_iter().count(), 4);
+    assert_eq!(src.pending(), 0);
+    // recycled nodes beyond the limit are deallocated by the next send.
+    src.try_send(4).unwrap();
+    assert_eq!((src.pending(), src.allocated_nodes()), (1, 2));
+    assert_eq!(sink.recv(), Ok(4));
+    src.send(5).unwrap();
+    assert_eq!(src.allocated_nodes(), 2);
+}
+
+#[test]
+fn st_debug() {
+    let (src, sink) = super::channel::<i32>();
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    assert_eq!(sink.recv(), Ok(1));
+    assert!(format!("{:?}", src).ends_with("len: 1, receiver_connected: true }"));
+    std::mem::drop(src);
+    assert!(format!("{:?}", sink).ends_with("len: 1, sender_connected: false }"));
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn st_stats() {
+    use super::TrySendAllocError;
+    let (src, sink) = super::Builder::new().max_len(1).build::<i32>();
+    assert_eq!(sink.try_recv(), Err(super::TryRecvError::Empty));
+    src.try_send(1).unwrap();
+    assert_eq!(src.try_send(2), Err(TrySendAllocError::Full(2)));
+    src.send(2).unwrap();
+    assert_eq!(sink.recv(), Ok(1));
+    let stats = sink.stats();
+    assert_eq!(stats, src.stats());
+    assert_eq!((stats.sent, stats.received), (2, 1));
+    assert_eq!((stats.full, stats.empty, stats.sender_parks), (1, 1, 0));
+    assert_eq!(stats.max_depth, 2);
+}
+
+#[test]
+fn mt_max_len() {
+    use super::TrySendAllocError;
+    let (src, sink) = super::Builder::new().max_len(8).build::<i32>();
+    let handle = std::thread::spawn(move || {
+        for i in 0..10_000 {
+            let mut item = i;
+            loop {
+                match src.try_send(item) {
+                    Ok(()) => break,
+                    Err(TrySendAllocError::Full(ret)) => item = ret,
+                    Err(_) => unreachable!(),
+                }
+                std::thread::yield_now();
+            }
+        }
+    });
+    for i in 0..10_000 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    handle.join().unwrap();
+}
+
+#[test]
+fn st_close() {
+    let (src, sink) = super::channel::<i32>();
+    src.send(1).unwrap();
+    src.close();
+    assert!(src.is_closed() && sink.is_closed());
+    assert_eq!(src.send(2), Err(super::SendError(2)));
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.recv(), Err(super::RecvError {}));
+    let src = src.into_pending().unwrap_err();
+    std::mem::drop(sink);
+    assert_eq!(src.into_pending().unwrap(), Vec::<i32>::new());
+
+    let (src, sink) = super::channel::<i32>();
+    sink.close();
+    assert_eq!(src.send(1), Err(super::SendError(1)));
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn mt_sink_stream() {
+    use futures::executor::block_on;
+    use futures::{stream, SinkExt, StreamExt};
+    let (mut src, sink) = super::channel::<u32>();
+    let handle = std::thread::spawn(move || block_on(sink.collect::<Vec<_>>()));
+    block_on(SinkExt::send_all(&mut src, &mut stream::iter(0..1000).map(Ok))).unwrap();
+    block_on(SinkExt::close(&mut src)).unwrap();
+    assert!(src.is_closed());
+    assert_eq!(handle.join().unwrap(), (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_set_notify() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let (src, sink) = super::channel::<i32>();
+    let notified = Arc::new(AtomicUsize::new(0));
+    let counter = notified.clone();
+    sink.set_notify(move || {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+    src.send(1).unwrap();
+    std::mem::drop(src);
+    assert_eq!(notified.load(Ordering::Relaxed), 2);
+}
+
 }
 
 cfg_loom! {
@@ -78,6 +293,41 @@ fn drop() {
     });
 }
 
+#[test]
+fn disconnect_is_permanent() {
+    loom::model(|| {
+        let (src, sink) = super::channel::<u8>();
+        loom::thread::spawn(move || std::mem::drop(sink));
+        let mut disconnected = false;
+        for i in 0..3 {
+            match src.send(i) {
+                Ok(()) => assert!(!disconnected, "sent after a disconnect"),
+                Err(err) => {
+                    assert_eq!(err.into_inner(), i);
+                    disconnected = true;
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn max_len() {
+    loom::model(|| {
+        use super::TrySendAllocError;
+        let (src, sink) = super::Builder::new().max_len(1).build::<u8>();
+        src.try_send(0).unwrap();
+        let handle = loom::thread::spawn(move || (sink.recv(), sink));
+        match src.try_send(1) {
+            // the receiver took the first value, so it's the only one pending.
+            Ok(()) => assert_eq!(src.try_send(2), Err(TrySendAllocError::Full(2))),
+            Err(TrySendAllocError::Full(1)) => {}
+            Err(_) => unreachable!(),
+        }
+        assert_eq!(handle.join().unwrap().0, Ok(0));
+    });
+}
+
 #[test]
 fn order() {
     let mut builder = loom::model::Builder::new();
@@ -96,6 +346,24 @@ fn order() {
     });
 }
 
+#[test]
+fn max_cached_nodes() {
+    let mut builder = loom::model::Builder::new();
+    builder.max_threads = 2;
+    builder.preemption_bound = Some(4);
+    builder.check(|| {
+        let (src, sink) = super::channel_with_max_cached_nodes::<u8>(0);
+        loom::thread::spawn(move || {
+            for i in 0..3 {
+                src.send(i).unwrap();
+            }
+        });
+        for i in 0..3 {
+            assert_eq!(sink.recv().unwrap_or_else(|_| panic!("failed at {i}")), i);
+        }
+    });
+}
+
 #[test]
 fn sender_dc() {
     loom::model(|| {
@@ -128,4 +396,79 @@ fn receiver_dc() {
     });
 }
 
+
+/// Models of node reuse, with [`force_refresh`](super::inner::Inner::force_refresh)
+/// making every send go through the slow path of `next_node`.
+mod reuse {
+    use loom::sync::Arc;
+
+    fn model(f: impl Fn() + Sync + Send + 'static) {
+        let mut builder = loom::model::Builder::new();
+        builder.max_threads = 2;
+        builder.preemption_bound = Some(3);
+        builder.check(f);
+    }
+
+    #[test]
+    fn wraparound() {
+        for max_cached in [0, 1, usize::MAX] {
+            model(move || {
+                let (src, sink) = super::super::channel_with_max_cached_nodes::<u8>(max_cached);
+                src.0.force_refresh();
+                let handle = loom::thread::spawn(move || {
+                    for i in 0..4 {
+                        src.send(i).unwrap();
+                    }
+                });
+                for i in 0..4 {
+                    assert_eq!(sink.recv(), Ok(i));
+                }
+                handle.join().unwrap();
+            });
+        }
+    }
+
+    #[test]
+    fn disconnect() {
+        model(|| {
+            let arc = Arc::new(());
+            {
+                let (src, sink) = super::super::channel_with_max_cached_nodes(1);
+                src.0.force_refresh();
+                let handle = loom::thread::spawn(move || {
+                    let _ = sink.recv();
+                });
+                for _ in 0..3 {
+                    if src.send(arc.clone()).is_err() {
+                        break;
+                    }
+                }
+                handle.join().unwrap();
+            }
+            assert_eq!(Arc::strong_count(&arc), 1);
+        });
+    }
+
+    #[test]
+    fn drop_pending() {
+        model(|| {
+            let arc = Arc::new(());
+            {
+                let (src, sink) = super::super::channel();
+                src.0.force_refresh();
+                src.send(arc.clone()).unwrap();
+                let handle = loom::thread::spawn(move || {
+                    assert!(sink.recv().is_ok());
+                    sink
+                });
+                src.send(arc.clone()).unwrap();
+                src.send(arc.clone()).unwrap();
+                std::mem::drop(src);
+                std::mem::drop(handle.join().unwrap());
+            }
+            assert_eq!(Arc::strong_count(&arc), 1);
+        });
+    }
+}
+
 }
@@ -0,0 +1,511 @@
+use crate::alloc::{self, Layout};
+use crate::cell::UnsafeCell;
+use crate::error::{AllocError, RecvError, RecvTimeoutError, SendError, TryRecvError};
+use crate::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+use crate::sync::atomic::{AtomicPtr, AtomicUsize};
+use crate::sync::Arc;
+
+use crate::util::cache::CachePadded;
+use crate::util::park::{Notify, Parker};
+
+use std::cell::Cell;
+use std::mem::MaybeUninit;
+use std::ptr::{self, NonNull};
+use std::time::Instant;
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{Counter, Stats};
+
+/// The number of values stored in a [`Block`].
+#[cfg(not(feature = "loom"))]
+pub(super) const BLOCK_CAP: usize = 32;
+// small blocks, so that loom tests cross block boundaries.
+#[cfg(feature = "loom")]
+pub(super) const BLOCK_CAP: usize = 2;
+
+/// The bit of `drop_count` set by `close`.
+const CLOSED: usize = 1 << (usize::BITS - 1);
+
+/*
+ * unbounded_chunked::channel uses a singly-linked list of blocks:
+ *
+ * `receiver.head_block` -> ... -> `sender.tail_block`
+ *
+ * Every sent value gets a position, which counts up from 0. Position `p`
+ * is stored in slot `p % BLOCK_CAP` of its block, and a new block is entered
+ * whenever `p % BLOCK_CAP == 0`. This is also true for position 0, so the
+ * first block never holds any values.
+ *
+ * When the receiver leaves a block, it's stored in `spare`, so that the sender
+ * can reuse it instead of allocating a new block.
+ */
+pub(super) struct Inner<T> {
+    sender: CachePadded<SenderData<T>>,
+    receiver: CachePadded<ReceiverData<T>>,
+    /// A block left by the receiver, taken by the sender when it needs a new one.
+    spare: AtomicPtr<Block<T>>,
+    // Sender "drops" twice, to allow unpark with drop_count != 0.
+    // The CLOSED bit is set by `close` and ignored when counting drops.
+    pub(super) drop_count: AtomicUsize,
+}
+
+struct SenderData<T> {
+    /// The position of the next sent value.
+    tail: AtomicUsize,
+    tail_block: Cell<NonNull<Block<T>>>,
+    park_receiver: Parker,
+    #[cfg(feature = "metrics")]
+    sent: Counter,
+}
+
+struct ReceiverData<T> {
+    /// The position of the next received value.
+    head: Cell<usize>,
+    tail_cache: Cell<usize>,
+    head_block: Cell<NonNull<Block<T>>>,
+    #[cfg(feature = "metrics")]
+    received: Counter,
+    #[cfg(feature = "metrics")]
+    empty: Counter,
+    /// The most values pending when `tail_cache` was refreshed.
+    #[cfg(feature = "metrics")]
+    max_depth: Counter,
+}
+
+struct Block<T> {
+    next: AtomicPtr<Block<T>>,
+    slots: [UnsafeCell<MaybeUninit<T>>; BLOCK_CAP],
+}
+
+impl<T> Inner<T> {
+    pub(super) fn peer_connected(&self) -> bool {
+        self.drop_count.load(Acquire) == 0
+    }
+
+    /// Checks if the other end point was dropped, ignoring [`close`](Inner::close).
+    pub(super) fn peer_dropped(&self) -> bool {
+        self.drop_count.load(Acquire) & !CLOSED != 0
+    }
+
+    /// Disconnects the channel without dropping an end point.
+    pub(super) fn close(&self) {
+        self.drop_count.fetch_or(CLOSED, AcqRel);
+    }
+
+    pub(super) fn send(&self, item: T) -> Result<(), SendError<T>> {
+        if self.drop_count.load(Relaxed) != 0 {
+            return Err(SendError(item));
+        }
+        #[cfg(not(feature = "loom"))]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.as_ptr().read() };
+        #[cfg(feature = "loom")]
+        //SAFETY: tail is only modified by the sender.
+        let tail = unsafe { self.sender.tail.unsync_load() };
+
+        let offset = tail % BLOCK_CAP;
+        if offset == 0 {
+            let block = self.next_block();
+            //SAFETY: blocks live until they're released by the receiver.
+            let old = unsafe { self.sender.tail_block.replace(block).as_ref() };
+            /*
+            Relaxed is enough, since the receiver only follows `next`
+            after seeing the `tail` store below.
+            */
+            old.next.store(block.as_ptr(), Relaxed);
+        }
+
+        //SAFETY: tail_block lives until the receiver leaves it.
+        let block = unsafe { self.sender.tail_block.get().as_ref() };
+        block.slots[offset].with_mut(|slot| unsafe {
+            /*SAFETY:
+             * - the slot at `tail` isn't accessed by the receiver until
+             *   `tail` is incremented.
+             * - MaybeUninit<T> has the same layout as T
+             */
+            (slot as *mut T).write(item)
+        });
+        self.sender.tail.store(tail.wrapping_add(1), Release);
+        metrics! { self.sender.sent.add(1); }
+
+        self.unpark_receiver();
+        Ok(())
+    }
+
+    pub(super) fn try_recv(&self) -> Result<T, TryRecvError> {
+        let head = self.receiver.head.get();
+
+        if head == self.receiver.tail_cache.get() {
+            self.receiver.tail_cache.set(self.sender.tail.load(Acquire));
+            metrics! {
+                let depth = self.receiver.tail_cache.get().wrapping_sub(head);
+                self.receiver.max_depth.max(depth as u64);
+            }
+            if head == self.receiver.tail_cache.get() {
+                if self.drop_count.load(Acquire) == 0 {
+                    metrics! { self.receiver.empty.add(1); }
+                    return Err(TryRecvError::Empty);
+                }
+                self.receiver.tail_cache.set(self.sender.tail.load(Acquire));
+                if head == self.receiver.tail_cache.get() {
+                    return Err(TryRecvError::Disconnected);
+                }
+            }
+        }
+
+        let offset = head % BLOCK_CAP;
+        if offset == 0 {
+            self.advance_head_block();
+        }
+
+        //SAFETY: head_block lives until the receiver leaves it.
+        let block = unsafe { self.receiver.head_block.get().as_ref() };
+        let ret = block.slots[offset].with_mut(|slot| unsafe {
+            //SAFETY: values before tail have been written by the sender.
+            slot.read().assume_init()
+        });
+        self.receiver.head.set(head.wrapping_add(1));
+        metrics! { self.receiver.received.add(1); }
+
+        Ok(ret)
+    }
+
+    pub(super) fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(t) => return Ok(t),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                Err(TryRecvError::Empty) => unsafe {
+                    //SAFETY: only Receiver parks and it's !Copy + !Clone + !Sync
+                    self.park(None);
+                },
+            }
+        }
+    }
+
+    /// Like [`recv`](Inner::recv), but gives up at `deadline`.
+    pub(super) fn recv_deadline(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        loop {
+            match self.try_recv() {
+                Ok(t) => return Ok(t),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Poisoned) => return Err(RecvTimeoutError::Poisoned),
+                Err(TryRecvError::Empty) => {}
+            }
+            let deadline = match deadline {
+                Some(deadline) => deadline,
+                None => {
+                    //SAFETY: only Receiver parks and it's !Copy + !Clone + !Sync
+                    unsafe { self.park(None) };
+                    continue;
+                }
+            };
+            if Instant::now() >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            //SAFETY: only Receiver parks and it's !Copy + !Clone + !Sync
+            unsafe { self.park(Some(deadline)) };
+        }
+    }
+
+    /// Parks the receiver until it's woken up or `deadline` passes.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver.
+    #[inline(always)]
+    unsafe fn park(&self, deadline: Option<Instant>) {
+        let park = || match deadline {
+            Some(deadline) => {
+                self.sender.park_receiver.park_deadline(deadline);
+            }
+            None => self.sender.park_receiver.park(),
+        };
+        #[cfg(feature = "tracing")]
+        // the receiver only parks on an empty queue.
+        return crate::util::trace::parked("recv", crate::util::addr(self), None, 0, park);
+        #[cfg(not(feature = "tracing"))]
+        park()
+    }
+
+    /// Returns a snapshot of the channel's counters.
+    #[cfg(feature = "metrics")]
+    pub(super) fn stats(&self) -> Stats {
+        Stats {
+            sent: self.sender.sent.get(),
+            received: self.receiver.received.get(),
+            // the channel is never full and the sender never blocks.
+            full: 0,
+            empty: self.receiver.empty.get(),
+            sender_parks: 0,
+            receiver_parks: self.sender.park_receiver.parks(),
+            max_depth: self.receiver.max_depth.get(),
+            sender_park_time: Duration::ZERO,
+            receiver_park_time: self.sender.park_receiver.park_time(),
+        }
+    }
+
+    pub(super) fn unpark_receiver(&self) {
+        self.sender.park_receiver.unpark();
+    }
+
+    /// Makes [`unpark_receiver`](Inner::unpark_receiver) also call `notify`.
+    pub(super) fn set_receiver_notify(&self, notify: Option<Notify>) {
+        self.sender.park_receiver.set_notify(notify);
+    }
+
+    /// Makes [`unpark_receiver`](Inner::unpark_receiver) also wake `waker`,
+    /// replacing the callback set by [`set_receiver_notify`](Inner::set_receiver_notify).
+    #[cfg(feature = "futures")]
+    pub(super) fn set_receiver_waker(&self, waker: &std::task::Waker) {
+        self.sender.park_receiver.set_waker(waker);
+    }
+
+    /// Returns a lower bound of how many values are pending.
+    ///
+    /// Can only be called by the receiver.
+    pub(super) fn len_hint(&self) -> usize {
+        self.sender
+            .tail
+            .load(Acquire)
+            .wrapping_sub(self.receiver.head.get())
+    }
+
+    /// Checks if `try_recv` would return something other than [`TryRecvError::Empty`].
+    pub(super) fn is_ready(&self) -> bool {
+        self.receiver.head.get() != self.sender.tail.load(Acquire)
+            || self.drop_count.load(Acquire) != 0
+    }
+
+    /// Makes [`unpark_receiver`](Inner::unpark_receiver) also wake `waker`.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::register`.
+    pub(super) unsafe fn register_receiver(&self, waker: Arc<Parker>) -> bool {
+        self.sender.park_receiver.register(waker)
+    }
+
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::unregister`.
+    pub(super) unsafe fn unregister_receiver(&self) {
+        self.sender.park_receiver.unregister()
+    }
+
+    pub(super) fn try_allocate() -> Result<(InnerHolder<T>, InnerHolder<T>), AllocError> {
+        let this = Self::try_new()?;
+        //SAFETY: deallocated in InnerHolder::drop
+        let store_self = unsafe { alloc::alloc(Layout::new::<Self>()) as *mut Self };
+        let store_self = NonNull::new(store_self).ok_or(AllocError {})?;
+        //SAFETY: checked for null and uninit
+        unsafe { store_self.as_ptr().write(this) };
+
+        Ok((InnerHolder(store_self), InnerHolder(store_self)))
+    }
+
+    fn try_new() -> Result<Self, AllocError> {
+        //SAFETY: released in Drop
+        let block = unsafe { Block::try_create() }.ok_or(AllocError {})?;
+        Ok(Self {
+            sender: CachePadded::new(SenderData {
+                tail: AtomicUsize::new(0),
+                tail_block: Cell::new(block),
+                park_receiver: Parker::new(),
+                #[cfg(feature = "metrics")]
+                sent: Counter::new(),
+            }),
+            receiver: CachePadded::new(ReceiverData {
+                head: Cell::new(0),
+                tail_cache: Cell::new(0),
+                head_block: Cell::new(block),
+                #[cfg(feature = "metrics")]
+                received: Counter::new(),
+                #[cfg(feature = "metrics")]
+                empty: Counter::new(),
+                #[cfg(feature = "metrics")]
+                max_depth: Counter::new(),
+            }),
+            spare: AtomicPtr::new(ptr::null_mut()),
+            drop_count: AtomicUsize::new(0),
+        })
+    }
+
+    /// Returns an empty block for the sender, reusing `spare` if possible.
+    fn next_block(&self) -> NonNull<Block<T>> {
+        match NonNull::new(self.spare.swap(ptr::null_mut(), Acquire)) {
+            Some(block) => {
+                /*SAFETY:
+                 * the receiver left the block before storing it in `spare`,
+                 * so the sender has exclusive access to it.
+                 */
+                unsafe { Block::with_mut_next(block, |next| *next = ptr::null_mut()) };
+                block
+            }
+            //SAFETY: released by the receiver or in Drop.
+            None => unsafe { Block::create() },
+        }
+    }
+
+    /// Moves the receiver to the next block and stores the old one in `spare`.
+    fn advance_head_block(&self) {
+        let old = self.receiver.head_block.get();
+        //SAFETY: blocks live until they're released by the receiver.
+        let next = unsafe { old.as_ref() }.next.load(Relaxed);
+        /*SAFETY:
+         * the sender links the next block before sending the value at
+         * a block boundary, and that value was already observed.
+         */
+        let next = unsafe {
+            debug_assert!(!next.is_null());
+            NonNull::new_unchecked(next)
+        };
+        self.receiver.head_block.set(next);
+
+        let prev = self.spare.swap(old.as_ptr(), AcqRel);
+        if let Some(prev) = NonNull::new(prev) {
+            /*SAFETY:
+             * - blocks are only created with `create`
+             * - `prev` was taken out of `spare`, so nobody else has it.
+             * - all values in `prev` were already received.
+             */
+            unsafe { Block::release(prev) };
+        }
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        let mut head = self.receiver.head.get();
+        let tail = self.sender.tail.load(Relaxed);
+
+        while head != tail {
+            let offset = head % BLOCK_CAP;
+            if offset == 0 {
+                self.advance_head_block();
+            }
+            //SAFETY: drop has exclusive access to this Inner
+            let block = unsafe { self.receiver.head_block.get().as_ref() };
+            block.slots[offset].with_mut(|slot| unsafe {
+                //SAFETY: all values in [head, tail) have been sent, but not received.
+                (slot as *mut T).drop_in_place()
+            });
+            head = head.wrapping_add(1);
+        }
+
+        let mut current = Some(self.receiver.head_block.get());
+        while let Some(block) = current {
+            //SAFETY: drop has exclusive access to this Inner
+            current = NonNull::new(unsafe { Block::with_mut_next(block, |x| *x) });
+            /*SAFETY:
+             * - blocks are only created with `create`
+             * - blocks after `head_block` haven't been released
+             * - all values were dropped above
+             */
+            unsafe { Block::release(block) };
+        }
+
+        if let Some(spare) = NonNull::new(self.spare.swap(ptr::null_mut(), Relaxed)) {
+            //SAFETY: same as above, the spare block isn't linked after `head_block`.
+            unsafe { Block::release(spare) };
+        }
+    }
+}
+
+impl<T> Block<T> {
+    const LAYOUT: Layout = Layout::new::<Self>();
+
+    /// Creates a new heap allocated block.
+    ///
+    /// # Safety
+    ///
+    /// If the returned block isn't later passed to
+    /// `release`, the memory leaks.
+    unsafe fn create() -> NonNull<Self> {
+        Self::try_create().expect("allocation failed")
+    }
+
+    /// Like `create`, but returns `None` if allocation fails.
+    ///
+    /// # Safety
+    ///
+    /// If the returned block isn't later passed to
+    /// `release`, the memory leaks.
+    unsafe fn try_create() -> Option<NonNull<Self>> {
+        let res = NonNull::new(alloc::alloc(Self::LAYOUT) as *mut Block<T>)?;
+        /*SAFETY:
+         * allocated with correct layout and checked for null. The slots
+         * are MaybeUninit, so uninitialised data is a valid value for them.
+         */
+        #[cfg(not(feature = "loom"))]
+        ptr::addr_of_mut!((*res.as_ptr()).next).write(AtomicPtr::new(ptr::null_mut()));
+        /*
+        In loom, UnsafeCell::new(MaybeUninit::uninit()) isn't uninitialised memory.
+        See spsc::bounded::Inner::new.
+        */
+        #[cfg(feature = "loom")]
+        ptr::write(
+            res.as_ptr(),
+            Block {
+                next: AtomicPtr::new(ptr::null_mut()),
+                slots: std::array::from_fn(|_| UnsafeCell::new(MaybeUninit::uninit())),
+            },
+        );
+
+        Some(res)
+    }
+
+    /// Releases the block.
+    ///
+    /// # Safety
+    ///
+    /// - Must have been `create`d and not `release`d before.
+    /// - The caller is responsible for dropping the values.
+    #[inline(always)]
+    unsafe fn release(block: NonNull<Self>) {
+        alloc::dealloc(block.as_ptr() as *mut u8, Self::LAYOUT);
+    }
+
+    /// # Safety
+    ///
+    /// - `block`.as_mut must be valid (See [`core::ptr::NonNull`])
+    #[inline(always)]
+    unsafe fn with_mut_next<R>(block: NonNull<Self>, f: impl FnOnce(&mut *mut Self) -> R) -> R {
+        #[cfg(not(feature = "loom"))]
+        return f((*block.as_ptr()).next.get_mut());
+        #[cfg(feature = "loom")]
+        return (*block.as_ptr()).next.with_mut(f);
+    }
+}
+
+pub(super) struct InnerHolder<T>(NonNull<Inner<T>>);
+
+impl<T> core::ops::Deref for InnerHolder<T> {
+    type Target = Inner<T>;
+    fn deref(&self) -> &Self::Target {
+        //SAFETY: Valid at least until InnerHolder::drop
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<T> Drop for InnerHolder<T> {
+    fn drop(&mut self) {
+        match self.drop_count.fetch_add(1, AcqRel) & !CLOSED {
+            0 | 1 => { /*some references still exist*/ }
+            2 => {
+                //happens only once, since drop count never decrements
+
+                let inner_ptr = self.0.as_ptr();
+                //SAFETY: inner still lives, happens once
+                unsafe { inner_ptr.drop_in_place() };
+                //SAFETY: allocated in Inner::try_allocate, happens once
+                unsafe { alloc::dealloc(inner_ptr as *mut _, Layout::new::<Inner<T>>()) };
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,626 @@
+use crate::sync::CancellationToken;
+use crate::util::marker::PhantomUnsync;
+
+use std::{fmt::Debug, ops::Deref};
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll};
+
+mod inner;
+
+pub use crate::error::{
+    AllocError, RecvCancelError, RecvError, RecvTimeoutError, SendError, TryRecvError,
+};
+
+/// Creates an SPSC channel with unbounded capacity.
+///
+/// Values are stored in blocks of several slots, so only
+/// one allocation is needed for every block of sent values.
+/// If items are received slower than they are sent, [`send`](Sender::send)
+/// will eventually consume all available memory and panic.
+///
+/// # Panics
+///
+/// This function panics if it can't allocate the inner state of the channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    try_channel().expect("failed to allocate memory")
+}
+
+/// Like [`channel`], but returns an [`AllocError`] instead of panicking
+/// if the inner state of the channel can't be allocated.
+pub fn try_channel<T>() -> Result<(Sender<T>, Receiver<T>), AllocError> {
+    let (h1, h2) = inner::Inner::<T>::try_allocate()?;
+    Ok((Sender(h1, PhantomUnsync {}), Receiver(h2, PhantomUnsync {})))
+}
+
+/// The sending endpoint of a [`channel`].
+///
+/// Data can be sent using the [`send`](Sender::send) method.
+///
+/// # Disconnection
+///
+/// Once a send fails because the [`Receiver`] disconnected, every later
+/// send fails the same way and gives the value back, so no value is
+/// silently lost.
+pub struct Sender<T>(inner::InnerHolder<T>, PhantomUnsync);
+
+/// The receiving endpoint of a [`channel`].
+///
+/// Data can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<T>(inner::InnerHolder<T>, PhantomUnsync);
+
+impl<T> Sender<T> {
+    /// Sends a value through this [`channel`].
+    ///
+    /// It does not block by itself, but it allocates a new block
+    /// whenever the previous one is full and none can be reused,
+    /// so [`send`](Sender::send) is **not** real-time safe by any means.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if no more memory is available.
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.0.send(item)
+    }
+
+    /// Sends a value through this [`channel`], passing it to `f`
+    /// instead if the [`Receiver`] is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded_chunked;
+    ///
+    /// let (src, sink) = unbounded_chunked::channel::<u32>();
+    /// drop(sink);
+    /// let mut lost = Vec::new();
+    /// src.send_or_else(1, |item| lost.push(item));
+    /// src.send_or_else(2, |item| lost.push(item));
+    /// assert_eq!(lost, [1, 2]);
+    /// ```
+    #[inline]
+    pub fn send_or_else(&self, item: T, f: impl FnOnce(T)) {
+        if let Err(SendError(item)) = self.send(item) {
+            f(item)
+        }
+    }
+
+    /// Checks if `receiver` belongs to the same [`channel`] as this [`Sender`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded_chunked;
+    ///
+    /// let (src1, sink1) = unbounded_chunked::channel::<u32>();
+    /// let (_src2, sink2) = unbounded_chunked::channel::<u32>();
+    /// assert!(src1.same_channel(&sink1));
+    /// assert!(!src1.same_channel(&sink2));
+    /// ```
+    #[inline]
+    pub fn same_channel(&self, receiver: &Receiver<T>) -> bool {
+        std::ptr::eq(self.0.deref(), receiver.0.deref())
+    }
+
+    /// Returns an id of the [`channel`], shared by its [`Sender`] and [`Receiver`].
+    ///
+    /// The id is unique among live channels, but may be reused
+    /// once both end points of the [`channel`] are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded_chunked;
+    /// use std::collections::HashMap;
+    ///
+    /// let (src, sink) = unbounded_chunked::channel::<u32>();
+    /// let mut names = HashMap::new();
+    /// names.insert(sink.channel_id(), "events");
+    /// assert_eq!(names[&src.channel_id()], "events");
+    /// ```
+    #[inline]
+    pub fn channel_id(&self) -> usize {
+        crate::util::addr(self.0.deref())
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.0.peer_connected()
+    }
+
+    /// Returns a snapshot of the [`channel`]'s counters.
+    /// Enabled by the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::metrics::Stats {
+        self.0.stats()
+    }
+
+    /// Disconnects the [`channel`] without dropping the [`Sender`].
+    ///
+    /// The [`Receiver`] is woken up if it's blocked and can still receive
+    /// the values sent before closing. Any further sends fail like
+    /// they would if the [`Receiver`] disconnected.
+    pub fn close(&self) {
+        self.0.close();
+        self.0.unpark_receiver();
+    }
+
+    /// Checks if the [`channel`] was closed by either end point or disconnected.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        !self.0.peer_connected()
+    }
+
+    /// Takes back the values the [`Receiver`] didn't receive before disconnecting.
+    ///
+    /// Returns the [`Sender`] unchanged if the [`Receiver`] is still connected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded_chunked;
+    ///
+    /// let (src, sink) = unbounded_chunked::channel::<u32>();
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// assert_eq!(sink.recv(), Ok(1));
+    ///
+    /// let src = src.into_pending().unwrap_err();
+    /// drop(sink);
+    /// assert_eq!(src.into_pending().unwrap(), vec![2]);
+    /// ```
+    pub fn into_pending(self) -> Result<Vec<T>, Sender<T>> {
+        if !self.0.peer_dropped() {
+            return Err(self);
+        }
+        /*
+        The Receiver only disconnects in its last InnerHolder::drop call,
+        so it's gone and the Sender can take its place.
+        */
+        let mut pending = Vec::new();
+        while let Ok(item) = self.0.try_recv() {
+            pending.push(item);
+        }
+        Ok(pending)
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Reads a value from the [`channel`].
+    ///
+    /// If the [`channel`] is empty, blocks and waits for the [`Sender`].
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after consuming all sent data. To
+    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.0.recv()
+    }
+
+    /// Reads a value from the [`channel`], waiting at most `timeout` for the [`Sender`].
+    ///
+    /// Returns [`RecvTimeoutError::Timeout`] if the [`channel`] is still empty
+    /// after `timeout` and [`RecvTimeoutError::Disconnected`] like
+    /// [`recv`](Receiver::recv) returns [`RecvError`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded_chunked;
+    /// use std::time::Duration;
+    ///
+    /// let (src, sink) = unbounded_chunked::channel::<u32>();
+    /// assert_eq!(
+    ///     sink.recv_timeout(Duration::from_millis(10)),
+    ///     Err(unbounded_chunked::RecvTimeoutError::Timeout)
+    /// );
+    /// src.send(1).unwrap();
+    /// assert_eq!(sink.recv_timeout(Duration::from_millis(10)), Ok(1));
+    /// ```
+    pub fn recv_timeout(&self, timeout: std::time::Duration) -> Result<T, RecvTimeoutError> {
+        self.0
+            .recv_deadline(std::time::Instant::now().checked_add(timeout))
+    }
+
+    /// Reads a value from the [`channel`], blocking until one is sent,
+    /// the [`Sender`] disconnects or `token` is cancelled.
+    ///
+    /// Once `token` is cancelled, this returns [`RecvCancelError::Cancelled`]
+    /// right away, even if values are pending. They stay in the [`channel`] and
+    /// can still be received with the other methods.
+    pub fn recv_cancellable(&self, token: &CancellationToken) -> Result<T, RecvCancelError> {
+        loop {
+            if token.is_cancelled() {
+                return Err(RecvCancelError::Cancelled);
+            }
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Empty) => token.wait(self),
+                Err(_) => return Err(RecvCancelError::Disconnected),
+            }
+        }
+    }
+
+    /// Tries to return a pending value.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after consuming all
+    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.0.try_recv()
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
+    /// methods return [`TryRecvError::Disconnected`] or [`RecvError`] only
+    /// after consuming all previously sent data, even if the [`Sender`] isn't
+    /// connected. This method doesn't take pending data into account and can
+    /// be used to avoid this behaviour.
+    pub fn sender_connected(&self) -> bool {
+        self.0.peer_connected()
+    }
+
+    /// Returns how many values are pending.
+    ///
+    /// The estimate can only be stale in one direction: the [`Sender`] may
+    /// send more values after the call, but only this [`Receiver`] can take
+    /// them out, so the result is a lower bound until the next receive.
+    /// Without receives in between, later calls never return less.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded_chunked;
+    ///
+    /// let (src, sink) = unbounded_chunked::channel::<u32>();
+    /// src.send(1).unwrap();
+    /// src.send(2).unwrap();
+    /// assert_eq!(sink.len_hint(), 2);
+    /// ```
+    #[inline]
+    pub fn len_hint(&self) -> usize {
+        self.0.len_hint()
+    }
+
+    /// Returns a snapshot of the [`channel`]'s counters.
+    /// Enabled by the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> crate::metrics::Stats {
+        self.0.stats()
+    }
+
+    /// Sets a callback that's called every time the [`Sender`] sends
+    /// a value or disconnects, replacing the previous callback.
+    ///
+    /// This allows waiting for the [`channel`] in other event loops, for
+    /// example by waking a [`std::task::Waker`]. The callback runs on the
+    /// [`Sender`]'s thread, so it should return quickly. Values sent
+    /// before the callback was set don't call it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use concurrent_qs::spsc::unbounded_chunked;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let (src, sink) = unbounded_chunked::channel::<u32>();
+    /// let sent = Arc::new(AtomicUsize::new(0));
+    /// let counter = sent.clone();
+    /// sink.set_notify(move || {
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// src.send(1).unwrap();
+    /// assert_eq!(sent.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn set_notify(&self, notify: impl Fn() + Send + Sync + 'static) {
+        self.0.set_receiver_notify(Some(Box::new(notify)))
+    }
+
+    /// Removes the callback set by [`set_notify`](Receiver::set_notify).
+    pub fn clear_notify(&self) {
+        self.0.set_receiver_notify(None)
+    }
+
+    /// Returns a file descriptor that becomes readable every time the
+    /// [`Sender`] sends a value or disconnects, replacing the
+    /// callback of [`set_notify`](Receiver::set_notify).
+    ///
+    /// Values sent before this call don't make the descriptor readable.
+    /// See [`NotifyFd`](crate::notify_fd::NotifyFd) for more.
+    #[cfg(all(unix, feature = "fd-notify"))]
+    pub fn notify_fd(&self) -> std::io::Result<crate::notify_fd::NotifyFd> {
+        let (fd, notify) = crate::notify_fd::NotifyFd::new()?;
+        self.0.set_receiver_notify(Some(Box::new(notify)));
+        Ok(fd)
+    }
+
+    /// Disconnects the [`channel`] without dropping the [`Receiver`].
+    ///
+    /// Any further sends fail, but values sent
+    /// before closing can still be received.
+    pub fn close(&self) {
+        //the Sender never blocks, so there's no one to wake.
+        self.0.close();
+    }
+
+    /// Checks if the [`channel`] was closed by either end point or disconnected.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        !self.0.peer_connected()
+    }
+
+    /// Returns an id of the [`channel`], shared by its [`Sender`] and [`Receiver`].
+    ///
+    /// See [`Sender::channel_id`].
+    #[inline]
+    pub fn channel_id(&self) -> usize {
+        crate::util::addr(self.0.deref())
+    }
+
+    /// Checks if `sender` belongs to the same [`channel`] as this [`Receiver`].
+    #[inline]
+    pub fn same_channel(&self, sender: &Sender<T>) -> bool {
+        sender.same_channel(self)
+    }
+
+    /// Returns an iterator that blocks waiting for values.
+    ///
+    /// The iterator ends when the [`Sender`] disconnects and
+    /// all previously sent data is received.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator over the currently pending values.
+    ///
+    /// The iterator never blocks and ends when the [`channel`]
+    /// is empty or the [`Sender`] disconnects.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+}
+
+/// A blocking iterator over the values of a [`channel`].
+///
+/// Created by [`Receiver::iter`].
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+/// A non-blocking iterator over the pending values of a [`channel`].
+///
+/// Created by [`Receiver::try_iter`].
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+/// An owning blocking iterator over the values of a [`channel`].
+///
+/// Created by [`Receiver::into_iter`].
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+impl<T> crate::traits::BlockingSend<T> for Sender<T> {
+    #[inline]
+    fn send(&self, item: T) -> Result<(), SendError<T>> {
+        Sender::send(self, item)
+    }
+}
+
+impl<T> crate::traits::TryRecv<T> for Receiver<T> {
+    #[inline]
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        Receiver::try_recv(self)
+    }
+}
+
+impl<T> crate::traits::BlockingRecv<T> for Receiver<T> {
+    #[inline]
+    fn recv(&self) -> Result<T, RecvError> {
+        Receiver::recv(self)
+    }
+}
+
+/// Enabled by the `futures` feature.
+///
+/// The [`Sender`] is always ready, since sending never blocks. Sending fails
+/// with a [`SendError`] once the [`channel`] is closed or disconnected, and
+/// [`poll_close`](futures_sink::Sink::poll_close) closes it, so a [`Receiver`]
+/// used as a [`Stream`](futures_core::Stream) ends.
+#[cfg(feature = "futures")]
+impl<T> futures_sink::Sink<T> for Sender<T> {
+    type Error = SendError<()>;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        if self.is_closed() {
+            Poll::Ready(Err(SendError(())))
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), SendError<()>> {
+        self.send(item).map_err(|_| SendError(()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), SendError<()>>> {
+        self.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Enabled by the `futures` feature.
+///
+/// Waiting for a value replaces the callback set by
+/// [`set_notify`](Receiver::set_notify) with one that wakes the task,
+/// while the [`Sender`] can still block on another thread.
+/// The stream ends once the [`channel`] is disconnected and empty.
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let ready = |receiver: &Self| match receiver.try_recv() {
+            Ok(item) => Poll::Ready(Some(item)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+            Err(_) => Poll::Ready(None),
+        };
+        if let Poll::Ready(item) = ready(&self) {
+            return Poll::Ready(item);
+        }
+        self.0.set_receiver_waker(cx.waker());
+        // a value sent before the waker was set didn't wake it.
+        ready(&self)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len_hint(), None)
+    }
+}
+
+impl<T> crate::select::Selectable for Receiver<T> {}
+
+impl<T> crate::select::sealed::Sealed for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        self.0.is_ready()
+    }
+
+    unsafe fn register(&self, waker: crate::select::sealed::Waker) -> bool {
+        self.0.register_receiver(waker.0)
+    }
+
+    unsafe fn unregister(&self) {
+        self.0.unregister_receiver()
+    }
+}
+
+unsafe impl<T: Send> Send for Sender<T> {}
+unsafe impl<T: Send> Send for Receiver<T> {}
+
+// values are moved in and out whole, so an unwinding operation leaves the channel valid.
+impl<T> std::panic::UnwindSafe for Sender<T> {}
+impl<T> std::panic::RefUnwindSafe for Sender<T> {}
+impl<T> std::panic::UnwindSafe for Receiver<T> {}
+impl<T> std::panic::RefUnwindSafe for Receiver<T> {}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        use crate::sync::atomic::Ordering::AcqRel;
+        self.0.drop_count.fetch_add(1, AcqRel);
+        self.0.unpark_receiver();
+        // InnerHolder does the rest
+    }
+}
+
+impl<T> PartialEq for Sender<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.channel_id() == other.channel_id()
+    }
+}
+
+impl<T> Eq for Sender<T> {}
+
+impl<T> std::hash::Hash for Sender<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.channel_id().hash(state)
+    }
+}
+
+impl<T> PartialEq for Receiver<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.channel_id() == other.channel_id()
+    }
+}
+
+impl<T> Eq for Receiver<T> {}
+
+impl<T> std::hash::Hash for Receiver<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.channel_id().hash(state)
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::unbounded_chunked::Sender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.0.deref() as *const _
+        )
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spsc::unbounded_chunked::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.0.deref() as *const _
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests;
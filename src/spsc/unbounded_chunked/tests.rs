@@ -0,0 +1,208 @@
+cfg_not_loom! {
+
+#[test]
+fn drop() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    {
+        let (src, sink) = super::channel();
+        for _ in 0..100 {
+            src.send(rc.clone()).unwrap();
+        }
+        for _ in 0..50 {
+            sink.recv().unwrap();
+        }
+        for _ in 0..100 {
+            src.send(rc.clone()).unwrap();
+        }
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn order() {
+    let (src, sink) = super::channel::<u32>();
+    std::thread::spawn(move || {
+        for i in 0..10_000 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..10_000 {
+        assert_eq!(sink.recv().unwrap(), i);
+    }
+    assert_eq!(sink.recv(), Err(super::RecvError {}));
+}
+
+#[test]
+fn block_reuse() {
+    use super::inner::BLOCK_CAP;
+    let (src, sink) = super::channel::<usize>();
+    for round in 0..10 {
+        for i in 0..BLOCK_CAP + 1 {
+            src.send(round * BLOCK_CAP + i).unwrap();
+        }
+        for i in 0..BLOCK_CAP + 1 {
+            assert_eq!(sink.try_recv(), Ok(round * BLOCK_CAP + i));
+        }
+        assert_eq!(sink.try_recv(), Err(super::TryRecvError::Empty));
+    }
+}
+
+#[test]
+fn sender_dc() {
+    let (src, sink) = super::channel::<u8>();
+    src.send(1).unwrap();
+    std::mem::drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(sink.try_recv(), Ok(1));
+    assert_eq!(sink.try_recv(), Err(super::TryRecvError::Disconnected));
+}
+
+#[test]
+fn receiver_dc() {
+    let (src, sink) = super::channel::<u8>();
+    assert!(src.receiver_connected());
+    std::mem::drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.send(1), Err(super::SendError(1)));
+}
+
+#[test]
+fn iterators() {
+    let (src, sink) = super::channel::<u32>();
+    for i in 0..100 {
+        src.send(i).unwrap();
+    }
+    assert_eq!(sink.try_iter().take(40).collect::<Vec<_>>(), (0..40).collect::<Vec<_>>());
+    std::mem::drop(src);
+    assert_eq!(sink.into_iter().collect::<Vec<_>>(), (40..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_recv_timeout() {
+    use super::RecvTimeoutError;
+    use std::time::Duration;
+    let (src, sink) = super::channel::<i32>();
+    assert_eq!(sink.recv_timeout(Duration::from_millis(10)), Err(RecvTimeoutError::Timeout));
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        src.send(1).unwrap();
+    });
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Ok(1));
+    handle.join().unwrap();
+    assert_eq!(sink.recv_timeout(Duration::from_secs(60)), Err(RecvTimeoutError::Disconnected));
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn st_stats() {
+    let (src, sink) = super::channel::<i32>();
+    assert_eq!(sink.try_recv(), Err(super::TryRecvError::Empty));
+    for i in 0..100 {
+        src.send(i).unwrap();
+    }
+    assert_eq!(sink.try_iter().count(), 100);
+    let stats = sink.stats();
+    assert_eq!(stats, src.stats());
+    assert_eq!((stats.sent, stats.received, stats.empty), (100, 100, 2));
+    assert_eq!(stats.max_depth, 100);
+}
+
+#[test]
+fn st_len_hint() {
+    let (src, sink) = super::channel::<i32>();
+    assert_eq!(sink.len_hint(), 0);
+    for i in 0..100 {
+        src.send(i).unwrap();
+    }
+    assert_eq!(sink.len_hint(), 100);
+    assert_eq!(sink.try_iter().take(40).count(), 40);
+    assert_eq!(sink.len_hint(), 60);
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn mt_sink_stream() {
+    use futures::executor::block_on;
+    use futures::{stream, SinkExt, StreamExt};
+    let (mut src, sink) = super::channel::<u32>();
+    let handle = std::thread::spawn(move || block_on(sink.collect::<Vec<_>>()));
+    block_on(SinkExt::send_all(&mut src, &mut stream::iter(0..1000).map(Ok))).unwrap();
+    block_on(SinkExt::close(&mut src)).unwrap();
+    assert!(src.is_closed());
+    assert_eq!(handle.join().unwrap(), (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn st_close() {
+    let (src, sink) = super::channel::<i32>();
+    src.send(1).unwrap();
+    src.close();
+    assert!(src.is_closed() && sink.is_closed());
+    assert_eq!(src.send(2), Err(super::SendError(2)));
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.recv(), Err(super::RecvError {}));
+    let src = src.into_pending().unwrap_err();
+    std::mem::drop(sink);
+    assert_eq!(src.into_pending().unwrap(), Vec::<i32>::new());
+
+    let (src, sink) = super::channel::<i32>();
+    sink.close();
+    assert_eq!(src.send(1), Err(super::SendError(1)));
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn drop() {
+    loom::model(|| {
+        use loom::sync::Arc;
+        let arc = Arc::new(());
+        {
+            let arc = arc.clone();
+            let (src, sink) = super::channel();
+            let handle = loom::thread::spawn(move || {
+                for _ in 0..3 {
+                    src.send(arc.clone()).unwrap();
+                }
+            });
+            if sink.recv().is_ok() {
+                handle.join().unwrap();
+            }
+        }
+        assert_eq!(Arc::strong_count(&arc), 1);
+    });
+}
+
+#[test]
+fn order() {
+    let mut builder = loom::model::Builder::new();
+    builder.max_threads = 2;
+    builder.preemption_bound = Some(4);
+    builder.check(|| {
+        let (src, sink) = super::channel::<u8>();
+        loom::thread::spawn(move || {
+            for i in 0..5 {
+                src.send(i).unwrap();
+            }
+        });
+        for i in 0..5 {
+            assert_eq!(sink.recv().unwrap_or_else(|_| panic!("failed at {i}")), i);
+        }
+    });
+}
+
+#[test]
+fn sender_dc() {
+    loom::model(|| {
+        let (src, sink) = super::channel::<()>();
+        loom::thread::spawn(move || {
+            std::mem::drop(src);
+        });
+        assert_eq!(sink.recv(), Err(super::RecvError {}))
+    });
+}
+
+}
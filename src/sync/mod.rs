@@ -0,0 +1,424 @@
+use crate::select::sealed::{Sealed, Waker};
+use crate::util::marker::PhantomUnsync;
+use crate::util::park;
+use std::time::{Duration, Instant};
+
+pub use crate::util::backoff::Backoff;
+
+cfg_loom! {
+    pub(crate) use loom::sync::*;
+}
+
+cfg_not_loom! {
+    pub(crate) use std::sync::*;
+}
+
+/// A thread parker that can be embedded in data structures, unlike
+/// [`std::thread::park`], which is tied to the current thread.
+///
+/// A [`Parker`] can only be parked on from one thread at a time, which is
+/// why it's [`!Sync`](Sync). Other threads wake it through [`Unparker`]s.
+///
+/// Like [`std::thread::park`], a call to [`unpark`](Unparker::unpark) before
+/// [`park`](Parker::park) makes the next [`park`](Parker::park) return immediately,
+/// and multiple [`unpark`](Unparker::unpark) calls only store one notification.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::sync::Parker;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+///
+/// let parker = Parker::new();
+/// let unparker = parker.unparker();
+/// let flag = Arc::new(AtomicBool::new(false));
+/// let set = flag.clone();
+///
+/// std::thread::spawn(move || {
+///     set.store(true, Ordering::Release);
+///     unparker.unpark();
+/// });
+/// while !flag.load(Ordering::Acquire) {
+///     parker.park();
+/// }
+/// ```
+pub struct Parker {
+    inner: Arc<park::Parker>,
+    _unsync: PhantomUnsync,
+}
+
+/// Wakes up the [`Parker`] it was created by.
+///
+/// Created with [`Parker::unparker`].
+#[derive(Clone)]
+pub struct Unparker {
+    inner: Arc<park::Parker>,
+}
+
+impl Parker {
+    /// Creates a new [`Parker`] without a notification.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(park::Parker::new()),
+            _unsync: PhantomUnsync {},
+        }
+    }
+
+    /// Blocks until the [`Parker`] is notified, consuming the notification.
+    ///
+    /// Like [`std::thread::park`], this may also return spuriously, so
+    /// it should be called in a loop that checks the awaited condition.
+    #[inline]
+    pub fn park(&self) {
+        //SAFETY: Parker is !Sync, so park can't be called concurrently.
+        unsafe { self.inner.park() }
+    }
+
+    /// Like [`park`](Parker::park), but waits at most `timeout`.
+    ///
+    /// Returns `true` if the [`Parker`] was notified.
+    pub fn park_timeout(&self, timeout: Duration) -> bool {
+        //SAFETY: Parker is !Sync, so park can't be called concurrently.
+        unsafe { self.inner.park_timeout(timeout) }
+    }
+
+    /// Like [`park`](Parker::park), but doesn't wait past `deadline`.
+    ///
+    /// Returns `true` if the [`Parker`] was notified.
+    pub fn park_deadline(&self, deadline: Instant) -> bool {
+        //SAFETY: Parker is !Sync, so park can't be called concurrently.
+        unsafe { self.inner.park_deadline(deadline) }
+    }
+
+    /// Creates an [`Unparker`] that wakes this [`Parker`].
+    pub fn unparker(&self) -> Unparker {
+        Unparker {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Unparker {
+    /// Notifies the [`Parker`], waking it up if it's parked.
+    #[inline]
+    pub fn unpark(&self) {
+        self.inner.unpark()
+    }
+}
+
+impl Default for Parker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Parker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Parker {{ id: {:p} }}", Arc::as_ptr(&self.inner))
+    }
+}
+
+impl std::fmt::Debug for Unparker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unparker {{ parker: {:p} }}", Arc::as_ptr(&self.inner))
+    }
+}
+
+/// A flag that unblocks receivers waiting on it once it's set.
+///
+/// Receivers wait on it with methods like
+/// [`recv_cancellable`](crate::spsc::bounded::Receiver::recv_cancellable),
+/// which return [`RecvCancelError::Cancelled`](crate::error::RecvCancelError::Cancelled)
+/// once any clone of the token is [`cancel`](CancellationToken::cancel)led,
+/// without disconnecting their `Sender`. Cancelling is permanent.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::error::RecvCancelError;
+/// use concurrent_qs::spsc::bounded;
+/// use concurrent_qs::sync::CancellationToken;
+/// use std::thread;
+///
+/// let (src, sink) = bounded::channel::<u32>(4);
+/// let token = CancellationToken::new();
+/// let shutdown = token.clone();
+/// let worker = thread::spawn(move || {
+///     let mut sum = 0;
+///     while let Ok(value) = sink.recv_cancellable(&token) {
+///         sum += value;
+///     }
+///     sum
+/// });
+/// src.send(1).unwrap();
+/// shutdown.cancel();
+/// // the worker stops even though `src` is still connected.
+/// assert!(worker.join().unwrap() <= 1);
+/// ```
+#[derive(Clone)]
+pub struct CancellationToken {
+    inner: Arc<TokenState>,
+}
+
+struct TokenState {
+    cancelled: atomic::AtomicBool,
+    /// The parkers of the threads waiting on the token.
+    waiters: park::WaitQueue,
+}
+
+impl CancellationToken {
+    /// Creates a token that isn't cancelled.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(TokenState {
+                cancelled: atomic::AtomicBool::new(false),
+                waiters: park::WaitQueue::new(),
+            }),
+        }
+    }
+
+    /// Cancels the token, waking every receiver waiting on it.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, atomic::Ordering::Release);
+        self.inner.waiters.wake_all();
+    }
+
+    /// Checks if the token was cancelled.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(atomic::Ordering::Acquire)
+    }
+
+    /// Blocks until `receiver` is ready or the token is cancelled.
+    #[allow(dead_code)]
+    pub(crate) fn wait(&self, receiver: &dyn Sealed) {
+        let waker = Arc::new(park::Parker::new());
+        /* The waker is added before checking the flag, so a
+         * cancel call that doesn't see it was seen by the check.
+         */
+        self.inner.waiters.push(waker.clone());
+        while !self.is_cancelled() && !receiver.is_ready() {
+            //SAFETY: unregistered below.
+            let notified = unsafe { receiver.register(Waker(waker.clone())) };
+            // data sent before registering doesn't unpark `waker`.
+            if !notified && !receiver.is_ready() && !self.is_cancelled() {
+                //SAFETY: `waker` is local to this call.
+                unsafe { waker.park() };
+            }
+            //SAFETY: registered above.
+            unsafe { receiver.unregister() };
+        }
+        self.inner.waiters.remove(&waker);
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CancellationToken {{ cancelled: {} }}",
+            self.is_cancelled()
+        )
+    }
+}
+
+/// A FIFO queue of threads waiting for a condition, like a [`Condvar`]
+/// that doesn't need a [`Mutex`].
+///
+/// Threads wait with [`wait_until`](WaitQueue::wait_until) and are woken in
+/// the order they started waiting by [`notify_one`](WaitQueue::notify_one),
+/// or all at once by [`notify_all`](WaitQueue::notify_all). The condition is
+/// checked after a thread joins the queue, so a notification that follows
+/// making it `true` can't be missed.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::sync::WaitQueue;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+///
+/// let queue = Arc::new(WaitQueue::new());
+/// let ready = Arc::new(AtomicBool::new(false));
+/// let waiters: Vec<_> = (0..4)
+///     .map(|_| {
+///         let (queue, ready) = (queue.clone(), ready.clone());
+///         std::thread::spawn(move || queue.wait_until(|| ready.load(Ordering::Acquire)))
+///     })
+///     .collect();
+/// ready.store(true, Ordering::Release);
+/// queue.notify_all();
+/// for waiter in waiters {
+///     waiter.join().unwrap();
+/// }
+/// ```
+pub struct WaitQueue {
+    inner: park::WaitQueue,
+}
+
+impl WaitQueue {
+    /// Creates a queue without waiting threads.
+    #[cfg(not(feature = "loom"))]
+    pub const fn new() -> Self {
+        Self {
+            inner: park::WaitQueue::new(),
+        }
+    }
+    /// Creates a queue without waiting threads.
+    #[cfg(feature = "loom")]
+    pub fn new() -> Self {
+        Self {
+            inner: park::WaitQueue::new(),
+        }
+    }
+
+    /// Blocks until `condition` returns `true`.
+    ///
+    /// `condition` is checked before waiting and after every wake-up,
+    /// so it's fine for other threads to be notified in between.
+    pub fn wait_until(&self, condition: impl FnMut() -> bool) {
+        self.inner.wait_until(condition, None);
+    }
+
+    /// Like [`wait_until`](WaitQueue::wait_until), but waits at most `timeout`.
+    ///
+    /// Returns the last result of `condition`.
+    pub fn wait_until_timeout(&self, condition: impl FnMut() -> bool, timeout: Duration) -> bool {
+        self.inner
+            .wait_until(condition, Instant::now().checked_add(timeout))
+    }
+
+    /// Like [`wait_until`](WaitQueue::wait_until), but doesn't wait past `deadline`.
+    ///
+    /// Returns the last result of `condition`.
+    pub fn wait_until_deadline(&self, condition: impl FnMut() -> bool, deadline: Instant) -> bool {
+        self.inner.wait_until(condition, Some(deadline))
+    }
+
+    /// Wakes the thread that has been waiting the longest.
+    ///
+    /// Returns `false` if no thread was waiting. A thread that gives up
+    /// after being woken passes the notification on to the next one.
+    pub fn notify_one(&self) -> bool {
+        self.inner.wake_one()
+    }
+
+    /// Wakes every waiting thread, returning how many were woken.
+    pub fn notify_all(&self) -> usize {
+        self.inner.wake_all()
+    }
+
+    /// Returns the number of waiting threads.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Checks if no threads are waiting.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for WaitQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for WaitQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WaitQueue {{ waiting: {} }}", self.len())
+    }
+}
+
+/// A wrapper that lets `!Sync` end points be shared between threads
+/// while they're only moved around.
+///
+/// The end points of the channels are [`!Sync`](Sync), so a `&` reference
+/// to a struct holding one can't cross threads, even if the end point is
+/// never used through it. A [`SendToken`] gives no access to the value
+/// it holds through a `&` reference, which makes it [`Sync`]: the value
+/// can only be reached with [`get_mut`](SendToken::get_mut) or taken out
+/// with [`into_inner`](SendToken::into_inner), both of which need the
+/// token itself. This allows migrating an end point across threads
+/// through scoped APIs that need [`Sync`] without any `unsafe` code.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::spsc::bounded;
+/// use concurrent_qs::sync::SendToken;
+/// use std::thread;
+///
+/// struct Job {
+///     name: String,
+///     src: SendToken<bounded::Sender<usize>>,
+/// }
+///
+/// let (src, sink) = bounded::channel(4);
+/// let job = Job {
+///     name: "job".to_string(),
+///     src: SendToken::new(src),
+/// };
+/// // `&job` is shared by the scoped threads, even though `Sender` is `!Sync`.
+/// thread::scope(|s| {
+///     s.spawn(|| assert_eq!(job.name, "job"));
+///     s.spawn(|| assert_eq!(job.name.len(), 3));
+/// });
+/// let src = job.src.into_inner();
+/// thread::spawn(move || src.send(job.name.len()).unwrap());
+/// assert_eq!(sink.recv(), Ok(3));
+/// ```
+pub struct SendToken<E> {
+    inner: E,
+}
+
+impl<E> SendToken<E> {
+    /// Wraps `inner` in a [`SendToken`].
+    #[inline]
+    pub const fn new(inner: E) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// This needs exclusive access to the token, so
+    /// no other thread can be using the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+
+    /// Takes the wrapped value out of the [`SendToken`].
+    #[inline]
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+impl<E> From<E> for SendToken<E> {
+    #[inline]
+    fn from(inner: E) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<E> std::fmt::Debug for SendToken<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the value can't be shown, since that needs a `&` reference to it.
+        write!(f, "SendToken<{}> {{ .. }}", std::any::type_name::<E>())
+    }
+}
+
+//SAFETY: a `&SendToken` gives no access to the wrapped value.
+unsafe impl<E> Sync for SendToken<E> {}
+
+#[cfg(test)]
+mod tests;
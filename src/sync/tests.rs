@@ -0,0 +1,147 @@
+use super::*;
+
+cfg_not_loom! {
+
+#[test]
+fn st_backoff() {
+    let backoff = Backoff::new();
+    for _ in 0..6 {
+        backoff.spin();
+    }
+    // spin never gets to yielding.
+    for _ in 0..100 {
+        backoff.spin();
+    }
+    assert!(!backoff.is_completed());
+    for _ in 0..5 {
+        backoff.snooze();
+    }
+    assert!(backoff.is_completed());
+    backoff.reset();
+    assert!(!backoff.is_completed());
+}
+
+#[test]
+#[cfg(feature = "spsc-bounded")]
+fn mt_send_token() {
+    use crate::spsc::bounded;
+    let (src, sink) = bounded::channel::<u32>(4);
+    let token = SendToken::from(src);
+    std::thread::scope(|s| {
+        for _ in 0..2 {
+            s.spawn(|| format!("{token:?}"));
+        }
+    });
+    let mut token = token;
+    token.get_mut().send(1).unwrap();
+    let src = token.into_inner();
+    std::thread::spawn(move || src.send(2).unwrap())
+        .join()
+        .unwrap();
+    assert_eq!(sink.recv(), Ok(1));
+    assert_eq!(sink.recv(), Ok(2));
+    assert_eq!(sink.recv(), Err(crate::error::RecvError {}));
+}
+
+#[test]
+fn mt_wait_queue() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    let queue = std::sync::Arc::new(WaitQueue::new());
+    let permits = std::sync::Arc::new(AtomicUsize::new(0));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let (queue, permits) = (queue.clone(), permits.clone());
+            std::thread::spawn(move || {
+                // takes one permit, like a semaphore.
+                queue.wait_until(|| {
+                    permits
+                        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+                        .is_ok()
+                })
+            })
+        })
+        .collect();
+    for _ in 0..4 {
+        permits.fetch_add(1, Ordering::Release);
+        queue.notify_one();
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert!(queue.is_empty());
+    assert!(!queue.wait_until_timeout(|| false, Duration::from_millis(10)));
+}
+
+#[test]
+fn st_unpark_first() {
+    let parker = Parker::new();
+    let unparker = parker.unparker();
+    unparker.unpark();
+    unparker.clone().unpark();
+    parker.park();
+    assert!(!parker.park_timeout(Duration::from_millis(10)));
+}
+
+#[test]
+fn st_deadline() {
+    let parker = Parker::default();
+    let deadline = Instant::now() + Duration::from_millis(10);
+    assert!(!parker.park_deadline(deadline));
+    assert!(Instant::now() >= deadline);
+    parker.unparker().unpark();
+    assert!(parker.park_deadline(deadline));
+}
+
+#[test]
+fn mt_unpark() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    let parker = Parker::new();
+    let unparker = parker.unparker();
+    let counter = std::sync::Arc::new(AtomicUsize::new(0));
+    let shared = counter.clone();
+    let handle = std::thread::spawn(move || {
+        for _ in 0..1000 {
+            shared.fetch_add(1, Ordering::Release);
+            unparker.unpark();
+        }
+    });
+    while counter.load(Ordering::Acquire) != 1000 {
+        parker.park();
+    }
+    handle.join().unwrap();
+}
+
+#[test]
+fn st_cancellation_token() {
+    let token = CancellationToken::default();
+    let clone = token.clone();
+    assert!(!clone.is_cancelled());
+    token.cancel();
+    assert!(clone.is_cancelled());
+    token.cancel();
+    assert_eq!(format!("{clone:?}"), "CancellationToken { cancelled: true }");
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn unpark() {
+    loom::model(|| {
+        use loom::sync::atomic::{AtomicBool, Ordering};
+        let parker = Parker::new();
+        let unparker = parker.unparker();
+        let flag = Arc::new(AtomicBool::new(false));
+        let set = flag.clone();
+        loom::thread::spawn(move || {
+            set.store(true, Ordering::Release);
+            unparker.unpark();
+        });
+        while !flag.load(Ordering::Acquire) {
+            parker.park();
+        }
+    });
+}
+
+}
@@ -0,0 +1,39 @@
+use crate::error::{RecvError, SendError, TryRecvError};
+
+/// Sending a value without blocking.
+///
+/// Implemented by the senders of the queues that can reject values,
+/// like bounded ones, which reject them when full.
+pub trait TrySend<T> {
+    /// The error returned when the value can't be sent.
+    /// Contains the value that failed to send.
+    type Error;
+
+    /// Sends `item` if it can be done without blocking.
+    fn try_send(&self, item: T) -> Result<(), Self::Error>;
+}
+
+/// Sending a value, waiting for room if the queue needs to.
+///
+/// Senders of unbounded queues never wait, but still implement
+/// this trait, since they can always send a value.
+pub trait BlockingSend<T> {
+    /// Sends `item`, failing only if the receiving side is disconnected.
+    fn send(&self, item: T) -> Result<(), SendError<T>>;
+}
+
+/// Receiving a value without blocking.
+pub trait TryRecv<T> {
+    /// Returns a pending value, if there's one.
+    fn try_recv(&self) -> Result<T, TryRecvError>;
+}
+
+/// Receiving a value, waiting for one if the queue is empty.
+pub trait BlockingRecv<T> {
+    /// Receives a value, failing only after the sending side
+    /// disconnected and all sent values were received.
+    fn recv(&self) -> Result<T, RecvError>;
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,82 @@
+cfg_not_loom! {
+
+#[cfg(any(
+    feature = "spsc-bounded",
+    feature = "spsc-unbounded",
+    feature = "spsc-unbounded-chunked",
+    feature = "unsync"
+))]
+use super::*;
+
+#[cfg(any(
+    feature = "spsc-bounded",
+    feature = "spsc-unbounded",
+    feature = "spsc-unbounded-chunked"
+))]
+fn round_trip<S, R>(src: S, sink: R)
+where
+    S: BlockingSend<u32> + Send + 'static,
+    R: BlockingRecv<u32> + TryRecv<u32>,
+{
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    let handle = std::thread::spawn(move || {
+        for i in 0..100 {
+            src.send(i).unwrap();
+        }
+    });
+    for i in 0..100 {
+        assert_eq!(sink.recv(), Ok(i));
+    }
+    handle.join().unwrap();
+    assert_eq!(sink.recv(), Err(RecvError {}));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[cfg(any(feature = "spsc-bounded", feature = "spsc-unbounded"))]
+fn fill<S: TrySend<u32>>(src: &S) -> u32 {
+    let mut sent = 0;
+    while src.try_send(sent).is_ok() {
+        sent += 1;
+    }
+    sent
+}
+
+#[test]
+fn generic_flavors() {
+    #[cfg(feature = "spsc-bounded")]
+    {
+        let (src, sink) = crate::spsc::bounded::channel_exact(4);
+        assert_eq!(fill(&src), 4);
+        assert_eq!(sink.try_iter().count(), 4);
+        round_trip(src, sink);
+    }
+    #[cfg(feature = "spsc-unbounded")]
+    {
+        let (src, sink) = crate::spsc::unbounded::Builder::new().max_len(3).build();
+        assert_eq!(fill(&src), 3);
+        assert_eq!(sink.try_iter().count(), 3);
+        round_trip(src, sink);
+    }
+    #[cfg(feature = "spsc-unbounded-chunked")]
+    {
+        let (src, sink) = crate::spsc::unbounded_chunked::channel();
+        round_trip(src, sink);
+    }
+    #[cfg(feature = "mpsc-sharded")]
+    {
+        let (src, sink) = crate::mpsc::sharded::channel(4);
+        assert_eq!(fill(&src), 4);
+        assert_eq!(std::iter::from_fn(|| sink.try_recv().ok()).count(), 4);
+        round_trip(src, sink);
+    }
+    #[cfg(feature = "unsync")]
+    {
+        let (src, sink) = crate::unsync::spsc::channel();
+        BlockingSend::send(&src, 1).unwrap();
+        drop(src);
+        assert_eq!(TryRecv::try_recv(&sink), Ok(1));
+        assert_eq!(TryRecv::try_recv(&sink), Err(TryRecvError::Disconnected));
+    }
+}
+
+}
@@ -0,0 +1,23 @@
+/// An unbounded Single Producer Single Consumer queue whose end points
+/// stay on one thread. Enabled by the `unsync` feature.
+///
+/// Works like [`spsc::unbounded`](crate::spsc::unbounded), but without atomics,
+/// since the [`Sender`](spsc::Sender) and [`Receiver`](spsc::Receiver) can't be
+/// sent to other threads. This fits interleaving stages on one thread, like
+/// parsing and processing, in code that's generic over the channel flavor.
+///
+/// There's no blocking `recv`, since nothing could send a value while the
+/// thread is blocked.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::unsync::spsc;
+///
+/// let (src, sink) = spsc::channel::<u32>();
+/// for line in ["1", "2", "3"] {
+///     src.send(line.parse().unwrap()).unwrap();
+/// }
+/// assert_eq!(sink.try_iter().sum::<u32>(), 6);
+/// ```
+pub mod spsc;
@@ -0,0 +1,177 @@
+use crate::error::{SendError, TryRecvError};
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Creates a single-threaded SPSC channel with unbounded capacity.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    channel_with_capacity(0)
+}
+
+/// Like [`channel`], but preallocates room for `capacity` values.
+pub fn channel_with_capacity<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let inner = Rc::new(Inner {
+        queue: UnsafeCell::new(VecDeque::with_capacity(capacity)),
+    });
+    (Sender(inner.clone()), Receiver(inner))
+}
+
+/// The sending endpoint of a [`channel`].
+///
+/// Data can be sent using the [`send`](Sender::send) method.
+pub struct Sender<T>(Rc<Inner<T>>);
+
+/// The receiving endpoint of a [`channel`].
+///
+/// Data can be received using the [`try_recv`](Receiver::try_recv) method.
+pub struct Receiver<T>(Rc<Inner<T>>);
+
+struct Inner<T> {
+    /*The end points are !Send and !Sync and no user code runs
+     *while the queue is borrowed, so borrows never overlap.*/
+    queue: UnsafeCell<VecDeque<T>>,
+}
+
+impl<T> Inner<T> {
+    /// Checks if the other end point is still alive.
+    #[inline]
+    fn peer_connected(self: &Rc<Self>) -> bool {
+        Rc::strong_count(self) == 2
+    }
+
+    #[inline]
+    fn with_queue<R>(&self, f: impl FnOnce(&mut VecDeque<T>) -> R) -> R {
+        //SAFETY: see `queue`.
+        f(unsafe { &mut *self.queue.get() })
+    }
+}
+
+impl<T> Sender<T> {
+    /// Sends a value through this [`channel`].
+    ///
+    /// Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if no more memory is available.
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        if !self.0.peer_connected() {
+            return Err(SendError(item));
+        }
+        self.0.with_queue(|queue| queue.push_back(item));
+        Ok(())
+    }
+
+    /// Checks if `receiver` belongs to the same [`channel`] as this [`Sender`].
+    #[inline]
+    pub fn same_channel(&self, receiver: &Receiver<T>) -> bool {
+        Rc::ptr_eq(&self.0, &receiver.0)
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.0.peer_connected()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to return a pending value.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after consuming all
+    /// sent data. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        match self.0.with_queue(|queue| queue.pop_front()) {
+            Some(item) => Ok(item),
+            None if self.0.peer_connected() => Err(TryRecvError::Empty),
+            None => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    /// Returns the number of pending values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.with_queue(|queue| queue.len())
+    }
+
+    /// Checks if there are no pending values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// [`try_recv`](Receiver::try_recv) returns [`TryRecvError::Disconnected`]
+    /// only after consuming all previously sent data, even if the [`Sender`]
+    /// isn't connected. This method doesn't take pending data into account
+    /// and can be used to avoid this behaviour.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.0.peer_connected()
+    }
+
+    /// Checks if `sender` belongs to the same [`channel`] as this [`Receiver`].
+    #[inline]
+    pub fn same_channel(&self, sender: &Sender<T>) -> bool {
+        Rc::ptr_eq(&self.0, &sender.0)
+    }
+
+    /// Returns an iterator over the currently pending values.
+    ///
+    /// The iterator ends when the [`channel`] is empty.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+}
+
+/// An iterator over the pending values of a [`channel`].
+///
+/// Created by [`Receiver::try_iter`].
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl<T> crate::traits::BlockingSend<T> for Sender<T> {
+    #[inline]
+    fn send(&self, item: T) -> Result<(), SendError<T>> {
+        Sender::send(self, item)
+    }
+}
+
+impl<T> crate::traits::TryRecv<T> for Receiver<T> {
+    #[inline]
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        Receiver::try_recv(self)
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unsync::spsc::Sender {{ channel: {:p} }}", Rc::as_ptr(&self.0))
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unsync::spsc::Receiver {{ channel: {:p} }}", Rc::as_ptr(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests;
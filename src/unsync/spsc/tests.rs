@@ -0,0 +1,46 @@
+cfg_not_loom! {
+
+use super::*;
+
+#[test]
+fn st_order() {
+    let (src, sink) = channel_with_capacity(2);
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    for i in 0..100 {
+        src.send(i).unwrap();
+    }
+    assert_eq!(sink.len(), 100);
+    assert!(sink.try_iter().eq(0..100));
+    assert!(sink.is_empty());
+    assert!(src.same_channel(&sink) && sink.same_channel(&src));
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, sink) = channel();
+    src.send(1).unwrap();
+    drop(src);
+    assert!(!sink.sender_connected());
+    assert_eq!(sink.try_recv(), Ok(1));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+
+    let (src, sink) = channel();
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn st_drop() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let (src, sink) = channel();
+    for _ in 0..10 {
+        src.send(rc.clone()).unwrap();
+    }
+    drop(sink.try_recv());
+    drop((src, sink));
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+}
@@ -0,0 +1,223 @@
+//Used by `spsc::unbounded::channel`, and exported by the `utils` feature.
+#![allow(dead_code)]
+use crate::sync::atomic::{AtomicPtr, Ordering};
+use std::fmt::Debug;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::ptr::NonNull;
+
+/// An [`AtomicPtr`](std::sync::atomic::AtomicPtr) that's never null.
+///
+/// Every operation takes and returns [`NonNull`] pointers, so lock-free
+/// structures that never store null, like linked lists with a sentinel
+/// node, don't need to check for it on every load.
+///
+/// The low bits of a pointer aligned to `T` are always 0, so they can hold
+/// flags, e.g. for marking a node as deleted. [`tag`](AtomicNonNull::tag) and
+/// [`untag`](AtomicNonNull::untag) set and read them, up to [`TAG_MASK`](AtomicNonNull::TAG_MASK).
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::utils::AtomicNonNull;
+/// use std::ptr::NonNull;
+/// use std::sync::atomic::Ordering::{Acquire, Release};
+///
+/// let mut nodes = [1u32, 2];
+/// let first = NonNull::from(&mut nodes[0]);
+/// let second = NonNull::from(&mut nodes[1]);
+///
+/// let head = AtomicNonNull::new(first);
+/// assert_eq!(head.swap(second, Release), first);
+/// // marks the head as deleted, in the pointer's low bit.
+/// let marked = head.fetch_update(Release, Acquire, |head| {
+///     Some(AtomicNonNull::tag(head, 1))
+/// });
+/// assert_eq!(marked, Ok(second));
+/// assert_eq!(AtomicNonNull::untag(head.load(Acquire)), (second, 1));
+/// ```
+pub struct AtomicNonNull<T>(AtomicPtr<T>);
+
+impl<T> AtomicNonNull<T> {
+    /// The bits of a pointer to `T` that can be used as a tag,
+    /// since they're 0 for every pointer aligned to `T`.
+    pub const TAG_MASK: usize = std::mem::align_of::<T>() - 1;
+
+    /// Creates a new [`AtomicNonNull`].
+    pub fn new(p: NonNull<T>) -> Self {
+        Self(AtomicPtr::new(p.as_ptr()))
+    }
+
+    /// Loads without any synchronisation.
+    ///
+    /// # Safety
+    ///
+    /// It's undefined behaviour if any atomic operations are performed on `self`.
+    /// That includes loads, stores, and RMW operations of any [`Ordering`](std::sync::atomic::Ordering).
+    #[inline]
+    pub(crate) unsafe fn unsync_load(&self) -> NonNull<T> {
+        #[cfg(feature = "loom")]
+        // The safety of this call is guaranteed by the caller.
+        let ptr = unsafe { self.0.unsync_load() };
+        #[cfg(not(feature = "loom"))]
+        // The safety of this call is guaranteed by the caller.
+        let ptr = unsafe { self.0.as_ptr().read() };
+        // SAFETY: the API only accepts and gives access to NonNull<T>, so ptr isn't null.
+        unsafe { NonNull::new_unchecked(ptr) }
+    }
+
+    pub(crate) fn with_mut<R>(&mut self, f: impl FnOnce(&mut NonNull<T>) -> R) -> R {
+        // SAFETY:
+        // - the layout of both AtomicPtr<T> and NonNull<T> are the same as *mut.
+        // - the API only accepts and gives access to NonNull<T>, so self.0 isn't null.
+        #[cfg(not(feature = "loom"))]
+        return f(unsafe { std::mem::transmute(self.0.get_mut()) });
+        #[cfg(feature = "loom")]
+        return self.0.with_mut(|me| unsafe { f(std::mem::transmute(me)) });
+    }
+
+    /// Returns a mutable reference to the pointer.
+    ///
+    /// This is safe, since the mutable reference guarantees
+    /// that no other threads are accessing the pointer.
+    #[cfg(not(feature = "loom"))]
+    pub fn get_mut(&mut self) -> &mut NonNull<T> {
+        // SAFETY:
+        // - the layout of both *mut T and NonNull<T> are the same.
+        // - the API only accepts and gives access to NonNull<T>, so self.0 isn't null.
+        unsafe { &mut *(self.0.get_mut() as *mut *mut T as *mut NonNull<T>) }
+    }
+
+    /// Loads the pointer, see [`AtomicPtr::load`](std::sync::atomic::AtomicPtr::load).
+    pub fn load(&self, ord: Ordering) -> NonNull<T> {
+        let ptr = self.0.load(ord);
+        // SAFETY: the API only accepts and gives access to NonNull<T>, so ptr isn't null.
+        unsafe { NonNull::new_unchecked(ptr) }
+    }
+
+    /// Stores `val`, see [`AtomicPtr::store`](std::sync::atomic::AtomicPtr::store).
+    pub fn store(&self, val: NonNull<T>, ord: Ordering) {
+        self.0.store(val.as_ptr(), ord)
+    }
+
+    /// Stores `val` and returns the previous pointer,
+    /// see [`AtomicPtr::swap`](std::sync::atomic::AtomicPtr::swap).
+    pub fn swap(&self, val: NonNull<T>, ord: Ordering) -> NonNull<T> {
+        let ptr = self.0.swap(val.as_ptr(), ord);
+        // SAFETY: the API only accepts and gives access to NonNull<T>, so ptr isn't null.
+        unsafe { NonNull::new_unchecked(ptr) }
+    }
+
+    /// Stores `new` if the pointer is `current`,
+    /// see [`AtomicPtr::compare_exchange`](std::sync::atomic::AtomicPtr::compare_exchange).
+    pub fn compare_exchange(
+        &self,
+        current: NonNull<T>,
+        new: NonNull<T>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<NonNull<T>, NonNull<T>> {
+        match self
+            .0
+            .compare_exchange(current.as_ptr(), new.as_ptr(), success, failure)
+        {
+            // SAFETY: the API only accepts and gives access to NonNull<T>, so ptr isn't null.
+            Ok(ptr) => Ok(unsafe { NonNull::new_unchecked(ptr) }),
+            Err(ptr) => Err(unsafe { NonNull::new_unchecked(ptr) }),
+        }
+    }
+
+    /// Like [`compare_exchange`](AtomicNonNull::compare_exchange), but can fail spuriously,
+    /// see [`AtomicPtr::compare_exchange_weak`].
+    ///
+    /// [`AtomicPtr::compare_exchange_weak`]: std::sync::atomic::AtomicPtr::compare_exchange_weak
+    pub fn compare_exchange_weak(
+        &self,
+        current: NonNull<T>,
+        new: NonNull<T>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<NonNull<T>, NonNull<T>> {
+        match self
+            .0
+            .compare_exchange_weak(current.as_ptr(), new.as_ptr(), success, failure)
+        {
+            // SAFETY: the API only accepts and gives access to NonNull<T>, so ptr isn't null.
+            Ok(ptr) => Ok(unsafe { NonNull::new_unchecked(ptr) }),
+            Err(ptr) => Err(unsafe { NonNull::new_unchecked(ptr) }),
+        }
+    }
+
+    /// Replaces the pointer with `f(pointer)` until no other thread changes it
+    /// in between, or `f` returns [`None`].
+    ///
+    /// Returns the previous pointer on success and the last loaded one if `f`
+    /// gave up, see [`AtomicPtr::fetch_update`](std::sync::atomic::AtomicPtr::fetch_update).
+    pub fn fetch_update<F>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: F,
+    ) -> Result<NonNull<T>, NonNull<T>>
+    where
+        F: FnMut(NonNull<T>) -> Option<NonNull<T>>,
+    {
+        let mut prev = self.load(fetch_order);
+        while let Some(next) = f(prev) {
+            match self.compare_exchange_weak(prev, next, set_order, fetch_order) {
+                Ok(prev) => return Ok(prev),
+                Err(actual) => prev = actual,
+            }
+        }
+        Err(prev)
+    }
+
+    /// Replaces the tag in the low bits of `ptr` with `tag`.
+    ///
+    /// # Panics
+    ///
+    /// The function panics if `tag` has bits outside [`TAG_MASK`](AtomicNonNull::TAG_MASK),
+    /// or if [`untag`](AtomicNonNull::untag) panics for `ptr`.
+    pub fn tag(ptr: NonNull<T>, tag: usize) -> NonNull<T> {
+        assert!(tag & !Self::TAG_MASK == 0, "tag doesn't fit in the alignment of T");
+        let (ptr, _) = Self::untag(ptr);
+        // byte offsets keep the pointer's provenance, unlike casting from an integer.
+        let tagged = (ptr.as_ptr() as *mut u8).wrapping_add(tag) as *mut T;
+        // SAFETY: adding to a non-null address below the next aligned one can't wrap to null.
+        unsafe { NonNull::new_unchecked(tagged) }
+    }
+
+    /// Splits `ptr` into the pointer without the tag, and the tag.
+    ///
+    /// # Panics
+    ///
+    /// The function panics if clearing the tag makes the pointer null,
+    /// which can only happen for pointers that aren't aligned to `T`.
+    pub fn untag(ptr: NonNull<T>) -> (NonNull<T>, usize) {
+        let tag = crate::util::addr(ptr.as_ptr()) & Self::TAG_MASK;
+        let untagged = (ptr.as_ptr() as *mut u8).wrapping_sub(tag) as *mut T;
+        (
+            NonNull::new(untagged).expect("tagged pointers must be aligned to T"),
+            tag,
+        )
+    }
+}
+
+//impl the same things as loom AtomicPtr<T>, except Default
+unsafe impl<T> Send for AtomicNonNull<T> {}
+unsafe impl<T> Sync for AtomicNonNull<T> {}
+impl<T> RefUnwindSafe for AtomicNonNull<T> {}
+impl<T> UnwindSafe for AtomicNonNull<T> {}
+impl<T> Unpin for AtomicNonNull<T> {}
+impl<T> Debug for AtomicNonNull<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+impl<T> From<NonNull<T>> for AtomicNonNull<T> {
+    fn from(value: NonNull<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,72 @@
+use super::AtomicNonNull;
+use crate::sync::atomic::Ordering::{Acquire, Release};
+use std::ptr::NonNull;
+
+cfg_not_loom! {
+
+#[test]
+fn test_tag() {
+    let mut value = 0u64;
+    let ptr = NonNull::from(&mut value);
+    assert_eq!(AtomicNonNull::<u64>::TAG_MASK, 7);
+    assert_eq!(AtomicNonNull::<u8>::TAG_MASK, 0);
+    let tagged = AtomicNonNull::tag(ptr, 5);
+    assert_ne!(tagged, ptr);
+    assert_eq!(AtomicNonNull::untag(tagged), (ptr, 5));
+    assert_eq!(AtomicNonNull::untag(AtomicNonNull::tag(tagged, 2)), (ptr, 2));
+    assert_eq!(AtomicNonNull::untag(ptr), (ptr, 0));
+}
+
+#[test]
+#[should_panic(expected = "tag doesn't fit")]
+fn test_tag_too_large() {
+    let mut value = 0u32;
+    AtomicNonNull::tag(NonNull::from(&mut value), 4);
+}
+
+#[test]
+fn test_fetch_update() {
+    let mut values = [0u32, 1];
+    let first = NonNull::from(&mut values[0]);
+    let second = NonNull::from(&mut values[1]);
+    let mut atomic = AtomicNonNull::new(first);
+    assert_eq!(atomic.fetch_update(Release, Acquire, |_| Some(second)), Ok(first));
+    assert_eq!(atomic.fetch_update(Release, Acquire, |_| None), Err(second));
+    *atomic.get_mut() = first;
+    assert_eq!(atomic.load(Acquire), first);
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn test_fetch_update_contended() {
+    loom::model(|| {
+        use loom::sync::Arc;
+        static VALUE: u64 = 0;
+        let ptr = NonNull::from(&VALUE);
+        let atomic = Arc::new(AtomicNonNull::new(ptr));
+        let threads: Vec<_> = [1, 2]
+            .into_iter()
+            .map(|flag| {
+                let atomic = atomic.clone();
+                loom::thread::spawn(move || {
+                    atomic
+                        .fetch_update(Release, Acquire, |old| {
+                            let (ptr, tag) = AtomicNonNull::untag(old);
+                            Some(AtomicNonNull::tag(ptr, tag | flag))
+                        })
+                        .unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        // neither update is lost.
+        assert_eq!(AtomicNonNull::untag(atomic.load(Acquire)), (ptr, 3));
+    });
+}
+
+}
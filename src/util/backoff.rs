@@ -0,0 +1,113 @@
+use std::cell::Cell;
+
+// Limits taken from crossbeam(https://crates.io/crates/crossbeam/0.8.2)
+const SPIN_LIMIT: u32 = 6;
+const YIELD_LIMIT: u32 = 10;
+
+/// Exponential backoff for loops that retry an operation, which
+/// escalates from busy-waiting to yielding the thread.
+///
+/// Blocking operations of the **spsc** channels back off like this while
+/// they spin before parking, see `Builder::spin`. Loops around `try_`
+/// methods can use it to share that policy: call [`spin`](Backoff::spin)
+/// after losing a race that's about to resolve, [`snooze`](Backoff::snooze)
+/// while waiting for another thread, and block some other way once
+/// [`is_completed`](Backoff::is_completed) returns `true`.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::sync::Backoff;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+///
+/// let ready = Arc::new(AtomicBool::new(false));
+/// let set = ready.clone();
+/// let handle = std::thread::spawn(move || set.store(true, Ordering::Release));
+///
+/// let backoff = Backoff::new();
+/// while !ready.load(Ordering::Acquire) {
+///     if backoff.is_completed() {
+///         std::thread::sleep(std::time::Duration::from_millis(1));
+///     } else {
+///         backoff.snooze();
+///     }
+/// }
+/// handle.join().unwrap();
+/// ```
+pub struct Backoff {
+    step: Cell<u32>,
+}
+
+impl Backoff {
+    /// Creates a [`Backoff`] at its first step.
+    pub const fn new() -> Self {
+        Self {
+            step: Cell::new(0),
+        }
+    }
+
+    /// Goes back to the first step, e.g. after the operation made progress.
+    #[inline]
+    pub fn reset(&self) {
+        self.step.set(0);
+    }
+
+    /// Busy-waits for a number of iterations that doubles on every call,
+    /// up to a limit.
+    #[inline]
+    pub fn spin(&self) {
+        let step = self.step.get();
+        for _ in 0..1u32 << step.min(SPIN_LIMIT) {
+            crate::util::spin_loop();
+        }
+        if step <= SPIN_LIMIT {
+            self.step.set(step + 1);
+        }
+    }
+
+    /// Like [`spin`](Backoff::spin), but yields the thread once
+    /// busy-waiting reached its limit.
+    #[inline]
+    pub fn snooze(&self) {
+        let step = self.step.get();
+        Self::pause(step);
+        if step <= YIELD_LIMIT {
+            self.step.set(step + 1);
+        }
+    }
+
+    /// Checks if backing off further only yields the thread,
+    /// so it's better to block instead.
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.step.get() > YIELD_LIMIT
+    }
+
+    /// Waits like the `step`th call to [`snooze`](Backoff::snooze).
+    #[inline]
+    pub(crate) fn pause(step: u32) {
+        if step <= SPIN_LIMIT {
+            for _ in 0..1u32 << step {
+                crate::util::spin_loop();
+            }
+        } else {
+            crate::thread::yield_now();
+        }
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Backoff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Backoff")
+            .field("step", &self.step.get())
+            .field("is_completed", &self.is_completed())
+            .finish()
+    }
+}
@@ -1,4 +1,3 @@
-use std::default::Default;
 use std::ops::{Deref, DerefMut};
 
 // Alignment values taken from crossbeam(https://crates.io/crates/crossbeam/0.8.2)
@@ -86,37 +85,82 @@ use std::ops::{Deref, DerefMut};
     )),
     repr(align(64))
 )]
-pub(crate) struct CacheAligned<T> {
+/// Pads and aligns a value to the length of a cache line.
+///
+/// Values that different threads write to, like the indices of a queue's
+/// end points, are slow to share a cache line, since every write takes the
+/// line away from the other threads. Wrapping each of them in a [`CachePadded`]
+/// keeps them on separate cache lines.
+///
+/// The alignment depends on the target architecture, and is 128 bytes on
+/// `x86_64` and `aarch64`, where the prefetcher pulls pairs of cache lines.
+///
+/// # Examples
+///
+/// ```rust
+/// use concurrent_qs::utils::CachePadded;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// struct Indices {
+///     head: CachePadded<AtomicUsize>,
+///     tail: CachePadded<AtomicUsize>,
+/// }
+///
+/// let indices = Indices {
+///     head: CachePadded::new(AtomicUsize::new(0)),
+///     tail: CachePadded::new(AtomicUsize::new(0)),
+/// };
+/// indices.tail.fetch_add(1, Ordering::Relaxed);
+/// assert_eq!(indices.tail.load(Ordering::Relaxed), 1);
+///
+/// let head = &indices.head as *const _ as usize;
+/// let tail = &indices.tail as *const _ as usize;
+/// assert!(tail.abs_diff(head) >= std::mem::align_of::<CachePadded<AtomicUsize>>());
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CachePadded<T> {
     value: T,
 }
 
-impl<T> CacheAligned<T> {
-    #[allow(dead_code)]
-    pub fn new(t: T) -> Self {
+impl<T> CachePadded<T> {
+    /// Pads and aligns `t` to the length of a cache line.
+    pub const fn new(t: T) -> Self {
         Self { value: t }
     }
-}
-
-unsafe impl<T: Send> Send for CacheAligned<T> {}
-unsafe impl<T: Sync> Sync for CacheAligned<T> {}
 
-impl<T: Default> Default for CacheAligned<T> {
-    fn default() -> Self {
-        Self {
-            value: T::default(),
-        }
+    /// Returns the padded value.
+    #[allow(dead_code)]
+    pub fn into_inner(self) -> T {
+        self.value
     }
 }
 
-impl<T> Deref for CacheAligned<T> {
+unsafe impl<T: Send> Send for CachePadded<T> {}
+unsafe impl<T: Sync> Sync for CachePadded<T> {}
+
+impl<T> Deref for CachePadded<T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
         &self.value
     }
 }
 
-impl<T> DerefMut for CacheAligned<T> {
+impl<T> DerefMut for CachePadded<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.value
     }
 }
+
+impl<T: std::fmt::Debug> std::fmt::Debug for CachePadded<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachePadded")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T> From<T> for CachePadded<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
@@ -1,4 +1,32 @@
 pub(crate) mod ann;
+pub(crate) mod backoff;
 pub(crate) mod cache;
 pub(crate) mod marker;
 pub(crate) mod park;
+#[cfg(feature = "tracing")]
+pub(crate) mod trace;
+
+/// Returns the address of `ptr`, which is used as a channel id.
+///
+/// With the `strict-provenance` feature this uses [`pointer::addr`], so the
+/// pointer's provenance isn't exposed and Miri's `-Zmiri-strict-provenance`
+/// mode doesn't have to guess it. All other pointer casts in the crate are
+/// between pointer types, which keep the provenance.
+#[allow(dead_code)]
+#[inline(always)]
+pub(crate) fn addr<T: ?Sized>(ptr: *const T) -> usize {
+    #[cfg(feature = "strict-provenance")]
+    return ptr.addr();
+    #[cfg(not(feature = "strict-provenance"))]
+    return ptr as *const () as usize;
+}
+
+/// A hint for busy-wait loops that also lets loom switch threads.
+#[allow(dead_code)]
+#[inline(always)]
+pub(crate) fn spin_loop() {
+    #[cfg(not(feature = "loom"))]
+    std::hint::spin_loop();
+    #[cfg(feature = "loom")]
+    loom::thread::yield_now();
+}
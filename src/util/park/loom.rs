@@ -1,18 +1,80 @@
-use loom::sync::Notify;
+use super::Signal;
+use loom::sync::{Arc, Mutex, Notify};
 
 /// loom mock implementation of [`Parker`](crate::util::park::real::Parker)
-pub(crate) struct Parker(Notify);
+pub(crate) struct Parker(
+    Notify,
+    Mutex<Option<Arc<Parker>>>,
+    Mutex<Option<Signal>>,
+);
 
 impl Parker {
     pub(crate) fn new() -> Self {
-        Self(Notify::new())
+        Self(Notify::new(), Mutex::new(None), Mutex::new(None))
     }
 
     pub(crate) unsafe fn park(&self) {
         self.0.wait();
     }
 
+    /// loom has no notion of time, so the timeout always elapses immediately.
+    #[allow(dead_code)]
+    pub(crate) unsafe fn park_timeout(&self, _timeout: std::time::Duration) -> bool {
+        loom::thread::yield_now();
+        false
+    }
+
+    /// loom has no notion of time, so the deadline always passes immediately.
+    pub(crate) unsafe fn park_deadline(&self, _deadline: std::time::Instant) -> bool {
+        loom::thread::yield_now();
+        false
+    }
+
     pub(crate) fn unpark(&self) {
         self.0.notify();
+        if let Some(parker) = self.1.lock().unwrap().as_ref() {
+            parker.unpark();
+        }
+        if let Some(notify) = self.2.lock().unwrap().as_ref() {
+            notify.wake();
+        }
+    }
+
+    /// The mock can't tell, so `unpark` is never skipped.
+    #[allow(dead_code)]
+    pub(crate) fn is_parked(&self) -> bool {
+        true
+    }
+
+    /// The mock doesn't count parks.
+    #[cfg(feature = "metrics")]
+    #[allow(dead_code)]
+    pub(crate) fn parks(&self) -> u64 {
+        0
+    }
+
+    /// The mock doesn't time parks.
+    #[cfg(feature = "metrics")]
+    #[allow(dead_code)]
+    pub(crate) fn park_time(&self) -> std::time::Duration {
+        std::time::Duration::ZERO
+    }
+
+    pub(crate) fn set_notify(&self, notify: Option<super::Notify>) {
+        *self.2.lock().unwrap() = notify.map(Signal::Notify);
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn set_waker(&self, waker: &std::task::Waker) {
+        *self.2.lock().unwrap() = Some(Signal::Waker(waker.clone()));
+    }
+
+    pub(crate) unsafe fn register(&self, waker: Arc<Parker>) -> bool {
+        *self.1.lock().unwrap() = Some(waker);
+        false
+    }
+
+    pub(crate) unsafe fn unregister(&self) {
+        *self.1.lock().unwrap() = None;
     }
 }
@@ -2,11 +2,146 @@
 mod loom;
 #[cfg(all(feature = "hl-loom", not(feature = "full-loom")))]
 pub(crate) use loom::Parker;
+#[cfg(all(feature = "hl-loom", not(feature = "full-loom")))]
+pub(crate) type Notify = Box<dyn Fn() + Send + Sync>;
 
 #[cfg(any(not(feature = "hl-loom"), feature = "full-loom"))]
 mod real;
 #[cfg(any(not(feature = "hl-loom"), feature = "full-loom"))]
-pub(crate) use real::Parker;
+#[allow(unused_imports)]
+pub(crate) use real::{Notify, Parker};
+
+mod wait_queue;
+pub(crate) use wait_queue::WaitQueue;
+
+use crate::util::backoff::Backoff;
+use std::time::{Duration, Instant};
+
+/// What a [`Parker`] wakes on every `unpark`, besides the thread parked on it.
+///
+/// Blocking end points park threads, while end points polled as futures
+/// register the [`Waker`](std::task::Waker) of their task, so one side of
+/// a channel can block while the other is async.
+#[allow(dead_code)]
+pub(crate) enum Signal {
+    /// A callback, set by `set_notify`.
+    Notify(Notify),
+    /// A task, set by `set_waker`.
+    Waker(std::task::Waker),
+}
+
+impl Signal {
+    #[inline]
+    pub(crate) fn wake(&self) {
+        match self {
+            Signal::Notify(notify) => notify(),
+            Signal::Waker(waker) => waker.wake_by_ref(),
+        }
+    }
+
+    /// Checks if replacing this with `Signal::Waker(waker)` would change nothing.
+    #[inline]
+    #[allow(dead_code)]
+    pub(crate) fn will_wake(&self, waker: &std::task::Waker) -> bool {
+        matches!(self, Signal::Waker(old) if old.will_wake(waker))
+    }
+}
+
+/// How blocking operations wait for the other end point.
+/// What a blocking operation does once it's done spinning.
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Idle {
+    /// Keeps backing off with [`Backoff::pause`], without ever parking.
+    Spin,
+    /// Yields the thread between checks.
+    Yield,
+    /// Parks until woken.
+    Park,
+    /// Parks for at most the duration at a time, calling
+    /// `on_idle` of [`wait_idle`](WaitStrategy::wait_idle) when it elapses.
+    ParkTimeout(Duration),
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct WaitStrategy {
+    pub(crate) spins: u32,
+    pub(crate) idle: Idle,
+}
+
+#[allow(dead_code)]
+impl WaitStrategy {
+    pub(crate) const DEFAULT: Self = Self {
+        spins: 0,
+        idle: Idle::Park,
+    };
+
+    #[inline]
+    pub(crate) unsafe fn wait_deadline(
+        &self,
+        parker: &Parker,
+        spun: &mut u32,
+        deadline: Option<Instant>,
+    ) -> bool {
+        self.wait_idle(parker, spun, deadline, || {})
+    }
+
+    /// Waits once, returning `false` if `deadline` passed.
+    /// A `deadline` of `None` never passes.
+    ///
+    /// `on_idle` is called if an [`Idle::ParkTimeout`] park timed out.
+    #[inline]
+    pub(crate) unsafe fn wait_idle(
+        &self,
+        parker: &Parker,
+        spun: &mut u32,
+        deadline: Option<Instant>,
+        on_idle: impl FnOnce(),
+    ) -> bool {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return false;
+        }
+        if !self.will_park(*spun) {
+            if self.idle == Idle::Yield && *spun >= self.spins {
+                crate::thread::yield_now();
+            } else {
+                Backoff::pause(*spun);
+                *spun = spun.saturating_add(1);
+            }
+            return true;
+        }
+        let idle_until = match self.idle {
+            Idle::ParkTimeout(timeout) => Instant::now().checked_add(timeout),
+            _ => None,
+        };
+        match (idle_until, deadline) {
+            (Some(until), Some(deadline)) if deadline <= until => {
+                parker.park_deadline(deadline);
+            }
+            (Some(until), _) => {
+                if !parker.park_deadline(until) {
+                    on_idle();
+                }
+            }
+            (None, Some(deadline)) => {
+                parker.park_deadline(deadline);
+            }
+            (None, None) => parker.park(),
+        }
+        true
+    }
+
+    #[inline(always)]
+    pub(crate) unsafe fn wait(&self, parker: &Parker, spun: &mut u32) {
+        self.wait_idle(parker, spun, None, || {});
+    }
+
+    #[inline(always)]
+    pub(crate) fn will_park(&self, spun: u32) -> bool {
+        matches!(self.idle, Idle::Park | Idle::ParkTimeout(_)) && spun >= self.spins
+    }
+}
 
 #[cfg(test)]
 mod tests;
@@ -1,8 +1,15 @@
 use crate::sync::atomic::{
-    AtomicUsize,
-    Ordering::{Acquire, Release},
+    fence, AtomicBool, AtomicUsize,
+    Ordering::{AcqRel, Acquire, Relaxed, Release, SeqCst},
 };
-use crate::sync::{Condvar, Mutex};
+use crate::sync::Arc;
+#[cfg(not(all(feature = "parking_lot", not(feature = "loom"))))]
+use crate::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(all(feature = "parking_lot", not(feature = "loom")))]
+use parking_lot::{Condvar, Mutex, MutexGuard};
+use super::Signal;
+use std::task::Waker;
+use std::time::{Duration, Instant};
 
 /// park/unpark equivalent, except can be embedded in objects.
 ///
@@ -13,9 +20,23 @@ use crate::sync::{Condvar, Mutex};
 pub(crate) struct Parker {
     state: AtomicUsize,
     condvar: Condvar,
-    mutex: Mutex<()>,
+    /// A `Parker` that's also woken by `unpark`, set by `register`.
+    forward: Mutex<Option<Arc<Parker>>>,
+    /// Woken by every `unpark`, set by `set_notify` or `set_waker`.
+    notify: Mutex<Option<Signal>>,
+    /// Set while `notify` holds a signal, so `unpark` doesn't have to lock.
+    has_notify: AtomicBool,
+    /// How many times `park` went to sleep.
+    #[cfg(feature = "metrics")]
+    parks: crate::metrics::Counter,
+    /// How many nanoseconds `park` slept.
+    #[cfg(feature = "metrics")]
+    park_time: crate::metrics::Counter,
 }
 
+/// A callback called by [`Parker::unpark`].
+pub(crate) type Notify = Box<dyn Fn() + Send + Sync>;
+
 const NOTIFIED: usize = 0;
 const EMPTY: usize = 1;
 const PARKED: usize = 2;
@@ -26,7 +47,13 @@ impl Parker {
         Self {
             state: AtomicUsize::new(EMPTY),
             condvar: Condvar::new(),
-            mutex: Mutex::new(()),
+            forward: Mutex::new(None),
+            notify: Mutex::new(None),
+            has_notify: AtomicBool::new(false),
+            #[cfg(feature = "metrics")]
+            parks: crate::metrics::Counter::new(),
+            #[cfg(feature = "metrics")]
+            park_time: crate::metrics::Counter::new(),
         }
     }
     #[cfg(feature = "loom")]
@@ -34,7 +61,13 @@ impl Parker {
         Self {
             state: AtomicUsize::new(EMPTY),
             condvar: Condvar::new(),
-            mutex: Mutex::new(()),
+            forward: Mutex::new(None),
+            notify: Mutex::new(None),
+            has_notify: AtomicBool::new(false),
+            #[cfg(feature = "metrics")]
+            parks: crate::metrics::Counter::new(),
+            #[cfg(feature = "metrics")]
+            park_time: crate::metrics::Counter::new(),
         }
     }
 
@@ -51,19 +84,22 @@ impl Parker {
          */
         // Do NOTIFIED=>EMPTY or EMPTY=>PARKED
         match self.state.fetch_add(1, Acquire) {
-            NOTIFIED => return,
-            EMPTY => self.park_slow(),
+            NOTIFIED => {}
+            EMPTY => {
+                metrics! { self.parks.add(1); }
+                #[cfg(feature = "metrics")]
+                let _timer = self.park_time.time();
+                self.park_slow()
+            }
             _ => panic!("Invalid call to Parker::park."),
         }
+        // pairs with the fence in is_parked.
+        fence(SeqCst);
     }
 
     #[inline(never)]
     fn park_slow(&self) {
-        //thread::park doesn't transmit panics, so we ignore poison.
-        let mut m = match self.mutex.lock() {
-            Ok(g) => g,
-            Err(poisoned) => poisoned.into_inner(),
-        };
+        let mut m = self.lock();
 
         loop {
             if self
@@ -76,10 +112,68 @@ impl Parker {
                 //spurious wake-up.
             }
 
-            m = match self.condvar.wait(m) {
-                Ok(g) => g,
-                Err(poisoned) => poisoned.into_inner(),
-            };
+            m = wait(&self.condvar, m);
+        }
+    }
+
+    /// Like `park`, but gives up after `timeout`.
+    ///
+    /// Returns `true` if the `Parker` was notified.
+    ///
+    /// SAFETY: same as `park`.
+    #[allow(dead_code)]
+    pub(crate) unsafe fn park_timeout(&self, timeout: Duration) -> bool {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.park_deadline(deadline),
+            None => {
+                self.park();
+                true
+            }
+        }
+    }
+
+    /// Like `park`, but gives up at `deadline`.
+    ///
+    /// Returns `true` if the `Parker` was notified.
+    ///
+    /// SAFETY: same as `park`.
+    pub(crate) unsafe fn park_deadline(&self, deadline: Instant) -> bool {
+        // Do NOTIFIED=>EMPTY or EMPTY=>PARKED
+        let notified = match self.state.fetch_add(1, Acquire) {
+            NOTIFIED => true,
+            EMPTY => {
+                metrics! { self.parks.add(1); }
+                #[cfg(feature = "metrics")]
+                let _timer = self.park_time.time();
+                self.park_deadline_slow(deadline)
+            }
+            _ => panic!("Invalid call to Parker::park_deadline."),
+        };
+        // pairs with the fence in is_parked.
+        fence(SeqCst);
+        notified
+    }
+
+    #[inline(never)]
+    fn park_deadline_slow(&self, deadline: Instant) -> bool {
+        let mut m = self.lock();
+
+        loop {
+            if self
+                .state
+                .compare_exchange(NOTIFIED, EMPTY, Acquire, Acquire)
+                .is_ok()
+            {
+                return true; //got our notification.
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                // An unpark call might have come in since the last check.
+                return self.state.swap(EMPTY, Acquire) == NOTIFIED;
+            }
+
+            m = wait_timeout(&self.condvar, m, remaining);
         }
     }
 
@@ -110,10 +204,192 @@ impl Parker {
              * but before going to sleep.
              *
              */
-            drop(self.mutex.lock());
+            let forward = self.lock();
+            if let Some(parker) = forward.as_ref() {
+                parker.unpark();
+            }
+            drop(forward);
             self.condvar.notify_one();
         }
+        if self.has_notify.load(Acquire) {
+            self.call_notify();
+        }
+    }
+
+    /// Checks if [`unpark`](Parker::unpark) can wake anything, so callers can skip it.
+    ///
+    /// Returns `false` only if no callback is set and a notification is already
+    /// pending, in which case the next `park` returns right away. Otherwise the
+    /// thread may be parked, or about to park.
+    ///
+    /// Everything written before this call is visible to the parked thread once
+    /// `park` returns, since the fence here pairs with the one in `park`.
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn is_parked(&self) -> bool {
+        fence(SeqCst);
+        self.state.load(Relaxed) != NOTIFIED || self.has_notify.load(Relaxed)
+    }
+
+    #[inline(never)]
+    fn call_notify(&self) {
+        //a panicking callback doesn't break the Parker, so poison is ignored.
+        let notify = lock(&self.notify);
+        if let Some(notify) = notify.as_ref() {
+            notify.wake();
+        }
     }
+
+    /// Returns how many times `park` went to sleep.
+    #[cfg(feature = "metrics")]
+    #[allow(dead_code)]
+    pub(crate) fn parks(&self) -> u64 {
+        self.parks.get()
+    }
+
+    /// Returns how long `park` slept.
+    #[cfg(feature = "metrics")]
+    #[allow(dead_code)]
+    pub(crate) fn park_time(&self) -> Duration {
+        self.park_time.get_duration()
+    }
+
+    /// Makes every `unpark` call `notify`, replacing the previous callback.
+    /// `None` removes the callback.
+    #[allow(dead_code)]
+    pub(crate) fn set_notify(&self, notify: Option<Notify>) {
+        self.set_signal(notify.map(Signal::Notify));
+    }
+
+    /// Makes every `unpark` wake `waker`, replacing the previous callback.
+    ///
+    /// Doesn't clone `waker` if it would wake the same task as the current one,
+    /// so it's cheap to call on every poll.
+    #[allow(dead_code)]
+    pub(crate) fn set_waker(&self, waker: &Waker) {
+        let mut guard = lock(&self.notify);
+        if guard.as_ref().is_some_and(|signal| signal.will_wake(waker)) {
+            return;
+        }
+        self.has_notify.store(true, Release);
+        // pairs with the fence in is_parked.
+        fence(SeqCst);
+        let _old = guard.replace(Signal::Waker(waker.clone()));
+        drop(guard);
+    }
+
+    fn set_signal(&self, signal: Option<Signal>) {
+        let mut guard = lock(&self.notify);
+        self.has_notify.store(signal.is_some(), Release);
+        // pairs with the fence in is_parked.
+        fence(SeqCst);
+        //the old signal is dropped outside the lock.
+        let _old = std::mem::replace(&mut *guard, signal);
+        drop(guard);
+    }
+
+    /// Makes `unpark` also wake `waker`, until `unregister` is called.
+    ///
+    /// Returns `true` if the `Parker` was already notified, in which case the
+    /// notification is consumed.
+    ///
+    /// SAFETY: this method can't be called concurrently with `park`,
+    /// `register` or `unregister` and `unregister` must be called
+    /// before the next `park` or `register`.
+    #[allow(dead_code)]
+    pub(crate) unsafe fn register(&self, waker: Arc<Parker>) -> bool {
+        /* The state is changed while holding the lock, so an unpark
+         * call that finds PARKED is guaranteed to see the waker.
+         */
+        let mut forward = self.lock();
+        *forward = Some(waker);
+        let notified = match self.state.fetch_add(1, AcqRel) {
+            NOTIFIED => true,
+            EMPTY => false,
+            _ => panic!("Invalid call to Parker::register."),
+        };
+        // pairs with the fence in is_parked.
+        fence(SeqCst);
+        notified
+    }
+
+    /// Undoes a previous `register` call.
+    ///
+    /// SAFETY: same as `register`.
+    #[allow(dead_code)]
+    pub(crate) unsafe fn unregister(&self) {
+        let mut forward = self.lock();
+        *forward = None;
+        // A leftover notification only results in a spurious wake-up.
+        let _ = self
+            .state
+            .compare_exchange(PARKED, EMPTY, Relaxed, Relaxed);
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Option<Arc<Parker>>> {
+        lock(&self.forward)
+    }
+}
+
+/*The parking_lot feature swaps the std Mutex and Condvar for the
+ *parking_lot ones, which don't poison, so only these helpers differ.
+ */
+
+/// Locks `mutex`, ignoring poison, since `thread::park` doesn't transmit panics.
+#[cfg(not(all(feature = "parking_lot", not(feature = "loom"))))]
+#[inline]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(g) => g,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+#[cfg(not(all(feature = "parking_lot", not(feature = "loom"))))]
+#[inline]
+fn wait<'a, T>(condvar: &Condvar, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    match condvar.wait(guard) {
+        Ok(g) => g,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+#[cfg(not(all(feature = "parking_lot", not(feature = "loom"))))]
+#[inline]
+fn wait_timeout<'a, T>(
+    condvar: &Condvar,
+    guard: MutexGuard<'a, T>,
+    timeout: Duration,
+) -> MutexGuard<'a, T> {
+    match condvar.wait_timeout(guard, timeout) {
+        Ok((g, _)) => g,
+        Err(poisoned) => poisoned.into_inner().0,
+    }
+}
+
+#[cfg(all(feature = "parking_lot", not(feature = "loom")))]
+#[inline]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
+
+#[cfg(all(feature = "parking_lot", not(feature = "loom")))]
+#[inline]
+fn wait<'a, T>(condvar: &Condvar, mut guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    condvar.wait(&mut guard);
+    guard
+}
+
+#[cfg(all(feature = "parking_lot", not(feature = "loom")))]
+#[inline]
+fn wait_timeout<'a, T>(
+    condvar: &Condvar,
+    mut guard: MutexGuard<'a, T>,
+    timeout: Duration,
+) -> MutexGuard<'a, T> {
+    condvar.wait_for(&mut guard, timeout);
+    guard
 }
 
 unsafe impl Send for Parker {}
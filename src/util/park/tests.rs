@@ -1,5 +1,5 @@
 #[cfg(test)]
-use super::Parker;
+use super::{Parker, WaitQueue};
 cfg_not_loom! {
 
 #[test]
@@ -16,6 +16,126 @@ fn test_one_thread() {
     unsafe { parker.park() };
 }
 
+#[test]
+fn test_timeout() {
+    use std::time::Duration;
+    let parker = Parker::new();
+    assert!(!unsafe { parker.park_timeout(Duration::from_millis(10)) });
+    parker.unpark();
+    assert!(unsafe { parker.park_timeout(Duration::from_millis(10)) });
+    assert!(!unsafe { parker.park_timeout(Duration::ZERO) });
+}
+
+#[test]
+fn test_deadline() {
+    use std::time::{Duration, Instant};
+    let parker = Parker::new();
+    let deadline = Instant::now() + Duration::from_millis(10);
+    assert!(!unsafe { parker.park_deadline(deadline) });
+    assert!(Instant::now() >= deadline);
+    parker.unpark();
+    assert!(unsafe { parker.park_deadline(deadline) });
+
+    static PARKER: Parker = Parker::new();
+    std::thread::spawn(|| PARKER.unpark());
+    assert!(unsafe { PARKER.park_deadline(Instant::now() + Duration::from_secs(60)) });
+}
+
+#[test]
+fn test_waker() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+    struct Count(AtomicUsize);
+    impl Wake for Count {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    let count = Arc::new(Count(AtomicUsize::new(0)));
+    let waker = Waker::from(count.clone());
+    let parker = Parker::new();
+    parker.set_waker(&waker);
+    // the same task isn't registered twice.
+    parker.set_waker(&waker.clone());
+    assert_eq!(Arc::strong_count(&count), 3);
+    parker.unpark();
+    assert!(unsafe { parker.park_timeout(std::time::Duration::ZERO) });
+    assert_eq!(count.0.load(Ordering::Relaxed), 1);
+    parker.set_notify(None);
+    parker.unpark();
+    assert_eq!(count.0.load(Ordering::Relaxed), 1);
+    assert_eq!(Arc::strong_count(&count), 2);
+}
+
+#[test]
+fn test_is_parked() {
+    let parker = Parker::new();
+    assert!(parker.is_parked());
+    parker.unpark();
+    // a pending notification makes unpark redundant.
+    assert!(!parker.is_parked());
+    unsafe { parker.park() };
+    assert!(parker.is_parked());
+    parker.unpark();
+    parker.set_notify(Some(Box::new(|| {})));
+    assert!(parker.is_parked());
+    parker.set_notify(None);
+    assert!(!parker.is_parked());
+}
+
+#[test]
+fn wait_queue_fifo() {
+    use std::sync::Arc;
+    use std::time::Duration;
+    let queue = WaitQueue::new();
+    let parkers: Vec<_> = (0..3).map(|_| Arc::new(Parker::new())).collect();
+    for parker in parkers.iter() {
+        queue.push(parker.clone());
+    }
+    assert!(queue.remove(&parkers[1]));
+    assert!(!queue.remove(&parkers[1]));
+    assert!(queue.wake_one());
+    assert_eq!(queue.len(), 1);
+    assert!(unsafe { parkers[0].park_timeout(Duration::ZERO) });
+    assert!(!unsafe { parkers[2].park_timeout(Duration::ZERO) });
+    assert_eq!(queue.wake_all(), 1);
+    assert!(unsafe { parkers[2].park_timeout(Duration::ZERO) });
+    assert!(!queue.wake_one());
+}
+
+#[test]
+fn wait_queue_timeout() {
+    use std::time::{Duration, Instant};
+    let queue = WaitQueue::new();
+    let deadline = Instant::now() + Duration::from_millis(10);
+    assert!(!queue.wait_until(|| false, Some(deadline)));
+    assert!(Instant::now() >= deadline);
+    assert_eq!(queue.len(), 0);
+    assert!(queue.wait_until(|| true, Some(deadline)));
+}
+
+#[test]
+fn wait_queue_threads() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static QUEUE: WaitQueue = WaitQueue::new();
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let handles: Vec<_> = (1..=4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                QUEUE.wait_until(|| COUNTER.load(Ordering::Acquire) >= i, None);
+            })
+        })
+        .collect();
+    for _ in 0..4 {
+        COUNTER.fetch_add(1, Ordering::Release);
+        QUEUE.wake_all();
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
 }
 
 cfg_loom! {
@@ -42,4 +162,21 @@ fn test_one_thread() {
     });
 }
 
+#[test]
+fn wait_queue() {
+    loom::model::model(|| {
+        use loom::sync::atomic::{AtomicBool, Ordering};
+        use loom::sync::Arc;
+        let queue = Arc::new(WaitQueue::new());
+        let ready = Arc::new(AtomicBool::new(false));
+        let (waker, set) = (queue.clone(), ready.clone());
+        loom::thread::spawn(move || {
+            set.store(true, Ordering::Release);
+            waker.wake_one();
+        });
+        queue.wait_until(|| ready.load(Ordering::Acquire), None);
+        assert_eq!(queue.len(), 0);
+    });
+}
+
 }
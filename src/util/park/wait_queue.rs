@@ -0,0 +1,128 @@
+use super::Parker;
+use crate::sync::{Arc, Mutex, MutexGuard};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// A FIFO list of [`Parker`]s waiting for the same condition.
+///
+/// Unlike a single [`Parker`], any number of threads can wait on it at once.
+/// Waking takes parkers from the front, so they're woken in the order they
+/// started waiting.
+pub(crate) struct WaitQueue {
+    waiters: Mutex<VecDeque<Arc<Parker>>>,
+}
+
+#[allow(dead_code)]
+impl WaitQueue {
+    #[cfg(not(feature = "loom"))]
+    pub(crate) const fn new() -> Self {
+        Self {
+            waiters: Mutex::new(VecDeque::new()),
+        }
+    }
+    #[cfg(feature = "loom")]
+    pub(crate) fn new() -> Self {
+        Self {
+            waiters: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Adds `parker` to the back of the queue.
+    pub(crate) fn push(&self, parker: Arc<Parker>) {
+        self.lock().push_back(parker);
+    }
+
+    /// Removes `parker` from the queue.
+    ///
+    /// Returns `false` if it was already taken by a wake-up.
+    pub(crate) fn remove(&self, parker: &Arc<Parker>) -> bool {
+        let mut waiters = self.lock();
+        match waiters.iter().position(|waiter| Arc::ptr_eq(waiter, parker)) {
+            Some(index) => waiters.remove(index).is_some(),
+            None => false,
+        }
+    }
+
+    /// Wakes the parker that has been waiting the longest.
+    ///
+    /// Returns `false` if the queue was empty.
+    pub(crate) fn wake_one(&self) -> bool {
+        // the parker is unparked outside the lock.
+        let waiter = self.lock().pop_front();
+        match waiter {
+            Some(waiter) => {
+                waiter.unpark();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Wakes every parker in the queue, in FIFO order.
+    ///
+    /// Returns how many were woken.
+    pub(crate) fn wake_all(&self) -> usize {
+        let waiters = std::mem::take(&mut *self.lock());
+        for waiter in waiters.iter() {
+            waiter.unpark();
+        }
+        waiters.len()
+    }
+
+    /// Returns the number of waiting parkers.
+    pub(crate) fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Blocks until `condition` returns `true`, or `deadline` passes.
+    /// A `deadline` of `None` never passes.
+    ///
+    /// `condition` is checked after joining the queue, so a wake-up that
+    /// follows making it `true` can't be missed. Returns the last result
+    /// of `condition`.
+    pub(crate) fn wait_until(
+        &self,
+        mut condition: impl FnMut() -> bool,
+        deadline: Option<Instant>,
+    ) -> bool {
+        if condition() {
+            return true;
+        }
+        let parker = Arc::new(Parker::new());
+        loop {
+            self.push(parker.clone());
+            if condition() {
+                break self.leave(&parker, true);
+            }
+            //SAFETY: `parker` is local to this call.
+            let notified = match deadline {
+                Some(deadline) => unsafe { parker.park_deadline(deadline) },
+                None => unsafe {
+                    parker.park();
+                    true
+                },
+            };
+            if !notified {
+                let ready = condition();
+                break self.leave(&parker, ready);
+            }
+            // a spurious wake-up leaves `parker` in the queue.
+            self.remove(&parker);
+        }
+    }
+
+    /// Takes `parker` out of the queue for good, returning `ready`.
+    fn leave(&self, parker: &Arc<Parker>, ready: bool) -> bool {
+        // a wake-up meant for a waiter that's giving up is passed on.
+        if !self.remove(parker) && !ready {
+            self.wake_one();
+        }
+        ready
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Arc<Parker>>> {
+        //a panic can't leave the queue half-modified, so poison is ignored.
+        self.waiters.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
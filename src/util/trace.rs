@@ -0,0 +1,23 @@
+/// Runs `park` inside a `park` span, so subscribers see when a blocking
+/// operation goes to sleep and when it wakes up.
+///
+/// `op` names the blocked operation, `channel` is the id returned by
+/// `channel_id`, `name` is the name the channel was built with, if any,
+/// and `depth` is how many values were queued when it parked.
+#[cold]
+#[allow(dead_code)]
+#[inline(never)]
+pub(crate) fn parked<R>(
+    op: &'static str,
+    channel: usize,
+    name: Option<&'static str>,
+    depth: usize,
+    park: impl FnOnce() -> R,
+) -> R {
+    let span = tracing::trace_span!("park", op, channel, name, depth).entered();
+    tracing::trace!("parking");
+    let res = park();
+    tracing::trace!("woke");
+    drop(span);
+    res
+}
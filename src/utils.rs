@@ -0,0 +1,2 @@
+pub use crate::util::ann::AtomicNonNull;
+pub use crate::util::cache::CachePadded;
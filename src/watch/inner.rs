@@ -0,0 +1,130 @@
+use crate::alloc::Layout;
+use crate::error::{RecvError, SendError, TryRecvError};
+use crate::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+use crate::sync::atomic::{AtomicPtr, AtomicUsize};
+use crate::sync::Arc;
+use crate::util::park::Parker;
+use std::ptr::null_mut;
+
+pub(super) struct Inner<T> {
+    /// The latest unreceived value, or null if there is none.
+    ///
+    /// Owned by whoever swaps it out.
+    slot: AtomicPtr<T>,
+    park: Parker,
+    /*
+    starts off as 0, incremented when entering Sender/Receiver drop.
+    match 'previous value' {
+        0 => {
+            Now the channel is disconnected. We try to wake the other end point.
+            If the other end point was asleep, it will detect the disconnect and unblock.
+            Then, we increment 'drop_count' again and repeat this decision tree with the
+            new 'previous value'.
+        }
+        1 => just fall off drop.
+        2 => deallocate the inner state.
+    }
+    */
+    pub(super) drop_count: AtomicUsize,
+}
+
+impl<T> Inner<T> {
+    pub(super) const LAYOUT: Layout = Layout::new::<Inner<T>>();
+
+    pub(super) fn new() -> Self {
+        Self {
+            slot: AtomicPtr::new(null_mut()),
+            park: Parker::new(),
+            drop_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Can only be called by the sender.
+    pub(super) fn send(&self, item: T) -> Result<(), SendError<T>> {
+        if self.drop_count.load(Relaxed) != 0 {
+            return Err(SendError(item));
+        }
+        let old = self.slot.swap(Box::into_raw(Box::new(item)), AcqRel);
+        self.wake_receiver();
+        if !old.is_null() {
+            //SAFETY: swapping the value out of slot transfers its ownership.
+            drop(unsafe { Box::from_raw(old) });
+        }
+        Ok(())
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut item = self.slot.swap(null_mut(), Acquire);
+        if item.is_null() {
+            if self.drop_count.load(Acquire) == 0 {
+                return Err(TryRecvError::Empty);
+            }
+            // the sender might have sent a value before disconnecting.
+            item = self.slot.swap(null_mut(), Acquire);
+            if item.is_null() {
+                return Err(TryRecvError::Disconnected);
+            }
+        }
+        //SAFETY: swapping the value out of slot transfers its ownership.
+        Ok(*unsafe { Box::from_raw(item) })
+    }
+
+    /// Can only be called by the receiver.
+    pub(super) fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(TryRecvError::Disconnected | TryRecvError::Poisoned) => {
+                    return Err(RecvError {})
+                }
+                //SAFETY: park can't be called by different threads, since Receiver is !Sync.
+                Err(TryRecvError::Empty) => unsafe { self.park.park() },
+            }
+        }
+    }
+
+    pub(super) fn peer_connected(&self) -> bool {
+        self.drop_count.load(Acquire) == 0
+    }
+
+    /// Checks if `try_recv` would return something other than [`TryRecvError::Empty`].
+    pub(super) fn is_ready(&self) -> bool {
+        !self.slot.load(Acquire).is_null() || self.drop_count.load(Acquire) != 0
+    }
+
+    /// Makes [`wake_receiver`](Inner::wake_receiver) also wake `waker`.
+    ///
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::register`.
+    pub(super) unsafe fn register_receiver(&self, waker: Arc<Parker>) -> bool {
+        self.park.register(waker)
+    }
+
+    /// # Safety
+    ///
+    /// Can only be called by the receiver, see `Parker::unregister`.
+    pub(super) unsafe fn unregister_receiver(&self) {
+        self.park.unregister()
+    }
+
+    #[inline]
+    pub(super) fn wake_receiver(&self) {
+        self.park.unpark();
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        #[cfg(not(feature = "loom"))]
+        let item = *self.slot.get_mut();
+        #[cfg(feature = "loom")]
+        let item = self.slot.with_mut(|slot| *slot);
+
+        if !item.is_null() {
+            //SAFETY: the value was sent, but not received.
+            drop(unsafe { Box::from_raw(item) });
+        }
+    }
+}
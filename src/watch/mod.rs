@@ -0,0 +1,205 @@
+use crate::alloc::{alloc, dealloc};
+use crate::error::{RecvError, SendError, TryRecvError};
+use crate::sync::atomic::Ordering::AcqRel;
+use crate::util::marker::PhantomUnsync;
+use std::ptr::NonNull;
+
+mod inner;
+use inner::Inner;
+
+/// Creates a channel that holds only the latest sent value.
+///
+/// # Panics
+///
+/// The function panics if it can't allocate the memory needed for the channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    //order is important: Inner is RAII, but NonNull isn't.
+    let inner = {
+        /*SAFETY: deallocated in either Sender's or Receiver's Drop*/
+        let inner_uninit = NonNull::new(unsafe { alloc(Inner::<T>::LAYOUT) as *mut Inner<T> })
+            .expect("failed to allocate memory for the shared state");
+        /*SAFETY: this is a safe way to write to _uninitialised memory_.*/
+        unsafe { inner_uninit.as_ptr().write(Inner::new()) };
+        inner_uninit
+    };
+    (
+        Sender {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+        Receiver {
+            inner,
+            _unsync: PhantomUnsync {},
+        },
+    )
+}
+
+/// The sending endpoint of a [`channel`].
+///
+/// Values are published with [`send`](Sender::send), which
+/// replaces the previous value if it wasn't received yet.
+pub struct Sender<T> {
+    inner: NonNull<Inner<T>>,
+    _unsync: PhantomUnsync,
+}
+
+/// The receiving endpoint of a [`channel`].
+///
+/// The latest value can be received using the [`try_recv`](Receiver::try_recv)
+/// and [`recv`](Receiver::recv) methods.
+pub struct Receiver<T> {
+    inner: NonNull<Inner<T>>,
+    _unsync: PhantomUnsync,
+}
+
+impl<T> Sender<T> {
+    /// Publishes a value through this [`channel`], dropping
+    /// the previous one if it wasn't received yet.
+    ///
+    /// Never blocks. Returns a [`SendError`] if the [`Receiver`] is disconnected.
+    #[inline]
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.inner_ref().send(item)
+    }
+
+    /// Checks if the [`channel`]'s [`Receiver`] is still connected.
+    #[inline]
+    pub fn receiver_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    fn inner_ref(&self) -> &Inner<T> {
+        /*SAFETY:
+         *This type and Receiver are responsible for inner's lifetime.
+         */
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Tries to return the latest value sent since the last receive.
+    ///
+    /// # Note
+    ///
+    /// Returns [`TryRecvError::Disconnected`] only after receiving the last
+    /// value. To avoid this, use [`sender_connected`](Receiver::sender_connected).
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner_ref().try_recv()
+    }
+
+    /// Returns the latest value sent since the last receive.
+    ///
+    /// If no new value was sent, blocks and waits for the [`Sender`].
+    ///
+    /// # Note
+    ///
+    /// [`RecvError`] is only returned after receiving the last value. To
+    /// avoid this, use [`sender_connected`](Receiver::sender_connected).
+    #[inline]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner_ref().recv()
+    }
+
+    /// Checks if the [`channel`]'s [`Sender`] is still connected.
+    ///
+    /// # Note
+    ///
+    /// The [`try_recv`](Receiver::try_recv) and [`recv`](Receiver::recv)
+    /// methods return [`TryRecvError::Disconnected`] or [`RecvError`] only
+    /// after receiving the last value, even if the [`Sender`] isn't
+    /// connected. This method doesn't take the last value into account.
+    #[inline]
+    pub fn sender_connected(&self) -> bool {
+        self.inner_ref().peer_connected()
+    }
+
+    fn inner_ref(&self) -> &Inner<T> {
+        /*SAFETY:
+         *This type and Sender are responsible for inner's lifetime.
+         */
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => self.inner_ref().wake_receiver(),
+                1 => break,
+                2 => {
+                    break unsafe {
+                        self.inner.as_ptr().drop_in_place();
+                        dealloc(self.inner.as_ptr() as *mut u8, Inner::<T>::LAYOUT)
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        //this protocol is described at the declaration of 'drop_count'
+        loop {
+            match self.inner_ref().drop_count.fetch_add(1, AcqRel) {
+                0 => { /*the Sender never blocks*/ }
+                1 => break,
+                2 => {
+                    break unsafe {
+                        self.inner.as_ptr().drop_in_place();
+                        dealloc(self.inner.as_ptr() as *mut u8, Inner::<T>::LAYOUT)
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "watch::Sender<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "watch::Receiver<{}> {{ channel: {:p} }}",
+            std::any::type_name::<T>(),
+            self.inner
+        )
+    }
+}
+
+impl<T> crate::select::Selectable for Receiver<T> {}
+
+impl<T> crate::select::sealed::Sealed for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        self.inner_ref().is_ready()
+    }
+
+    unsafe fn register(&self, waker: crate::select::sealed::Waker) -> bool {
+        self.inner_ref().register_receiver(waker.0)
+    }
+
+    unsafe fn unregister(&self) {
+        self.inner_ref().unregister_receiver()
+    }
+}
+
+unsafe impl<T: Send> Send for Sender<T> {}
+unsafe impl<T: Send> Send for Receiver<T> {}
+
+#[cfg(test)]
+mod tests;
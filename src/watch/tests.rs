@@ -0,0 +1,100 @@
+use super::*;
+cfg_not_loom! {
+
+#[test]
+fn st_latest_value() {
+    let (src, sink) = channel::<i32>();
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    src.send(1).unwrap();
+    src.send(2).unwrap();
+    assert_eq!(sink.try_recv(), Ok(2));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Empty));
+    src.send(3).unwrap();
+    drop(src);
+    assert_eq!(sink.try_recv(), Ok(3));
+    assert_eq!(sink.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn st_disconnect() {
+    let (src, sink) = channel::<i32>();
+    drop(src);
+    assert_eq!(sink.recv(), Err(RecvError {}));
+
+    let (src, sink) = channel::<i32>();
+    drop(sink);
+    assert!(!src.receiver_connected());
+    assert_eq!(src.send(1), Err(SendError(1)));
+}
+
+#[test]
+fn drop_overwritten() {
+    use std::rc::Rc;
+    let rc = Rc::new(());
+    let (src, sink) = channel();
+    src.send(rc.clone()).unwrap();
+    src.send(rc.clone()).unwrap();
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(sink);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(src);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn mt_send_recv() {
+    let (src, sink) = channel::<u32>();
+    std::thread::spawn(move || {
+        for i in 0..10000 {
+            src.send(i).unwrap();
+        }
+    });
+    let mut last = None;
+    while let Ok(i) = sink.recv() {
+        assert!(last < Some(i));
+        last = Some(i);
+    }
+    assert_eq!(last, Some(9999));
+}
+
+}
+
+cfg_loom! {
+
+#[test]
+fn send_recv() {
+    loom::model(|| {
+        let (src, sink) = channel::<i32>();
+        loom::thread::spawn(move || {
+            src.send(1).unwrap();
+            src.send(2).unwrap();
+        });
+        let mut last = 0;
+        while let Ok(i) = sink.recv() {
+            assert!(last < i);
+            last = i;
+        }
+        assert_eq!(last, 2);
+    });
+}
+
+#[test]
+fn receiver_dc() {
+    loom::model(|| {
+        use loom::sync::Arc;
+        let arc = Arc::new(());
+        {
+            let (src, sink) = channel();
+            let arc = arc.clone();
+            let handle = loom::thread::spawn(move || {
+                let _ = src.send(arc.clone());
+                let _ = src.send(arc);
+            });
+            drop(sink);
+            handle.join().unwrap();
+        }
+        assert_eq!(Arc::strong_count(&arc), 1);
+    });
+}
+
+}
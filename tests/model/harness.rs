@@ -0,0 +1,132 @@
+//! The reference model shared by the model tests and the fuzz targets.
+
+use concurrent_qs::error::{ErrorKind, SendError, TryRecvError};
+use concurrent_qs::traits::{BlockingRecv, BlockingSend, TryRecv, TrySend};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Op {
+    TrySend,
+    /// Only blocks if that can't hang, otherwise it's a `TrySend`.
+    Send,
+    TryRecv,
+    /// Only blocks if that can't hang, otherwise it's a `TryRecv`.
+    Recv,
+    DropSender,
+    DropReceiver,
+}
+
+impl Op {
+    /// Maps a byte to an operation, so fuzzers can generate them.
+    /// Sends and receives are four times as likely as drops.
+    pub fn from_byte(byte: u8) -> Self {
+        match byte % 14 {
+            0..=3 => Op::TrySend,
+            4..=5 => Op::Send,
+            6..=9 => Op::TryRecv,
+            10..=11 => Op::Recv,
+            12 => Op::DropSender,
+            _ => Op::DropReceiver,
+        }
+    }
+}
+
+/// A value that counts how many of its kind are alive, to catch leaks.
+pub struct Item {
+    value: u32,
+    live: Rc<Cell<usize>>,
+}
+
+impl Item {
+    fn new(value: u32, live: &Rc<Cell<usize>>) -> Self {
+        live.set(live.get() + 1);
+        Self {
+            value,
+            live: live.clone(),
+        }
+    }
+}
+
+impl Drop for Item {
+    fn drop(&mut self) {
+        self.live.set(self.live.get() - 1);
+    }
+}
+
+/// Runs `ops` on a channel with room for `capacity` values,
+/// or any number of them if it's `None`.
+///
+/// `classify` turns the error of a failed `try_send` into its kind and the value.
+pub fn run<S, R>(
+    (src, sink): (S, R),
+    capacity: Option<usize>,
+    ops: &[Op],
+    classify: fn(S::Error) -> (ErrorKind, Item),
+) where
+    S: TrySend<Item> + BlockingSend<Item>,
+    R: TryRecv<Item> + BlockingRecv<Item>,
+{
+    let live = Rc::new(Cell::new(0));
+    let (mut src, mut sink) = (Some(src), Some(sink));
+    let mut model = VecDeque::new();
+    let mut next = 0;
+    for &op in ops {
+        match op {
+            Op::TrySend | Op::Send => {
+                let Some(tx) = &src else { continue };
+                let full = capacity.is_some_and(|cap| model.len() == cap);
+                let item = Item::new(next, &live);
+                if op == Op::Send && (!full || sink.is_none()) {
+                    match tx.send(item) {
+                        Ok(()) => model.push_back(next),
+                        Err(SendError(item)) => {
+                            assert!(sink.is_none(), "send failed while connected");
+                            assert_eq!(item.value, next);
+                        }
+                    }
+                } else {
+                    match tx.try_send(item) {
+                        Ok(()) => {
+                            assert!(sink.is_some() && !full, "try_send should have failed");
+                            model.push_back(next);
+                        }
+                        Err(err) => {
+                            let (kind, item) = classify(err);
+                            let expected = match sink {
+                                Some(_) => ErrorKind::Full,
+                                None => ErrorKind::Disconnected,
+                            };
+                            assert!(sink.is_none() || full, "try_send failed with room left");
+                            assert_eq!(kind, expected);
+                            assert_eq!(item.value, next);
+                        }
+                    }
+                }
+                next += 1;
+            }
+            Op::TryRecv | Op::Recv => {
+                let Some(rx) = &sink else { continue };
+                if op == Op::Recv && (!model.is_empty() || src.is_none()) {
+                    assert_eq!(rx.recv().map(|item| item.value).ok(), model.pop_front());
+                } else {
+                    let expected = match model.pop_front() {
+                        Some(value) => Ok(value),
+                        None if src.is_some() => Err(TryRecvError::Empty),
+                        None => Err(TryRecvError::Disconnected),
+                    };
+                    assert_eq!(rx.try_recv().map(|item| item.value), expected);
+                }
+            }
+            Op::DropSender => drop(src.take()),
+            Op::DropReceiver => drop(sink.take()),
+        }
+        // the values left behind by the Receiver may be dropped any time.
+        if sink.is_some() {
+            assert_eq!(live.get(), model.len(), "values were leaked or lost");
+        }
+    }
+    drop((src, sink));
+    assert_eq!(live.get(), 0, "values were leaked");
+}
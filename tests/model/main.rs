@@ -0,0 +1,39 @@
+//! Model tests that drive the **spsc** channels with random sequences of
+//! operations on one thread and check every result against a `VecDeque`.
+//!
+//! They don't find concurrency bugs, but catch accounting errors in the
+//! single-threaded logic, like index wraparound or values that are lost or
+//! leaked when an end point disconnects with values pending.
+//! Run them with `cargo test --features model-tests --test model`, the
+//! same model is used by the `cargo fuzz` targets in `fuzz/`.
+
+mod harness;
+
+use concurrent_qs::spsc::{bounded, unbounded};
+use harness::{run, Op};
+use proptest::prelude::*;
+
+fn ops() -> impl Strategy<Value = Vec<Op>> {
+    prop::collection::vec(any::<u8>().prop_map(Op::from_byte), 0..512)
+}
+
+proptest! {
+    #[test]
+    fn bounded_model(capacity in 1usize..=16, ops in ops()) {
+        let channel = bounded::channel(capacity);
+        run(channel, Some(capacity.next_power_of_two()), &ops, |err| {
+            (err.kind(), err.into_inner())
+        });
+    }
+
+    #[test]
+    fn bounded_exact_model(capacity in 1usize..=16, ops in ops()) {
+        let channel = bounded::channel_exact(capacity);
+        run(channel, Some(capacity), &ops, |err| (err.kind(), err.into_inner()));
+    }
+
+    #[test]
+    fn unbounded_model(ops in ops()) {
+        run(unbounded::channel(), None, &ops, |err| (err.kind(), err.into_inner()));
+    }
+}